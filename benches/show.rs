@@ -4,7 +4,8 @@ use ratatui::{backend::TestBackend, Terminal};
 
 fn show(c: &mut Criterion) {
     c.bench_function("show", |b| {
-        let mut terminal = Terminal::new(TermBackend::Test(TestBackend::new(80, 1000))).unwrap();
+        let mut terminal =
+            Terminal::new(TermBackend::Test(TestBackend::new(80, 1000), None)).unwrap();
         b.iter(|| {
             gitu::run(
                 &gitu::cli::Args {
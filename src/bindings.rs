@@ -4,8 +4,10 @@ use crate::{
     key_parser,
     menu::{Menu, PendingMenu},
     ops::Op,
+    Res,
 };
 use crossterm::event::{KeyCode, KeyModifiers};
+use serde::Serialize;
 
 pub(crate) struct Bindings {
     vec: Vec<Binding>,
@@ -53,6 +55,32 @@ impl Bindings {
             .filter(move |keybind| keybind.menu == expected)
     }
 
+    /// Describes bindings that can never be triggered, because another binding
+    /// in the same menu has the same keys or extends them.
+    pub(crate) fn conflicts(&self) -> Vec<String> {
+        self.vec
+            .iter()
+            .enumerate()
+            .flat_map(|(i, a)| self.vec[i + 1..].iter().filter_map(move |b| conflict(a, b)))
+            .collect()
+    }
+
+    /// The effective bindings in the same layout as the `[bindings]` table of the config,
+    /// so they can be read by other tools (e.g. to generate a cheatsheet).
+    pub(crate) fn to_toml(&self) -> Res<String> {
+        let mut menus = BTreeMap::<String, BTreeMap<String, Vec<&str>>>::new();
+        for binding in &self.vec {
+            menus
+                .entry(name(&binding.menu))
+                .or_default()
+                .entry(name(&binding.op))
+                .or_default()
+                .push(&binding.raw);
+        }
+
+        Ok(toml::to_string(&BTreeMap::from([("bindings", menus)]))?)
+    }
+
     pub(crate) fn arg_list<'a>(
         &'a self,
         pending: &'a PendingMenu,
@@ -76,6 +104,47 @@ impl Bindings {
     }
 }
 
+fn conflict(a: &Binding, b: &Binding) -> Option<String> {
+    if a.menu != b.menu || a.op == b.op {
+        return None;
+    }
+
+    if a.keys == b.keys {
+        Some(format!(
+            "{}: {} is bound to both {} and {}",
+            name(&a.menu),
+            a.raw,
+            name(&a.op),
+            name(&b.op)
+        ))
+    } else if b.keys.starts_with(&a.keys) {
+        Some(shadowed(a, b))
+    } else if a.keys.starts_with(&b.keys) {
+        Some(shadowed(b, a))
+    } else {
+        None
+    }
+}
+
+fn shadowed(shadowed: &Binding, by: &Binding) -> String {
+    format!(
+        "{}: {} ({}) is shadowed by {} ({})",
+        name(&shadowed.menu),
+        shadowed.raw,
+        name(&shadowed.op),
+        by.raw,
+        name(&by.op)
+    )
+}
+
+/// The name of a menu or op, as written in the config.
+fn name<T: Serialize>(value: &T) -> String {
+    match toml::Value::try_from(value) {
+        Ok(toml::Value::String(name)) => name,
+        _ => "?".to_string(),
+    }
+}
+
 pub(crate) struct Binding {
     pub menu: Menu,
     pub raw: String,
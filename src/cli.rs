@@ -1,4 +1,5 @@
 use clap::{Parser, Subcommand};
+use std::path::PathBuf;
 
 #[derive(Default, Debug, Parser)]
 #[command(name = "gitu")]
@@ -26,9 +27,25 @@ pub struct Args {
     #[clap(long, action)]
     /// Print version
     pub version: bool,
+
+    /// Edit a file in the gitu that runs git, which sets this as git's editor itself.
+    #[clap(long, value_name = "FILE")]
+    pub edit: Option<PathBuf>,
 }
 
 #[derive(Debug, Subcommand)]
 pub enum Commands {
-    Show { reference: String },
+    Show {
+        reference: String,
+    },
+    /// Diff two files or directories, which needn't be in a repository
+    Diff {
+        /// Compare the paths themselves rather than revisions, the only mode supported for now
+        #[clap(long, required = true)]
+        no_index: bool,
+        old: PathBuf,
+        new: PathBuf,
+    },
+    /// Print the effective keybindings (defaults merged with your config) as TOML
+    Keys,
 }
@@ -5,10 +5,15 @@ use ratatui::style::Stylize;
 use ratatui::text::Line;
 use ratatui::text::Text;
 use std::borrow::Cow;
+use std::io;
+use std::io::Read;
 use std::iter;
 use std::process::Command;
+use std::process::Stdio;
 use std::sync::Arc;
 use std::sync::RwLock;
+use std::thread;
+use std::time::Duration;
 
 pub(crate) struct CmdLog {
     pub(crate) entries: Vec<Arc<RwLock<CmdLogEntry>>>,
@@ -23,6 +28,7 @@ impl CmdLog {
         let value = Arc::new(RwLock::new(CmdLogEntry::Cmd {
             args: command_args(cmd),
             out: None,
+            progress: None,
         }));
 
         self.entries.push(Arc::clone(&value));
@@ -37,6 +43,7 @@ impl CmdLog {
         let value = Arc::new(RwLock::new(CmdLogEntry::Cmd {
             args: command_args(cmd),
             out: Some(out),
+            progress: None,
         }));
 
         self.entries.push(Arc::clone(&value));
@@ -72,16 +79,153 @@ pub(crate) fn command_args(cmd: &Command) -> Cow<'static, str> {
         .into()
 }
 
+/// Reads the stderr of a running command to the end, keeping its latest line in `log`.
+/// That's how git's `--progress` output is shown, as it updates its lines in place with `\r`.
+pub(crate) fn read_progress(
+    mut stderr: impl Read,
+    log: &RwLock<CmdLogEntry>,
+) -> io::Result<Vec<u8>> {
+    let mut out = vec![];
+    let mut buf = [0; 4096];
+
+    loop {
+        let len = stderr.read(&mut buf)?;
+        if len == 0 {
+            return Ok(out);
+        }
+
+        out.extend_from_slice(&buf[..len]);
+        if let CmdLogEntry::Cmd { progress, .. } = &mut *log.write().unwrap() {
+            *progress = last_line(&out);
+        }
+    }
+}
+
+fn last_line(out: &[u8]) -> Option<String> {
+    // Only the end is needed, a line of progress is far shorter than this
+    let tail = String::from_utf8_lossy(&out[out.len().saturating_sub(512)..]);
+    tail.rsplit(['\r', '\n'])
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(String::from)
+}
+
+/// Removes the lines of git's `--progress` output, which were only of interest while it ran.
+/// Those are updated in place with `\r`, apart from the summaries that pack-objects adds
+/// and phases that finished before their first update.
+pub(crate) fn strip_progress(out: &str) -> String {
+    out.split_inclusive('\n')
+        .filter(|line| {
+            let message = line.strip_prefix("remote: ").unwrap_or(line);
+            !line.contains('\r')
+                && !PROGRESS_TITLES
+                    .iter()
+                    .any(|title| message.starts_with(title))
+        })
+        .collect()
+}
+
+const PROGRESS_TITLES: &[&str] = &[
+    "Enumerating objects: ",
+    "Counting objects: ",
+    "Compressing objects: ",
+    "Writing objects: ",
+    "Receiving objects: ",
+    "Resolving deltas: ",
+    "Unpacking objects: ",
+    "Total ",
+    "Delta compression using up to ",
+];
+
+/// A note on how a finished command went and how long it took, if `general.command_toasts` asks for one.
+pub(crate) fn toast(
+    config: &Config,
+    log: &CmdLogEntry,
+    success: bool,
+    elapsed: Duration,
+) -> Option<CmdLogEntry> {
+    let toasts = &config.general.command_toasts;
+    let CmdLogEntry::Cmd { args, .. } = log else {
+        return None;
+    };
+
+    if !toasts.enabled || (success && elapsed < Duration::from_secs(toasts.min_duration)) {
+        return None;
+    }
+
+    let seconds = elapsed.as_secs_f64();
+    Some(if success {
+        CmdLogEntry::Info(format!("'{}' done in {:.1}s", args, seconds))
+    } else {
+        CmdLogEntry::Error(format!("'{}' failed after {:.1}s", args, seconds))
+    })
+}
+
+/// Tells the desktop that a fetch, pull, push or rebase is done, for when the terminal
+/// isn't being looked at. Other commands are over too quickly to be worth one.
+pub(crate) fn notify_desktop(log: &CmdLogEntry, success: bool) {
+    let CmdLogEntry::Cmd { args, .. } = log else {
+        return;
+    };
+
+    if !is_long_operation(args) {
+        return;
+    }
+
+    let message = format!("'{}' {}", args, if success { "done" } else { "failed" });
+    let Some(mut cmd) = notification_command(&message) else {
+        return;
+    };
+
+    cmd.stdin(Stdio::null());
+    cmd.stdout(Stdio::null());
+    cmd.stderr(Stdio::null());
+    // Nothing is done with the result, it's only waited for so the process is reaped
+    thread::spawn(move || cmd.status());
+}
+
+fn is_long_operation(args: &str) -> bool {
+    let mut words = args.split(' ');
+    words.next() == Some("git")
+        && words
+            .next()
+            .is_some_and(|subcommand| ["fetch", "pull", "push", "rebase"].contains(&subcommand))
+}
+
+fn notification_command(message: &str) -> Option<Command> {
+    if cfg!(target_os = "macos") {
+        let message = message.replace('\\', "\\\\").replace('"', "\\\"");
+        let mut cmd = Command::new("osascript");
+        cmd.arg("-e");
+        cmd.arg(format!(
+            "display notification \"{}\" with title \"gitu\"",
+            message
+        ));
+        Some(cmd)
+    } else if cfg!(unix) {
+        let mut cmd = Command::new("notify-send");
+        cmd.args(["--app-name=gitu", "gitu", message]);
+        Some(cmd)
+    } else {
+        None
+    }
+}
+
 pub(crate) fn format_log_entry<'a>(
     config: &Config,
     log: &Arc<RwLock<CmdLogEntry>>,
 ) -> Vec<Line<'a>> {
     match &*log.read().unwrap() {
-        CmdLogEntry::Cmd { args, out } => [Line::styled(
+        CmdLogEntry::Cmd {
+            args,
+            out,
+            progress,
+        } => [Line::styled(
             format!("{}{}", if out.is_some() { "$ " } else { "Running: " }, args),
             &config.style.command,
         )]
         .into_iter()
+        .chain(progress.iter().map(|progress| Line::raw(progress.clone())))
         .chain(out.iter().flat_map(|out| {
             if out.is_empty() {
                 vec![]
@@ -109,7 +253,119 @@ pub(crate) enum CmdLogEntry {
     Cmd {
         args: Cow<'static, str>,
         out: Option<Cow<'static, str>>,
+        /// The latest line a command that is still running wrote, like how far a fetch got
+        progress: Option<String>,
     },
     Error(String),
     Info(String),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn toast_message(min_duration: u64, success: bool, elapsed: Duration) -> Option<String> {
+        let mut config = Config::default();
+        config.general.command_toasts.enabled = true;
+        config.general.command_toasts.min_duration = min_duration;
+
+        let log = CmdLogEntry::Cmd {
+            args: "git push".into(),
+            out: Some("".into()),
+            progress: None,
+        };
+
+        match toast(&config, &log, success, elapsed)? {
+            CmdLogEntry::Info(msg) => Some(format!("> {}", msg)),
+            CmdLogEntry::Error(err) => Some(format!("! {}", err)),
+            CmdLogEntry::Cmd { .. } => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn toast_slow_commands() {
+        assert_eq!(toast_message(5, true, Duration::from_millis(4900)), None);
+        assert_eq!(
+            toast_message(5, true, Duration::from_millis(6300)),
+            Some("> 'git push' done in 6.3s".to_string())
+        );
+    }
+
+    #[test]
+    fn toast_failed_commands() {
+        assert_eq!(
+            toast_message(5, false, Duration::from_millis(300)),
+            Some("! 'git push' failed after 0.3s".to_string())
+        );
+    }
+
+    #[test]
+    fn no_toasts_when_disabled() {
+        let config = Config::default();
+        let log = CmdLogEntry::Cmd {
+            args: "git push".into(),
+            out: None,
+            progress: None,
+        };
+        assert!(toast(&config, &log, false, Duration::from_secs(10)).is_none());
+    }
+
+    #[test]
+    fn progress_is_the_latest_line() {
+        let log = RwLock::new(CmdLogEntry::Cmd {
+            args: "git fetch --progress".into(),
+            out: None,
+            progress: None,
+        });
+
+        let out = read_progress(
+            "Receiving objects:  50% (1/2)\rReceiving objects: 100% (2/2), done.\r\n".as_bytes(),
+            &log,
+        )
+        .unwrap();
+
+        assert_eq!(out.len(), 68);
+        let CmdLogEntry::Cmd { progress, .. } = &*log.read().unwrap() else {
+            unreachable!();
+        };
+        assert_eq!(
+            progress.as_deref(),
+            Some("Receiving objects: 100% (2/2), done.")
+        );
+    }
+
+    #[test]
+    fn strip_progress_lines() {
+        assert_eq!(
+            strip_progress(
+                "Enumerating objects: 1\rEnumerating objects: 3, done.\n\
+                 Delta compression using up to 8 threads\n\
+                 Total 3 (delta 0), reused 0 (delta 0), pack-reused 0\n\
+                 remote: Enumerating objects: 3, done.\n\
+                 remote: Total 3 (delta 0), reused 0 (delta 0), pack-reused 0\n\
+                 To ../remote\n\
+                 \x20  1111111..2222222  main -> main\n"
+            ),
+            "To ../remote\n   1111111..2222222  main -> main\n"
+        );
+    }
+
+    #[test]
+    fn notify_only_for_long_operations() {
+        assert!(is_long_operation("git push --progress origin main"));
+        assert!(is_long_operation("git rebase --continue"));
+        assert!(!is_long_operation("git add file"));
+        assert!(!is_long_operation("sh -c git fetch"));
+    }
+
+    #[test]
+    #[cfg(all(unix, not(target_os = "macos")))]
+    fn notification_with_notify_send() {
+        let cmd = notification_command("'git fetch' done").unwrap();
+        assert_eq!(cmd.get_program(), "notify-send");
+        assert_eq!(
+            cmd.get_args().collect::<Vec<_>>(),
+            ["--app-name=gitu", "gitu", "'git fetch' done"]
+        );
+    }
+}
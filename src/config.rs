@@ -16,12 +16,46 @@ pub(crate) struct Config {
     pub general: GeneralConfig,
     pub style: StyleConfig,
     pub bindings: BTreeMap<Menu, BTreeMap<Op, Vec<String>>>,
+    #[serde(default)]
+    pub commands: BTreeMap<String, CommandConfig>,
+    #[serde(default)]
+    pub forge: ForgeConfig,
+    #[serde(default)]
+    pub gerrit: GerritConfig,
+    #[serde(default)]
+    pub format: FormatConfig,
+    #[serde(default)]
+    pub conventional_commits: ConventionalCommitsConfig,
 }
 
 #[derive(Default, Debug, Deserialize)]
 pub struct GeneralConfig {
     pub always_show_help: BoolConfigEntry,
     pub confirm_quit: BoolConfigEntry,
+    pub confirm_push: BoolConfigEntry,
+    pub wip_snapshots: BoolConfigEntry,
+    pub sign_commits: BoolConfigEntry,
+    pub auto_fetch: AutoFetchConfig,
+    pub command_toasts: CommandToastsConfig,
+    pub desktop_notifications: BoolConfigEntry,
+    pub upstream_commits: BoolConfigEntry,
+    pub recent_commits: RecentCommitsConfig,
+    pub log_page_size: usize,
+    pub edit_in_gitu: BoolConfigEntry,
+    pub side_by_side_diff: BoolConfigEntry,
+    pub diff_context_lines: usize,
+    #[serde(default)]
+    pub collapsed_sections: Vec<String>,
+    #[serde(default)]
+    pub collapse_diffs_larger_than: Option<usize>,
+    #[serde(default)]
+    pub default_expansion_level: Option<usize>,
+    #[serde(default)]
+    pub tag_sort: Option<String>,
+    #[serde(default)]
+    pub file_viewer: Option<String>,
+    #[serde(default)]
+    pub browser: Option<String>,
 }
 
 #[derive(Default, Debug, Deserialize)]
@@ -30,6 +64,95 @@ pub struct BoolConfigEntry {
     pub enabled: bool,
 }
 
+/// A shell command bound to keys as `"command:<name>"`, see `[commands]` in the default config.
+#[derive(Debug, Deserialize)]
+pub struct CommandConfig {
+    pub run: String,
+    /// Hand over the terminal instead of logging the output
+    #[serde(default)]
+    pub interactive: bool,
+}
+
+/// Access to the REST API of the forge that `origin` is hosted on, for listing pull requests.
+#[derive(Default, Debug, Deserialize)]
+pub struct ForgeConfig {
+    /// Needed for self-hosted forges with an API at a different host or path
+    #[serde(default)]
+    pub api_url: Option<String>,
+    /// Falls back to `$GITHUB_TOKEN`
+    #[serde(default)]
+    pub github_token: Option<String>,
+    /// Falls back to `$GITLAB_TOKEN`
+    #[serde(default)]
+    pub gitlab_token: Option<String>,
+}
+
+/// Pushing changes for review to `refs/for/<branch>` of a Gerrit remote.
+#[derive(Default, Debug, Deserialize)]
+pub struct GerritConfig {
+    /// The remote to push to, instead of the upstream's remote
+    #[serde(default)]
+    pub remote: Option<String>,
+    /// Added as `r=<reviewer>` push options
+    #[serde(default)]
+    pub reviewers: Vec<String>,
+}
+
+/// Choices offered when assembling a `<type>(<scope>): <subject>` commit message.
+/// Repositories can have their own with the `gitu.conventionalTypes` / `gitu.conventionalScopes` git config.
+#[derive(Default, Debug, Deserialize)]
+pub struct ConventionalCommitsConfig {
+    #[serde(default)]
+    pub types: Vec<String>,
+    #[serde(default)]
+    pub scopes: Vec<String>,
+}
+
+/// Templates for rows of the log, branch and stash lists, see `[format]` in the default config.
+#[derive(Default, Debug, Deserialize)]
+pub struct FormatConfig {
+    #[serde(default)]
+    pub log: String,
+    #[serde(default)]
+    pub branch: String,
+    #[serde(default)]
+    pub stash: String,
+}
+
+#[derive(Default, Debug, Deserialize)]
+pub struct AutoFetchConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Seconds between fetches, 0 disables fetching
+    #[serde(default)]
+    pub interval: u64,
+    /// Remotes to fetch, all of them if empty
+    #[serde(default)]
+    pub remotes: Vec<String>,
+    /// Remove remote-tracking branches that no longer exist on the remote
+    #[serde(default)]
+    pub prune: bool,
+}
+
+#[derive(Default, Debug, Deserialize)]
+pub struct RecentCommitsConfig {
+    /// Shown at first, "Show more" shows more while running
+    #[serde(default)]
+    pub count: usize,
+    /// Also show the commits of local branches that aren't merged into HEAD
+    #[serde(default)]
+    pub unmerged_branches: bool,
+}
+
+#[derive(Default, Debug, Deserialize)]
+pub struct CommandToastsConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Seconds a successful command must take to be reported, failures always are
+    #[serde(default)]
+    pub min_duration: u64,
+}
+
 #[derive(Default, Debug, Deserialize)]
 pub struct StyleConfig {
     pub section_header: StyleConfigEntry,
@@ -51,6 +174,17 @@ pub struct StyleConfig {
     pub branch: StyleConfigEntry,
     pub remote: StyleConfigEntry,
     pub tag: StyleConfigEntry,
+    pub head: StyleConfigEntry,
+    pub history_rewrite: StyleConfigEntry,
+
+    pub signature_good: StyleConfigEntry,
+    pub signature_bad: StyleConfigEntry,
+    pub signature_untrusted: StyleConfigEntry,
+
+    #[serde(default)]
+    pub log_marks: LogMarksConfig,
+
+    pub search_match: StyleConfigEntry,
 
     pub command: StyleConfigEntry,
     pub active_arg: StyleConfigEntry,
@@ -71,6 +205,20 @@ pub struct DiffHighlightConfig {
     pub changed_old: StyleConfigEntry,
     #[serde(default)]
     pub changed_new: StyleConfigEntry,
+    #[serde(default)]
+    pub whitespace_error: StyleConfigEntry,
+}
+
+#[derive(Default, Debug, Deserialize)]
+pub struct LogMarksConfig {
+    #[serde(default)]
+    pub head: StyleConfigEntry,
+    #[serde(default)]
+    pub upstream: StyleConfigEntry,
+    #[serde(default)]
+    pub merge_base: StyleConfigEntry,
+    #[serde(default)]
+    pub local: StyleConfigEntry,
 }
 
 #[derive(Default, Debug, Deserialize)]
@@ -194,12 +342,20 @@ pub fn config_path() -> PathBuf {
         .join("gitu/config.toml")
 }
 
-#[cfg(test)]
-pub(crate) fn init_test_config() -> Res<Config> {
-    let mut config: Config = Figment::new()
+/// The defaults with `toml` merged over them, ignoring the user's config file.
+#[cfg(any(test, feature = "test-backend"))]
+pub(crate) fn init_config_from(toml: &str) -> Res<Config> {
+    let config = Figment::new()
         .merge(Toml::string(DEFAULT_CONFIG))
+        .merge(Toml::string(toml))
         .extract()?;
 
+    Ok(config)
+}
+
+#[cfg(test)]
+pub(crate) fn init_test_config() -> Res<Config> {
+    let mut config = init_config_from("")?;
     config.general.always_show_help.enabled = false;
     Ok(config)
 }
@@ -213,6 +369,7 @@ mod tests {
     use ratatui::style::Color;
 
     use super::{Config, DEFAULT_CONFIG};
+    use crate::{menu::Menu, ops::Op};
 
     #[test]
     fn config_merges() {
@@ -230,4 +387,26 @@ mod tests {
         assert_eq!(config.style.hunk_header.bg, Some(Color::LightGreen));
         assert_eq!(config.style.hunk_header.fg, Some(Color::Blue));
     }
+
+    #[test]
+    fn command_bindings() {
+        let config: Config = Figment::new()
+            .merge(Toml::string(DEFAULT_CONFIG))
+            .merge(Toml::string(
+                r#"
+                [commands]
+                stat = { run = "git show --stat %(commit)" }
+
+                [bindings]
+                root."command:stat" = ["!s"]
+                "#,
+            ))
+            .extract()
+            .unwrap();
+
+        assert_eq!(config.commands["stat"].run, "git show --stat %(commit)");
+        let root = &config.bindings[&Menu::Root];
+        assert_eq!(root[&Op::RunCommand("stat".into())], vec!["!s"]);
+        assert!(config.bindings[&Menu::Log].contains_key(&Op::ToggleArg("--grep".into())));
+    }
 }
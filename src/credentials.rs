@@ -0,0 +1,170 @@
+use crate::{cmd_log::CmdLogEntry, prompt::PromptData, state::State, term::Term, Res};
+use std::{process::Command, rc::Rc};
+use tui_prompts::State as _;
+
+/// Answers git's credential requests with what was typed into gitu's prompts,
+/// passed on through the environment rather than the command line.
+const CREDENTIAL_HELPER: &str = "!f() { test \"$1\" = get || return 0; \
+    test -z \"$GITU_USERNAME\" || echo \"username=$GITU_USERNAME\"; \
+    echo \"password=$GITU_PASSWORD\"; }; f";
+
+/// Makes git fail rather than ask for credentials on the terminal, which the TUI is drawn on.
+pub(crate) fn disable_terminal_prompt(cmd: &mut Command) {
+    cmd.env("GIT_TERMINAL_PROMPT", "0");
+}
+
+/// The URL that git needed credentials for, if that's why the command failed.
+pub(crate) fn needed_for(log: &CmdLogEntry) -> Option<String> {
+    let CmdLogEntry::Cmd { out: Some(out), .. } = log else {
+        return None;
+    };
+
+    parse_url(out)
+}
+
+/// Parses `fatal: could not read Username for 'https://host': terminal prompts disabled`.
+fn parse_url(output: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let (_, rest) = line.split_once("could not read ")?;
+        let (_, url) = rest.split_once(" for '")?;
+        let (url, _) = url.split_once('\'')?;
+        Some(url.to_string())
+    })
+}
+
+/// Asks for a username (unless the URL has one) and password, then runs `cmd` again with them.
+pub(crate) fn prompt_and_retry(state: &mut State, url: String, cmd: Command, input: Vec<u8>) {
+    let mut retry = Some((cmd, input));
+
+    if has_username(&url) {
+        ask_password(state, url, String::new(), retry);
+        return;
+    }
+
+    ask(
+        state,
+        format!("Username for {}:", url),
+        false,
+        move |state, _term, username| {
+            ask_password(state, url.clone(), username, retry.take());
+            Ok(())
+        },
+    );
+}
+
+fn ask_password(
+    state: &mut State,
+    url: String,
+    username: String,
+    mut retry: Option<(Command, Vec<u8>)>,
+) {
+    ask(
+        state,
+        format!("Password for {}:", url),
+        true,
+        move |state, term, password| {
+            let Some((cmd, input)) = retry.take() else {
+                return Ok(());
+            };
+
+            state.run_cmd_async(term, &input, with_credentials(&cmd, &username, &password))
+        },
+    );
+}
+
+fn ask(
+    state: &mut State,
+    prompt_text: String,
+    hide_input: bool,
+    mut on_done: impl FnMut(&mut State, &mut Term, String) -> Res<()> + 'static,
+) {
+    state.prompt.set(PromptData {
+        prompt_text: prompt_text.into(),
+        update_fn: Rc::new(move |state: &mut State, term: &mut Term| {
+            if state.prompt.state.status().is_done() {
+                let input = state.prompt.state.value().to_string();
+                state.prompt.reset(term)?;
+                on_done(state, term, input)?;
+            }
+            Ok(())
+        }),
+    });
+    state.prompt.hide_input = hide_input;
+    state.prompt.on_abort = Some(Rc::new(cancel_queued_cmds));
+}
+
+/// Queued commands waited for the retry, which won't happen without credentials.
+fn cancel_queued_cmds(state: &mut State, _term: &mut Term) -> Res<()> {
+    if !state.queued_cmds.is_empty() {
+        let cancelled = state.queued_cmds.len();
+        state.queued_cmds.clear();
+        state.display_error(format!("Cancelled {} queued command(s)", cancelled));
+    }
+    Ok(())
+}
+
+fn has_username(url: &str) -> bool {
+    let without_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+    without_scheme
+        .split('/')
+        .next()
+        .is_some_and(|host| host.contains('@'))
+}
+
+/// A copy of `cmd`, with the credentials made available to git.
+fn with_credentials(cmd: &Command, username: &str, password: &str) -> Command {
+    let mut retry = copy(cmd);
+    retry.env("GIT_CONFIG_COUNT", "1");
+    retry.env("GIT_CONFIG_KEY_0", "credential.helper");
+    retry.env("GIT_CONFIG_VALUE_0", CREDENTIAL_HELPER);
+    retry.env("GITU_USERNAME", username);
+    retry.env("GITU_PASSWORD", password);
+    retry
+}
+
+/// `Command` can't be cloned, this builds an equivalent one.
+pub(crate) fn copy(cmd: &Command) -> Command {
+    let mut copy = Command::new(cmd.get_program());
+    copy.args(cmd.get_args());
+
+    for (key, value) in cmd.get_envs() {
+        match value {
+            Some(value) => copy.env(key, value),
+            None => copy.env_remove(key),
+        };
+    }
+
+    if let Some(dir) = cmd.get_current_dir() {
+        copy.current_dir(dir);
+    }
+
+    copy
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_credentials_needed() {
+        assert_eq!(
+            parse_url(
+                "fatal: could not read Username for 'https://github.com': terminal prompts disabled\n"
+            ),
+            Some("https://github.com".to_string())
+        );
+        assert_eq!(
+            parse_url(
+                "fatal: could not read Password for 'https://me@example.com': terminal prompts disabled"
+            ),
+            Some("https://me@example.com".to_string())
+        );
+        assert_eq!(parse_url("fatal: repository not found"), None);
+    }
+
+    #[test]
+    fn url_with_username() {
+        assert!(has_username("https://me@example.com"));
+        assert!(!has_username("https://example.com/a@b"));
+    }
+}
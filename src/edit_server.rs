@@ -0,0 +1,306 @@
+use crate::{
+    git::rebase_todo::RebaseTodo,
+    menu::{Menu, PendingMenu},
+    prompt::PromptData,
+    screen,
+    state::State,
+    term::Term,
+    Res,
+};
+use std::{
+    cell::RefCell,
+    env, fs,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    rc::Rc,
+    thread,
+    time::{Duration, Instant},
+};
+use tui_prompts::State as _;
+
+/// Where `gitu --edit` finds the gitu that ran the git command it was started by.
+const DIR_VAR: &str = "GITU_EDIT_DIR";
+const REQUEST: &str = "request";
+const RESPONSE: &str = "response";
+const DONE: &str = "done";
+const ABORTED: &str = "aborted";
+/// Followed by the pid of the gitu serving the directory.
+const DIR_PREFIX: &str = "gitu-edit-";
+const OWNER_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A directory of this gitu's own in `.git`, for requests to edit files and their responses.
+pub(crate) fn dir(repo: &git2::Repository) -> PathBuf {
+    repo.path()
+        .join(format!("{}{}", DIR_PREFIX, std::process::id()))
+}
+
+/// Has git open the todos and messages of `cmd` in gitu rather than in an editor,
+/// which couldn't share the terminal with gitu while it runs in the background.
+pub(crate) fn serve(cmd: &mut Command, dir: &Path) -> Res<()> {
+    fs::create_dir_all(dir)?;
+    let editor = editor_cmd()?;
+    cmd.env("GIT_EDITOR", &editor);
+    cmd.env("GIT_SEQUENCE_EDITOR", &editor);
+    cmd.env(DIR_VAR, dir);
+    Ok(())
+}
+
+#[cfg(not(test))]
+fn editor_cmd() -> Res<String> {
+    let exe = env::current_exe()?;
+    let quoted = exe.to_string_lossy().replace('\'', r"'\''");
+    Ok(format!("'{}' --edit", quoted))
+}
+
+/// The test binary can't stand in for gitu, the same requests are made from the shell instead.
+#[cfg(test)]
+fn editor_cmd() -> Res<String> {
+    Ok(format!(
+        "f() {{ d=\"${dir}\"; realpath \"$1\" > \"$d/{request}.tmp\" && mv \"$d/{request}.tmp\" \"$d/{request}\"; \
+        until test -e \"$d/{response}\"; do sleep 0.05; done; \
+        r=$(cat \"$d/{response}\"); rm \"$d/{response}\"; test \"$r\" = {done}; }}; f",
+        dir = DIR_VAR,
+        request = REQUEST,
+        response = RESPONSE,
+        done = DONE,
+    ))
+}
+
+/// What `gitu --edit <file>` does: hands the file to the gitu that ran git and waits until
+/// it's been edited there. Fails if it was aborted, so that git gives up too.
+pub(crate) fn edit(file: &Path) -> Res<()> {
+    let dir = PathBuf::from(
+        env::var_os(DIR_VAR).ok_or("gitu --edit is meant to be run by git, for a running gitu")?,
+    );
+    edit_in(&dir, file)
+}
+
+fn edit_in(dir: &Path, file: &Path) -> Res<()> {
+    let file = fs::canonicalize(file)?;
+
+    let pending = dir.join(format!("{}.tmp", REQUEST));
+    fs::write(&pending, file.to_string_lossy().as_bytes())?;
+    fs::rename(pending, dir.join(REQUEST))?;
+
+    let response = dir.join(RESPONSE);
+    let mut owner_checked: Option<Instant> = None;
+    loop {
+        if let Ok(answer) = fs::read_to_string(&response) {
+            fs::remove_file(&response)?;
+            return if answer == DONE {
+                Ok(())
+            } else {
+                Err(format!("Editing {} was aborted", file.display()).into())
+            };
+        }
+
+        if !dir.exists() {
+            return Err("gitu stopped before the edit was done".into());
+        }
+
+        // A gitu that crashed or was killed leaves its directory behind, don't hold git up forever
+        if owner_checked.is_none_or(|checked| checked.elapsed() >= OWNER_CHECK_INTERVAL) {
+            if !owner_running(dir) {
+                let _ = fs::remove_dir_all(dir);
+                return Err("gitu exited before the edit was done".into());
+            }
+            owner_checked = Some(Instant::now());
+        }
+
+        thread::sleep(Duration::from_millis(50));
+    }
+}
+
+/// Whether the gitu that `dir` belongs to is still running. Taken to be if that can't be told.
+fn owner_running(dir: &Path) -> bool {
+    let Some(pid) = dir
+        .file_name()
+        .and_then(|name| name.to_str())
+        .and_then(|name| name.strip_prefix(DIR_PREFIX))
+        .and_then(|pid| pid.parse::<u32>().ok())
+    else {
+        return true;
+    };
+
+    process_running(pid)
+}
+
+#[cfg(unix)]
+fn process_running(pid: u32) -> bool {
+    Command::new("kill")
+        .args(["-0", &pid.to_string()])
+        .stderr(Stdio::null())
+        .status()
+        .map_or(true, |status| status.success())
+}
+
+#[cfg(windows)]
+fn process_running(pid: u32) -> bool {
+    Command::new("tasklist")
+        .args(["/FI", &format!("PID eq {}", pid), "/NH"])
+        .stderr(Stdio::null())
+        .output()
+        .map_or(true, |out| {
+            String::from_utf8_lossy(&out.stdout).contains(&pid.to_string())
+        })
+}
+
+#[cfg(not(any(unix, windows)))]
+fn process_running(_pid: u32) -> bool {
+    true
+}
+
+/// The file git is waiting to have edited, if it asked since the last time.
+pub(crate) fn take_request(dir: &Path) -> Res<Option<PathBuf>> {
+    let request = dir.join(REQUEST);
+    let Ok(file) = fs::read_to_string(&request) else {
+        return Ok(None);
+    };
+
+    fs::remove_file(request)?;
+    Ok(Some(PathBuf::from(file.trim_end())))
+}
+
+/// Lets git go on, or abort if `done` isn't set.
+pub(crate) fn respond(dir: &Path, done: bool) -> Res<()> {
+    let pending = dir.join(format!("{}.tmp", RESPONSE));
+    fs::write(&pending, if done { DONE } else { ABORTED })?;
+    fs::rename(pending, dir.join(RESPONSE))?;
+    Ok(())
+}
+
+/// Opens a rebase todo on the todo screen, anything else is taken for a message to edit
+/// in the prompt.
+pub(crate) fn open(state: &mut State, file: PathBuf) -> Res<()> {
+    let content = fs::read_to_string(&file)?;
+
+    if file
+        .file_name()
+        .is_some_and(|name| name == "git-rebase-todo")
+    {
+        let Some(todo) = RebaseTodo::parse(&content, file) else {
+            state.finish_edit(false)?;
+            return Err("The rebase todo has commands gitu can't edit, aborted".into());
+        };
+
+        let size = state.screens.last().unwrap().size;
+        state.screens.push(screen::rebase_todo::create(
+            Rc::clone(&state.config),
+            Rc::new(RefCell::new(todo)),
+            size,
+        )?);
+        state.pending_menu = Some(PendingMenu::init(Menu::RebaseTodo));
+        return Ok(());
+    }
+
+    edit_message(state, file, &content);
+    Ok(())
+}
+
+/// Has the subject of the message edited, keeping its body and comments as they are.
+fn edit_message(state: &mut State, file: PathBuf, content: &str) {
+    let mut lines = content.lines().map(String::from).collect::<Vec<_>>();
+    let subject_line = lines.iter().position(|line| !line.starts_with('#'));
+    let subject = subject_line.map_or(String::new(), |i| lines[i].clone());
+
+    state.prompt.set(PromptData {
+        prompt_text: format!(
+            "Message for {} (empty to abort):",
+            file.file_name().unwrap_or_default().to_string_lossy()
+        )
+        .into(),
+        update_fn: Rc::new(move |state: &mut State, term: &mut Term| {
+            if state.prompt.state.status().is_done() {
+                let input = state.prompt.state.value().to_string();
+                state.prompt.reset(term)?;
+
+                // Git gives up on an empty message, like it would when left empty in an editor
+                let message = if input.trim().is_empty() {
+                    String::new()
+                } else {
+                    match subject_line {
+                        Some(i) => lines[i] = input,
+                        None => lines.insert(0, input),
+                    }
+                    lines.iter().map(|line| format!("{}\n", line)).collect()
+                };
+
+                fs::write(&file, message)?;
+                state.finish_edit(true)?;
+            }
+            Ok(())
+        }),
+    });
+    state.prompt.on_abort = Some(Rc::new(|state: &mut State, _term: &mut Term| {
+        state.finish_edit(false)
+    }));
+
+    *state.prompt.state.value_mut() = subject;
+    state.prompt.state.move_end();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use temp_dir::TempDir;
+
+    /// A directory served by this process, with a file to edit.
+    fn setup() -> (TempDir, PathBuf, PathBuf) {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.child(format!("{}{}", DIR_PREFIX, std::process::id()));
+        fs::create_dir(&dir).unwrap();
+        let file = tmp.child("COMMIT_EDITMSG");
+        fs::write(&file, "").unwrap();
+        (tmp, dir, file)
+    }
+
+    fn edit_in_background(dir: &Path, file: &Path) -> thread::JoinHandle<Result<(), String>> {
+        let (dir, file) = (dir.to_path_buf(), file.to_path_buf());
+        thread::spawn(move || edit_in(&dir, &file).map_err(|err| err.to_string()))
+    }
+
+    fn await_request(dir: &Path) -> PathBuf {
+        loop {
+            if let Some(file) = take_request(dir).unwrap() {
+                return file;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn edit_done() {
+        let (_tmp, dir, file) = setup();
+        let editing = edit_in_background(&dir, &file);
+
+        assert_eq!(await_request(&dir), fs::canonicalize(&file).unwrap());
+        assert!(take_request(&dir).unwrap().is_none());
+        respond(&dir, true).unwrap();
+
+        assert!(editing.join().unwrap().is_ok());
+        assert!(!dir.join(RESPONSE).exists());
+    }
+
+    #[test]
+    fn edit_aborted() {
+        let (_tmp, dir, file) = setup();
+        let editing = edit_in_background(&dir, &file);
+
+        await_request(&dir);
+        respond(&dir, false).unwrap();
+
+        assert!(editing.join().unwrap().is_err());
+    }
+
+    #[test]
+    fn edit_after_gitu_exited() {
+        let (tmp, _dir, file) = setup();
+        let mut exited = Command::new("git").arg("--version").spawn().unwrap();
+        exited.wait().unwrap();
+        let dir = tmp.child(format!("{}{}", DIR_PREFIX, exited.id()));
+        fs::create_dir(&dir).unwrap();
+
+        assert!(edit_in(&dir, &file).is_err());
+        assert!(!dir.exists());
+    }
+}
@@ -0,0 +1,118 @@
+use crate::{cmd_log::CmdLogEntry, git, ops::Op};
+use git2::Repository;
+
+/// A common way for git commands to fail, recognized from their output so that gitu can
+/// tell what to do about it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum GitError {
+    /// A push rejected because the remote has commits that the branch doesn't.
+    NonFastForward,
+    /// A push or pull while no branch is checked out.
+    DetachedHead,
+    /// A commit, pull or checkout in the middle of resolving conflicts.
+    UnmergedPaths,
+    /// A push or pull of a branch without an upstream.
+    NoUpstream,
+}
+
+impl GitError {
+    /// The failure the output of a command shows, if it's one gitu knows.
+    pub(crate) fn from_log(log: &CmdLogEntry) -> Option<Self> {
+        let CmdLogEntry::Cmd { out: Some(out), .. } = log else {
+            return None;
+        };
+
+        parse(out)
+    }
+
+    pub(crate) fn hint(self) -> &'static str {
+        match self {
+            GitError::NonFastForward => {
+                "The remote has commits this branch doesn't, pull them first or fetch to see them"
+            }
+            GitError::DetachedHead => "No branch is checked out, create one or check one out",
+            GitError::UnmergedPaths => {
+                "There are conflicts to resolve, stage the resolved files or abort"
+            }
+            GitError::NoUpstream => "The branch has no upstream, push it to set one",
+        }
+    }
+
+    /// The ops that would get past the failure, offered in the follow-up menu.
+    pub(crate) fn follow_ups(self, repo: &Repository) -> Vec<Op> {
+        match self {
+            GitError::NonFastForward => vec![Op::Pull, Op::FetchAll],
+            GitError::DetachedHead => vec![Op::CheckoutNewBranch, Op::Checkout],
+            GitError::UnmergedPaths => {
+                if git::rebase_status(repo).is_ok_and(|status| status.is_some()) {
+                    vec![Op::RebaseContinue, Op::RebaseAbort]
+                } else if git::merge_status(repo).is_ok_and(|status| status.is_some()) {
+                    vec![Op::MergeContinue, Op::MergeAbort]
+                } else if git::revert_status(repo).is_ok_and(|status| status.is_some()) {
+                    vec![Op::RevertContinue, Op::RevertAbort]
+                } else if git::cherry_pick_status(repo).is_ok_and(|status| status.is_some()) {
+                    vec![Op::CherryPickContinue, Op::CherryPickAbort]
+                } else {
+                    vec![]
+                }
+            }
+            GitError::NoUpstream => vec![Op::PushSetUpstream, Op::PushElsewhere],
+        }
+    }
+}
+
+fn parse(output: &str) -> Option<GitError> {
+    output.lines().find_map(|line| {
+        if line.contains("[rejected]")
+            && (line.contains("non-fast-forward") || line.contains("fetch first"))
+        {
+            Some(GitError::NonFastForward)
+        } else if line.contains("You are not currently on a branch") {
+            Some(GitError::DetachedHead)
+        } else if line.contains("you have unmerged files")
+            || line.contains("resolve your current index first")
+        {
+            Some(GitError::UnmergedPaths)
+        } else if line.contains("has no upstream branch")
+            || line.contains("There is no tracking information for the current branch")
+        {
+            Some(GitError::NoUpstream)
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_git_errors() {
+        assert_eq!(
+            parse(
+                "To ../remote\n \
+                 ! [rejected]        main -> main (fetch first)\n\
+                 error: failed to push some refs to '../remote'\n"
+            ),
+            Some(GitError::NonFastForward)
+        );
+        assert_eq!(
+            parse(" ! [rejected]        main -> main (non-fast-forward)"),
+            Some(GitError::NonFastForward)
+        );
+        assert_eq!(
+            parse("fatal: You are not currently on a branch.\nTo push the history..."),
+            Some(GitError::DetachedHead)
+        );
+        assert_eq!(
+            parse("error: Committing is not possible because you have unmerged files."),
+            Some(GitError::UnmergedPaths)
+        );
+        assert_eq!(
+            parse("fatal: The current branch feature has no upstream branch."),
+            Some(GitError::NoUpstream)
+        );
+        assert_eq!(parse("fatal: repository not found"), None);
+    }
+}
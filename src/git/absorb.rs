@@ -0,0 +1,15 @@
+use super::diff::Diff;
+
+/// Staged hunks grouped by the commit they should be absorbed into.
+pub(crate) struct Absorb {
+    pub fixups: Vec<AbsorbFixup>,
+    /// Hunks that don't belong to a single recent commit, these stay staged.
+    pub unabsorbed: Diff,
+}
+
+pub(crate) struct AbsorbFixup {
+    pub commit: String,
+    pub hash: String,
+    pub summary: String,
+    pub diff: Diff,
+}
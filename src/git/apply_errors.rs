@@ -0,0 +1,33 @@
+use itertools::Itertools;
+
+/// Parses the files named by the errors of `git apply`, like `error: patch failed: <file>:<line>`
+/// or `error: <file>: already exists in working directory`.
+pub(crate) fn parse(stderr: &str) -> Vec<String> {
+    stderr
+        .lines()
+        .filter_map(|line| line.strip_prefix("error: "))
+        .filter_map(|error| match error.strip_prefix("patch failed: ") {
+            Some(location) => location.rsplit_once(':').map(|(file, _line)| file),
+            None => error.split_once(": ").map(|(file, _reason)| file),
+        })
+        .unique()
+        .map(String::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_apply_check() {
+        let files = parse(
+            "error: patch failed: src/main.rs:12\n\
+             error: src/main.rs: patch does not apply\n\
+             error: new-file: already exists in working directory\n\
+             error: gone: does not exist in index\n",
+        );
+
+        assert_eq!(files, vec!["src/main.rs", "new-file", "gone"]);
+    }
+}
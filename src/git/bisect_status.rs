@@ -0,0 +1,11 @@
+pub(crate) struct BisectStatus {
+    pub candidate: String,
+    pub first_bad: Option<String>,
+    pub remaining: Option<BisectRemaining>,
+    pub log: Vec<String>,
+}
+
+pub(crate) struct BisectRemaining {
+    pub revisions: usize,
+    pub steps: usize,
+}
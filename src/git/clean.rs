@@ -0,0 +1,23 @@
+use std::ffi::OsString;
+
+#[derive(Debug, Clone)]
+pub(crate) struct CleanEntry {
+    pub path: String,
+    pub selected: bool,
+}
+
+/// The untracked files `git clean` would remove, picked from within Gitu before anything is deleted.
+#[derive(Debug)]
+pub(crate) struct Clean {
+    pub args: Vec<OsString>,
+    pub entries: Vec<CleanEntry>,
+}
+
+impl Clean {
+    pub(crate) fn selected_paths(&self) -> impl Iterator<Item = &str> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.selected)
+            .map(|entry| entry.path.as_str())
+    }
+}
@@ -1,5 +1,8 @@
+use super::signature::SignatureStatus;
+
 #[derive(Debug)]
 pub(crate) struct Commit {
     pub hash: String,
     pub details: String,
+    pub signature: SignatureStatus,
 }
@@ -0,0 +1,37 @@
+use std::ffi::OsString;
+
+#[derive(Debug, Clone)]
+pub(crate) struct PickerCommit {
+    pub commit: String,
+    pub hash: String,
+    pub summary: String,
+    pub touches_staged: bool,
+    /// Whether blame attributes some of the staged hunks to this commit, like absorb does.
+    pub suggested: bool,
+}
+
+/// Recent commits to pick the target of a `git commit --fixup` or `--squash` from.
+#[derive(Debug)]
+pub(crate) struct CommitPicker {
+    pub flag: &'static str,
+    pub args: Vec<OsString>,
+    pub commits: Vec<PickerCommit>,
+    pub filter: String,
+    /// Whether to list commits that don't touch any of the staged files.
+    pub show_all: bool,
+    /// Whether the fixup is squashed into the picked commit right away.
+    pub instant: bool,
+}
+
+impl CommitPicker {
+    /// The commits to list, matching the filter against the summary or hash.
+    pub(crate) fn visible(&self) -> impl Iterator<Item = &PickerCommit> {
+        let filter = self.filter.to_lowercase();
+
+        self.commits.iter().filter(move |commit| {
+            (self.show_all || commit.touches_staged)
+                && (commit.summary.to_lowercase().contains(&filter)
+                    || commit.commit.starts_with(&filter))
+        })
+    }
+}
@@ -0,0 +1,23 @@
+use git2::Oid;
+
+/// How two branches diverged, each side with what the other doesn't have.
+pub(crate) struct Compare {
+    pub merge_base: Oid,
+    pub ours: CompareSide,
+    pub theirs: CompareSide,
+}
+
+pub(crate) struct CompareSide {
+    pub name: String,
+    pub id: Oid,
+    /// Commits that aren't on the other side
+    pub count: usize,
+    /// What changed since the merge base, like `git diff --stat`
+    pub stats: Vec<FileStat>,
+}
+
+pub(crate) struct FileStat {
+    pub path: String,
+    pub insertions: usize,
+    pub deletions: usize,
+}
@@ -0,0 +1,167 @@
+/// One `<<<<<<<` ... `>>>>>>>` region of a conflicted file.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Conflict {
+    pub ours_label: String,
+    pub ours: Vec<String>,
+    /// Only present with `merge.conflictStyle` set to `diff3` or `zdiff3`.
+    pub base: Option<(String, Vec<String>)>,
+    pub theirs_label: String,
+    pub theirs: Vec<String>,
+    /// Line numbers (starting at 1) of the opening and closing markers.
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Side {
+    Ours,
+    Theirs,
+}
+
+enum Part {
+    Ours,
+    Base,
+    Theirs,
+}
+
+/// Parses the conflict markers of a file's content.
+pub(crate) fn parse(content: &str) -> Vec<Conflict> {
+    let mut conflicts = vec![];
+    let mut current: Option<(Conflict, Part)> = None;
+
+    for (i, line) in content.lines().enumerate() {
+        if let Some(label) = marker(line, '<') {
+            current = Some((
+                Conflict {
+                    ours_label: label.to_string(),
+                    ours: vec![],
+                    base: None,
+                    theirs_label: String::new(),
+                    theirs: vec![],
+                    start_line: i + 1,
+                    end_line: i + 1,
+                },
+                Part::Ours,
+            ));
+            continue;
+        }
+
+        let Some((conflict, part)) = current.as_mut() else {
+            continue;
+        };
+
+        if let (Some(label), Part::Ours) = (marker(line, '|'), &part) {
+            conflict.base = Some((label.to_string(), vec![]));
+            *part = Part::Base;
+        } else if let (Some(""), Part::Ours | Part::Base) = (marker(line, '='), &part) {
+            *part = Part::Theirs;
+        } else if let (Some(label), Part::Theirs) = (marker(line, '>'), &part) {
+            conflict.theirs_label = label.to_string();
+            conflict.end_line = i + 1;
+            conflicts.push(current.take().unwrap().0);
+        } else {
+            match part {
+                Part::Ours => conflict.ours.push(line.to_string()),
+                Part::Base => conflict.base.as_mut().unwrap().1.push(line.to_string()),
+                Part::Theirs => conflict.theirs.push(line.to_string()),
+            }
+        }
+    }
+
+    conflicts
+}
+
+/// Replaces the `index`th conflict of `content` with one side of it.
+/// The kept lines are copied as they are, line endings included.
+pub(crate) fn resolve(content: &str, index: usize, side: Side) -> Option<String> {
+    let conflict = parse(content).into_iter().nth(index)?;
+    // Indices of lines, counting from 0, between the markers
+    let kept = match side {
+        Side::Ours => conflict.start_line..conflict.start_line + conflict.ours.len(),
+        Side::Theirs => conflict.end_line - 1 - conflict.theirs.len()..conflict.end_line - 1,
+    };
+    let replaced = conflict.start_line - 1..conflict.end_line;
+
+    Some(
+        content
+            .split_inclusive('\n')
+            .enumerate()
+            .filter(|(i, _)| !replaced.contains(i) || kept.contains(i))
+            .map(|(_, line)| line)
+            .collect(),
+    )
+}
+
+/// A marker line like `<<<<<<< HEAD`, returning its label.
+fn marker(line: &str, c: char) -> Option<&str> {
+    let rest = line.strip_prefix(&c.to_string().repeat(7))?;
+    if rest.is_empty() {
+        Some("")
+    } else {
+        rest.strip_prefix(' ')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MERGE: &str = "\
+before
+<<<<<<< HEAD
+hi
+=======
+hey
+>>>>>>> other-branch
+between
+<<<<<<< HEAD
+one
+||||||| base
+zero
+=======
+two
+>>>>>>> other-branch
+after
+";
+
+    #[test]
+    fn parse_conflicts() {
+        let conflicts = parse(MERGE);
+        assert_eq!(conflicts.len(), 2);
+        assert_eq!(conflicts[0].ours_label, "HEAD");
+        assert_eq!(conflicts[0].ours, vec!["hi"]);
+        assert_eq!(conflicts[0].base, None);
+        assert_eq!(conflicts[0].theirs, vec!["hey"]);
+        assert_eq!(conflicts[0].theirs_label, "other-branch");
+        assert_eq!((conflicts[0].start_line, conflicts[0].end_line), (2, 6));
+        assert_eq!(
+            conflicts[1].base,
+            Some(("base".to_string(), vec!["zero".to_string()]))
+        );
+        assert_eq!((conflicts[1].start_line, conflicts[1].end_line), (8, 14));
+    }
+
+    #[test]
+    fn resolve_conflicts() {
+        assert_eq!(
+            resolve(MERGE, 1, Side::Theirs).unwrap(),
+            "before\n<<<<<<< HEAD\nhi\n=======\nhey\n>>>>>>> other-branch\nbetween\ntwo\nafter\n"
+        );
+        assert_eq!(
+            resolve(MERGE, 0, Side::Ours).unwrap(),
+            "before\nhi\nbetween\n<<<<<<< HEAD\none\n||||||| base\nzero\n=======\ntwo\n>>>>>>> other-branch\nafter\n"
+        );
+        assert_eq!(resolve(MERGE, 2, Side::Ours), None);
+    }
+
+    #[test]
+    fn resolve_conflicts_with_crlf() {
+        let merge = "a\r\n<<<<<<< HEAD\r\nhi\r\n=======\r\nhey\r\n>>>>>>> other-branch\r\nb\r\n";
+        assert_eq!(parse(merge)[0].theirs, vec!["hey"]);
+        assert_eq!(resolve(merge, 0, Side::Ours).unwrap(), "a\r\nhi\r\nb\r\n");
+        assert_eq!(
+            resolve(merge, 0, Side::Theirs).unwrap(),
+            "a\r\nhey\r\nb\r\n"
+        );
+    }
+}
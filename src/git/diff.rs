@@ -1,3 +1,4 @@
+use super::whitespace::WhitespaceRules;
 use crate::{
     config::Config,
     syntax_highlight::{self},
@@ -11,10 +12,12 @@ use ratatui::{
 };
 use similar::{Algorithm, DiffOp, DiffTag, DiffableStr, TextDiff};
 use std::{
+    borrow::Cow,
+    collections::BTreeSet,
     fs,
     iter::{self},
-    ops::Range,
-    path::PathBuf,
+    ops::{Range, RangeInclusive},
+    path::{Path, PathBuf},
     rc::Rc,
     str,
 };
@@ -42,15 +45,47 @@ pub(crate) struct Hunk {
     pub content: Text<'static>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub(crate) enum PatchMode {
     Normal,
     Reverse,
 }
 
+impl Diff {
+    pub(crate) fn format_patch(&self) -> String {
+        self.deltas.iter().map(Delta::format_patch).collect()
+    }
+
+    /// Adds `hunk` of `delta`, to the last delta if it's the same file.
+    pub(crate) fn push_hunk(&mut self, delta: &Delta, hunk: Rc<Hunk>) {
+        match self.deltas.last_mut() {
+            Some(last) if last.file_header == delta.file_header => last.hunks.push(hunk),
+            _ => self.deltas.push(Delta {
+                hunks: vec![hunk],
+                ..delta.clone()
+            }),
+        }
+    }
+}
+
+impl Delta {
+    pub(crate) fn format_patch(&self) -> String {
+        self.hunks
+            .iter()
+            .fold(self.file_header.clone(), |patch, hunk| {
+                patch + &hunk.format()
+            })
+    }
+}
+
 impl Hunk {
+    /// The header and lines of the hunk, each ending with a newline.
+    pub(crate) fn format(&self) -> String {
+        format!("{}\n{}\n", self.header, self.content)
+    }
+
     pub(crate) fn format_patch(&self) -> String {
-        format!("{}{}\n{}\n", &self.file_header, self.header, self.content)
+        format!("{}{}", &self.file_header, self.format())
     }
 
     pub(crate) fn format_line_patch(&self, line_range: Range<usize>, mode: PatchMode) -> String {
@@ -90,6 +125,30 @@ impl Hunk {
         )
     }
 
+    /// The line of the new file that line `i` of the hunk is at. Removed lines are at the line following them.
+    pub(crate) fn new_line_number(&self, i: usize) -> u32 {
+        self.content.lines[..i]
+            .iter()
+            .filter(|line| !line.spans.first().unwrap().content.starts_with('-'))
+            .count() as u32
+            + self.new_start
+    }
+
+    /// The line number in the old file of line `i` of the hunk. Added lines get the number of
+    /// the next old line.
+    pub(crate) fn old_line_number(&self, i: usize) -> u32 {
+        self.content.lines[..i]
+            .iter()
+            .filter(|line| !line.spans.first().unwrap().content.starts_with('+'))
+            .count() as u32
+            + self.old_start() as u32
+    }
+
+    /// The lines of the new file that the hunk spans.
+    pub(crate) fn new_line_range(&self) -> RangeInclusive<u32> {
+        self.new_start..=self.new_line_number(self.content.lines.len())
+    }
+
     pub(crate) fn first_diff_line(&self) -> u32 {
         self.content
             .lines
@@ -104,15 +163,90 @@ impl Hunk {
             .unwrap_or(0) as u32
             + self.new_start
     }
+
+    /// Line numbers in the old file of the lines this hunk removes.
+    /// For hunks that only add lines, those surrounding the first addition instead.
+    pub(crate) fn old_lines_touched(&self) -> Vec<usize> {
+        let mut old_line = self.old_start();
+        let mut removed = vec![];
+        let mut insertion = None;
+
+        for line in &self.content.lines {
+            match format!("{line}").chars().next() {
+                Some('-') => {
+                    removed.push(old_line);
+                    old_line += 1;
+                }
+                Some('+') => {
+                    insertion.get_or_insert(old_line);
+                }
+                _ => old_line += 1,
+            }
+        }
+
+        match (removed.is_empty(), insertion) {
+            (false, _) | (true, None) => removed,
+            (true, Some(line)) => [line - 1, line]
+                .into_iter()
+                .filter(|&line| 0 < line && line < old_line)
+                .collect(),
+        }
+    }
+
+    fn old_start(&self) -> usize {
+        self.header
+            .strip_prefix("@@ -")
+            .and_then(|header| header.split([',', ' ']).next())
+            .and_then(|start| start.parse().ok())
+            .unwrap_or(1)
+            .max(1)
+    }
+}
+
+/// Settings for generating diffs that can be changed while running.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct DiffOptions {
+    /// Unchanged lines shown around each change
+    pub context_lines: usize,
+    /// Like `git diff --ignore-all-space`
+    pub ignore_all_space: bool,
+    /// Like `git diff --ignore-blank-lines`
+    pub ignore_blank_lines: bool,
+    /// Like `git diff --find-renames`
+    pub find_renames: bool,
+}
+
+impl DiffOptions {
+    pub(crate) fn new(config: &Config) -> Self {
+        Self {
+            context_lines: config.general.diff_context_lines,
+            ignore_all_space: false,
+            ignore_blank_lines: false,
+            find_renames: false,
+        }
+    }
+
+    /// Whether some changes are left out of diffs, so that their hunks don't match the files.
+    pub(crate) fn ignores_changes(&self) -> bool {
+        self.ignore_all_space || self.ignore_blank_lines
+    }
 }
 
 pub(crate) fn convert_diff(
     config: &Config,
+    options: &DiffOptions,
     repo: &Repository,
-    diff: git2::Diff,
+    mut diff: git2::Diff,
     workdir: bool,
 ) -> Res<Diff> {
     let mut deltas = vec![];
+    let whitespace = WhitespaceRules::from_repo(repo);
+    if options.find_renames {
+        diff.find_similar(Some(git2::DiffFindOptions::new().renames(true)))?;
+    }
+
+    // The header lines following a file's first one are skipped along with it
+    let mut skipping = false;
 
     diff.print(
         git2::DiffFormat::PatchHeader,
@@ -130,12 +264,27 @@ pub(crate) fn convert_diff(
                     status: diffdelta.status(),
                 };
 
-                if let Ok(hunks) = diff_files(repo, diffdelta, workdir, config, &delta) {
+                if let Ok(hunks) = diff_files(
+                    repo,
+                    diffdelta,
+                    workdir,
+                    config,
+                    options,
+                    &whitespace,
+                    &delta,
+                ) {
                     delta.hunks = hunks;
                 }
 
-                deltas.push(delta);
-            } else {
+                // Like git, leave out files that only have changes being ignored
+                skipping = options.ignores_changes()
+                    && delta.hunks.is_empty()
+                    && delta.status == git2::Delta::Modified;
+
+                if !skipping {
+                    deltas.push(delta);
+                }
+            } else if !skipping {
                 let delta = deltas.last_mut().unwrap();
                 delta.file_header.push_str(line_content);
             }
@@ -147,26 +296,165 @@ pub(crate) fn convert_diff(
     Ok(Diff { deltas })
 }
 
+/// Compares two files of the working tree with each other, like `git diff --no-index <old> <new>`.
+pub(crate) fn convert_workdir_files(
+    config: &Config,
+    options: &DiffOptions,
+    repo: &Repository,
+    old: &Path,
+    new: &Path,
+) -> Res<Diff> {
+    let workdir = repo.workdir().expect("No workdir");
+    let read = |path: &Path| -> Res<String> {
+        Ok(fs::read_to_string(workdir.join(path))?.replace("\r\n", "\n"))
+    };
+
+    let mut delta = Delta {
+        file_header: format!("diff --git a/{} b/{}\n", old.display(), new.display()),
+        old_file: old.to_path_buf(),
+        new_file: new.to_path_buf(),
+        hunks: vec![],
+        status: git2::Delta::Modified,
+    };
+    let whitespace = WhitespaceRules::from_repo(repo);
+    delta.hunks = diff_content(
+        config,
+        options,
+        &whitespace,
+        &delta,
+        &read(old)?,
+        &read(new)?,
+    )?;
+
+    Ok(Diff {
+        deltas: vec![delta],
+    })
+}
+
+/// Compares two files, or two directories file by file, outside of git's knowledge,
+/// like `git diff --no-index <old> <new>`.
+pub(crate) fn convert_paths(
+    config: &Config,
+    options: &DiffOptions,
+    repo: &Repository,
+    old: &Path,
+    new: &Path,
+) -> Res<Diff> {
+    for path in [old, new] {
+        if !path.exists() {
+            return Err(format!("{} doesn't exist", path.display()).into());
+        }
+    }
+
+    let pairs = if old.is_dir() && new.is_dir() {
+        let mut files = BTreeSet::new();
+        list_files(old, Path::new(""), &mut files)?;
+        list_files(new, Path::new(""), &mut files)?;
+        files
+            .into_iter()
+            .map(|file| (old.join(&file), new.join(&file)))
+            .collect()
+    } else {
+        vec![(old.to_path_buf(), new.to_path_buf())]
+    };
+
+    let whitespace = WhitespaceRules::from_repo(repo);
+    let read = |path: &Path| -> Res<String> {
+        if !path.exists() {
+            return Ok(String::new());
+        }
+        Ok(String::from_utf8_lossy(&fs::read(path)?).replace("\r\n", "\n"))
+    };
+
+    let mut deltas = vec![];
+    for (old_file, new_file) in pairs {
+        let status = match (old_file.exists(), new_file.exists()) {
+            (false, _) => git2::Delta::Added,
+            (_, false) => git2::Delta::Deleted,
+            _ => git2::Delta::Modified,
+        };
+        let (old_content, new_content) = (read(&old_file)?, read(&new_file)?);
+        if status == git2::Delta::Modified && old_content == new_content {
+            continue;
+        }
+
+        let mut delta = Delta {
+            file_header: format!(
+                "diff --git a/{} b/{}\n",
+                old_file.display(),
+                new_file.display()
+            ),
+            old_file,
+            new_file,
+            hunks: vec![],
+            status,
+        };
+        delta.hunks = diff_content(
+            config,
+            options,
+            &whitespace,
+            &delta,
+            &old_content,
+            &new_content,
+        )?;
+        deltas.push(delta);
+    }
+
+    Ok(Diff { deltas })
+}
+
+/// Collects the paths of the files under `dir`, relative to the directory the walk started in.
+fn list_files(dir: &Path, prefix: &Path, files: &mut BTreeSet<PathBuf>) -> Res<()> {
+    for entry in fs::read_dir(dir.join(prefix))? {
+        let entry = entry?;
+        let path = prefix.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            list_files(dir, &path, files)?;
+        } else {
+            files.insert(path);
+        }
+    }
+
+    Ok(())
+}
+
 fn diff_files(
     repo: &Repository,
     diffdelta: git2::DiffDelta<'_>,
     workdir: bool,
     config: &Config,
+    options: &DiffOptions,
+    whitespace: &WhitespaceRules,
     delta: &Delta,
 ) -> Res<Vec<Rc<Hunk>>> {
     let old_content = read_blob(repo, &diffdelta.old_file())?.replace("\r\n", "\n");
-    let new_content = if workdir {
+    let new_content = if workdir && diffdelta.status() == git2::Delta::Deleted {
+        String::new()
+    } else if workdir {
         read_workdir(repo, &diffdelta.new_file())?
     } else {
         read_blob(repo, &diffdelta.new_file())?
     }
     .replace("\r\n", "\n");
 
-    diff_content(config, delta, &old_content, &new_content)
+    diff_content(
+        config,
+        options,
+        whitespace,
+        delta,
+        &old_content,
+        &new_content,
+    )
 }
 
+/// Changed lines sharing less than this with what they replace are highlighted as a whole,
+/// rather than word by word, as matching up their few common words would just be noise.
+const MIN_WORD_DIFF_SIMILARITY: f32 = 0.5;
+
 fn diff_content(
     config: &Config,
+    options: &DiffOptions,
+    whitespace: &WhitespaceRules,
     delta: &Delta,
     old_content: &str,
     new_content: &str,
@@ -178,9 +466,16 @@ fn diff_content(
     let old_line_indices = byte_ranges(&old_lines);
     let new_line_indices = byte_ranges(&new_lines);
 
+    let old_keys = comparison_keys(&old_lines, options.ignore_all_space);
+    let new_keys = comparison_keys(&new_lines, options.ignore_all_space);
+    let old_key_strs = old_keys.iter().map(AsRef::as_ref).collect::<Vec<&str>>();
+    let new_key_strs = new_keys.iter().map(AsRef::as_ref).collect::<Vec<&str>>();
+
     let text_diff = TextDiff::configure()
         .algorithm(Algorithm::Patience)
-        .diff_slices(&old_lines, &new_lines);
+        .diff_slices(&old_key_strs, &new_key_strs);
+
+    let old_scopes = syntax_highlight::scopes(&delta.old_file, old_content);
 
     let mut old_syntax_highlights = if config.style.syntax_highlight.enabled {
         syntax_highlight::highlight(config, &delta.old_file, old_content)
@@ -200,18 +495,18 @@ fn diff_content(
 
     Ok(text_diff
         .unified_diff()
+        .context_radius(options.context_lines)
         .iter_hunks()
+        .filter(|hunk| {
+            !options.ignore_blank_lines
+                || !only_changes_blank_lines(hunk.ops(), &old_lines, &new_lines)
+        })
         .map(|hunk| {
             let mut lines = vec![];
 
             hunk.ops().iter().for_each(|op| {
                 let (line_tag, old_line, new_line) = op.as_tag_tuple();
 
-                let old_prefix = match line_tag {
-                    DiffTag::Equal => Span::raw(" "),
-                    _ => Span::styled("-", &style.diff_highlight.tag_old),
-                };
-
                 let old_lines_range = total_range(&old_line_indices[old_line.clone()]);
                 let old_words = old_content[old_lines_range.clone()].tokenize_unicode_words();
                 let old_word_indices = byte_ranges(&old_words);
@@ -223,71 +518,90 @@ fn diff_content(
                 let word_diff = TextDiff::configure()
                     .algorithm(Algorithm::Myers)
                     .diff_slices(&old_words, &new_words);
+                let refine = word_similarity(&word_diff) >= MIN_WORD_DIFF_SIMILARITY;
 
-                let mut old_diff_highlights = iter_token_tag_ranges(&word_diff)
-                    .map(|(word_tag, old_word_token_range, _)| (old_word_token_range, word_tag))
-                    .filter_map(|(word_token_range, word_tag)| {
-                        let words_range = total_range(&old_word_indices[word_token_range]);
-                        map_from_token_to_byte_range(&words_range, &old_lines_range, word_tag)
-                    })
-                    .flat_map(|style_range| {
-                        syntax_highlight::split_at_newlines(old_content, style_range)
-                    })
-                    .map(|(word_range, word_tag)| {
-                        let diff_style = match word_tag {
-                            DiffTag::Equal => Style::from(&style.diff_highlight.unchanged_old),
-                            DiffTag::Delete => Style::from(&style.diff_highlight.changed_old),
-                            DiffTag::Insert => unreachable!(),
-                            DiffTag::Replace => Style::from(&style.diff_highlight.changed_old),
-                        };
-
-                        (word_range.clone(), diff_style)
-                    })
-                    .peekable();
-
-                create_lines(
-                    &old_line_indices[old_line.clone()],
-                    &mut old_syntax_highlights,
-                    &mut old_diff_highlights,
-                    old_prefix,
-                    old_content,
-                    &mut lines,
-                );
-
-                // Don't print both old/new if equal
+                // Unchanged lines are printed once, as they are now, since with whitespace
+                // being ignored they can still differ from the old ones
                 if line_tag != DiffTag::Equal {
-                    let mut new_diff_highlights = iter_token_tag_ranges(&word_diff)
-                        .map(|(word_tag, _, new_word_token_range)| (new_word_token_range, word_tag))
+                    let mut old_diff_highlights = iter_token_tag_ranges(&word_diff)
+                        .map(|(word_tag, old_word_token_range, _)| (old_word_token_range, word_tag))
                         .filter_map(|(word_token_range, word_tag)| {
-                            let words_range = total_range(&new_word_indices[word_token_range]);
-                            map_from_token_to_byte_range(&words_range, &new_lines_range, word_tag)
+                            let words_range = total_range(&old_word_indices[word_token_range]);
+                            map_from_token_to_byte_range(&words_range, &old_lines_range, word_tag)
                         })
                         .flat_map(|style_range| {
-                            syntax_highlight::split_at_newlines(new_content, style_range)
+                            syntax_highlight::split_at_newlines(old_content, style_range)
                         })
                         .map(|(word_range, word_tag)| {
                             let diff_style = match word_tag {
-                                DiffTag::Equal => Style::from(&style.diff_highlight.unchanged_new),
-                                DiffTag::Delete => unreachable!(),
-                                DiffTag::Insert => Style::from(&style.diff_highlight.changed_new),
-                                DiffTag::Replace => Style::from(&style.diff_highlight.changed_new),
+                                DiffTag::Equal if refine => {
+                                    Style::from(&style.diff_highlight.unchanged_old)
+                                }
+                                DiffTag::Equal => Style::from(&style.diff_highlight.changed_old),
+                                DiffTag::Delete => Style::from(&style.diff_highlight.changed_old),
+                                DiffTag::Insert => unreachable!(),
+                                DiffTag::Replace => Style::from(&style.diff_highlight.changed_old),
                             };
 
                             (word_range.clone(), diff_style)
                         })
                         .peekable();
 
-                    let new_prefix = Span::styled("+", &style.diff_highlight.tag_new);
-
                     create_lines(
-                        &new_line_indices[new_line.clone()],
-                        &mut new_syntax_highlights,
-                        &mut new_diff_highlights,
-                        new_prefix,
-                        new_content,
+                        &old_line_indices[old_line.clone()],
+                        &mut old_syntax_highlights,
+                        &mut old_diff_highlights,
+                        Span::styled("-", &style.diff_highlight.tag_old),
+                        old_content,
+                        None,
                         &mut lines,
                     );
                 }
+
+                let mut new_diff_highlights = iter_token_tag_ranges(&word_diff)
+                    .map(|(word_tag, _, new_word_token_range)| (new_word_token_range, word_tag))
+                    .filter_map(|(word_token_range, word_tag)| {
+                        let words_range = total_range(&new_word_indices[word_token_range]);
+                        map_from_token_to_byte_range(&words_range, &new_lines_range, word_tag)
+                    })
+                    .flat_map(|style_range| {
+                        syntax_highlight::split_at_newlines(new_content, style_range)
+                    })
+                    .map(|(word_range, word_tag)| {
+                        let diff_style = match word_tag {
+                            DiffTag::Equal if refine => {
+                                Style::from(&style.diff_highlight.unchanged_new)
+                            }
+                            DiffTag::Equal => Style::from(&style.diff_highlight.changed_new),
+                            DiffTag::Delete => unreachable!(),
+                            DiffTag::Insert => Style::from(&style.diff_highlight.changed_new),
+                            DiffTag::Replace => Style::from(&style.diff_highlight.changed_new),
+                        };
+
+                        (word_range.clone(), diff_style)
+                    })
+                    .peekable();
+
+                let (new_prefix, whitespace) = match line_tag {
+                    DiffTag::Equal => (Span::raw(" "), None),
+                    _ => (
+                        Span::styled("+", &style.diff_highlight.tag_new),
+                        Some((
+                            whitespace,
+                            Style::from(&style.diff_highlight.whitespace_error),
+                        )),
+                    ),
+                };
+
+                create_lines(
+                    &new_line_indices[new_line.clone()],
+                    &mut new_syntax_highlights,
+                    &mut new_diff_highlights,
+                    new_prefix,
+                    new_content,
+                    whitespace,
+                    &mut lines,
+                );
             });
 
             let formatted_hunk = Text::from(lines);
@@ -303,17 +617,84 @@ fn diff_content(
                 .parse()
                 .unwrap();
 
+            let first_old_line = hunk.ops().first().map_or(0, |op| op.old_range().start);
+            let header = match enclosing_scope(&old_scopes, &old_lines, first_old_line) {
+                Some(scope) => format!("{} {}", hunk.header(), scope),
+                None => format!("{}", hunk.header()),
+            };
+
             Rc::new(Hunk {
                 file_header: delta.file_header.clone(),
                 new_file: delta.new_file.clone(),
                 new_start,
-                header: format!("{}", hunk.header()),
+                header,
                 content: formatted_hunk,
             })
         })
         .collect::<Vec<_>>())
 }
 
+/// The first line of the innermost scope that starts above `line` and contains it,
+/// which is what git shows after a hunk's line numbers.
+fn enclosing_scope<'a>(scopes: &[Range<usize>], lines: &[&'a str], line: usize) -> Option<&'a str> {
+    scopes
+        .iter()
+        .filter(|scope| scope.start < line && line < scope.end)
+        .max_by_key(|scope| scope.start)
+        .map(|scope| lines[scope.start].trim())
+}
+
+/// What lines are compared by, which leaves out their whitespace if it's ignored.
+fn comparison_keys<'a>(lines: &[&'a str], ignore_all_space: bool) -> Vec<Cow<'a, str>> {
+    lines
+        .iter()
+        .map(|line| {
+            if ignore_all_space {
+                Cow::Owned(line.chars().filter(|c| !c.is_whitespace()).collect())
+            } else {
+                Cow::Borrowed(*line)
+            }
+        })
+        .collect()
+}
+
+fn only_changes_blank_lines(ops: &[DiffOp], old_lines: &[&str], new_lines: &[&str]) -> bool {
+    ops.iter()
+        .filter(|op| op.tag() != DiffTag::Equal)
+        .all(|op| {
+            old_lines[op.old_range()]
+                .iter()
+                .chain(&new_lines[op.new_range()])
+                .all(|line| line.trim().is_empty())
+        })
+}
+
+/// Like `TextDiff::ratio`, but weighted by length and ignoring whitespace,
+/// so that lines aren't considered similar just because both contain spaces.
+fn word_similarity(word_diff: &TextDiff<'_, '_, '_, str>) -> f32 {
+    let non_whitespace_len = |word: &str| word.chars().filter(|c| !c.is_whitespace()).count();
+    let total = word_diff
+        .old_slices()
+        .iter()
+        .chain(word_diff.new_slices())
+        .map(|word| non_whitespace_len(word))
+        .sum::<usize>();
+
+    if total == 0 {
+        return 1.0;
+    }
+
+    let equal = word_diff
+        .ops()
+        .iter()
+        .filter(|op| op.tag() == DiffTag::Equal)
+        .flat_map(|op| &word_diff.old_slices()[op.old_range()])
+        .map(|word| non_whitespace_len(word))
+        .sum::<usize>();
+
+    2.0 * equal as f32 / total as f32
+}
+
 fn map_from_token_to_byte_range(
     word_range: &Range<usize>,
     old_lines_range: &Range<usize>,
@@ -357,6 +738,7 @@ fn create_lines(
     diff_highlights: &mut iter::Peekable<impl Iterator<Item = (Range<usize>, Style)>>,
     prefix: Span<'static>,
     content: &str,
+    whitespace: Option<(&WhitespaceRules, Style)>,
     lines: &mut Vec<Line<'_>>,
 ) {
     for line in line_indices {
@@ -371,30 +753,37 @@ fn create_lines(
             .peeking_take_while(|(h_range, _)| h_range.start < line.end)
             .peekable();
 
-        let spans = iter::once(prefix.clone())
-            .chain(
-                iter::from_fn(|| next_merged_style_range(a, b))
-                    .flatten()
-                    .map(|(h_range, h_style)| {
-                        (
-                            // clamp to line
-                            line.start.max(h_range.start)..line.end.min(h_range.end),
-                            h_style,
-                        )
-                    })
-                    .map(|(h_range, h_style)| {
-                        Span::styled(
-                            content[h_range]
-                                // TODO only need to do this for the last span
-                                .trim_end_matches(|s| s == '\r' || s == '\n')
-                                .to_string(),
-                            h_style,
-                        )
-                    }),
-            )
+        let spans = iter::from_fn(|| next_merged_style_range(a, b))
+            .flatten()
+            .map(|(h_range, h_style)| {
+                (
+                    // clamp to line
+                    line.start.max(h_range.start)..line.end.min(h_range.end),
+                    h_style,
+                )
+            })
+            .map(|(h_range, h_style)| {
+                Span::styled(
+                    content[h_range]
+                        // TODO only need to do this for the last span
+                        .trim_end_matches(['\r', '\n'])
+                        .to_string(),
+                    h_style,
+                )
+            })
             .collect::<Vec<_>>();
 
-        lines.push(Line::from(spans));
+        let spans = match whitespace {
+            Some((rules, ws_style)) => {
+                let text = content[line.clone()].trim_end_matches(['\r', '\n']);
+                patch_style_ranges(spans, &rules.violations(text), ws_style)
+            }
+            None => spans,
+        };
+
+        lines.push(Line::from(
+            iter::once(prefix.clone()).chain(spans).collect::<Vec<_>>(),
+        ));
 
         if !content[line.clone()].ends_with('\n') {
             lines.push(Line::from("\\ No newline at end of file"))
@@ -402,6 +791,52 @@ fn create_lines(
     }
 }
 
+/// Patches `style` onto the given byte ranges of the spans' joined content,
+/// splitting spans at the range boundaries.
+fn patch_style_ranges(
+    spans: Vec<Span<'static>>,
+    ranges: &[Range<usize>],
+    style: Style,
+) -> Vec<Span<'static>> {
+    if ranges.is_empty() {
+        return spans;
+    }
+
+    let mut result = vec![];
+    let mut offset = 0;
+
+    for span in spans {
+        let span_range = offset..offset + span.content.len();
+        offset = span_range.end;
+
+        let mut boundaries = ranges
+            .iter()
+            .flat_map(|range| [range.start, range.end])
+            .filter(|&i| span_range.start < i && i < span_range.end)
+            .collect::<Vec<_>>();
+        boundaries.insert(0, span_range.start);
+        boundaries.push(span_range.end);
+
+        for part in boundaries.windows(2) {
+            let part_range = part[0]..part[1];
+            let part_style = if ranges.iter().any(|range| range.contains(&part_range.start)) {
+                span.style.patch(style)
+            } else {
+                span.style
+            };
+
+            result.push(Span::styled(
+                span.content
+                    [(part_range.start - span_range.start)..(part_range.end - span_range.start)]
+                    .to_string(),
+                part_style,
+            ));
+        }
+    }
+
+    result
+}
+
 fn advance_to(iter: &mut iter::Peekable<impl Iterator<Item = (Range<usize>, Style)>>, to: usize) {
     while let Some((range, _style)) = iter.peek() {
         if range.end <= to {
@@ -492,8 +927,9 @@ fn path(file: &git2::DiffFile) -> PathBuf {
 
 #[cfg(test)]
 mod tests {
-    use super::Delta;
+    use super::{Delta, DiffOptions};
     use crate::config;
+    use ratatui::{style::Modifier, text::Line};
 
     #[test]
     fn changed_line() {
@@ -513,13 +949,107 @@ mod tests {
         insta::assert_snapshot!(hunks[0].format_patch());
     }
 
+    #[test]
+    fn similar_lines_highlight_changed_words() {
+        let hunks = diff_content("let count = 1;\n", "let count = 2;\n");
+        assert_eq!(
+            dimmed_words(&hunks[0].content.lines[0]),
+            ["let", "count", "=", ";"]
+        );
+        assert_eq!(
+            dimmed_words(&hunks[0].content.lines[1]),
+            ["let", "count", "=", ";"]
+        );
+    }
+
+    #[test]
+    fn dissimilar_lines_highlight_whole_line() {
+        let hunks = diff_content("if ready {\n", "return ready(a, b);\n");
+        assert!(dimmed_words(&hunks[0].content.lines[0]).is_empty());
+        assert!(dimmed_words(&hunks[0].content.lines[1]).is_empty());
+    }
+
+    #[test]
+    fn new_line_numbers() {
+        let hunks = diff_content("a\nb\nc\nd\n", "a\nB\nc\nd\nE\n");
+        let numbers = (0..hunks[0].content.lines.len())
+            .map(|i| hunks[0].new_line_number(i))
+            .collect::<Vec<_>>();
+
+        // a, -b, +B, c, d, +E
+        assert_eq!(numbers, [1, 2, 2, 3, 4, 5]);
+        assert_eq!(hunks[0].new_line_range(), 1..=6);
+    }
+
+    #[test]
+    fn old_line_numbers() {
+        let hunks = diff_content("a\nb\nc\nd\n", "a\nB\nc\nd\nE\n");
+        let numbers = (0..hunks[0].content.lines.len())
+            .map(|i| hunks[0].old_line_number(i))
+            .collect::<Vec<_>>();
+
+        // a, -b, +B, c, d, +E
+        assert_eq!(numbers, [1, 2, 3, 3, 4, 5]);
+    }
+
+    const RUST_FILE: &str = "\
+struct Counter {
+    count: u32,
+}
+
+impl Counter {
+    fn increment(&mut self) {
+        let a = 1;
+        let b = 2;
+        let c = 3;
+        self.count += 1;
+    }
+}
+";
+
+    #[test]
+    fn hunk_header_names_enclosing_scope() {
+        let hunks = diff_file_content("counter.rs", RUST_FILE, &RUST_FILE.replace("+= 1", "+= 2"));
+        assert_eq!(hunks[0].header, "@@ -7,6 +7,6 @@ fn increment(&mut self) {");
+    }
+
+    #[test]
+    fn hunk_header_without_enclosing_scope() {
+        let hunks = diff_file_content(
+            "counter.rs",
+            RUST_FILE,
+            &RUST_FILE.replace("Counter {\n    count", "Counter {\n    total"),
+        );
+        assert_eq!(hunks[0].header, "@@ -1,5 +1,5 @@");
+    }
+
+    /// Words styled as unchanged, which are dimmed in the default config.
+    fn dimmed_words(line: &Line) -> Vec<String> {
+        line.spans
+            .iter()
+            .filter(|span| span.style.add_modifier.contains(Modifier::DIM))
+            .flat_map(|span| span.content.split_whitespace().map(String::from))
+            .collect()
+    }
+
     fn diff_content(old_content: &str, new_content: &str) -> Vec<std::rc::Rc<super::Hunk>> {
+        diff_file_content("file", old_content, new_content)
+    }
+
+    fn diff_file_content(
+        path: &str,
+        old_content: &str,
+        new_content: &str,
+    ) -> Vec<std::rc::Rc<super::Hunk>> {
+        let config = config::init_test_config().unwrap();
         super::diff_content(
-            &config::init_test_config().unwrap(),
+            &config,
+            &DiffOptions::new(&config),
+            &Default::default(),
             &Delta {
                 file_header: "header\n".into(),
-                new_file: "new_file".into(),
-                old_file: "old_file".into(),
+                new_file: path.into(),
+                old_file: path.into(),
                 hunks: vec![],
                 status: git2::Delta::Modified,
             },
@@ -0,0 +1,578 @@
+use crate::Res;
+use serde::{de::DeserializeOwned, Deserialize};
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+    str,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ForgeKind {
+    GitHub,
+    GitLab,
+    Codeberg,
+    Bitbucket,
+}
+
+/// A repository hosted on a forge, to link to its web pages.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Forge {
+    pub kind: ForgeKind,
+    /// Like `https://github.com/owner/repo`
+    pub base_url: String,
+}
+
+impl Forge {
+    /// Recognizes the `https://`, `ssh://` and scp-like (`git@host:owner/repo.git`) remote URLs
+    /// of the supported forges.
+    pub(crate) fn from_remote_url(url: &str) -> Option<Self> {
+        let without_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+        let without_user = without_scheme
+            .split_once('@')
+            .filter(|(user, _)| !user.contains('/'))
+            .map_or(without_scheme, |(_, rest)| rest);
+
+        let (host, path) = if url.contains("://") {
+            without_user.split_once('/')?
+        } else {
+            without_user.split_once(':')?
+        };
+        // Drop any port, forges serve their web pages on the default one
+        let host = host.split(':').next()?;
+        let path = path.trim_end_matches('/').trim_end_matches(".git");
+
+        let kind = if host.contains("github") {
+            ForgeKind::GitHub
+        } else if host.contains("gitlab") {
+            ForgeKind::GitLab
+        } else if host.contains("codeberg") {
+            ForgeKind::Codeberg
+        } else if host.contains("bitbucket") {
+            ForgeKind::Bitbucket
+        } else {
+            return None;
+        };
+
+        Some(Self {
+            kind,
+            base_url: format!("https://{}/{}", host, path),
+        })
+    }
+
+    pub(crate) fn commit_url(&self, hash: &str) -> String {
+        match self.kind {
+            ForgeKind::GitHub | ForgeKind::Codeberg => format!("{}/commit/{}", self.base_url, hash),
+            ForgeKind::GitLab => format!("{}/-/commit/{}", self.base_url, hash),
+            ForgeKind::Bitbucket => format!("{}/commits/{}", self.base_url, hash),
+        }
+    }
+
+    /// A page to open a pull request of `branch` from, against the default branch.
+    pub(crate) fn new_pull_request_url(&self, branch: &str) -> String {
+        match self.kind {
+            ForgeKind::GitHub => format!("{}/compare/{}?expand=1", self.base_url, branch),
+            ForgeKind::GitLab => format!(
+                "{}/-/merge_requests/new?merge_request%5Bsource_branch%5D={}",
+                self.base_url, branch
+            ),
+            ForgeKind::Codeberg => format!("{}/compare/{}", self.base_url, branch),
+            ForgeKind::Bitbucket => {
+                format!("{}/pull-requests/new?source={}", self.base_url, branch)
+            }
+        }
+    }
+
+    pub(crate) fn file_url(&self, rev: &str, path: &str, line: Option<u32>) -> String {
+        let url = match self.kind {
+            ForgeKind::GitHub => format!("{}/blob/{}/{}", self.base_url, rev, path),
+            ForgeKind::GitLab => format!("{}/-/blob/{}/{}", self.base_url, rev, path),
+            ForgeKind::Codeberg => format!("{}/src/commit/{}/{}", self.base_url, rev, path),
+            ForgeKind::Bitbucket => format!("{}/src/{}/{}", self.base_url, rev, path),
+        };
+
+        match (line, self.kind) {
+            (None, _) => url,
+            (Some(line), ForgeKind::Bitbucket) => format!("{}#lines-{}", url, line),
+            (Some(line), _) => format!("{}#L{}", url, line),
+        }
+    }
+}
+
+/// An open pull request, or merge request on GitLab.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PullRequest {
+    pub number: u64,
+    pub title: String,
+    pub author: String,
+    /// The branch the changes are on
+    pub branch: String,
+    pub ci: CiState,
+}
+
+/// The combined result of the CI runs on the latest commit of a pull request.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum CiState {
+    None,
+    Pending,
+    Success,
+    Failure,
+}
+
+impl Forge {
+    /// The host and `owner/repo` parts of the URL.
+    fn host_and_path(&self) -> (&str, &str) {
+        self.base_url
+            .trim_start_matches("https://")
+            .split_once('/')
+            .unwrap_or((&self.base_url, ""))
+    }
+
+    /// The default REST API endpoint, a self-hosted forge may need `forge.api_url` configured.
+    pub(crate) fn api_url(&self) -> Res<String> {
+        let (host, _) = self.host_and_path();
+
+        match self.kind {
+            ForgeKind::GitHub if host == "github.com" => Ok("https://api.github.com".into()),
+            ForgeKind::GitHub => Ok(format!("https://{}/api/v3", host)),
+            ForgeKind::GitLab => Ok(format!("https://{}/api/v4", host)),
+            ForgeKind::Codeberg | ForgeKind::Bitbucket => {
+                Err("Pull requests are only supported on GitHub and GitLab".into())
+            }
+        }
+    }
+
+    /// The ref on the remote that the forge keeps the head of a pull request at.
+    pub(crate) fn pull_request_ref(&self, number: u64) -> String {
+        match self.kind {
+            ForgeKind::GitLab => format!("refs/merge-requests/{}/head", number),
+            _ => format!("refs/pull/{}/head", number),
+        }
+    }
+
+    /// Lists the open pull requests with the state of their CI.
+    pub(crate) fn pull_requests(
+        &self,
+        api_url: &str,
+        token: Option<&str>,
+    ) -> Res<Vec<PullRequest>> {
+        let api_url = api_url.trim_end_matches('/');
+
+        match self.kind {
+            ForgeKind::GitHub => {
+                let repo_url = format!("{}/repos/{}", api_url, self.host_and_path().1);
+                let pulls: Vec<GitHubPull> = self.get(
+                    &format!("{}/pulls?state=open&per_page=100", repo_url),
+                    token,
+                )?;
+
+                // The CI state of all of them in one request. It's left unknown if that fails,
+                // like when there's no token, which the GraphQL API requires.
+                let rollups = token
+                    .and_then(|token| self.check_rollups(api_url, token).ok())
+                    .unwrap_or_default();
+
+                Ok(pulls
+                    .into_iter()
+                    .map(|pull| PullRequest {
+                        ci: rollups
+                            .iter()
+                            .find(|rollup| rollup.number == pull.number)
+                            .map_or(CiState::None, GitHubRollupPull::state),
+                        number: pull.number,
+                        title: pull.title,
+                        author: pull.user.login,
+                        branch: pull.head.branch,
+                    })
+                    .collect())
+            }
+            ForgeKind::GitLab => {
+                let project_url = format!(
+                    "{}/projects/{}",
+                    api_url,
+                    self.host_and_path().1.replace('/', "%2F")
+                );
+                let merge_requests: Vec<GitLabMergeRequest> = self.get(
+                    &format!("{}/merge_requests?state=opened&per_page=100", project_url),
+                    token,
+                )?;
+
+                // The pipeline of all of them in one request, left unknown if that fails
+                let pipelines = self.head_pipelines(api_url, token).unwrap_or_default();
+
+                Ok(merge_requests
+                    .into_iter()
+                    .map(|merge_request| PullRequest {
+                        ci: pipelines
+                            .iter()
+                            .find(|node| node.iid == merge_request.iid.to_string())
+                            .and_then(|node| node.head_pipeline.as_ref())
+                            .map_or(CiState::None, GitLabPipeline::state),
+                        number: merge_request.iid,
+                        title: merge_request.title,
+                        author: merge_request.author.username,
+                        branch: merge_request.source_branch,
+                    })
+                    .collect())
+            }
+            ForgeKind::Codeberg | ForgeKind::Bitbucket => {
+                Err("Pull requests are only supported on GitHub and GitLab".into())
+            }
+        }
+    }
+
+    /// The combined state of the checks on the latest commit of each open GitHub pull request.
+    fn check_rollups(&self, api_url: &str, token: &str) -> Res<Vec<GitHubRollupPull>> {
+        let (_, path) = self.host_and_path();
+        let (owner, name) = path.split_once('/').unwrap_or((path, ""));
+        // GitHub Enterprise serves GraphQL next to the REST API, not beneath it
+        let graphql_url = match api_url.strip_suffix("/v3") {
+            Some(api_url) => format!("{}/graphql", api_url),
+            None => format!("{}/graphql", api_url),
+        };
+        let query = serde_json::json!({
+            "query": "query($owner: String!, $name: String!) {
+                repository(owner: $owner, name: $name) {
+                    pullRequests(states: OPEN, first: 100) {
+                        nodes { number commits(last: 1) { nodes { commit { statusCheckRollup { state } } } } }
+                    }
+                }
+            }",
+            "variables": { "owner": owner, "name": name },
+        });
+
+        let response: GitHubRollupResponse =
+            self.request(&graphql_url, Some(&query.to_string()), Some(token))?;
+        Ok(response.data.repository.pull_requests.nodes)
+    }
+
+    /// The pipeline of the latest commit of each open GitLab merge request, which the REST API
+    /// only includes when asking for them one by one.
+    fn head_pipelines(
+        &self,
+        api_url: &str,
+        token: Option<&str>,
+    ) -> Res<Vec<GitLabPipelineMergeRequest>> {
+        let (_, path) = self.host_and_path();
+        let graphql_url = match api_url.strip_suffix("/v4") {
+            Some(api_url) => format!("{}/graphql", api_url),
+            None => format!("{}/graphql", api_url),
+        };
+        let query = serde_json::json!({
+            "query": "query($path: ID!) {
+                project(fullPath: $path) {
+                    mergeRequests(state: opened, first: 100) { nodes { iid headPipeline { status } } }
+                }
+            }",
+            "variables": { "path": path },
+        });
+
+        let response: GitLabPipelineResponse =
+            self.request(&graphql_url, Some(&query.to_string()), token)?;
+        Ok(response.data.project.merge_requests.nodes)
+    }
+
+    fn get<T: DeserializeOwned>(&self, url: &str, token: Option<&str>) -> Res<T> {
+        self.request(url, None, token)
+    }
+
+    /// Requests `url` with curl, posting `data` if there is any. The token is passed on stdin
+    /// to keep it out of the process list.
+    fn request<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        data: Option<&str>,
+        token: Option<&str>,
+    ) -> Res<T> {
+        let mut cmd = Command::new("curl");
+        cmd.args(["--silent", "--show-error", "--fail", "--location"]);
+        if let Some(data) = data {
+            cmd.args(["--header", "Content-Type: application/json", "--data", data]);
+        }
+        cmd.stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        if token.is_some() {
+            cmd.args(["--header", "@-"]);
+        }
+        cmd.arg(url);
+
+        let mut child = cmd
+            .spawn()
+            .map_err(|err| format!("Couldn't run curl: {}", err))?;
+
+        if let Some(token) = token {
+            let header = match self.kind {
+                ForgeKind::GitLab => format!("PRIVATE-TOKEN: {}\n", token),
+                _ => format!("Authorization: Bearer {}\n", token),
+            };
+            child.stdin.take().unwrap().write_all(header.as_bytes())?;
+        }
+
+        let out = child.wait_with_output()?;
+        if !out.status.success() {
+            return Err(format!(
+                "Request to {} failed: {}",
+                url,
+                String::from_utf8_lossy(&out.stderr).trim()
+            )
+            .into());
+        }
+
+        Ok(serde_json::from_slice(&out.stdout)?)
+    }
+}
+
+#[derive(Deserialize)]
+struct GitHubPull {
+    number: u64,
+    title: String,
+    user: GitHubUser,
+    head: GitHubHead,
+}
+
+#[derive(Deserialize)]
+struct GitHubUser {
+    login: String,
+}
+
+#[derive(Deserialize)]
+struct GitHubHead {
+    #[serde(rename = "ref")]
+    branch: String,
+}
+
+#[derive(Deserialize)]
+struct GitHubRollupResponse {
+    data: GitHubRollupData,
+}
+
+#[derive(Deserialize)]
+struct GitHubRollupData {
+    repository: GitHubRollupRepository,
+}
+
+#[derive(Deserialize)]
+struct GitHubRollupRepository {
+    #[serde(rename = "pullRequests")]
+    pull_requests: GraphQlNodes<GitHubRollupPull>,
+}
+
+#[derive(Deserialize)]
+struct GraphQlNodes<T> {
+    nodes: Vec<T>,
+}
+
+#[derive(Deserialize)]
+struct GitHubRollupPull {
+    number: u64,
+    commits: GraphQlNodes<GitHubRollupCommitNode>,
+}
+
+#[derive(Deserialize)]
+struct GitHubRollupCommitNode {
+    commit: GitHubRollupCommit,
+}
+
+#[derive(Deserialize)]
+struct GitHubRollupCommit {
+    #[serde(rename = "statusCheckRollup")]
+    status_check_rollup: Option<GitHubRollup>,
+}
+
+#[derive(Deserialize)]
+struct GitHubRollup {
+    state: String,
+}
+
+impl GitHubRollupPull {
+    fn state(&self) -> CiState {
+        let rollup = self
+            .commits
+            .nodes
+            .last()
+            .and_then(|node| node.commit.status_check_rollup.as_ref());
+
+        match rollup.map(|rollup| rollup.state.as_str()) {
+            None => CiState::None,
+            Some("SUCCESS") => CiState::Success,
+            Some("FAILURE" | "ERROR") => CiState::Failure,
+            Some(_) => CiState::Pending,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct GitLabMergeRequest {
+    iid: u64,
+    title: String,
+    author: GitLabUser,
+    source_branch: String,
+}
+
+#[derive(Deserialize)]
+struct GitLabUser {
+    username: String,
+}
+
+#[derive(Deserialize)]
+struct GitLabPipelineResponse {
+    data: GitLabPipelineData,
+}
+
+#[derive(Deserialize)]
+struct GitLabPipelineData {
+    project: GitLabPipelineProject,
+}
+
+#[derive(Deserialize)]
+struct GitLabPipelineProject {
+    #[serde(rename = "mergeRequests")]
+    merge_requests: GraphQlNodes<GitLabPipelineMergeRequest>,
+}
+
+#[derive(Deserialize)]
+struct GitLabPipelineMergeRequest {
+    iid: String,
+    #[serde(rename = "headPipeline")]
+    head_pipeline: Option<GitLabPipeline>,
+}
+
+#[derive(Deserialize)]
+struct GitLabPipeline {
+    status: String,
+}
+
+impl GitLabPipeline {
+    fn state(&self) -> CiState {
+        match self.status.as_str() {
+            "SUCCESS" => CiState::Success,
+            "FAILED" | "CANCELED" => CiState::Failure,
+            "SKIPPED" => CiState::None,
+            _ => CiState::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_remote_urls() {
+        for url in [
+            "https://github.com/altsem/gitu.git",
+            "https://github.com/altsem/gitu",
+            "git@github.com:altsem/gitu.git",
+            "ssh://git@github.com/altsem/gitu.git",
+            "ssh://git@github.com:22/altsem/gitu.git",
+        ] {
+            assert_eq!(
+                Forge::from_remote_url(url),
+                Some(Forge {
+                    kind: ForgeKind::GitHub,
+                    base_url: "https://github.com/altsem/gitu".into()
+                }),
+                "{}",
+                url
+            );
+        }
+
+        assert_eq!(
+            Forge::from_remote_url("git@gitlab.com:group/subgroup/project.git")
+                .unwrap()
+                .base_url,
+            "https://gitlab.com/group/subgroup/project"
+        );
+        assert_eq!(Forge::from_remote_url("/srv/git/project.git"), None);
+        assert_eq!(Forge::from_remote_url("https://example.com/project"), None);
+    }
+
+    #[test]
+    fn urls() {
+        let forge = |url| Forge::from_remote_url(url).unwrap();
+
+        assert_eq!(
+            forge("git@github.com:o/r.git").file_url("abc", "src/main.rs", Some(3)),
+            "https://github.com/o/r/blob/abc/src/main.rs#L3"
+        );
+        assert_eq!(
+            forge("git@gitlab.com:o/r.git").commit_url("abc"),
+            "https://gitlab.com/o/r/-/commit/abc"
+        );
+        assert_eq!(
+            forge("https://codeberg.org/o/r.git").file_url("abc", "README.md", None),
+            "https://codeberg.org/o/r/src/commit/abc/README.md"
+        );
+        assert_eq!(
+            forge("git@bitbucket.org:o/r.git").file_url("abc", "a.rs", Some(7)),
+            "https://bitbucket.org/o/r/src/abc/a.rs#lines-7"
+        );
+        assert_eq!(
+            forge("git@bitbucket.org:o/r.git").commit_url("abc"),
+            "https://bitbucket.org/o/r/commits/abc"
+        );
+        assert_eq!(
+            forge("git@gitlab.com:o/r.git").new_pull_request_url("feature"),
+            "https://gitlab.com/o/r/-/merge_requests/new?merge_request%5Bsource_branch%5D=feature"
+        );
+    }
+
+    #[test]
+    fn api_urls() {
+        let forge = |url| Forge::from_remote_url(url).unwrap();
+
+        assert_eq!(
+            forge("git@github.com:o/r.git").api_url().unwrap(),
+            "https://api.github.com"
+        );
+        assert_eq!(
+            forge("https://github.example.com/o/r").api_url().unwrap(),
+            "https://github.example.com/api/v3"
+        );
+        assert_eq!(
+            forge("git@gitlab.com:group/sub/r.git").api_url().unwrap(),
+            "https://gitlab.com/api/v4"
+        );
+        assert!(forge("git@codeberg.org:o/r.git").api_url().is_err());
+        assert_eq!(
+            forge("git@gitlab.com:o/r.git").pull_request_ref(3),
+            "refs/merge-requests/3/head"
+        );
+    }
+
+    #[test]
+    fn github_check_rollups() {
+        let state = |rollup| {
+            serde_json::from_str::<GitHubRollupPull>(&format!(
+                r#"{{"number": 1, "commits": {{"nodes": [{{"commit": {{"statusCheckRollup": {}}}}}]}}}}"#,
+                rollup
+            ))
+            .unwrap()
+            .state()
+        };
+
+        assert_eq!(state("null"), CiState::None);
+        assert_eq!(state(r#"{"state": "EXPECTED"}"#), CiState::Pending);
+        assert_eq!(state(r#"{"state": "ERROR"}"#), CiState::Failure);
+        assert_eq!(state(r#"{"state": "SUCCESS"}"#), CiState::Success);
+    }
+
+    #[test]
+    fn gitlab_head_pipelines() {
+        let state = |pipeline| {
+            let merge_request = serde_json::from_str::<GitLabPipelineMergeRequest>(&format!(
+                r#"{{"iid": "1", "headPipeline": {}}}"#,
+                pipeline
+            ))
+            .unwrap();
+            merge_request
+                .head_pipeline
+                .map_or(CiState::None, |pipeline| pipeline.state())
+        };
+
+        assert_eq!(state("null"), CiState::None);
+        assert_eq!(state(r#"{"status": "RUNNING"}"#), CiState::Pending);
+        assert_eq!(state(r#"{"status": "CANCELED"}"#), CiState::Failure);
+        assert_eq!(state(r#"{"status": "SUCCESS"}"#), CiState::Success);
+    }
+}
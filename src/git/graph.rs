@@ -0,0 +1,151 @@
+use git2::Oid;
+
+/// A two character wide column of a graph row, and the lane it belongs to.
+pub(crate) struct GraphCell {
+    pub text: String,
+    pub lane: usize,
+}
+
+/// Lays out commits, newest first, as a graph like `git log --graph` draws it.
+/// Every commit gets exactly one row, with merges and forks drawn on the same row.
+pub(crate) fn graph(commits: &[(Oid, Vec<Oid>)]) -> Vec<Vec<GraphCell>> {
+    // The commit each lane is waiting for
+    let mut lanes: Vec<Option<Oid>> = vec![];
+
+    commits
+        .iter()
+        .map(|(oid, parents)| {
+            let before = lanes.clone();
+            let col = match lanes.iter().position(|lane| lane == &Some(*oid)) {
+                Some(col) => col,
+                None => free_lane(&mut lanes, &[]),
+            };
+
+            let mut ends = vec![];
+            for (i, lane) in lanes.iter_mut().enumerate() {
+                if i != col && lane == &Some(*oid) {
+                    *lane = None;
+                    ends.push(i);
+                }
+            }
+
+            lanes[col] = parents.first().copied();
+            let mut joins = vec![];
+            let mut starts = vec![];
+            for parent in parents.iter().skip(1) {
+                match lanes.iter().position(|lane| lane == &Some(*parent)) {
+                    Some(i) => joins.push(i),
+                    None => {
+                        let i = free_lane(&mut lanes, &ends);
+                        lanes[i] = Some(*parent);
+                        starts.push(i);
+                    }
+                }
+            }
+
+            let connected = ends.iter().chain(&joins).chain(&starts);
+            let left = connected.clone().copied().min().unwrap_or(col).min(col);
+            let right = connected.copied().max().unwrap_or(col).max(col);
+
+            let width = before.len().max(lanes.len());
+            let mut row = (0..width)
+                .map(|i| {
+                    let passing = before.get(i).is_some_and(Option::is_some)
+                        && lanes.get(i).is_some_and(Option::is_some);
+                    let across = left < i && i < right;
+
+                    let symbol = if i == col {
+                        '●'
+                    } else if ends.contains(&i) {
+                        if i < col {
+                            '└'
+                        } else {
+                            '┘'
+                        }
+                    } else if starts.contains(&i) {
+                        if i < col {
+                            '┌'
+                        } else {
+                            '┐'
+                        }
+                    } else if joins.contains(&i) {
+                        if i < col {
+                            '├'
+                        } else {
+                            '┤'
+                        }
+                    } else if passing && across {
+                        '┼'
+                    } else if passing {
+                        '│'
+                    } else if across {
+                        '─'
+                    } else {
+                        ' '
+                    };
+
+                    let fill = if left <= i && i < right { '─' } else { ' ' };
+
+                    GraphCell {
+                        text: format!("{}{}", symbol, fill),
+                        lane: i,
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            while row.last().is_some_and(|cell| cell.text.trim().is_empty()) {
+                row.pop();
+            }
+
+            while lanes.last().is_some_and(Option::is_none) {
+                lanes.pop();
+            }
+
+            row
+        })
+        .collect()
+}
+
+/// The first lane not in use, other than `taken`, adding one if they all are.
+fn free_lane(lanes: &mut Vec<Option<Oid>>, taken: &[usize]) -> usize {
+    match (0..lanes.len()).find(|i| lanes[*i].is_none() && !taken.contains(i)) {
+        Some(i) => i,
+        None => {
+            lanes.push(None);
+            lanes.len() - 1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn oid(n: u8) -> Oid {
+        Oid::from_bytes(&[n; 20]).unwrap()
+    }
+
+    fn texts(rows: Vec<Vec<GraphCell>>) -> Vec<String> {
+        rows.into_iter()
+            .map(|row| row.into_iter().map(|cell| cell.text).collect::<String>())
+            .map(|text| text.trim_end().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn linear() {
+        let rows = graph(&[(oid(2), vec![oid(1)]), (oid(1), vec![])]);
+        assert_eq!(texts(rows), ["●", "●"]);
+    }
+
+    #[test]
+    fn merge() {
+        let rows = graph(&[
+            (oid(4), vec![oid(3), oid(2)]),
+            (oid(3), vec![oid(1)]),
+            (oid(2), vec![oid(1)]),
+            (oid(1), vec![]),
+        ]);
+        assert_eq!(texts(rows), ["●─┐", "● │", "│ ●", "●─┘"]);
+    }
+}
@@ -0,0 +1,49 @@
+use git2::Oid;
+use std::collections::{HashMap, HashSet};
+
+/// Commits whose history isn't what their objects say, which the log would otherwise show
+/// without a hint of why history seems missing or rewritten.
+#[derive(Default)]
+pub(crate) struct HistoryRewrites {
+    /// Objects replaced with `git replace`, and what replaces them
+    pub replaced: HashMap<Oid, Oid>,
+    /// Commits given other parents in `info/grafts`
+    pub grafted: HashSet<Oid>,
+    /// Commits of a shallow clone whose parents weren't fetched
+    pub shallow: HashSet<Oid>,
+}
+
+impl HistoryRewrites {
+    /// Short labels for the log.
+    pub(crate) fn labels(&self, oid: Oid) -> Vec<&'static str> {
+        [
+            (self.replaced.contains_key(&oid), "replaced"),
+            (self.grafted.contains(&oid), "grafted"),
+            (self.shallow.contains(&oid), "shallow"),
+        ]
+        .into_iter()
+        .filter(|(applies, _)| *applies)
+        .map(|(_, label)| label)
+        .collect()
+    }
+
+    /// What each rewrite means for the commit, for the show screen.
+    pub(crate) fn describe(&self, oid: Oid) -> Vec<String> {
+        let mut descriptions = vec![];
+
+        if let Some(replacement) = self.replaced.get(&oid) {
+            descriptions.push(format!(
+                "Replaced by {} with `git replace`, git shows that commit instead",
+                replacement
+            ));
+        }
+        if self.grafted.contains(&oid) {
+            descriptions.push("Grafted onto other parents in `info/grafts`".to_string());
+        }
+        if self.shallow.contains(&oid) {
+            descriptions.push("Shallow clone boundary, its parents weren't fetched".to_string());
+        }
+
+        descriptions
+    }
+}
@@ -0,0 +1,12 @@
+use git2::Oid;
+use std::collections::HashSet;
+
+/// Commits the log points out, relative to the checked out branch and its upstream.
+pub(crate) struct LogMarks {
+    pub head: Oid,
+    pub upstream: Option<Oid>,
+    /// Where HEAD and its upstream diverged.
+    pub merge_base: Option<Oid>,
+    /// Commits on HEAD that its upstream doesn't have.
+    pub local: HashSet<Oid>,
+}
@@ -0,0 +1,68 @@
+use crate::Res;
+use git2::{Oid, Repository};
+use std::{
+    path::Path,
+    sync::mpsc::{self, Receiver, SyncSender},
+    thread,
+};
+
+/// A walk of the log that goes on from where it was left, for loading it a page at a time.
+/// A `Revwalk` can't outlive the repository it borrows, so the walk runs on a thread
+/// with a repository of its own, staying a few commits ahead of what has been taken.
+pub(crate) struct LogWalk {
+    oids: Receiver<Result<Oid, String>>,
+}
+
+impl LogWalk {
+    /// Walks from `rev`, or HEAD, and with `topological` never lists a parent before its children.
+    pub(crate) fn start(repo: &Repository, rev: Option<Oid>, topological: bool) -> Self {
+        let path = repo.path().to_path_buf();
+        let (sender, oids) = mpsc::sync_channel(64);
+
+        thread::spawn(move || {
+            if let Err(err) = walk(&path, rev, topological, &sender) {
+                // Nothing is left to do if the log screen is gone
+                let _ = sender.send(Err(err.to_string()));
+            }
+        });
+
+        Self { oids }
+    }
+}
+
+impl Iterator for LogWalk {
+    type Item = Res<Oid>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let oid = self.oids.recv().ok()?;
+        Some(oid.map_err(Into::into))
+    }
+}
+
+fn walk(
+    path: &Path,
+    rev: Option<Oid>,
+    topological: bool,
+    sender: &SyncSender<Result<Oid, String>>,
+) -> Result<(), git2::Error> {
+    let repo = Repository::open(path)?;
+    let mut revwalk = repo.revwalk()?;
+    if let Some(rev) = rev {
+        revwalk.push(rev)?;
+    } else if revwalk.push_head().is_err() {
+        return Ok(());
+    }
+
+    if topological {
+        revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME)?;
+    }
+
+    for oid in revwalk {
+        // Stops once the walk is dropped
+        if sender.send(oid.map_err(|err| err.to_string())).is_err() {
+            break;
+        }
+    }
+
+    Ok(())
+}
@@ -1,19 +1,68 @@
 use git2::Repository;
 use itertools::Itertools;
 
-use self::{commit::Commit, diff::Diff, merge_status::MergeStatus, rebase_status::RebaseStatus};
+use self::{
+    absorb::{Absorb, AbsorbFixup},
+    bisect_status::{BisectRemaining, BisectStatus},
+    clean::{Clean, CleanEntry},
+    commit::Commit,
+    commit_picker::{CommitPicker, PickerCommit},
+    compare::{Compare, CompareSide, FileStat},
+    conflict::{Conflict, Side},
+    diff::{Diff, DiffOptions},
+    forge::Forge,
+    history_rewrites::HistoryRewrites,
+    log_marks::LogMarks,
+    merge_status::MergeStatus,
+    outgoing::{Outgoing, OutgoingCommit},
+    range_diff::RangeDiffPair,
+    rebase_status::RebaseStatus,
+    rebase_todo::{RebaseAction, RebaseTodo, RebaseTodoLine},
+    remote_refs::RemoteRef,
+    repo_info::{ObjectCounts, RemoteInfo, RepoInfo},
+    signature::SignatureStatus,
+    tag::Tag,
+    wip::WipSnapshot,
+    worktree::Worktree,
+};
 use crate::{config::Config, git2_opts, Res};
 use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    ffi::OsString,
     fs,
-    path::Path,
-    process::Command,
+    io::{Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    rc::Rc,
     str::{self},
 };
 
+pub(crate) mod absorb;
+pub(crate) mod apply_errors;
+pub(crate) mod bisect_status;
+pub(crate) mod clean;
 pub(crate) mod commit;
+pub(crate) mod commit_picker;
+pub(crate) mod compare;
+pub(crate) mod conflict;
 pub(crate) mod diff;
+pub(crate) mod forge;
+pub(crate) mod graph;
+pub(crate) mod history_rewrites;
+pub(crate) mod log_marks;
+pub(crate) mod log_walk;
 pub(crate) mod merge_status;
+pub(crate) mod outgoing;
+pub(crate) mod range_diff;
 pub(crate) mod rebase_status;
+pub(crate) mod rebase_todo;
+pub(crate) mod remote_refs;
+pub(crate) mod repo_info;
+pub(crate) mod signature;
+pub(crate) mod tag;
+pub(crate) mod whitespace;
+pub(crate) mod wip;
+pub(crate) mod worktree;
 
 // TODO Use only plumbing commands
 
@@ -35,6 +84,7 @@ pub(crate) fn rebase_status(repo: &Repository) -> Res<Option<RebaseStatus>> {
                     .strip_prefix("refs/heads/")
                     .unwrap()
                     .to_string(),
+                stopped_at: rebase_stopped_at(repo),
                 // TODO include log of 'done' items
             }))
         }
@@ -49,6 +99,181 @@ pub(crate) fn rebase_status(repo: &Repository) -> Res<Option<RebaseStatus>> {
     }
 }
 
+/// The last commit in `.git/rebase-merge/done`, which is the one being applied.
+fn rebase_stopped_at(repo: &Repository) -> Option<(String, String)> {
+    let done = fs::read_to_string(repo.path().join("rebase-merge/done")).ok()?;
+    let hash = done.lines().last()?.split_whitespace().nth(1)?;
+    let commit = repo.revparse_single(hash).ok()?.peel_to_commit().ok()?;
+    Some((
+        short_id(&commit),
+        commit.summary().unwrap_or("").to_string(),
+    ))
+}
+
+/// Lists the commits a `git rebase -i <base>` would put in its todo, oldest first.
+pub(crate) fn rebase_todo(repo: &Repository, base: &str, args: Vec<OsString>) -> Res<RebaseTodo> {
+    let base_commit = repo.revparse_single(base)?.peel_to_commit()?;
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push_head()?;
+    revwalk.hide(base_commit.id())?;
+    revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::REVERSE)?;
+
+    let lines = revwalk
+        .map(|oid| -> Res<git2::Commit> { Ok(repo.find_commit(oid?)?) })
+        .filter_ok(|commit| commit.parent_count() <= 1)
+        .map_ok(|commit| RebaseTodoLine {
+            action: RebaseAction::Pick,
+            hash: short_id(&commit),
+            summary: commit.summary().unwrap_or("").to_string(),
+        })
+        .collect::<Res<Vec<_>>>()?;
+
+    let autosquash = args.iter().any(|arg| arg == "--autosquash");
+    let mut todo = RebaseTodo {
+        base: short_id(&base_commit),
+        args,
+        lines,
+        file: None,
+    };
+
+    if autosquash {
+        todo.autosquash();
+    }
+
+    Ok(todo)
+}
+
+/// Compares `ours` with `theirs`, revs of any kind, from where they diverged.
+pub(crate) fn compare(repo: &Repository, ours: &str, theirs: &str) -> Res<Compare> {
+    let ours_commit = repo.revparse_single(ours)?.peel_to_commit()?;
+    let theirs_commit = repo.revparse_single(theirs)?.peel_to_commit()?;
+    let merge_base = repo
+        .merge_base(ours_commit.id(), theirs_commit.id())
+        .map_err(|_| format!("{} and {} have no history in common", ours, theirs))?;
+    let base_tree = repo.find_commit(merge_base)?.tree()?;
+    let (ahead, behind) = repo.graph_ahead_behind(ours_commit.id(), theirs_commit.id())?;
+
+    let side = |name: &str, commit: &git2::Commit, count| -> Res<CompareSide> {
+        Ok(CompareSide {
+            name: name.to_string(),
+            id: commit.id(),
+            count,
+            stats: file_stats(repo, &base_tree, &commit.tree()?)?,
+        })
+    };
+
+    Ok(Compare {
+        merge_base,
+        ours: side(ours, &ours_commit, ahead)?,
+        theirs: side(theirs, &theirs_commit, behind)?,
+    })
+}
+
+/// Lines added and removed per file, binary files count as neither.
+fn file_stats(repo: &Repository, old: &git2::Tree, new: &git2::Tree) -> Res<Vec<FileStat>> {
+    let diff = repo.diff_tree_to_tree(Some(old), Some(new), None)?;
+
+    (0..diff.deltas().len())
+        .map(|i| {
+            let delta = diff.get_delta(i).unwrap();
+            let path = delta
+                .new_file()
+                .path()
+                .or(delta.old_file().path())
+                .map(|path| path.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let (_, insertions, deletions) = match git2::Patch::from_diff(&diff, i)? {
+                Some(patch) => patch.line_stats()?,
+                None => (0, 0, 0),
+            };
+
+            Ok(FileStat {
+                path,
+                insertions,
+                deletions,
+            })
+        })
+        .collect()
+}
+
+/// How many of the most recent commits `absorb` considers, like `git absorb`.
+const ABSORB_MAX_STACK: usize = 10;
+const COMMIT_PICKER_LIMIT: usize = 100;
+
+/// Finds the commit each staged hunk should be absorbed into. That's the one commit, not yet
+/// pushed upstream and among the most recent ones, that last touched all lines the hunk changes.
+pub(crate) fn absorb(config: &Config, repo: &Repository) -> Res<Absorb> {
+    let head = repo.head()?;
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push_head()?;
+    if let Ok(upstream) = git2::Branch::wrap(head).upstream() {
+        if let Some(upstream_id) = upstream.get().target() {
+            revwalk.hide(upstream_id)?;
+        }
+    }
+    let stack = revwalk
+        .take(ABSORB_MAX_STACK)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // By position in the stack, so the newest commit comes first
+    let mut fixups = BTreeMap::<usize, Diff>::new();
+    let mut unabsorbed = Diff { deltas: vec![] };
+
+    for delta in diff_staged(config, &DiffOptions::new(config), repo)?.deltas {
+        let blame = match delta.status {
+            git2::Delta::Modified => Some(repo.blame_file(&delta.old_file, None)?),
+            _ => None,
+        };
+
+        for hunk in &delta.hunks {
+            let target = blame.as_ref().and_then(|blame| {
+                hunk.old_lines_touched()
+                    .into_iter()
+                    .map(|line| {
+                        let commit = blame.get_line(line)?.final_commit_id();
+                        stack.iter().position(|id| id == &commit)
+                    })
+                    .reduce(|a, b| if a == b { a } else { None })
+                    .flatten()
+            });
+
+            match target {
+                Some(i) => fixups
+                    .entry(i)
+                    .or_insert_with(|| Diff { deltas: vec![] })
+                    .push_hunk(&delta, Rc::clone(hunk)),
+                None => unabsorbed.push_hunk(&delta, Rc::clone(hunk)),
+            }
+        }
+    }
+
+    Ok(Absorb {
+        fixups: fixups
+            .into_iter()
+            .map(|(i, diff)| -> Res<AbsorbFixup> {
+                let commit = repo.find_commit(stack[i])?;
+                Ok(AbsorbFixup {
+                    commit: commit.id().to_string(),
+                    hash: short_id(&commit),
+                    summary: commit.summary().unwrap_or("").to_string(),
+                    diff,
+                })
+            })
+            .collect::<Res<_>>()?,
+        unabsorbed,
+    })
+}
+
+pub(crate) fn short_id(commit: &git2::Commit) -> String {
+    commit
+        .as_object()
+        .short_id()
+        .ok()
+        .and_then(|id| id.as_str().map(str::to_string))
+        .unwrap_or_else(|| commit.id().to_string())
+}
+
 pub(crate) fn merge_status(repo: &Repository) -> Res<Option<MergeStatus>> {
     let dir = repo.workdir().expect("No workdir");
     let mut merge_head_file = dir.to_path_buf();
@@ -99,6 +324,1030 @@ pub(crate) fn revert_status(repo: &Repository) -> Res<Option<RevertStatus>> {
     }
 }
 
+pub(crate) struct CherryPickStatus {
+    pub head: String,
+}
+
+pub(crate) fn cherry_pick_status(repo: &Repository) -> Res<Option<CherryPickStatus>> {
+    let dir = repo.workdir().expect("No workdir");
+    // In `repo.path()`, which a linked worktree has of its own
+    let cherry_pick_head_file = repo.path().join("CHERRY_PICK_HEAD");
+
+    match fs::read_to_string(&cherry_pick_head_file) {
+        Ok(content) => {
+            let head = content.trim();
+            Ok(Some(CherryPickStatus {
+                head: branch_name(dir, head)?.unwrap_or(head.get(..7).unwrap_or(head).to_string()),
+            }))
+        }
+        Err(err) => {
+            log::warn!(
+                "Couldn't read {}, due to {}",
+                cherry_pick_head_file.to_string_lossy(),
+                err
+            );
+            Ok(None)
+        }
+    }
+}
+
+pub(crate) fn bisect_status(repo: &Repository) -> Res<Option<BisectStatus>> {
+    if !repo.path().join("BISECT_START").exists() {
+        return Ok(None);
+    }
+
+    let dir = repo.workdir().expect("No workdir");
+    let log = git_output(dir, &["bisect", "log"])?
+        .lines()
+        .filter_map(|line| line.strip_prefix("# "))
+        .map(str::to_string)
+        .collect();
+
+    let goods = git_output(
+        dir,
+        &["for-each-ref", "--format=%(refname)", "refs/bisect/good-*"],
+    )?;
+    let has_bad = repo.find_reference("refs/bisect/bad").is_ok();
+
+    let mut status = BisectStatus {
+        candidate: repo.head()?.peel_to_commit()?.id().to_string(),
+        first_bad: None,
+        remaining: None,
+        log,
+    };
+
+    if !has_bad || goods.trim().is_empty() {
+        return Ok(Some(status));
+    }
+
+    let vars = git_output(
+        dir,
+        &["rev-list", "--bisect-vars", "refs/bisect/bad", "--not"]
+            .into_iter()
+            .chain(goods.lines())
+            .collect::<Vec<_>>(),
+    )?;
+
+    let var = |name: &str| {
+        vars.lines()
+            .find_map(|line| line.strip_prefix(name)?.strip_prefix('='))
+            .map(|value| value.trim_matches('\'').to_string())
+    };
+    let count = |name: &str| var(name).and_then(|value| value.parse::<usize>().ok());
+
+    if count("bisect_all") == Some(1) {
+        status.first_bad = var("bisect_rev");
+    } else if let (Some(revisions), Some(steps)) = (count("bisect_nr"), count("bisect_steps")) {
+        status.remaining = Some(BisectRemaining { revisions, steps });
+    }
+
+    Ok(Some(status))
+}
+
+/// Locations of whitespace errors in the staged changes, or all changes if `all` is set.
+pub(crate) fn whitespace_errors(repo: &Repository, all: bool) -> Res<Vec<String>> {
+    let dir = repo.workdir().expect("No workdir");
+    let mut cmd = Command::new("git");
+    cmd.arg("diff");
+    if !all {
+        cmd.arg("--cached");
+    } else if repo.head().is_ok() {
+        cmd.arg("HEAD");
+    } else {
+        // HEAD is unborn, so everything there is would be committed
+        cmd.arg(repo.treebuilder(None)?.write()?.to_string());
+    }
+
+    let out = cmd.arg("--check").current_dir(dir).output()?;
+
+    // It exits with 2 when there are any
+    if !out.status.success() && out.status.code() != Some(2) {
+        return Err(String::from_utf8_lossy(&out.stderr).into_owned().into());
+    }
+
+    Ok(str::from_utf8(&out.stdout)?
+        .lines()
+        .filter(|line| !line.starts_with('+'))
+        .map(|line| line.trim_end_matches('.').to_string())
+        .collect())
+}
+
+/// Tags ordered by `sort` (any `git tag --sort` key), or git's `tag.sort` config if `None`.
+/// A `pattern` without wildcards matches tags containing it.
+pub(crate) fn tags(repo: &Repository, sort: Option<&str>, pattern: Option<&str>) -> Res<Vec<Tag>> {
+    let dir = repo.workdir().expect("No workdir");
+    let sort = sort.map(|sort| format!("--sort={}", sort));
+    let pattern = pattern.map(|pattern| {
+        if pattern.contains(['*', '?', '[']) {
+            pattern.to_string()
+        } else {
+            format!("*{}*", pattern)
+        }
+    });
+
+    let mut args = vec![
+        "tag",
+        "--list",
+        "--format=%(refname:lstrip=2)%00%(creatordate:short)%00%(if)%(*subject)%(then)%(*subject)%(else)%(subject)%(end)",
+    ];
+    args.extend(sort.as_deref());
+    args.extend(pattern.as_deref());
+
+    Ok(git_output(dir, &args)?
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\0');
+            Some(Tag {
+                name: fields.next()?.to_string(),
+                date: fields.next()?.to_string(),
+                subject: fields.next()?.to_string(),
+            })
+        })
+        .collect())
+}
+
+/// Recent commits on HEAD, marking the ones that touch any of the staged files
+/// and the ones absorb would create fixups for.
+/// Only those are shown at first, unless there are none.
+pub(crate) fn commit_picker(
+    config: &Config,
+    repo: &Repository,
+    flag: &'static str,
+    args: Vec<OsString>,
+) -> Res<CommitPicker> {
+    let staged = diff_staged(config, &DiffOptions::new(config), repo)?
+        .deltas
+        .into_iter()
+        .flat_map(|delta| [delta.old_file, delta.new_file])
+        .unique()
+        .collect::<Vec<_>>();
+
+    let suggested = absorb(config, repo)?
+        .fixups
+        .into_iter()
+        .map(|fixup| fixup.commit)
+        .collect::<Vec<_>>();
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push_head()?;
+
+    let commits = revwalk
+        .take(COMMIT_PICKER_LIMIT)
+        .map(|id| {
+            let commit = repo.find_commit(id?)?;
+            let touches_staged = !staged.is_empty() && {
+                let mut opts = git2::DiffOptions::new();
+                staged.iter().for_each(|path| {
+                    opts.pathspec(path);
+                });
+                opts.disable_pathspec_match(true);
+
+                let parent_tree = commit.parent(0).ok().map(|p| p.tree()).transpose()?;
+                let diff = repo.diff_tree_to_tree(
+                    parent_tree.as_ref(),
+                    Some(&commit.tree()?),
+                    Some(&mut opts),
+                )?;
+                diff.deltas().len() > 0
+            };
+
+            let id = commit.id().to_string();
+            Ok(PickerCommit {
+                suggested: suggested.contains(&id),
+                commit: id,
+                hash: short_id(&commit),
+                summary: commit.summary().unwrap_or("").to_string(),
+                touches_staged,
+            })
+        })
+        .collect::<Res<Vec<_>>>()?;
+
+    Ok(CommitPicker {
+        flag,
+        args,
+        show_all: !commits.iter().any(|commit| commit.touches_staged),
+        commits,
+        filter: String::new(),
+        instant: false,
+    })
+}
+
+/// What `git clean -d <args>` would remove, with every entry selected. It's listed by
+/// `git ls-files`, whose `-z` output is neither translated nor quoted.
+pub(crate) fn clean_preview(repo: &Repository, args: Vec<OsString>) -> Res<Clean> {
+    let mut cmd = Command::new("git");
+    cmd.args(["ls-files", "--others", "--directory", "-z"]);
+    if !args.iter().any(|arg| arg == "-x") {
+        cmd.arg("--exclude-standard");
+    }
+    let out = cmd
+        .current_dir(repo.workdir().expect("No workdir"))
+        .output()?;
+
+    if !out.status.success() {
+        return Err(String::from_utf8_lossy(&out.stderr).into_owned().into());
+    }
+
+    let entries = out
+        .stdout
+        .split(|&byte| byte == b'\0')
+        .filter(|path| !path.is_empty())
+        .map(|path| CleanEntry {
+            path: String::from_utf8_lossy(path).into_owned(),
+            selected: true,
+        })
+        .collect();
+
+    Ok(Clean { args, entries })
+}
+
+/// Short name of the upstream of the checked out branch.
+pub(crate) fn head_upstream(repo: &Repository) -> Option<String> {
+    let branch = git2::Branch::wrap(repo.head().ok()?);
+    let upstream = branch.upstream().ok()?;
+    upstream.name().ok()?.map(String::from)
+}
+
+/// The upstream of the local branch `branch`, if it has one.
+pub(crate) fn upstream_of(repo: &Repository, branch: &str) -> Option<String> {
+    let branch = repo.find_branch(branch, git2::BranchType::Local).ok()?;
+    let upstream = branch.upstream().ok()?;
+    upstream.name().ok()?.map(String::from)
+}
+
+/// Replace refs, grafts and the boundary of a shallow clone.
+pub(crate) fn history_rewrites(repo: &Repository) -> HistoryRewrites {
+    let replaced = repo
+        .references_glob("refs/replace/*")
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .filter_map(|reference| {
+            let replaced = reference.name()?.strip_prefix("refs/replace/")?;
+            Some((git2::Oid::from_str(replaced).ok()?, reference.target()?))
+        })
+        .collect();
+
+    // Linked worktrees share these with the main one
+    let common_dir = match fs::read_to_string(repo.path().join("commondir")) {
+        Ok(common_dir) => repo.path().join(common_dir.trim()),
+        Err(_) => repo.path().to_path_buf(),
+    };
+
+    // Both list one commit per line, grafts followed by the parents it's given
+    let first_oids = |file: &str| -> HashSet<git2::Oid> {
+        fs::read_to_string(common_dir.join(file))
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| line.split_whitespace().next())
+            .filter_map(|oid| git2::Oid::from_str(oid).ok())
+            .collect()
+    };
+
+    HistoryRewrites {
+        replaced,
+        grafted: first_oids("info/grafts"),
+        shallow: first_oids("shallow"),
+    }
+}
+
+/// HEAD, its upstream and the commits between them, to mark in the log.
+pub(crate) fn log_marks(repo: &Repository) -> Res<LogMarks> {
+    let head = repo.head()?.peel_to_commit()?.id();
+    let upstream = head_upstream(repo)
+        .and_then(|name| repo.revparse_single(&name).ok())
+        .and_then(|object| object.peel_to_commit().ok())
+        .map(|commit| commit.id());
+
+    let Some(upstream) = upstream else {
+        return Ok(LogMarks {
+            head,
+            upstream: None,
+            merge_base: None,
+            local: HashSet::new(),
+        });
+    };
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(head)?;
+    revwalk.hide(upstream)?;
+
+    Ok(LogMarks {
+        head,
+        upstream: Some(upstream),
+        merge_base: repo.merge_base(head, upstream).ok(),
+        local: revwalk.collect::<Result<_, _>>()?,
+    })
+}
+
+/// The remote of the checked out branch's upstream.
+pub(crate) fn head_remote(repo: &Repository) -> Option<String> {
+    let head = repo.head().ok()?;
+    let remote = repo.branch_upstream_remote(head.name()?).ok()?;
+    remote.as_str().map(String::from)
+}
+
+/// The remote-tracking ref of the branch that `git push` updates, if the checked out branch
+/// is pushed somewhere else than its upstream with `branch.<name>.pushRemote` or `remote.pushDefault`.
+pub(crate) fn head_push_ref(repo: &Repository) -> Option<String> {
+    let head = repo.head().ok()?;
+    if !head.is_branch() {
+        return None;
+    }
+
+    let branch = head.shorthand()?;
+    let config = repo.config().ok()?;
+    let remote = config
+        .get_string(&format!("branch.{}.pushRemote", branch))
+        .or_else(|_| config.get_string("remote.pushDefault"))
+        .ok()?;
+
+    Some(format!("refs/remotes/{}/{}", remote, branch))
+}
+
+/// The forge that the `origin` remote is hosted on.
+pub(crate) fn origin_forge(repo: &Repository) -> Res<Forge> {
+    let remote = repo
+        .find_remote("origin")
+        .map_err(|_| "No remote named origin")?;
+    let url = remote.url().ok_or("The origin remote has no URL")?;
+
+    Forge::from_remote_url(url)
+        .ok_or_else(|| format!("Couldn't recognize a forge from the origin URL {}", url).into())
+}
+
+/// The refs a remote currently has, like `git ls-remote <remote>`. Doesn't fetch anything.
+pub(crate) fn remote_refs(repo: &Repository, remote: &str) -> Res<Vec<RemoteRef>> {
+    let out = Command::new("git")
+        .args(["ls-remote", remote])
+        .current_dir(repo.workdir().expect("No workdir"))
+        // A credential prompt would hang the UI
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .output()?;
+
+    if !out.status.success() {
+        return Err(String::from_utf8_lossy(&out.stderr).into_owned().into());
+    }
+
+    let mut refs = remote_refs::parse(str::from_utf8(&out.stdout)?);
+
+    for remote_ref in refs.iter_mut() {
+        remote_ref.missing = git2::Oid::from_str(&remote_ref.hash)
+            .and_then(|oid| repo.find_object(oid, None))
+            .is_err();
+    }
+
+    Ok(refs)
+}
+
+/// Changes in the working tree and index since `rev` and HEAD diverged, like
+/// `git diff $(git merge-base <rev> HEAD)`. Also returns the short hash of the merge-base.
+pub(crate) fn diff_merge_base(
+    config: &Config,
+    options: &DiffOptions,
+    repo: &Repository,
+    rev: &str,
+) -> Res<(String, Diff)> {
+    let other = repo.revparse_single(rev)?.peel_to_commit()?;
+    let head = repo.head()?.peel_to_commit()?;
+    let merge_base = repo.find_commit(repo.merge_base(other.id(), head.id())?)?;
+
+    let diff = repo.diff_tree_to_workdir_with_index(
+        Some(&merge_base.tree()?),
+        Some(&mut git2_opts::diff(repo)?),
+    )?;
+
+    Ok((
+        short_id(&merge_base),
+        diff::convert_diff(config, options, repo, diff, true)?,
+    ))
+}
+
+/// Hidden ref whose reflog keeps a `WipSnapshot` per entry.
+pub(crate) const WIP_REF: &str = "refs/gitu/wip";
+
+/// Records the uncommitted changes to tracked files, like a stash that isn't put on the stash list.
+/// Does nothing if there aren't any.
+pub(crate) fn wip_snapshot(repo: &Repository, message: &str) -> Res<()> {
+    let workdir = repo.workdir().expect("No workdir");
+    let out = Command::new("git")
+        .args(["stash", "create", message])
+        .current_dir(workdir)
+        .output()?;
+
+    if !out.status.success() {
+        return Err(String::from_utf8_lossy(&out.stderr).into_owned().into());
+    }
+
+    let commit = str::from_utf8(&out.stdout)?.trim();
+    if commit.is_empty() {
+        return Ok(());
+    }
+
+    record_wip_snapshot(workdir, message, commit)
+}
+
+/// Like `wip_snapshot`, but also records the untracked `files` (and what's in untracked
+/// directories among them), like `git stash --include-untracked`, so that applying the
+/// snapshot brings them back. Ignored files are only recorded with `ignored`.
+/// Does nothing on an unborn HEAD, as there's nothing to record the changes on top of.
+pub(crate) fn wip_snapshot_untracked(
+    repo: &Repository,
+    message: &str,
+    files: &[PathBuf],
+    ignored: bool,
+) -> Res<()> {
+    if repo.head().is_err() {
+        return Ok(());
+    }
+
+    let workdir = repo.workdir().expect("No workdir");
+    let untracked_tree = untracked_tree(repo, files, ignored)?;
+    let untracked = git_output(
+        workdir,
+        &["commit-tree", &untracked_tree, "-m", "untracked files"],
+    )?;
+
+    // A stash's second parent records the index, its tree the worktree
+    let stash = git_output(workdir, &["stash", "create", message])?;
+    let (tree, index) = match stash.trim() {
+        "" => {
+            let index_tree = git_output(workdir, &["write-tree"])?;
+            let index = git_output(
+                workdir,
+                &[
+                    "commit-tree",
+                    index_tree.trim(),
+                    "-p",
+                    "HEAD",
+                    "-m",
+                    "index",
+                ],
+            )?;
+            ("HEAD^{tree}".to_string(), index.trim().to_string())
+        }
+        stash => (format!("{}^{{tree}}", stash), format!("{}^2", stash)),
+    };
+
+    let commit = git_output(
+        workdir,
+        &[
+            "commit-tree",
+            &tree,
+            "-p",
+            "HEAD",
+            "-p",
+            &index,
+            "-p",
+            untracked.trim(),
+            "-m",
+            message,
+        ],
+    )?;
+    record_wip_snapshot(workdir, message, commit.trim())
+}
+
+/// Writes a tree of the untracked `files` alone, staging them in an index of its own.
+fn untracked_tree(repo: &Repository, files: &[PathBuf], ignored: bool) -> Res<String> {
+    let workdir = repo.workdir().expect("No workdir");
+    let index = repo.path().join("gitu-untracked-index");
+
+    let git = |args: &[&str]| {
+        let mut cmd = Command::new("git");
+        cmd.env("GIT_INDEX_FILE", &index);
+        cmd.args(args);
+        cmd.current_dir(workdir);
+        cmd
+    };
+
+    let mut add = git(&["--literal-pathspecs", "add"]);
+    if ignored {
+        add.arg("--force");
+    }
+    add.arg("--").args(files);
+
+    let tree = command_output(&mut add).and_then(|_| command_output(&mut git(&["write-tree"])));
+    let _ = fs::remove_file(&index);
+    Ok(tree?.trim().to_string())
+}
+
+fn record_wip_snapshot(workdir: &Path, message: &str, commit: &str) -> Res<()> {
+    git_output(
+        workdir,
+        &[
+            "update-ref",
+            "--create-reflog",
+            "-m",
+            message,
+            WIP_REF,
+            commit,
+        ],
+    )?;
+    Ok(())
+}
+
+/// The recorded `WipSnapshot`s, newest first.
+pub(crate) fn wip_snapshots(repo: &Repository) -> Res<Vec<WipSnapshot>> {
+    let Ok(reflog) = repo.reflog(WIP_REF) else {
+        return Ok(vec![]);
+    };
+
+    reflog
+        .iter()
+        .map(|entry| {
+            let commit = repo.find_commit(entry.id_new())?;
+            Ok(WipSnapshot {
+                commit: commit.id().to_string(),
+                hash: short_id(&commit),
+                message: entry.message().unwrap_or("").to_string(),
+            })
+        })
+        .collect()
+}
+
+/// What to compare in a diff of arbitrary revisions.
+#[derive(Clone)]
+pub(crate) enum RevDiff {
+    /// The working tree against the index, like `git diff`
+    Unstaged,
+    /// The index against HEAD, like `git diff --cached`
+    Staged,
+    /// The working tree against a rev, like `git diff <rev>`
+    Worktree(String),
+    /// The index against a rev, like `git diff --cached <rev>`
+    Index(String),
+    /// One rev against another, like `git diff <old>..<new>`
+    Range(String, String),
+    /// One file of the working tree against another, like `git diff --no-index <old> <new>`
+    Files(PathBuf, PathBuf),
+    /// Two files or directories anywhere, not necessarily in a repository, like
+    /// `gitu diff --no-index <old> <new>`. Relative paths are relative to where gitu was started.
+    NoIndex(PathBuf, PathBuf),
+}
+
+pub(crate) fn diff_revs(
+    config: &Config,
+    options: &DiffOptions,
+    repo: &Repository,
+    rev_diff: &RevDiff,
+) -> Res<Diff> {
+    let opts = &mut git2_opts::diff(repo)?;
+    let tree = |rev: &str| repo.revparse_single(rev)?.peel_to_tree();
+
+    match rev_diff {
+        RevDiff::Unstaged => diff_unstaged(config, options, repo),
+        RevDiff::Staged => diff_staged(config, options, repo),
+        RevDiff::Worktree(rev) => {
+            let diff = repo.diff_tree_to_workdir_with_index(Some(&tree(rev)?), Some(opts))?;
+            diff::convert_diff(config, options, repo, diff, true)
+        }
+        RevDiff::Index(rev) => {
+            let diff = repo.diff_tree_to_index(Some(&tree(rev)?), None, Some(opts))?;
+            diff::convert_diff(config, options, repo, diff, false)
+        }
+        RevDiff::Range(old, new) => {
+            let diff = repo.diff_tree_to_tree(Some(&tree(old)?), Some(&tree(new)?), Some(opts))?;
+            diff::convert_diff(config, options, repo, diff, false)
+        }
+        RevDiff::Files(old, new) => diff::convert_workdir_files(config, options, repo, old, new),
+        RevDiff::NoIndex(old, new) => diff::convert_paths(config, options, repo, old, new),
+    }
+}
+
+/// Commits on HEAD that its upstream doesn't have, or that no remote has if there's no upstream.
+pub(crate) fn outgoing(config: &Config, options: &DiffOptions, repo: &Repository) -> Res<Outgoing> {
+    let head = repo.head()?.peel_to_commit()?;
+    let upstream = head_upstream(repo);
+    let upstream_commit = match &upstream {
+        Some(name) => Some(repo.revparse_single(name)?.peel_to_commit()?),
+        None => None,
+    };
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(head.id())?;
+    match &upstream_commit {
+        Some(commit) => revwalk.hide(commit.id())?,
+        None => revwalk.hide_glob("refs/remotes/*")?,
+    }
+    let commits = revwalk
+        .map(|id| Ok(repo.find_commit(id?)?))
+        .collect::<Res<Vec<_>>>()?;
+
+    let base = match (&upstream_commit, commits.last()) {
+        (Some(commit), _) => Some(repo.find_commit(repo.merge_base(commit.id(), head.id())?)?),
+        (None, Some(oldest)) => oldest.parent(0).ok(),
+        (None, None) => Some(head.clone()),
+    };
+    let diff = repo.diff_tree_to_tree(
+        base.map(|base| base.tree()).transpose()?.as_ref(),
+        Some(&head.tree()?),
+        Some(&mut git2_opts::diff(repo)?),
+    )?;
+
+    Ok(Outgoing {
+        upstream,
+        commits: commits
+            .iter()
+            .map(|commit| OutgoingCommit {
+                commit: commit.id().to_string(),
+                hash: short_id(commit),
+                summary: commit.summary().unwrap_or("").to_string(),
+            })
+            .collect(),
+        diff: diff::convert_diff(config, options, repo, diff, false)?,
+    })
+}
+
+/// How the commits of `new` differ from those of `old`, like `git range-diff <old>...<new>`.
+pub(crate) fn range_diff(repo: &Repository, old: &str, new: &str) -> Res<Vec<RangeDiffPair>> {
+    let out = Command::new("git")
+        .args(["range-diff", "--no-color", &format!("{}...{}", old, new)])
+        .current_dir(repo.workdir().expect("No workdir"))
+        .output()?;
+
+    if !out.status.success() {
+        return Err(String::from_utf8_lossy(&out.stderr).into_owned().into());
+    }
+
+    Ok(range_diff::parse(str::from_utf8(&out.stdout)?))
+}
+
+/// Commits touching `path`, newest first, like `git log --follow -- <path>`.
+pub(crate) fn file_history(repo: &Repository, path: &Path) -> Res<Vec<git2::Oid>> {
+    let dir = repo.workdir().expect("No workdir");
+    let path = path.to_str().ok_or("Path is not valid UTF-8")?;
+
+    git_output(dir, &["log", "--follow", "--format=%H", "--", path])?
+        .lines()
+        .map(|line| Ok(git2::Oid::from_str(line)?))
+        .collect()
+}
+
+/// Commits changing a region of `path` as it is in HEAD, newest first, like `git log -L <range>:<path>`.
+/// The range is anything `-L` takes, like `<start>,<end>` or `:<funcname>`.
+pub(crate) fn line_history(repo: &Repository, path: &Path, range: &str) -> Res<Vec<git2::Oid>> {
+    let dir = repo.workdir().expect("No workdir");
+    let path = path.to_str().ok_or("Path is not valid UTF-8")?;
+    let range = format!("{}:{}", range, path);
+
+    git_output(dir, &["log", "-L", &range, "--no-patch", "--format=%H"])?
+        .lines()
+        .map(|line| Ok(git2::Oid::from_str(line)?))
+        .collect()
+}
+
+/// The conflict markers left in a file of the worktree.
+pub(crate) fn conflicts(repo: &Repository, path: &Path) -> Res<Vec<Conflict>> {
+    let content = fs::read_to_string(repo.workdir().expect("No workdir").join(path))?;
+    Ok(conflict::parse(&content))
+}
+
+/// Resolves the `index`th conflict of a file in the worktree by keeping one side of it.
+pub(crate) fn resolve_conflict(
+    repo: &Repository,
+    path: &Path,
+    index: usize,
+    side: Side,
+) -> Res<()> {
+    let file = repo.workdir().expect("No workdir").join(path);
+    let content = fs::read_to_string(&file)?;
+    let resolved = conflict::resolve(&content, index, side).ok_or("Conflict no longer exists")?;
+    fs::write(file, resolved)?;
+    Ok(())
+}
+
+/// The lock git holds on the index while changing it. In a linked worktree, this is the one of its own index.
+/// If it's left behind, another git process is running or crashed.
+pub(crate) fn index_lock(repo: &Repository) -> Option<PathBuf> {
+    Some(repo.path().join("index.lock")).filter(|lock| lock.exists())
+}
+
+/// Adds files to the `.gitignore` at the root of the worktree, each anchored to its own path.
+pub(crate) fn ignore(repo: &Repository, files: &[PathBuf]) -> Res<()> {
+    let gitignore = repo.workdir().expect("No workdir").join(".gitignore");
+    // Only appended to, what's there already is never rewritten
+    let mut gitignore = fs::OpenOptions::new()
+        .read(true)
+        .append(true)
+        .create(true)
+        .open(gitignore)?;
+
+    let mut content = String::new();
+    if gitignore.seek(SeekFrom::End(-1)).is_ok() {
+        let mut last = [0];
+        gitignore.read_exact(&mut last)?;
+        if last != *b"\n" {
+            content.push('\n');
+        }
+    }
+
+    for file in files {
+        let path = file.to_str().ok_or("Path is not valid UTF-8")?;
+        content.push('/');
+        for c in path.chars() {
+            if matches!(c, '*' | '?' | '[' | '\\') {
+                content.push('\\');
+            }
+            content.push(c);
+        }
+        content.push('\n');
+    }
+
+    gitignore.write_all(content.as_bytes())?;
+    Ok(())
+}
+
+/// The upstream of a local branch, if it's configured but no longer exists,
+/// like when the remote branch was deleted after merging.
+pub(crate) fn gone_upstream(repo: &Repository, refname: &str) -> Option<String> {
+    let upstream = repo.branch_upstream_name(refname).ok()?;
+    let upstream = upstream.as_str()?;
+    if repo.find_reference(upstream).is_ok() {
+        return None;
+    }
+
+    Some(
+        upstream
+            .strip_prefix("refs/remotes/")
+            .unwrap_or(upstream)
+            .to_string(),
+    )
+}
+
+/// The upstream of the local branch `refname` and how many commits the branch is ahead
+/// and behind it. `None` without an upstream, or when it's gone.
+pub(crate) fn upstream_ahead_behind(
+    repo: &Repository,
+    refname: &str,
+) -> Option<(String, usize, usize)> {
+    let upstream = repo.branch_upstream_name(refname).ok()?;
+    let upstream = repo.find_reference(upstream.as_str()?).ok()?;
+    let branch = repo.find_reference(refname).ok()?;
+    let (ahead, behind) = repo
+        .graph_ahead_behind(branch.target()?, upstream.target()?)
+        .ok()?;
+
+    Some((upstream.shorthand()?.to_string(), ahead, behind))
+}
+
+/// Whether `git branch -d` would delete the local `branch`: it's merged into its upstream,
+/// or into HEAD when it has none.
+pub(crate) fn is_merged(repo: &Repository, branch: &str) -> Res<bool> {
+    let local = repo.find_branch(branch, git2::BranchType::Local)?;
+    let into = match local.upstream() {
+        Ok(upstream) => upstream.get().target(),
+        Err(_) => repo.head()?.target(),
+    }
+    .ok_or("No commit to compare with")?;
+    let id = local.get().target().ok_or("Branch has no commit")?;
+
+    Ok(id == into || repo.graph_descendant_of(into, id)?)
+}
+
+/// Local branches whose upstream is gone, except the checked out one.
+pub(crate) fn gone_branches(repo: &Repository) -> Res<Vec<String>> {
+    let head = repo
+        .head()
+        .ok()
+        .and_then(|head| head.name().map(String::from));
+
+    Ok(repo
+        .branches(Some(git2::BranchType::Local))?
+        .filter_map(Result::ok)
+        .filter_map(|(branch, _)| {
+            let refname = branch.get().name()?;
+            if Some(refname) == head.as_deref() {
+                return None;
+            }
+
+            gone_upstream(repo, refname)?;
+            branch.name().ok()?.map(String::from)
+        })
+        .collect())
+}
+
+/// A commit message the way git would commit it: without comments, the diff
+/// below the scissors line that `--verbose` adds, or surrounding whitespace.
+pub(crate) fn strip_commit_message(repo: &Repository, content: &str) -> String {
+    let comment_char = repo
+        .config()
+        .and_then(|config| config.get_string("core.commentChar"))
+        .ok()
+        .filter(|comment_char| comment_char.chars().count() == 1)
+        .unwrap_or_else(|| "#".to_string());
+    let scissors = format!(
+        "{} ------------------------ >8 ------------------------",
+        comment_char
+    );
+
+    content
+        .lines()
+        .take_while(|line| *line != scissors)
+        .filter(|line| !line.starts_with(&comment_char))
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+/// A git config value holding a list separated by whitespace, if it's set.
+pub(crate) fn config_list(repo: &Repository, key: &str) -> Option<Vec<String>> {
+    let value = repo.config().ok()?.get_string(key).ok()?;
+    Some(value.split_whitespace().map(String::from).collect())
+}
+
+/// The message of the `commit.template` file, as it would be committed.
+pub(crate) fn commit_template(repo: &Repository) -> Option<String> {
+    let path = repo.config().ok()?.get_path("commit.template").ok()?;
+    let path = repo.workdir().expect("No workdir").join(path);
+    Some(strip_commit_message(repo, &fs::read_to_string(path).ok()?))
+}
+
+/// Where git looks for hooks: `core.hooksPath` if it's set, else `hooks` in the git dir.
+pub(crate) fn hooks_dir(repo: &Repository) -> PathBuf {
+    match repo
+        .config()
+        .ok()
+        .and_then(|config| config.get_path("core.hooksPath").ok())
+    {
+        Some(path) => repo.workdir().expect("No workdir").join(path),
+        None => common_dir(repo).join("hooks"),
+    }
+}
+
+/// The git dir shared by all worktrees, a linked worktree's own git dir points at it.
+fn common_dir(repo: &Repository) -> PathBuf {
+    match fs::read_to_string(repo.path().join("commondir")) {
+        Ok(common_dir) => repo.path().join(common_dir.trim_end()),
+        Err(_) => repo.path().to_path_buf(),
+    }
+}
+
+/// Names of the hooks git would run, leaving out the `.sample`s and those not executable.
+pub(crate) fn active_hooks(repo: &Repository) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(hooks_dir(repo)) else {
+        return vec![];
+    };
+
+    entries
+        .filter_map(Result::ok)
+        .filter(|entry| is_executable(&entry.path()))
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| !name.ends_with(".sample"))
+        .sorted()
+        .collect()
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::metadata(path).is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// Paths, branch, remotes, object counts and features of the repository at a glance.
+pub(crate) fn repo_info(repo: &Repository) -> Res<RepoInfo> {
+    let workdir = repo.workdir().expect("No workdir");
+    let config = repo.config()?;
+    let head = repo.head().ok();
+
+    let remotes = repo
+        .remotes()?
+        .iter()
+        .flatten()
+        .filter_map(|name| {
+            let remote = repo.find_remote(name).ok()?;
+            Some(RemoteInfo {
+                name: name.to_string(),
+                url: remote.url()?.to_string(),
+            })
+        })
+        .collect();
+
+    let lfs = common_dir(repo).join("lfs").is_dir()
+        || fs::read_to_string(workdir.join(".gitattributes"))
+            .is_ok_and(|attributes| attributes.contains("filter=lfs"));
+
+    Ok(RepoInfo {
+        workdir: workdir.to_path_buf(),
+        git_dir: repo.path().to_path_buf(),
+        hooks_dir: hooks_dir(repo),
+        hooks: active_hooks(repo),
+        branch: head
+            .filter(|head| head.is_branch())
+            .and_then(|head| head.shorthand().map(String::from)),
+        upstream: head_upstream(repo),
+        remotes,
+        objects: ObjectCounts::parse(&git_output(workdir, &["count-objects", "-v"])?),
+        lfs,
+        sparse: config.get_bool("core.sparseCheckout").unwrap_or(false),
+        shallow: repo.is_shallow(),
+    })
+}
+
+/// The local branch `git switch` would create to track a remote branch like `origin/feature`.
+pub(crate) fn remote_branch_local_name(repo: &Repository, rev: &str) -> Option<String> {
+    let branch = repo.find_branch(rev, git2::BranchType::Remote).ok()?;
+    let remote = repo.branch_remote_name(branch.get().name()?).ok()?;
+    let local = rev.strip_prefix(remote.as_str()?)?.strip_prefix('/')?;
+
+    (local != "HEAD").then(|| local.to_string())
+}
+
+/// Short names of all branches, remote branches and tags.
+pub(crate) fn ref_names(repo: &Repository) -> Res<Vec<String>> {
+    Ok(repo
+        .references()?
+        .filter_map(Result::ok)
+        .filter(|reference| reference.is_branch() || reference.is_remote() || reference.is_tag())
+        .filter_map(|reference| reference.shorthand().map(String::from))
+        .sorted()
+        .dedup()
+        .collect())
+}
+
+/// Verifies the signatures of `oids`. Git (and so gpg or ssh-keygen) is only asked about
+/// the signed commits it hasn't verified before, others are reported as unsigned straight away.
+/// If it fails, their status is unknown.
+pub(crate) fn signature_statuses(
+    repo: &Repository,
+    oids: &[git2::Oid],
+) -> HashMap<git2::Oid, SignatureStatus> {
+    let mut statuses = oids
+        .iter()
+        .filter_map(|oid| Some((*oid, signature::cached(oid)?)))
+        .collect::<HashMap<_, _>>();
+
+    let (signed, unsigned): (Vec<&git2::Oid>, Vec<&git2::Oid>) = oids
+        .iter()
+        .filter(|oid| !statuses.contains_key(oid))
+        .partition(|oid| repo.extract_signature(oid, None).is_ok());
+
+    statuses.extend(
+        unsigned
+            .into_iter()
+            .map(|oid| (*oid, SignatureStatus::Unsigned)),
+    );
+
+    if signed.is_empty() {
+        return statuses;
+    }
+
+    match verify_signatures(repo, &signed) {
+        Ok(verified) => {
+            signature::cache(&verified);
+            statuses.extend(verified);
+        }
+        Err(err) => {
+            log::warn!("Couldn't verify signatures: {}", err);
+            statuses.extend(
+                signed
+                    .into_iter()
+                    .map(|oid| (*oid, SignatureStatus::Unknown)),
+            );
+        }
+    }
+
+    statuses
+}
+
+fn verify_signatures(
+    repo: &Repository,
+    oids: &[&git2::Oid],
+) -> Res<Vec<(git2::Oid, SignatureStatus)>> {
+    let hashes = oids.iter().map(|oid| oid.to_string()).collect::<Vec<_>>();
+    let out = Command::new("git")
+        .args(["show", "--no-patch", "--no-walk", "--format=%H %G?"])
+        .args(&hashes)
+        .current_dir(repo.workdir().unwrap_or(repo.path()))
+        .output()?;
+
+    if !out.status.success() {
+        return Err(String::from_utf8_lossy(&out.stderr).into_owned().into());
+    }
+
+    Ok(signature::parse(str::from_utf8(&out.stdout)?))
+}
+
+/// The stdout of a git command, or what it printed to stderr if it failed.
+fn git_output(dir: &Path, args: &[&str]) -> Res<String> {
+    command_output(Command::new("git").args(args).current_dir(dir))
+}
+
+fn command_output(cmd: &mut Command) -> Res<String> {
+    let out = cmd.output()?;
+
+    if !out.status.success() {
+        return Err(String::from_utf8_lossy(&out.stderr).into_owned().into());
+    }
+
+    Ok(String::from_utf8(out.stdout)?)
+}
+
 // TODO replace with libgit2
 fn branch_name(dir: &Path, hash: &str) -> Res<Option<String>> {
     let out = Command::new("git")
@@ -113,12 +1362,56 @@ fn branch_name(dir: &Path, hash: &str) -> Res<Option<String>> {
         .map(|line| line.split(' ').nth(1).unwrap().to_string()))
 }
 
-pub(crate) fn diff_unstaged(config: &Config, repo: &Repository) -> Res<Diff> {
+// TODO replace with libgit2 once it can list the main worktree
+pub(crate) fn worktrees(repo: &Repository) -> Res<Vec<Worktree>> {
+    let out = Command::new("git")
+        .args(["worktree", "list", "--porcelain"])
+        .current_dir(repo.workdir().expect("No workdir"))
+        .output()?
+        .stdout;
+
+    Ok(str::from_utf8(&out)?
+        .split("\n\n")
+        .filter_map(|block| {
+            let mut lines = block.lines();
+            let path = PathBuf::from(lines.next()?.strip_prefix("worktree ")?);
+            let mut worktree = Worktree {
+                path,
+                branch: None,
+                locked: false,
+                prunable: false,
+            };
+
+            for line in lines {
+                if let Some(branch) = line.strip_prefix("branch ") {
+                    worktree.branch = Some(
+                        branch
+                            .strip_prefix("refs/heads/")
+                            .unwrap_or(branch)
+                            .to_string(),
+                    );
+                } else if line.starts_with("locked") {
+                    worktree.locked = true;
+                } else if line.starts_with("prunable") {
+                    worktree.prunable = true;
+                }
+            }
+
+            Some(worktree)
+        })
+        .collect())
+}
+
+pub(crate) fn diff_unstaged(
+    config: &Config,
+    options: &DiffOptions,
+    repo: &Repository,
+) -> Res<Diff> {
     let diff = repo.diff_index_to_workdir(None, Some(&mut git2_opts::diff(repo)?))?;
-    diff::convert_diff(config, repo, diff, true)
+    diff::convert_diff(config, options, repo, diff, true)
 }
 
-pub(crate) fn diff_staged(config: &Config, repo: &Repository) -> Res<Diff> {
+pub(crate) fn diff_staged(config: &Config, options: &DiffOptions, repo: &Repository) -> Res<Diff> {
     let opts = &mut git2_opts::diff(repo)?;
 
     let diff = match repo.head() {
@@ -126,10 +1419,15 @@ pub(crate) fn diff_staged(config: &Config, repo: &Repository) -> Res<Diff> {
         Err(_) => repo.diff_tree_to_index(None, None, Some(opts))?,
     };
 
-    diff::convert_diff(config, repo, diff, false)
+    diff::convert_diff(config, options, repo, diff, false)
 }
 
-pub(crate) fn show(config: &Config, repo: &Repository, reference: &str) -> Res<Diff> {
+pub(crate) fn show(
+    config: &Config,
+    options: &DiffOptions,
+    repo: &Repository,
+    reference: &str,
+) -> Res<Diff> {
     let object = &repo.revparse_single(reference)?;
 
     let commit = object.peel_to_commit()?;
@@ -145,7 +1443,68 @@ pub(crate) fn show(config: &Config, repo: &Repository, reference: &str) -> Res<D
         Some(&mut git2_opts::diff(repo)?),
     )?;
 
-    diff::convert_diff(config, repo, diff, false)
+    diff::convert_diff(config, options, repo, diff, false)
+}
+
+/// The files that a stash's changes wouldn't apply cleanly to, as found by
+/// `git stash show -p <stash> | git apply --check`.
+pub(crate) fn stash_conflicts(repo: &Repository, stash: &str) -> Res<Vec<String>> {
+    let dir = repo.workdir().expect("No workdir");
+    let patch = Command::new("git")
+        .args(["stash", "show", "-p", "--binary", stash])
+        .current_dir(dir)
+        .output()?;
+
+    if !patch.status.success() {
+        return Err(String::from_utf8_lossy(&patch.stderr).into_owned().into());
+    }
+
+    // Like a stash of only untracked files, which aren't part of its diff
+    if patch.stdout.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let mut child = Command::new("git")
+        .args(["apply", "--check"])
+        .current_dir(dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    child.stdin.take().unwrap().write_all(&patch.stdout)?;
+    let out = child.wait_with_output()?;
+
+    if out.status.success() {
+        return Ok(vec![]);
+    }
+
+    Ok(apply_errors::parse(str::from_utf8(&out.stderr)?))
+}
+
+/// The files a stash made with `--include-untracked` keeps in its third parent,
+/// which aren't part of the diff against its first parent.
+pub(crate) fn stash_untracked(
+    config: &Config,
+    options: &DiffOptions,
+    repo: &Repository,
+    reference: &str,
+) -> Res<Option<Diff>> {
+    let commit = repo.revparse_single(reference)?.peel_to_commit()?;
+    let is_stash = repo
+        .reflog("refs/stash")
+        .is_ok_and(|reflog| reflog.iter().any(|entry| entry.id_new() == commit.id()));
+
+    if !is_stash || commit.parent_count() < 3 {
+        return Ok(None);
+    }
+
+    let tree = commit.parent(2)?.tree()?;
+    let diff = repo.diff_tree_to_tree(None, Some(&tree), Some(&mut git2_opts::diff(repo)?))?;
+
+    Ok(Some(diff::convert_diff(
+        config, options, repo, diff, false,
+    )?))
 }
 
 pub(crate) fn show_summary(repo: &Repository, reference: &str) -> Res<Commit> {
@@ -181,8 +1540,13 @@ pub(crate) fn show_summary(repo: &Repository, reference: &str) -> Res<Commit> {
         message
     );
 
+    let signature = signature_statuses(repo, &[commit.id()])
+        .remove(&commit.id())
+        .unwrap_or(SignatureStatus::Unsigned);
+
     Ok(Commit {
         hash: commit.id().to_string(),
         details,
+        signature,
     })
 }
@@ -0,0 +1,16 @@
+use super::diff::Diff;
+
+/// What a push of the checked out branch would send.
+pub(crate) struct Outgoing {
+    /// The upstream being pushed to, `None` if the branch isn't on a remote yet.
+    pub upstream: Option<String>,
+    pub commits: Vec<OutgoingCommit>,
+    /// The combined changes of all outgoing commits.
+    pub diff: Diff,
+}
+
+pub(crate) struct OutgoingCommit {
+    pub commit: String,
+    pub hash: String,
+    pub summary: String,
+}
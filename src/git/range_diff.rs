@@ -0,0 +1,55 @@
+/// A commit of the old range matched up with one of the new range by `git range-diff`.
+#[derive(Debug, Clone)]
+pub(crate) struct RangeDiffPair {
+    pub old_hash: Option<String>,
+    pub new_hash: Option<String>,
+    /// `=` if unchanged, `!` if changed, `<` if only in the old range and `>` if only in the new.
+    pub status: char,
+    pub summary: String,
+    /// The diff between the two commits' patches, if they differ.
+    pub diff: Vec<String>,
+}
+
+/// Parses the output of `git range-diff --no-color`.
+pub(crate) fn parse(output: &str) -> Vec<RangeDiffPair> {
+    let mut pairs: Vec<RangeDiffPair> = vec![];
+
+    for line in output.lines() {
+        match (parse_pair(line), pairs.last_mut()) {
+            (Some(pair), _) => pairs.push(pair),
+            (None, Some(pair)) => pair
+                .diff
+                .push(line.strip_prefix("    ").unwrap_or(line).to_string()),
+            (None, None) => (),
+        }
+    }
+
+    pairs
+}
+
+/// A line like `1:  abc1234 ! 1:  def5678 Summary`, where a missing commit is `-:  -------`.
+fn parse_pair(line: &str) -> Option<RangeDiffPair> {
+    let (old, rest) = parse_commit(line)?;
+    let mut chars = rest.chars();
+    let status = chars.next().filter(|c| "=!<>".contains(*c))?;
+    let (new, summary) = parse_commit(chars.as_str().strip_prefix(' ')?)?;
+
+    Some(RangeDiffPair {
+        old_hash: old,
+        new_hash: new,
+        status,
+        summary: summary.to_string(),
+        diff: vec![],
+    })
+}
+
+fn parse_commit(input: &str) -> Option<(Option<String>, &str)> {
+    let (number, rest) = input.trim_start().split_once(':')?;
+    if number != "-" && !number.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let (hash, rest) = rest.trim_start().split_once(' ')?;
+    let hash = (!hash.starts_with('-')).then(|| hash.to_string());
+    Some((hash, rest))
+}
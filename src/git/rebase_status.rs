@@ -1,4 +1,6 @@
 pub(crate) struct RebaseStatus {
     pub onto: String,
     pub head_name: String,
+    /// The short hash and summary of the commit the rebase stopped at.
+    pub stopped_at: Option<(String, String)>,
 }
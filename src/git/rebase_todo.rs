@@ -0,0 +1,162 @@
+use derive_more::Display;
+use std::{ffi::OsString, mem, path::PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+pub(crate) enum RebaseAction {
+    #[display(fmt = "pick")]
+    Pick,
+    #[display(fmt = "reword")]
+    Reword,
+    #[display(fmt = "edit")]
+    Edit,
+    #[display(fmt = "squash")]
+    Squash,
+    #[display(fmt = "fixup")]
+    Fixup,
+    #[display(fmt = "drop")]
+    Drop,
+}
+
+impl RebaseAction {
+    fn parse(word: &str) -> Option<Self> {
+        Some(match word {
+            "p" | "pick" => RebaseAction::Pick,
+            "r" | "reword" => RebaseAction::Reword,
+            "e" | "edit" => RebaseAction::Edit,
+            "s" | "squash" => RebaseAction::Squash,
+            "f" | "fixup" => RebaseAction::Fixup,
+            "d" | "drop" => RebaseAction::Drop,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct RebaseTodoLine {
+    pub action: RebaseAction,
+    pub hash: String,
+    pub summary: String,
+}
+
+/// A todo list for `git rebase -i`, edited within Gitu before the rebase is started.
+#[derive(Debug)]
+pub(crate) struct RebaseTodo {
+    pub base: String,
+    pub args: Vec<OsString>,
+    pub lines: Vec<RebaseTodoLine>,
+    /// The todo of a rebase git is running, which is written back here rather than starting one
+    pub file: Option<PathBuf>,
+}
+
+impl RebaseTodo {
+    /// Moves `fixup! <subject>` and `squash! <subject>` commits right after the commit they're
+    /// for and sets their action, like `git rebase --autosquash` does with its todo.
+    pub(crate) fn autosquash(&mut self) {
+        let mut groups: Vec<Vec<RebaseTodoLine>> = vec![];
+
+        for mut line in mem::take(&mut self.lines) {
+            let target = squash_target(&line.summary).and_then(|(action, subject)| {
+                let group = groups.iter().position(|group| {
+                    group[0].summary == subject || group[0].hash.starts_with(subject)
+                })?;
+                Some((action, group))
+            });
+
+            match target {
+                Some((action, group)) => {
+                    line.action = action;
+                    groups[group].push(line);
+                }
+                None => groups.push(vec![line]),
+            }
+        }
+
+        self.lines = groups.into_iter().flatten().collect();
+    }
+
+    /// Formats the todo list the way git expects it in `.git/rebase-merge/git-rebase-todo`.
+    pub(crate) fn format(&self) -> String {
+        self.lines
+            .iter()
+            .map(|line| format!("{} {} {}\n", line.action, line.hash, line.summary))
+            .collect()
+    }
+
+    /// Parses the todo git asks its sequence editor to edit. There's no telling what to do with
+    /// other commands, like `exec` or `merge`, so those aren't parsed.
+    pub(crate) fn parse(content: &str, file: PathBuf) -> Option<Self> {
+        // Git comments with `# Rebase 1a2b3c4..5d6e7f8 onto 1a2b3c4 (2 commands)`
+        let base = content
+            .lines()
+            .find_map(|line| line.strip_prefix("# Rebase "))
+            .and_then(|line| line.split(" onto ").nth(1))
+            .and_then(|onto| onto.split_whitespace().next())
+            .unwrap_or("")
+            .to_string();
+
+        let lines = content
+            .lines()
+            .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+            .map(|line| {
+                let mut words = line.splitn(3, ' ');
+                Some(RebaseTodoLine {
+                    action: RebaseAction::parse(words.next()?)?,
+                    hash: words.next()?.to_string(),
+                    summary: words
+                        .next()
+                        .unwrap_or("")
+                        .trim_start_matches("# ")
+                        .to_string(),
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(Self {
+            base,
+            args: vec![],
+            lines,
+            file: Some(file),
+        })
+    }
+}
+
+/// The action and the subject of the commit a `fixup!` or `squash!` commit is for, if it is one.
+/// Nested ones, like `fixup! fixup! <subject>`, are for the same commit.
+fn squash_target(summary: &str) -> Option<(RebaseAction, &str)> {
+    let (action, mut subject) = if let Some(subject) = summary.strip_prefix("fixup! ") {
+        (RebaseAction::Fixup, subject)
+    } else {
+        (RebaseAction::Squash, summary.strip_prefix("squash! ")?)
+    };
+
+    while let Some(rest) = subject
+        .strip_prefix("fixup! ")
+        .or_else(|| subject.strip_prefix("squash! "))
+    {
+        subject = rest;
+    }
+
+    Some((action, subject))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_todo() {
+        let todo = RebaseTodo::parse(
+            "pick 1a2b3c4 First\nr 5d6e7f8 # Second\n\n# Rebase 0f0f0f0..5d6e7f8 onto 0f0f0f0 (2 commands)\n",
+            PathBuf::from("git-rebase-todo"),
+        )
+        .unwrap();
+
+        assert_eq!(todo.base, "0f0f0f0");
+        assert_eq!(todo.format(), "pick 1a2b3c4 First\nreword 5d6e7f8 Second\n");
+    }
+
+    #[test]
+    fn parse_todo_with_exec() {
+        assert!(RebaseTodo::parse("pick 1a2b3c4 First\nexec make\n", PathBuf::new()).is_none());
+    }
+}
@@ -0,0 +1,59 @@
+/// A ref advertised by a remote, as listed by `git ls-remote`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RemoteRef {
+    /// Full name, like `refs/heads/main`.
+    pub name: String,
+    /// What the ref points at, the commit for annotated tags.
+    pub hash: String,
+    /// Whether the object is missing in the local repository, needing a fetch.
+    pub missing: bool,
+}
+
+/// Parses the `<hash>\t<ref>` lines of `git ls-remote`.
+/// The `<tag>^{}` entries that git adds for annotated tags replace the tag's own hash.
+pub(crate) fn parse(output: &str) -> Vec<RemoteRef> {
+    let mut refs: Vec<RemoteRef> = vec![];
+
+    for (hash, name) in output.lines().filter_map(|line| line.split_once('\t')) {
+        if let Some(tag) = name.strip_suffix("^{}") {
+            if let Some(remote_ref) = refs.iter_mut().find(|remote_ref| remote_ref.name == tag) {
+                remote_ref.hash = hash.to_string();
+            }
+            continue;
+        }
+
+        refs.push(RemoteRef {
+            name: name.to_string(),
+            hash: hash.to_string(),
+            missing: false,
+        });
+    }
+
+    refs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_ls_remote() {
+        let refs = parse(
+            "1111111111111111111111111111111111111111\tHEAD\n\
+             1111111111111111111111111111111111111111\trefs/heads/main\n\
+             2222222222222222222222222222222222222222\trefs/tags/v1\n\
+             3333333333333333333333333333333333333333\trefs/tags/v1^{}\n",
+        );
+
+        assert_eq!(
+            refs.iter()
+                .map(|r| (r.name.as_str(), &r.hash[..1]))
+                .collect::<Vec<_>>(),
+            vec![
+                ("HEAD", "1"),
+                ("refs/heads/main", "1"),
+                ("refs/tags/v1", "3")
+            ]
+        );
+    }
+}
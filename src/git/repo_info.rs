@@ -0,0 +1,86 @@
+use std::path::PathBuf;
+
+/// An overview of a repository, for the info screen.
+pub(crate) struct RepoInfo {
+    pub workdir: PathBuf,
+    pub git_dir: PathBuf,
+    pub hooks_dir: PathBuf,
+    pub hooks: Vec<String>,
+    /// The checked out branch, `None` when HEAD is detached.
+    pub branch: Option<String>,
+    pub upstream: Option<String>,
+    pub remotes: Vec<RemoteInfo>,
+    pub objects: ObjectCounts,
+    pub lfs: bool,
+    pub sparse: bool,
+    pub shallow: bool,
+}
+
+pub(crate) struct RemoteInfo {
+    pub name: String,
+    pub url: String,
+}
+
+/// What `git count-objects -v` reports, sizes in KiB.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct ObjectCounts {
+    pub loose: usize,
+    pub loose_size: usize,
+    pub packed: usize,
+    pub packs: usize,
+    pub pack_size: usize,
+}
+
+impl ObjectCounts {
+    /// Parses the `<key>: <value>` lines of `git count-objects -v`.
+    pub(crate) fn parse(output: &str) -> Self {
+        let mut counts = Self::default();
+
+        for (key, value) in output.lines().filter_map(|line| line.split_once(": ")) {
+            let Ok(value) = value.trim().parse() else {
+                continue;
+            };
+
+            match key {
+                "count" => counts.loose = value,
+                "size" => counts.loose_size = value,
+                "in-pack" => counts.packed = value,
+                "packs" => counts.packs = value,
+                "size-pack" => counts.pack_size = value,
+                _ => (),
+            }
+        }
+
+        counts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_count_objects() {
+        let counts = ObjectCounts::parse(
+            "count: 12\n\
+             size: 48\n\
+             in-pack: 340\n\
+             packs: 2\n\
+             size-pack: 120\n\
+             prune-packable: 0\n\
+             garbage: 0\n\
+             size-garbage: 0\n",
+        );
+
+        assert_eq!(
+            counts,
+            ObjectCounts {
+                loose: 12,
+                loose_size: 48,
+                packed: 340,
+                packs: 2,
+                pack_size: 120,
+            }
+        );
+    }
+}
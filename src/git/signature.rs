@@ -0,0 +1,93 @@
+use git2::Oid;
+use std::{cell::RefCell, collections::HashMap};
+
+/// How a commit's GPG or SSH signature verifies, as reported by `git log --format=%G?`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SignatureStatus {
+    Good,
+    Bad,
+    /// Valid, but made with a key that isn't trusted, has expired, or was revoked,
+    /// or one that couldn't be checked at all.
+    Untrusted,
+    Unsigned,
+    /// Git failed to verify it.
+    Unknown,
+}
+
+impl SignatureStatus {
+    fn from_code(code: &str) -> Self {
+        match code {
+            "G" => Self::Good,
+            "B" => Self::Bad,
+            "U" | "X" | "Y" | "R" | "E" => Self::Untrusted,
+            _ => Self::Unsigned,
+        }
+    }
+
+    pub(crate) fn describe(&self) -> &'static str {
+        match self {
+            Self::Good => "Good signature",
+            Self::Bad => "Bad signature",
+            Self::Untrusted => "Untrusted signature",
+            Self::Unsigned => "Unsigned",
+            Self::Unknown => "Unknown signature",
+        }
+    }
+}
+
+thread_local! {
+    /// Verifying a signature runs gpg or ssh-keygen, so each commit is only verified once.
+    static VERIFIED: RefCell<HashMap<Oid, SignatureStatus>> = RefCell::new(HashMap::new());
+}
+
+pub(crate) fn cached(oid: &Oid) -> Option<SignatureStatus> {
+    VERIFIED.with(|verified| verified.borrow().get(oid).copied())
+}
+
+pub(crate) fn cache(statuses: &[(Oid, SignatureStatus)]) {
+    VERIFIED.with(|verified| verified.borrow_mut().extend(statuses.iter().copied()));
+}
+
+/// Parses the `<hash> <code>` lines of `git show --no-patch --format='%H %G?'`.
+pub(crate) fn parse(output: &str) -> Vec<(Oid, SignatureStatus)> {
+    output
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .filter_map(|(hash, code)| {
+            Some((
+                Oid::from_str(hash).ok()?,
+                SignatureStatus::from_code(code.trim()),
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_status_codes() {
+        let statuses = parse(
+            "1111111111111111111111111111111111111111 G\n\
+             2222222222222222222222222222222222222222 B\n\
+             3333333333333333333333333333333333333333 U\n\
+             4444444444444444444444444444444444444444 E\n\
+             5555555555555555555555555555555555555555 N\n",
+        );
+
+        assert_eq!(
+            statuses
+                .into_iter()
+                .map(|(_, status)| status)
+                .collect::<Vec<_>>(),
+            vec![
+                SignatureStatus::Good,
+                SignatureStatus::Bad,
+                SignatureStatus::Untrusted,
+                SignatureStatus::Untrusted,
+                SignatureStatus::Unsigned,
+            ]
+        );
+    }
+}
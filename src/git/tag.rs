@@ -0,0 +1,6 @@
+pub(crate) struct Tag {
+    pub name: String,
+    pub date: String,
+    /// Subject of the tagged commit, rather than of the tag message.
+    pub subject: String,
+}
@@ -0,0 +1,138 @@
+use git2::Repository;
+use std::ops::Range;
+
+/// The whitespace errors to look for in added lines, as configured by `core.whitespace`.
+/// `blank-at-eof` is only reported by `git diff --check`, as it isn't tied to a single line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct WhitespaceRules {
+    pub blank_at_eol: bool,
+    pub space_before_tab: bool,
+    pub indent_with_non_tab: bool,
+    pub tab_in_indent: bool,
+    pub tab_width: usize,
+}
+
+impl Default for WhitespaceRules {
+    fn default() -> Self {
+        Self {
+            blank_at_eol: true,
+            space_before_tab: true,
+            indent_with_non_tab: false,
+            tab_in_indent: false,
+            tab_width: 8,
+        }
+    }
+}
+
+impl WhitespaceRules {
+    pub(crate) fn from_repo(repo: &Repository) -> Self {
+        repo.config()
+            .and_then(|config| config.get_string("core.whitespace"))
+            .map(|value| Self::parse(&value))
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn parse(value: &str) -> Self {
+        let mut rules = Self::default();
+
+        for rule in value
+            .split(',')
+            .map(str::trim)
+            .filter(|rule| !rule.is_empty())
+        {
+            let (enabled, name) = match rule.strip_prefix('-') {
+                Some(name) => (false, name),
+                None => (true, rule),
+            };
+
+            match name {
+                "blank-at-eol" | "trailing-space" => rules.blank_at_eol = enabled,
+                "space-before-tab" => rules.space_before_tab = enabled,
+                "indent-with-non-tab" => rules.indent_with_non_tab = enabled,
+                "tab-in-indent" => rules.tab_in_indent = enabled,
+                _ => {
+                    if let Some(width) = name.strip_prefix("tabwidth=") {
+                        rules.tab_width = width.parse().unwrap_or(rules.tab_width);
+                    }
+                }
+            }
+        }
+
+        rules
+    }
+
+    /// Byte ranges of `line` (without its line ending) that violate the rules.
+    pub(crate) fn violations(&self, line: &str) -> Vec<Range<usize>> {
+        let mut ranges = vec![];
+        let indent_len = line.len() - line.trim_start_matches([' ', '\t']).len();
+        let indent = &line[..indent_len];
+
+        if self.blank_at_eol {
+            let content_len = line.trim_end_matches([' ', '\t']).len();
+            if content_len < line.len() {
+                ranges.push(content_len..line.len());
+            }
+        }
+
+        let mut spaces_start = 0;
+        for (i, c) in indent.char_indices() {
+            if c == '\t' {
+                if self.space_before_tab && spaces_start < i {
+                    ranges.push(spaces_start..i);
+                }
+                if self.tab_in_indent {
+                    ranges.push(i..i + 1);
+                }
+                spaces_start = i + 1;
+            } else if self.indent_with_non_tab && i + 1 - spaces_start == self.tab_width {
+                ranges.push(spaces_start..indent_len);
+            }
+        }
+
+        merge_ranges(ranges)
+    }
+}
+
+fn merge_ranges(mut ranges: Vec<Range<usize>>) -> Vec<Range<usize>> {
+    ranges.sort_by_key(|range| range.start);
+
+    ranges.into_iter().fold(vec![], |mut merged, range| {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+        merged
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WhitespaceRules;
+
+    #[test]
+    fn default_rules() {
+        let rules = WhitespaceRules::parse("");
+        assert_eq!(rules.violations("fn main() {}  "), vec![12..14]);
+        assert_eq!(rules.violations("  \tindented"), vec![0..2]);
+        assert!(rules.violations("\tindented").is_empty());
+    }
+
+    #[test]
+    fn disabled_rules() {
+        let rules = WhitespaceRules::parse("-trailing-space,-space-before-tab");
+        assert!(rules.violations("  \tindented  ").is_empty());
+    }
+
+    #[test]
+    fn tab_in_indent() {
+        let rules = WhitespaceRules::parse("tab-in-indent");
+        assert_eq!(rules.violations("\t\tindented\tnot indent"), vec![0..2]);
+    }
+
+    #[test]
+    fn indent_with_non_tab() {
+        let rules = WhitespaceRules::parse("indent-with-non-tab,tabwidth=4");
+        assert!(rules.violations("   three").is_empty());
+        assert_eq!(rules.violations("\t      six"), vec![1..7]);
+    }
+}
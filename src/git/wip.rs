@@ -0,0 +1,9 @@
+/// Uncommitted changes recorded before an operation that could lose them.
+pub(crate) struct WipSnapshot {
+    /// A stash-like commit, as made by `git stash create`. Like `git stash --include-untracked`,
+    /// untracked files that were about to be removed are kept in a third parent.
+    pub commit: String,
+    pub hash: String,
+    /// What was about to happen, like "Before hard reset to HEAD~1"
+    pub message: String,
+}
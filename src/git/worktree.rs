@@ -0,0 +1,8 @@
+use std::path::PathBuf;
+
+pub(crate) struct Worktree {
+    pub path: PathBuf,
+    pub branch: Option<String>,
+    pub locked: bool,
+    pub prunable: bool,
+}
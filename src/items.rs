@@ -1,17 +1,29 @@
 use crate::config::Config;
+use crate::git;
+use crate::git::clean::Clean;
+use crate::git::commit_picker::CommitPicker;
 use crate::git::diff::Delta;
 use crate::git::diff::Diff;
 use crate::git::diff::Hunk;
+use crate::git::forge::PullRequest;
+use crate::git::graph::GraphCell;
+use crate::git::log_marks::LogMarks;
+use crate::git::log_walk::LogWalk;
+use crate::git::rebase_todo::RebaseTodo;
+use crate::git::signature::SignatureStatus;
 use crate::Res;
 use git2::Commit;
 use git2::Oid;
 use git2::Repository;
+use ratatui::style::Color;
 use ratatui::style::Style;
 use ratatui::text::Line;
 use ratatui::text::Span;
 use regex::Regex;
 use std::borrow::Cow;
+use std::cell::RefCell;
 use std::iter;
+use std::path::Path;
 use std::path::PathBuf;
 use std::rc::Rc;
 
@@ -19,10 +31,14 @@ use std::rc::Rc;
 pub(crate) struct Item {
     pub(crate) id: Cow<'static, str>,
     pub(crate) display: Line<'static>,
+    /// Shown in a second column next to `display`, like the new side of a side-by-side diff.
+    pub(crate) display_new: Option<Line<'static>>,
     pub(crate) section: bool,
     pub(crate) default_collapsed: bool,
     pub(crate) depth: usize,
     pub(crate) unselectable: bool,
+    /// Break `display` into more lines between words, rather than cutting it off when it doesn't fit
+    pub(crate) wrap: bool,
     pub(crate) target_data: Option<TargetData>,
 }
 
@@ -32,12 +48,27 @@ pub(crate) enum TargetData {
     AllUnstaged,
     AllUntracked(Vec<PathBuf>),
     Branch(String),
+    CleanEntry(Rc<RefCell<Clean>>, usize),
     Commit(String),
+    Conflict(PathBuf, usize),
     Delta(Delta),
+    FastForward(String),
     File(PathBuf),
     Hunk(Rc<Hunk>),
     HunkLine(Rc<Hunk>, usize),
+    IndexLock(PathBuf),
+    MoreRecentCommits,
+    PickerCommit(Rc<RefCell<CommitPicker>>, Option<String>),
+    PullRequest(PullRequest),
+    RebaseTodoLine(Rc<RefCell<RebaseTodo>>, usize),
+    Remote { name: String, url: String },
     Stash { commit: String, id: usize },
+    Submodule(PathBuf),
+    Tag(String),
+    Unmerged(PathBuf),
+    UnstagedFiles(Vec<PathBuf>),
+    WipSnapshot { commit: String, id: usize },
+    Worktree(PathBuf),
 }
 
 pub(crate) fn create_diff_items<'a>(
@@ -56,12 +87,19 @@ pub(crate) fn create_diff_items<'a>(
                 format!(
                     "{}   {}",
                     format!("{:?}", delta.status).to_lowercase(),
-                    delta.new_file.to_string_lossy()
+                    match delta.status {
+                        git2::Delta::Renamed => format!(
+                            "{} -> {}",
+                            delta.old_file.to_string_lossy(),
+                            delta.new_file.to_string_lossy()
+                        ),
+                        _ => delta.new_file.to_string_lossy().to_string(),
+                    }
                 ),
                 &config.style.file_header,
             ),
             section: true,
-            default_collapsed,
+            default_collapsed: default_collapsed || is_large_delta(&config, delta),
             depth: *depth,
             target_data: Some(target_data),
             ..Default::default()
@@ -76,6 +114,20 @@ pub(crate) fn create_diff_items<'a>(
     })
 }
 
+fn is_large_delta(config: &Config, delta: &Delta) -> bool {
+    config
+        .general
+        .collapse_diffs_larger_than
+        .is_some_and(|max_lines| {
+            delta
+                .hunks
+                .iter()
+                .map(|hunk| hunk.content.lines.len())
+                .sum::<usize>()
+                > max_lines
+        })
+}
+
 fn create_hunk_items(
     config: Rc<Config>,
     hunk: Rc<Hunk>,
@@ -112,6 +164,74 @@ fn format_diff_hunk_items(depth: usize, hunk: Rc<Hunk>) -> Vec<Item> {
         .collect()
 }
 
+/// Shows the lines of each hunk side-by-side, pairing up the removed and added lines of each
+/// change and showing context lines on both sides.
+pub(crate) fn side_by_side(items: Vec<Item>) -> Vec<Item> {
+    let mut result = Vec::with_capacity(items.len());
+    let mut items = items.into_iter().peekable();
+
+    while let Some(item) = items.next() {
+        let Some(TargetData::HunkLine(hunk, _)) = &item.target_data else {
+            result.push(item);
+            continue;
+        };
+
+        let hunk = Rc::clone(hunk);
+        let depth = item.depth;
+        let mut lines = vec![item.display];
+        while let Some(next) = items.next_if(|next| {
+            matches!(&next.target_data, Some(TargetData::HunkLine(h, _)) if Rc::ptr_eq(h, &hunk))
+        }) {
+            lines.push(next.display);
+        }
+
+        result.extend(side_by_side_rows(lines).into_iter().map(|(old, new)| Item {
+            display: old.unwrap_or_default(),
+            display_new: Some(new.unwrap_or_default()),
+            unselectable: true,
+            depth,
+            ..Default::default()
+        }));
+    }
+
+    result
+}
+
+fn side_by_side_rows(
+    lines: Vec<Line<'static>>,
+) -> Vec<(Option<Line<'static>>, Option<Line<'static>>)> {
+    let prefix = |line: &Line| line.spans.first().and_then(|s| s.content.chars().next());
+
+    let mut rows = vec![];
+    let mut i = 0;
+    while i < lines.len() {
+        let removed = lines[i..]
+            .iter()
+            .take_while(|line| prefix(line) == Some('-'))
+            .count();
+        let added = lines[i + removed..]
+            .iter()
+            .take_while(|line| prefix(line) == Some('+'))
+            .count();
+
+        if removed + added == 0 {
+            rows.push((Some(lines[i].clone()), Some(lines[i].clone())));
+            i += 1;
+            continue;
+        }
+
+        rows.extend((0..removed.max(added)).map(|row| {
+            (
+                (row < removed).then(|| lines[i + row].clone()),
+                (row < added).then(|| lines[i + removed + row].clone()),
+            )
+        }));
+        i += removed + added;
+    }
+
+    rows
+}
+
 fn replace_tabs_with_spaces(line: Line<'_>) -> Line<'_> {
     let spans = line
         .spans
@@ -131,19 +251,24 @@ pub(crate) fn stash_list(config: &Config, repo: &Repository, limit: usize) -> Re
         .iter()
         .enumerate()
         .map(|(i, stash)| -> Res<Item> {
-            let spans = itertools::intersperse(
-                iter::once(Span::styled(format!("stash@{i}"), &style.hash)).chain([stash
-                    .message()
-                    .unwrap_or("")
-                    .to_string()
-                    .into()]),
-                Span::raw(" "),
-            )
-            .collect::<Vec<_>>();
+            let commit = repo.find_commit(stash.id_new())?;
+            let fields = [
+                (
+                    "name",
+                    vec![Span::styled(format!("stash@{i}"), &style.hash)],
+                ),
+                (
+                    "subject",
+                    vec![stash.message().unwrap_or("").to_string().into()],
+                ),
+            ];
 
             Ok(Item {
                 id: stash.id_new().to_string().into(),
-                display: Line::from(spans),
+                display: format_row(
+                    &config.format.stash,
+                    &[&fields[..], &commit_fields(config, &commit)?[..]].concat(),
+                ),
                 depth: 1,
                 target_data: Some(TargetData::Stash {
                     commit: stash.id_new().to_string(),
@@ -164,14 +289,66 @@ pub(crate) fn stash_list(config: &Config, repo: &Repository, limit: usize) -> Re
         .collect::<Vec<_>>())
 }
 
+/// Narrows down a log to the commits matching all of what's given.
+#[derive(Default, Clone)]
+pub(crate) struct LogFilter {
+    pub message: Option<Regex>,
+    /// Matched against `Name <email>` of the author, like `git log --author`.
+    pub author: Option<Regex>,
+    /// Only commits changing files matching this pathspec.
+    pub path: Option<String>,
+}
+
+impl LogFilter {
+    fn matches(&self, repo: &Repository, commit: &Commit) -> Res<bool> {
+        if let Some(re) = &self.message {
+            if !re.is_match(commit.message().unwrap_or("")) {
+                return Ok(false);
+            }
+        }
+
+        if let Some(re) = &self.author {
+            let author = commit.author();
+            let author = format!(
+                "{} <{}>",
+                author.name().unwrap_or(""),
+                author.email().unwrap_or("")
+            );
+            if !re.is_match(&author) {
+                return Ok(false);
+            }
+        }
+
+        if let Some(path) = &self.path {
+            let parent_tree = match commit.parent(0) {
+                Ok(parent) => Some(parent.tree()?),
+                Err(_) => None,
+            };
+            let mut opts = git2::DiffOptions::new();
+            opts.pathspec(path);
+            let diff = repo.diff_tree_to_tree(
+                parent_tree.as_ref(),
+                Some(&commit.tree()?),
+                Some(&mut opts),
+            )?;
+            if diff.deltas().len() == 0 {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+}
+
 pub(crate) fn log(
     config: &Config,
     repo: &Repository,
     limit: usize,
     rev: Option<Oid>,
-    msg_regex: Option<Regex>,
+    filter: LogFilter,
+    graph: bool,
+    marks: bool,
 ) -> Res<Vec<Item>> {
-    let style = &config.style;
     let mut revwalk = repo.revwalk()?;
     if let Some(r) = rev {
         revwalk.push(r)?;
@@ -179,17 +356,218 @@ pub(crate) fn log(
         return Ok(vec![]);
     }
 
-    let references = repo
+    if graph {
+        // Parents are drawn below all of their children
+        revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME)?;
+    }
+
+    let marks = if marks {
+        git::log_marks(repo).ok()
+    } else {
+        None
+    };
+
+    commit_items(
+        config,
+        repo,
+        revwalk.map(|oid| oid.map_err(Into::into)),
+        limit,
+        filter,
+        graph,
+        marks.as_ref(),
+    )
+}
+
+/// A `log` loaded a page at a time, keeping the walk so that each page goes on
+/// from where the last one stopped, rather than from HEAD.
+pub(crate) struct LogPages {
+    rev: Option<Oid>,
+    filter: LogFilter,
+    graph: bool,
+    walk: Option<LogWalk>,
+    /// Commits matching the filter that were walked so far.
+    oids: Vec<Oid>,
+}
+
+impl LogPages {
+    pub(crate) fn new(rev: Option<Oid>, filter: LogFilter, graph: bool) -> Self {
+        Self {
+            rev,
+            filter,
+            graph,
+            walk: None,
+            oids: vec![],
+        }
+    }
+
+    /// Walks from the start again on the next `items`, for when HEAD or the refs may have moved.
+    pub(crate) fn restart(&mut self) {
+        self.walk = None;
+        self.oids.clear();
+    }
+
+    pub(crate) fn items(
+        &mut self,
+        config: &Config,
+        repo: &Repository,
+        limit: usize,
+    ) -> Res<Vec<Item>> {
+        let walk = self
+            .walk
+            .get_or_insert_with(|| LogWalk::start(repo, self.rev, self.graph));
+
+        while self.oids.len() < limit {
+            let Some(oid) = walk.next() else {
+                break;
+            };
+
+            let oid = oid?;
+            if self.filter.matches(repo, &repo.find_commit(oid)?)? {
+                self.oids.push(oid);
+            }
+        }
+
+        commit_items(
+            config,
+            repo,
+            self.oids.iter().copied().map(Ok),
+            limit,
+            LogFilter::default(),
+            self.graph,
+            git::log_marks(repo).ok().as_ref(),
+        )
+    }
+}
+
+/// The commits of HEAD and, with `unmerged_branches`, those of local branches HEAD doesn't
+/// contain, newest first.
+pub(crate) fn recent_log(
+    config: &Config,
+    repo: &Repository,
+    limit: usize,
+    unmerged_branches: bool,
+) -> Res<Vec<Item>> {
+    let mut revwalk = repo.revwalk()?;
+    if revwalk.push_head().is_err() {
+        return Ok(vec![]);
+    }
+
+    if unmerged_branches {
+        // Branches that are merged have no commits HEAD doesn't, so all of them can be pushed.
+        // Left in the default order, by date as they're reached, like `git log --branches`,
+        // as sorting them topologically would walk the whole history first.
+        revwalk.push_glob("refs/heads/*")?;
+    }
+
+    commit_items(
+        config,
+        repo,
+        revwalk.map(|oid| oid.map_err(Into::into)),
+        limit,
+        LogFilter::default(),
+        false,
+        None,
+    )
+}
+
+/// Commits reachable from `to` but not from `from`, like `git log from..to`.
+pub(crate) fn range_log(
+    config: &Config,
+    repo: &Repository,
+    limit: usize,
+    from: Oid,
+    to: Oid,
+) -> Res<Vec<Item>> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(to)?;
+    revwalk.hide(from)?;
+
+    commit_items(
+        config,
+        repo,
+        revwalk.map(|oid| oid.map_err(Into::into)),
+        limit,
+        LogFilter::default(),
+        false,
+        None,
+    )
+}
+
+/// Commits that touched `path`, following it across renames.
+pub(crate) fn file_log(
+    config: &Config,
+    repo: &Repository,
+    limit: usize,
+    path: &Path,
+    filter: LogFilter,
+) -> Res<Vec<Item>> {
+    let oids = git::file_history(repo, path)?;
+    commit_items(
+        config,
+        repo,
+        oids.into_iter().map(Ok),
+        limit,
+        filter,
+        false,
+        None,
+    )
+}
+
+pub(crate) fn line_log(
+    config: &Config,
+    repo: &Repository,
+    limit: usize,
+    path: &Path,
+    range: &str,
+    filter: LogFilter,
+) -> Res<Vec<Item>> {
+    let oids = git::line_history(repo, path, range)?;
+    commit_items(
+        config,
+        repo,
+        oids.into_iter().map(Ok),
+        limit,
+        filter,
+        false,
+        None,
+    )
+}
+
+fn commit_items(
+    config: &Config,
+    repo: &Repository,
+    oids: impl Iterator<Item = Res<Oid>>,
+    limit: usize,
+    filter: LogFilter,
+    graph: bool,
+    marks: Option<&LogMarks>,
+) -> Res<Vec<Item>> {
+    let style = &config.style;
+    let head = repo.head().ok();
+    let head_branch = head
+        .as_ref()
+        .filter(|head| head.is_branch())
+        .and_then(|head| head.shorthand())
+        .map(String::from);
+
+    let mut references = repo
         .references()?
         .filter_map(Result::ok)
         .filter_map(
             |reference| match (reference.peel_to_commit(), reference.shorthand()) {
                 (Ok(target), Some(name)) => {
-                    if name.ends_with("/HEAD") || name.starts_with("prefetch/remotes/") {
+                    // Replace refs are marked on the replaced commit instead
+                    if name.ends_with("/HEAD")
+                        || name.starts_with("prefetch/remotes/")
+                        || name.starts_with("replace/")
+                    {
                         return None;
                     }
 
-                    let style: Style = if reference.is_remote() {
+                    let is_head = reference.is_branch() && head_branch.as_deref() == Some(name);
+                    let style: Style = if is_head {
+                        &style.head
+                    } else if reference.is_remote() {
                         &style.remote
                     } else if reference.is_tag() {
                         &style.tag
@@ -205,49 +583,106 @@ pub(crate) fn log(
         )
         .collect::<Vec<(Commit, Span)>>();
 
-    let items: Vec<Item> = revwalk
-        .map(|oid_result| -> Res<Option<Item>> {
-            let oid = oid_result?;
-            let commit = repo.find_commit(oid)?;
-            let short_id = commit.as_object().short_id()?.as_str().unwrap().to_string();
-
-            let spans = itertools::intersperse(
-                iter::once(Span::styled(short_id, &style.hash))
-                    .chain(
-                        references
-                            .iter()
-                            .filter(|(commit, _)| commit.id() == oid)
-                            .map(|(_, name)| name.clone()),
-                    )
-                    .chain([commit.summary().unwrap_or("").to_string().into()]),
-                Span::raw(" "),
-            )
+    // Detached, there's no branch to tell where HEAD is
+    if let (Some(head), None) = (head, &head_branch) {
+        if let Ok(commit) = head.peel_to_commit() {
+            references.insert(0, (commit, Span::styled("HEAD", &style.head)));
+        }
+    }
+
+    let commits = oids
+        .map(|oid_result| -> Res<Option<Commit>> {
+            let commit = repo.find_commit(oid_result?)?;
+
+            if !filter.matches(repo, &commit)? {
+                return Ok(None);
+            }
+
+            Ok(Some(commit))
+        })
+        .filter_map(Result::transpose)
+        .take(limit)
+        .collect::<Vec<_>>();
+
+    let oids = commits
+        .iter()
+        .filter_map(|commit| commit.as_ref().ok().map(Commit::id))
+        .collect::<Vec<_>>();
+    let signatures = git::signature_statuses(repo, &oids);
+    let rewrites = git::history_rewrites(repo);
+
+    let mut graph_rows = if graph {
+        let parents = commits
+            .iter()
+            .filter_map(|commit| commit.as_ref().ok())
+            .map(|commit| (commit.id(), commit.parent_ids().collect()))
             .collect::<Vec<_>>();
+        git::graph::graph(&parents)
+    } else {
+        vec![]
+    }
+    .into_iter();
 
-            if let Some(re) = &msg_regex {
-                if !re.is_match(commit.message().unwrap_or("")) {
-                    return Ok(None);
-                }
+    let items = commits
+        .into_iter()
+        .map(|commit_result| -> Res<Item> {
+            let commit = commit_result?;
+            let oid = commit.id();
+
+            let fields: [(&str, Vec<Span<'static>>); 3] = [
+                (
+                    "mark",
+                    marks
+                        .map(|marks| log_mark(config, marks, oid))
+                        .into_iter()
+                        .collect(),
+                ),
+                (
+                    "signature",
+                    signatures
+                        .get(&oid)
+                        .and_then(|status| signature_mark(config, *status))
+                        .into_iter()
+                        .collect(),
+                ),
+                (
+                    "refs",
+                    references
+                        .iter()
+                        .filter(|(commit, _)| commit.id() == oid)
+                        .map(|(_, name)| name.clone())
+                        .chain(rewrites.labels(oid).into_iter().map(|label| {
+                            Span::styled(format!("[{}]", label), &style.history_rewrite)
+                        }))
+                        .collect(),
+                ),
+            ];
+
+            let mut display = format_row(
+                &config.format.log,
+                &[&fields[..], &commit_fields(config, &commit)?[..]].concat(),
+            );
+            if let Some(row) = graph_rows.next() {
+                display.spans.splice(0..0, row.into_iter().map(graph_span));
             }
 
-            Ok(Some(Item {
+            Ok(Item {
                 id: oid.to_string().into(),
-                display: Line::from(spans),
+                display,
                 depth: 1,
                 target_data: Some(TargetData::Commit(oid.to_string())),
                 ..Default::default()
-            }))
+            })
         })
-        .filter_map(|result| match result {
+        .map(|result| match result {
             Ok(item) => item,
-            Err(err) => Some(Item {
+            Err(err) => Item {
                 id: err.to_string().into(),
                 display: err.to_string().into(),
                 ..Default::default()
-            }),
+            },
         })
-        .take(limit)
-        .collect();
+        .collect::<Vec<_>>();
 
     if items.is_empty() {
         Ok(vec![Item {
@@ -259,6 +694,128 @@ pub(crate) fn log(
     }
 }
 
+/// Colors of the lanes of the log graph, repeating when there are more lanes.
+const GRAPH_COLORS: [Color; 6] = [
+    Color::Blue,
+    Color::Green,
+    Color::Yellow,
+    Color::Magenta,
+    Color::Cyan,
+    Color::Red,
+];
+
+fn graph_span(cell: GraphCell) -> Span<'static> {
+    Span::styled(
+        cell.text,
+        Style::new().fg(GRAPH_COLORS[cell.lane % GRAPH_COLORS.len()]),
+    )
+}
+
+/// Points out HEAD, its upstream, where they diverged and the commits only HEAD has.
+/// Other commits get a blank, keeping the rows aligned.
+fn log_mark(config: &Config, marks: &LogMarks, oid: Oid) -> Span<'static> {
+    let style = &config.style.log_marks;
+    if oid == marks.head {
+        Span::styled("@", &style.head)
+    } else if Some(oid) == marks.upstream {
+        Span::styled("u", &style.upstream)
+    } else if Some(oid) == marks.merge_base {
+        Span::styled("b", &style.merge_base)
+    } else if marks.local.contains(&oid) {
+        Span::styled("+", &style.local)
+    } else {
+        Span::raw(" ")
+    }
+}
+
+/// The placeholders of a commit that all `[format]` templates can use.
+pub(crate) fn commit_fields(
+    config: &Config,
+    commit: &Commit,
+) -> Res<Vec<(&'static str, Vec<Span<'static>>)>> {
+    let short_id = commit.as_object().short_id()?.as_str().unwrap().to_string();
+    let author = commit.author();
+    let date = chrono::FixedOffset::east_opt(author.when().offset_minutes() * 60)
+        .zip(chrono::DateTime::from_timestamp(author.when().seconds(), 0))
+        .map(|(offset, time)| time.with_timezone(&offset).format("%Y-%m-%d").to_string())
+        .unwrap_or_default();
+
+    Ok(vec![
+        ("hash", vec![Span::styled(short_id, &config.style.hash)]),
+        (
+            "author",
+            vec![author.name().unwrap_or("").to_string().into()],
+        ),
+        ("date", vec![date.into()]),
+        (
+            "subject",
+            vec![commit.summary().unwrap_or("").to_string().into()],
+        ),
+    ])
+}
+
+/// Lays out a row after one of the `[format]` templates, replacing each `{placeholder}` with
+/// the spans of the first field of that name. Whitespace after a placeholder without spans
+/// is dropped, so that e.g. a commit without refs doesn't get a double space.
+pub(crate) fn format_row(template: &str, fields: &[(&str, Vec<Span<'static>>)]) -> Line<'static> {
+    let mut spans = vec![];
+    let mut after_empty_field = false;
+    let mut rest = template;
+
+    while !rest.is_empty() {
+        let field = rest
+            .strip_prefix('{')
+            .and_then(|after| after.split_once('}'))
+            .and_then(|(name, after)| {
+                let (_, field_spans) = fields.iter().find(|(field, _)| *field == name)?;
+                Some((field_spans, after))
+            });
+
+        if let Some((field_spans, after)) = field {
+            spans.extend(itertools::intersperse(
+                field_spans.iter().cloned(),
+                Span::raw(" "),
+            ));
+            after_empty_field = field_spans.is_empty();
+            rest = after;
+        } else {
+            let end = rest
+                .char_indices()
+                .skip(1)
+                .find(|(_, c)| *c == '{')
+                .map_or(rest.len(), |(i, _)| i);
+            let text = &rest[..end];
+            if !(after_empty_field && text.trim().is_empty()) {
+                spans.push(Span::raw(text.to_string()));
+            }
+            after_empty_field = false;
+            rest = &rest[end..];
+        }
+    }
+
+    while spans
+        .last()
+        .is_some_and(|span| span.content.trim().is_empty())
+    {
+        spans.pop();
+    }
+
+    Line::from(spans)
+}
+
+/// A colored mark for signed commits, nothing for unsigned ones.
+pub(crate) fn signature_mark(config: &Config, status: SignatureStatus) -> Option<Span<'static>> {
+    let style = &config.style;
+    match status {
+        SignatureStatus::Good => Some(Span::styled("✓", &style.signature_good)),
+        SignatureStatus::Bad => Some(Span::styled("✗", &style.signature_bad)),
+        SignatureStatus::Untrusted | SignatureStatus::Unknown => {
+            Some(Span::styled("?", &style.signature_untrusted))
+        }
+        SignatureStatus::Unsigned => None,
+    }
+}
+
 pub(crate) fn blank_line() -> Item {
     Item {
         display: Line::raw(""),
@@ -267,3 +824,44 @@ pub(crate) fn blank_line() -> Item {
         ..Default::default()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(template: &str, fields: &[(&str, Vec<Span<'static>>)]) -> String {
+        format_row(template, fields)
+            .spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect()
+    }
+
+    #[test]
+    fn format_row_placeholders() {
+        let fields = [
+            ("hash", vec!["abc1234".into()]),
+            ("refs", vec!["main".into(), "origin/main".into()]),
+            ("subject", vec!["add file".into()]),
+        ];
+        assert_eq!(
+            row("{hash} {refs} {subject}", &fields),
+            "abc1234 main origin/main add file"
+        );
+        assert_eq!(
+            row("{subject} ({hash}) {unknown}", &fields),
+            "add file (abc1234) {unknown}"
+        );
+    }
+
+    #[test]
+    fn format_row_empty_placeholders() {
+        let fields = [
+            ("hash", vec!["abc1234".into()]),
+            ("refs", vec![]),
+            ("subject", vec!["add file".into()]),
+        ];
+        assert_eq!(row("{hash} {refs} {subject}", &fields), "abc1234 add file");
+        assert_eq!(row("{hash} {subject} {refs}", &fields), "abc1234 add file");
+    }
+}
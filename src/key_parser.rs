@@ -53,7 +53,16 @@ fn parse_special_key(input: &str) -> IResult<&str, (KeyModifiers, KeyCode)> {
         value(KeyCode::Esc, tag("esc")),
         value(KeyCode::CapsLock, tag("capslock")),
     ))(input)
-    .map(|(rem, key)| (rem, (KeyModifiers::NONE, key)))
+    .map(|(rem, key)| {
+        // Like uppercase chars, it's reported with shift held
+        let modifiers = if key == KeyCode::BackTab {
+            KeyModifiers::SHIFT
+        } else {
+            KeyModifiers::NONE
+        };
+
+        (rem, (modifiers, key))
+    })
 }
 
 fn parse_modifier(input: &str) -> IResult<&str, KeyModifiers> {
@@ -109,6 +118,14 @@ mod tests {
         );
     }
 
+    #[test]
+    fn backtab() {
+        assert_eq!(
+            parse_keys("<backtab>"),
+            Ok(("", vec![(KeyModifiers::SHIFT, KeyCode::BackTab)]))
+        );
+    }
+
     #[test]
     fn modifier() {
         assert_eq!(
@@ -2,6 +2,9 @@ mod bindings;
 pub mod cli;
 mod cmd_log;
 pub mod config;
+mod credentials;
+mod edit_server;
+mod error;
 mod git;
 mod git2_opts;
 mod items;
@@ -13,6 +16,8 @@ mod screen;
 pub mod state;
 mod syntax_highlight;
 pub mod term;
+#[cfg(any(test, feature = "test-backend"))]
+pub mod testing;
 #[cfg(test)]
 mod tests;
 mod ui;
@@ -21,7 +26,13 @@ use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventState, KeyModifie
 use git2::Repository;
 use items::Item;
 use ops::Action;
-use std::{error::Error, path::PathBuf, process::Command, rc::Rc, time::Duration};
+use std::{
+    error::Error,
+    path::{Path, PathBuf},
+    process::Command,
+    rc::Rc,
+    time::Duration,
+};
 use term::Term;
 
 //                                An overview of Gitu's ui and terminology:
@@ -64,26 +75,26 @@ use term::Term;
 pub type Res<T> = Result<T, Box<dyn Error>>;
 
 pub fn run(args: &cli::Args, term: &mut Term) -> Res<()> {
-    log::debug!("Finding git dir");
-    let dir = PathBuf::from(
-        String::from_utf8(
-            Command::new("git")
-                .args(["rev-parse", "--show-toplevel"])
-                .output()?
-                .stdout,
-        )?
-        .trim_end(),
-    );
-
-    log::debug!("Opening repo");
-    let repo = Repository::open_from_env()?;
-    repo.set_workdir(&dir, false)?;
+    let repo = match (&args.command, open_repo()) {
+        (Some(cli::Commands::Diff { .. }), Err(err)) => {
+            log::debug!("Not in a repo ({}), diffing without one", err);
+            detached_repo(&std::env::current_dir()?)?
+        }
+        (_, repo) => repo?,
+    };
 
     log::debug!("Initializing config");
     let config = config::init_config()?;
 
     log::debug!("Creating initial state");
-    let mut state = state::State::create(Rc::new(repo), term.size()?, args, Rc::new(config), true)?;
+    let mut state = state::State::create(
+        Rc::new(repo),
+        term.size()?,
+        args,
+        Rc::new(config),
+        || chrono::Utc::now().timestamp(),
+        true,
+    )?;
 
     log::debug!("Initial update");
     state.update(term, &[Event::FocusGained])?;
@@ -112,6 +123,44 @@ pub fn run(args: &cli::Args, term: &mut Term) -> Res<()> {
     Ok(())
 }
 
+fn open_repo() -> Res<Repository> {
+    log::debug!("Finding git dir");
+    let dir = PathBuf::from(
+        String::from_utf8(
+            Command::new("git")
+                .args(["rev-parse", "--show-toplevel"])
+                .output()?
+                .stdout,
+        )?
+        .trim_end(),
+    );
+
+    log::debug!("Opening repo");
+    let repo = Repository::open_from_env()?;
+    repo.set_workdir(&dir, false)?;
+    Ok(repo)
+}
+
+/// A repository without any objects or refs, rooted at `dir`, for the screens that
+/// need to be handed one while looking at files that aren't in any repository.
+pub(crate) fn detached_repo(dir: &Path) -> Res<Repository> {
+    let repo = Repository::from_odb(git2::Odb::new()?)?;
+    repo.set_workdir(dir, false)?;
+    Ok(repo)
+}
+
+/// Prints the keybindings in effect after merging the user's config with the defaults.
+pub fn print_keys() -> Res<()> {
+    let config = config::init_config()?;
+    print!("{}", bindings::Bindings::from(&config.bindings).to_toml()?);
+    Ok(())
+}
+
+/// Stands in for git's editor, see `gitu --edit`.
+pub fn edit(file: &Path) -> Res<()> {
+    edit_server::edit(file)
+}
+
 fn handle_initial_send_keys(
     keys: &[(KeyModifiers, KeyCode)],
     state: &mut state::State,
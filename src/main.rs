@@ -1,5 +1,8 @@
 use clap::Parser;
-use gitu::{cli::Args, term, Res};
+use gitu::{
+    cli::{Args, Commands},
+    term, Res,
+};
 use log::LevelFilter;
 use ratatui::Terminal;
 use std::{backtrace::Backtrace, panic};
@@ -14,6 +17,14 @@ pub fn main() -> Res<()> {
         return Ok(());
     }
 
+    if let Some(file) = &args.edit {
+        return gitu::edit(file);
+    }
+
+    if let Some(Commands::Keys) = args.command {
+        return gitu::print_keys();
+    }
+
     if args.log {
         simple_logging::log_to_file("gitu.log", LevelFilter::Trace)?;
     }
@@ -2,8 +2,10 @@ use std::borrow::Cow;
 use std::collections::BTreeMap;
 use std::ffi::OsString;
 
+use git2::Repository;
 use serde::{Deserialize, Serialize};
 
+use crate::git;
 use crate::ops;
 
 pub(crate) mod arg;
@@ -13,34 +15,64 @@ pub(crate) mod arg;
 pub(crate) enum Menu {
     #[serde(rename = "root")]
     Root,
+    #[serde(rename = "bisect_menu")]
+    Bisect,
     #[serde(rename = "branch_menu")]
     Branch,
+    #[serde(rename = "cherry_pick_menu")]
+    CherryPick,
+    #[serde(rename = "clean_menu")]
+    Clean,
+    #[serde(rename = "clean_preview_menu")]
+    CleanPreview,
     #[serde(rename = "commit_menu")]
     Commit,
+    #[serde(rename = "commit_picker_menu")]
+    CommitPicker,
+    #[serde(rename = "diff_menu")]
+    Diff,
     #[serde(rename = "fetch_menu")]
     Fetch,
+    #[serde(rename = "follow_up_menu")]
+    FollowUp,
     #[serde(rename = "help_menu")]
     Help,
     #[serde(rename = "log_menu")]
     Log,
+    #[serde(rename = "merge_menu")]
+    Merge,
+    #[serde(rename = "patch_menu")]
+    Patch,
     #[serde(rename = "pull_menu")]
     Pull,
     #[serde(rename = "push_menu")]
     Push,
     #[serde(rename = "rebase_menu")]
     Rebase,
+    #[serde(rename = "rebase_todo_menu")]
+    RebaseTodo,
     #[serde(rename = "reset_menu")]
     Reset,
     #[serde(rename = "revert_menu")]
     Revert,
     #[serde(rename = "stash_menu")]
     Stash,
+    #[serde(rename = "submodule_menu")]
+    Submodule,
+    #[serde(rename = "worktree_menu")]
+    Worktree,
 }
 
 pub(crate) struct PendingMenu {
     pub menu: Menu,
     pub is_hidden: bool,
     pub(crate) args: BTreeMap<Cow<'static, str>, arg::Arg>,
+    /// Limits the menu to these ops, what the follow-up menu offers after a failure.
+    pub(crate) follow_ups: Vec<ops::Op>,
+    /// The installed hooks that its commands run, read once as it's opened
+    pub(crate) hooks: Vec<String>,
+    /// Those of `hooks` that the `--no-verify` argument skips
+    pub(crate) skippable_hooks: Vec<String>,
 }
 
 impl PendingMenu {
@@ -50,24 +82,79 @@ impl PendingMenu {
             is_hidden: false,
             args: match menu {
                 Menu::Root => vec![],
+                Menu::Bisect => ops::bisect::init_args(),
                 Menu::Branch => ops::checkout::init_args(),
+                Menu::CherryPick => ops::cherry_pick::init_args(),
+                Menu::Clean => ops::clean::init_args(),
+                Menu::CleanPreview => vec![],
                 Menu::Commit => ops::commit::init_args(),
+                Menu::CommitPicker => vec![],
+                Menu::Diff => ops::diff::init_args(),
                 Menu::Fetch => ops::fetch::init_args(),
+                Menu::FollowUp => vec![],
                 Menu::Help => vec![],
                 Menu::Log => ops::log::init_args(),
+                Menu::Merge => ops::merge::init_args(),
+                Menu::Patch => ops::patch::init_args(),
                 Menu::Pull => ops::pull::init_args(),
                 Menu::Push => ops::push::init_args(),
                 Menu::Rebase => ops::rebase::init_args(),
+                Menu::RebaseTodo => vec![],
                 Menu::Reset => ops::reset::init_args(),
                 Menu::Revert => ops::revert::init_args(),
                 Menu::Stash => ops::stash::init_args(),
+                Menu::Submodule => ops::submodule::init_args(),
+                Menu::Worktree => ops::worktree::init_args(),
             }
             .into_iter()
             .map(|arg| (Cow::from(arg.arg), arg))
             .collect(),
+            follow_ups: vec![],
+            hooks: vec![],
+            skippable_hooks: vec![],
         }
     }
 
+    /// Finds which of the hooks that the menu's commands run are installed.
+    pub fn load_hooks(&mut self, repo: &Repository) {
+        let (run, skippable): (&[&str], &[&str]) = match self.menu {
+            Menu::Commit => (
+                &[
+                    "pre-commit",
+                    "prepare-commit-msg",
+                    "commit-msg",
+                    "post-commit",
+                ],
+                &["pre-commit", "commit-msg"],
+            ),
+            Menu::Push => (&["pre-push"], &["pre-push"]),
+            Menu::Rebase => (
+                &["pre-rebase", "post-checkout", "post-rewrite"],
+                &["pre-rebase"],
+            ),
+            _ => return,
+        };
+
+        // In the order they run
+        let active = git::active_hooks(repo);
+        self.hooks = run
+            .iter()
+            .filter(|hook| active.iter().any(|active| active == *hook))
+            .map(|hook| hook.to_string())
+            .collect();
+        self.skippable_hooks = self
+            .hooks
+            .iter()
+            .filter(|hook| skippable.contains(&hook.as_str()))
+            .cloned()
+            .collect();
+    }
+
+    /// Whether `op` can be run from the menu, follow-up menus only offer some ops.
+    pub fn offers(&self, op: &ops::Op) -> bool {
+        self.follow_ups.is_empty() || *op == ops::Op::Quit || self.follow_ups.contains(op)
+    }
+
     pub fn args(&self) -> Vec<OsString> {
         self.args
             .iter()
@@ -75,4 +162,28 @@ impl PendingMenu {
             .map(|(_, v)| v.get_cli_token().into())
             .collect()
     }
+
+    /// The hooks that would run, leaving out those skipped with the `--no-verify` argument.
+    pub fn running_hooks(&self) -> Vec<&str> {
+        self.hooks
+            .iter()
+            .filter(|hook| !self.skips_hooks() || !self.skippable_hooks.contains(hook))
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// The hooks that would run, but could be skipped with the `--no-verify` argument.
+    pub fn skippable_hooks(&self) -> &[String] {
+        if self.skips_hooks() {
+            &[]
+        } else {
+            &self.skippable_hooks
+        }
+    }
+
+    fn skips_hooks(&self) -> bool {
+        self.args
+            .get("--no-verify")
+            .is_some_and(|no_verify| no_verify.is_active())
+    }
 }
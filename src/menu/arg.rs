@@ -176,6 +176,14 @@ pub fn any_regex(s: &str) -> Res<Regex> {
     Ok(Regex::try_from(s)?)
 }
 
+pub fn any_string(s: &str) -> Res<String> {
+    if s.is_empty() {
+        return Err("Value must not be empty".into());
+    }
+
+    Ok(s.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use crate::menu::arg::{self, Arg};
@@ -0,0 +1,47 @@
+use super::{Action, OpTrait};
+use crate::{git::diff::PatchMode, items::TargetData};
+use derive_more::Display;
+use std::{process::Command, rc::Rc};
+
+#[derive(Display)]
+#[display(fmt = "Apply")]
+pub(crate) struct Apply;
+impl OpTrait for Apply {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        apply_patch(target, &[], PatchMode::Normal)
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+}
+
+/// Applies the patch of the selected file, hunk or line to the worktree with `git apply`,
+/// given `args` on top. A single line is patched in `line_mode`.
+pub(super) fn apply_patch(
+    target: Option<&TargetData>,
+    args: &'static [&'static str],
+    line_mode: PatchMode,
+) -> Option<Action> {
+    let (patch, recount): (Box<dyn Fn() -> String>, bool) = match target.cloned()? {
+        TargetData::Delta(d) => (Box::new(move || d.format_patch()), false),
+        TargetData::Hunk(h) => (Box::new(move || h.format_patch()), false),
+        TargetData::HunkLine(h, i) => (
+            Box::new(move || h.format_line_patch(i..(i + 1), line_mode)),
+            true,
+        ),
+        _ => return None,
+    };
+
+    Some(Rc::new(move |state, term| {
+        let mut cmd = Command::new("git");
+        cmd.arg("apply");
+        cmd.args(args);
+        if recount {
+            cmd.arg("--recount");
+        }
+
+        state.close_menu();
+        state.run_cmd(term, &patch().into_bytes(), cmd)
+    }))
+}
@@ -0,0 +1,113 @@
+use super::{create_prompt_with_default, selected_rev, Action, OpTrait};
+use crate::{items::TargetData, menu::arg::Arg, screen, state::State, term::Term, Res};
+use derive_more::Display;
+use std::{process::Command, rc::Rc};
+
+pub(crate) fn init_args() -> Vec<Arg> {
+    vec![]
+}
+
+#[derive(Display)]
+#[display(fmt = "Show bisect")]
+pub(crate) struct ShowBisect;
+impl OpTrait for ShowBisect {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            state.close_menu();
+            goto_bisect_screen(state)
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Start")]
+pub(crate) struct BisectStart;
+impl OpTrait for BisectStart {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt_with_default(
+            "Bisect start, good revision",
+            bisect_start,
+            selected_rev,
+            true,
+        ))
+    }
+}
+
+fn bisect_start(state: &mut State, term: &mut Term, good: &str) -> Res<()> {
+    let mut cmd = Command::new("git");
+    cmd.args(["bisect", "start", "HEAD", good]);
+
+    state.close_menu();
+    state.run_cmd(term, &[], cmd)?;
+    goto_bisect_screen(state)
+}
+
+#[derive(Display)]
+#[display(fmt = "Good")]
+pub(crate) struct BisectGood;
+impl OpTrait for BisectGood {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        bisect_mark("good", target)
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Bad")]
+pub(crate) struct BisectBad;
+impl OpTrait for BisectBad {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        bisect_mark("bad", target)
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Skip")]
+pub(crate) struct BisectSkip;
+impl OpTrait for BisectSkip {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        bisect_mark("skip", target)
+    }
+}
+
+/// Marks the selected commit, or the current candidate if no commit is selected.
+fn bisect_mark(term_name: &'static str, target: Option<&TargetData>) -> Option<Action> {
+    let rev = match target {
+        Some(TargetData::Commit(rev)) => Some(rev.clone()),
+        _ => None,
+    };
+
+    Some(Rc::new(move |state: &mut State, term: &mut Term| {
+        let mut cmd = Command::new("git");
+        cmd.args(["bisect", term_name]);
+        cmd.args(&rev);
+
+        state.close_menu();
+        state.run_cmd(term, &[], cmd)
+    }))
+}
+
+#[derive(Display)]
+#[display(fmt = "Reset")]
+pub(crate) struct BisectReset;
+impl OpTrait for BisectReset {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            let mut cmd = Command::new("git");
+            cmd.args(["bisect", "reset"]);
+
+            state.close_menu();
+            state.run_cmd(term, &[], cmd)
+        }))
+    }
+}
+
+fn goto_bisect_screen(state: &mut State) -> Res<()> {
+    state.screens.drain(1..);
+    let size = state.screens.last().unwrap().size;
+    state.screens.push(screen::bisect::create(
+        Rc::clone(&state.config),
+        Rc::clone(&state.repo),
+        size,
+    )?);
+    Ok(())
+}
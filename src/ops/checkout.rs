@@ -1,5 +1,10 @@
-use super::{create_prompt_with_default, selected_rev, Action, OpTrait};
-use crate::{items::TargetData, menu::arg::Arg, prompt::PromptData, state::State, term::Term, Res};
+use super::{
+    create_prompt_with_default, create_y_n_prompt, selected_rev, set_prompt, Action, OpTrait,
+};
+use crate::{
+    git, items::TargetData, menu::arg::Arg, prompt::PromptData, screen, state::State, term::Term,
+    Res,
+};
 use derive_more::Display;
 use std::{process::Command, rc::Rc};
 use tui_prompts::State as _;
@@ -23,6 +28,30 @@ impl OpTrait for Checkout {
 }
 
 fn checkout(state: &mut State, term: &mut Term, rev: &str) -> Res<()> {
+    if let Some(local) = git::remote_branch_local_name(&state.repo, rev) {
+        // Offer the remote's name for the local branch, unless that's taken
+        let default = if state
+            .repo
+            .find_branch(&local, git2::BranchType::Local)
+            .is_ok()
+        {
+            state.display_info(format!("Branch '{}' already exists", local));
+            None
+        } else {
+            Some(local)
+        };
+
+        set_prompt(
+            state,
+            "Name of the tracking branch",
+            checkout_tracking,
+            Box::new(move |_| default.clone()),
+            rev.to_string(),
+            true,
+        );
+        return Ok(());
+    }
+
     let mut cmd = Command::new("git");
     cmd.args(["checkout"]);
     cmd.args(state.pending_menu.as_ref().unwrap().args());
@@ -33,6 +62,16 @@ fn checkout(state: &mut State, term: &mut Term, rev: &str) -> Res<()> {
     Ok(())
 }
 
+/// Creates a local branch tracking the remote branch `upstream`, like `git switch` does.
+fn checkout_tracking(state: &mut State, term: &mut Term, name: &str, upstream: &String) -> Res<()> {
+    let mut cmd = Command::new("git");
+    cmd.args(["checkout", "-b", name, "--track"]);
+    cmd.arg(upstream);
+
+    state.close_menu();
+    state.run_cmd(term, &[], cmd)
+}
+
 #[derive(Display)]
 #[display(fmt = "Checkout new branch")]
 pub(crate) struct CheckoutNewBranch;
@@ -61,3 +100,255 @@ fn checkout_new_branch_prompt_update(state: &mut State, term: &mut Term) -> Res<
     }
     Ok(())
 }
+
+#[derive(Display)]
+#[display(fmt = "Delete branches with gone upstream")]
+pub(crate) struct DeleteGoneBranches;
+impl OpTrait for DeleteGoneBranches {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            let branches = git::gone_branches(&state.repo)?;
+            state.close_menu();
+
+            if branches.is_empty() {
+                state.display_info("No branches with a gone upstream".to_string());
+                return Ok(());
+            }
+
+            state.display_info(branches.join("  "));
+
+            let mut prompt = create_y_n_prompt(
+                Rc::new(move |state: &mut State, term: &mut Term| {
+                    // Branches with unmerged commits are kept, git reports them as an error
+                    let mut cmd = Command::new("git");
+                    cmd.args(["branch", "-d"]);
+                    cmd.args(&branches);
+
+                    state.run_cmd(term, &[], cmd)
+                }),
+                "Delete these branches?",
+            );
+            Rc::get_mut(&mut prompt).unwrap()(state, term)
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Set upstream")]
+pub(crate) struct SetUpstream;
+impl OpTrait for SetUpstream {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let Some(TargetData::Branch(branch)) = target else {
+            return None;
+        };
+        let branch = branch.clone();
+
+        Some(Rc::new(move |state: &mut State, _term: &mut Term| {
+            set_prompt(
+                state,
+                "Set upstream to",
+                set_upstream,
+                Box::new(|_| None),
+                branch.clone(),
+                true,
+            );
+            state.prompt.completions = git::ref_names(&state.repo)?;
+            Ok(())
+        }))
+    }
+    fn is_target_op(&self) -> bool {
+        true
+    }
+}
+
+fn set_upstream(state: &mut State, term: &mut Term, upstream: &str, branch: &String) -> Res<()> {
+    let mut cmd = Command::new("git");
+    cmd.args(["branch", "--set-upstream-to", upstream]);
+    cmd.arg(branch);
+
+    state.close_menu();
+    state.run_cmd(term, &[], cmd)
+}
+
+#[derive(Display)]
+#[display(fmt = "Switch to branch")]
+pub(crate) struct SwitchBranch;
+impl OpTrait for SwitchBranch {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let Some(TargetData::Branch(branch)) = target else {
+            return None;
+        };
+        let branch = branch.clone();
+
+        Some(Rc::new(move |state: &mut State, term: &mut Term| {
+            checkout(state, term, &branch)
+        }))
+    }
+    fn is_target_op(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Rename branch")]
+pub(crate) struct RenameBranch;
+impl OpTrait for RenameBranch {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let Some(TargetData::Branch(branch)) = target else {
+            return None;
+        };
+        let branch = branch.clone();
+
+        Some(Rc::new(move |state: &mut State, _term: &mut Term| {
+            ensure_local_branch(state, &branch)?;
+
+            let current = branch.clone();
+            set_prompt(
+                state,
+                "Rename branch to",
+                rename_branch,
+                Box::new(move |_| Some(current.clone())),
+                branch.clone(),
+                true,
+            );
+            Ok(())
+        }))
+    }
+    fn is_target_op(&self) -> bool {
+        true
+    }
+}
+
+fn rename_branch(state: &mut State, term: &mut Term, name: &str, branch: &String) -> Res<()> {
+    state.close_menu();
+    if name == branch {
+        return Ok(());
+    }
+
+    let mut cmd = Command::new("git");
+    cmd.args(["branch", "--move"]);
+    cmd.args([branch, name]);
+
+    state.run_cmd(term, &[], cmd)
+}
+
+#[derive(Display)]
+#[display(fmt = "Delete branch")]
+pub(crate) struct DeleteBranch;
+impl OpTrait for DeleteBranch {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let Some(TargetData::Branch(branch)) = target else {
+            return None;
+        };
+        let branch = branch.clone();
+
+        Some(create_y_n_prompt(
+            Rc::new(move |state: &mut State, term: &mut Term| {
+                delete_branch(state, term, branch.clone())
+            }),
+            "Really delete branch?",
+        ))
+    }
+    fn is_target_op(&self) -> bool {
+        true
+    }
+}
+
+/// Deletes a local branch, asking again before forcing it when its commits aren't merged.
+pub(crate) fn delete_branch(state: &mut State, term: &mut Term, branch: String) -> Res<()> {
+    ensure_local_branch(state, &branch)?;
+    state.close_menu();
+
+    if git::is_merged(&state.repo, &branch)? {
+        let mut cmd = Command::new("git");
+        cmd.args(["branch", "--delete"]);
+        cmd.arg(&branch);
+        return state.run_cmd(term, &[], cmd);
+    }
+
+    state.display_info(format!("Branch '{}' is not fully merged", branch));
+    let mut prompt = create_y_n_prompt(
+        Rc::new(move |state: &mut State, term: &mut Term| {
+            let mut cmd = Command::new("git");
+            cmd.args(["branch", "--delete", "--force"]);
+            cmd.arg(&branch);
+            state.run_cmd(term, &[], cmd)
+        }),
+        "Delete it anyway, losing its unmerged commits?",
+    );
+    Rc::get_mut(&mut prompt).unwrap()(state, term)
+}
+
+fn ensure_local_branch(state: &State, branch: &str) -> Res<()> {
+    if state
+        .repo
+        .find_branch(branch, git2::BranchType::Local)
+        .is_err()
+    {
+        return Err(format!("'{}' is not a local branch", branch).into());
+    }
+    Ok(())
+}
+
+#[derive(Display)]
+#[display(fmt = "Compare branches")]
+pub(crate) struct CompareBranches;
+impl OpTrait for CompareBranches {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let selected = match target {
+            Some(TargetData::Branch(branch) | TargetData::Tag(branch)) => Some(branch.clone()),
+            _ => None,
+        };
+
+        Some(Rc::new(move |state: &mut State, _term: &mut Term| {
+            let selected = selected.clone();
+            set_prompt(
+                state,
+                "Compare",
+                prompt_compare_with,
+                Box::new(move |state| {
+                    selected
+                        .clone()
+                        .or_else(|| state.repo.head().ok()?.shorthand().map(String::from))
+                }),
+                (),
+                true,
+            );
+            state.prompt.completions = git::ref_names(&state.repo)?;
+            Ok(())
+        }))
+    }
+}
+
+fn prompt_compare_with(state: &mut State, _term: &mut Term, ours: &str, _: &()) -> Res<()> {
+    let ours = ours.to_string();
+    let upstream = git::upstream_of(&state.repo, &ours);
+
+    set_prompt(
+        state,
+        "Compare with",
+        compare_branches,
+        Box::new(move |_| upstream.clone()),
+        ours,
+        true,
+    );
+    state.prompt.completions = git::ref_names(&state.repo)?;
+    Ok(())
+}
+
+fn compare_branches(state: &mut State, _term: &mut Term, theirs: &str, ours: &String) -> Res<()> {
+    state.close_menu();
+    if ours.is_empty() || theirs.is_empty() {
+        return Err("Two branches are needed to compare".into());
+    }
+
+    let size = state.screens.last().unwrap().size;
+    state.screens.push(screen::compare::create(
+        Rc::clone(&state.config),
+        Rc::clone(&state.repo),
+        size,
+        ours.to_string(),
+        theirs.to_string(),
+    )?);
+    Ok(())
+}
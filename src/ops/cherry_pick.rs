@@ -0,0 +1,86 @@
+use std::{process::Command, rc::Rc};
+
+use crate::{items::TargetData, menu::arg::Arg, state::State, term::Term, Res};
+use derive_more::*;
+
+use super::{create_prompt_with_default, selected_rev, Action, OpTrait};
+
+pub(crate) fn init_args() -> Vec<Arg> {
+    vec![
+        Arg::new_flag("-x", "Reference cherry in commit message", false),
+        Arg::new_flag("--edit", "Edit commit message", false),
+        Arg::new_flag("--signoff", "Add Signed-off-by lines", false),
+    ]
+}
+
+#[derive(Display)]
+#[display(fmt = "Cherry-pick abort")]
+pub(crate) struct CherryPickAbort;
+impl OpTrait for CherryPickAbort {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            let mut cmd = Command::new("git");
+            cmd.args(["cherry-pick", "--abort"]);
+
+            state.close_menu();
+            state.run_cmd_interactive(term, cmd)?;
+            Ok(())
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Cherry-pick continue")]
+pub(crate) struct CherryPickContinue;
+impl OpTrait for CherryPickContinue {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            let mut cmd = Command::new("git");
+            cmd.args(["cherry-pick", "--continue"]);
+
+            state.close_menu();
+            state.run_cmd_with_editor(term, cmd)?;
+            Ok(())
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Cherry-pick skip")]
+pub(crate) struct CherryPickSkip;
+impl OpTrait for CherryPickSkip {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            let mut cmd = Command::new("git");
+            cmd.args(["cherry-pick", "--skip"]);
+
+            state.close_menu();
+            state.run_cmd_with_editor(term, cmd)?;
+            Ok(())
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Cherry-pick commit")]
+pub(crate) struct CherryPickCommit;
+impl OpTrait for CherryPickCommit {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt_with_default(
+            "Cherry-pick commit",
+            cherry_pick_commit,
+            selected_rev,
+            true,
+        ))
+    }
+}
+
+fn cherry_pick_commit(state: &mut State, term: &mut Term, input: &str) -> Res<()> {
+    let mut cmd = Command::new("git");
+    cmd.args(["cherry-pick"]);
+    cmd.args(state.pending_menu.as_ref().unwrap().args());
+    cmd.arg(input);
+
+    state.close_menu();
+    state.run_cmd_with_editor(term, cmd)
+}
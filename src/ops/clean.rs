@@ -0,0 +1,136 @@
+use super::{Action, OpTrait};
+use crate::{
+    git,
+    items::TargetData,
+    menu::{arg::Arg, Menu, PendingMenu},
+    screen::{self, NavMode},
+    state::State,
+    term::Term,
+};
+use derive_more::Display;
+use std::{cell::RefCell, path::PathBuf, process::Command, rc::Rc};
+
+pub(crate) fn init_args() -> Vec<Arg> {
+    vec![Arg::new_flag("-x", "Include ignored files", false)]
+}
+
+#[derive(Display)]
+#[display(fmt = "Clean")]
+pub(crate) struct Clean;
+impl OpTrait for Clean {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            let args = state.pending_menu.as_ref().unwrap().args();
+            let clean = git::clean_preview(&state.repo, args)?;
+
+            if clean.entries.is_empty() {
+                state.close_menu();
+                state.display_info("Nothing to clean".to_string());
+                return Ok(());
+            }
+
+            let size = state.screens.last().unwrap().size;
+            state.screens.push(screen::clean::create(
+                Rc::clone(&state.config),
+                Rc::new(RefCell::new(clean)),
+                size,
+            )?);
+            state.pending_menu = Some(PendingMenu::init(Menu::CleanPreview));
+            Ok(())
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Up")]
+pub(crate) struct CleanPreviewUp;
+impl OpTrait for CleanPreviewUp {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state, _term| {
+            state.screen_mut().select_previous(NavMode::Normal);
+            Ok(())
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Down")]
+pub(crate) struct CleanPreviewDown;
+impl OpTrait for CleanPreviewDown {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state, _term| {
+            state.screen_mut().select_next(NavMode::Normal);
+            Ok(())
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Toggle")]
+pub(crate) struct CleanPreviewToggle;
+impl OpTrait for CleanPreviewToggle {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let Some(TargetData::CleanEntry(clean, i)) = target else {
+            return None;
+        };
+        let (clean, i) = (Rc::clone(clean), *i);
+
+        Some(Rc::new(move |state, _term| {
+            clean.borrow_mut().entries[i].selected ^= true;
+            state.screen_mut().update()?;
+            state.screen_mut().select_next(NavMode::Normal);
+            Ok(())
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Remove selected")]
+pub(crate) struct CleanPreviewStart;
+impl OpTrait for CleanPreviewStart {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let Some(TargetData::CleanEntry(clean, _)) = target else {
+            return None;
+        };
+        let clean = Rc::clone(clean);
+
+        Some(Rc::new(move |state: &mut State, term: &mut Term| {
+            let clean = clean.borrow();
+            state.screens.pop();
+            state.close_menu();
+
+            if clean.selected_paths().next().is_none() {
+                state.display_info("Nothing to clean".to_string());
+                return Ok(());
+            }
+
+            // So that a `*` in a name doesn't remove every file it matches
+            let mut cmd = Command::new("git");
+            cmd.args(["--literal-pathspecs", "clean", "-f", "-d"]);
+            cmd.args(&clean.args);
+            cmd.arg("--");
+            cmd.args(clean.selected_paths());
+
+            let paths = clean
+                .selected_paths()
+                .map(PathBuf::from)
+                .collect::<Vec<_>>();
+            let ignored = clean.args.iter().any(|arg| arg == "-x");
+            super::wip_snapshot_untracked(state, "Before clean", &paths, ignored)?;
+            state.run_cmd(term, &[], cmd)
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Abort")]
+pub(crate) struct CleanPreviewAbort;
+impl OpTrait for CleanPreviewAbort {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            state.screens.pop();
+            state.close_menu();
+            Ok(())
+        }))
+    }
+}
@@ -0,0 +1,166 @@
+use super::{Action, OpTrait};
+use crate::{items::TargetData, Res};
+use serde::{Deserialize, Deserializer, Serializer};
+use std::{fmt::Display, process::Command, rc::Rc};
+
+const PREFIX: &str = "command:";
+
+/// A command from the `[commands]` section of the config, bound as `"command:<name>"`.
+pub(crate) struct RunCommand(pub String);
+impl Display for RunCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+impl OpTrait for RunCommand {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let name = self.0.clone();
+        let target = target.cloned();
+
+        Some(Rc::new(move |state, term| {
+            let Some(command) = state.config.commands.get(&name) else {
+                return Err(format!("No command named {} in [commands]", name).into());
+            };
+            let interactive = command.interactive;
+            let line = expand(&command.run, target.as_ref())?;
+
+            let mut cmd = Command::new("sh");
+            cmd.args(["-c", &line]);
+
+            state.close_menu();
+            if interactive {
+                state.run_cmd_interactive(term, cmd)?;
+                state.screen_mut().update()
+            } else {
+                state.run_cmd(term, &[], cmd)
+            }
+        }))
+    }
+}
+
+/// Replaces the `%(commit)`, `%(branch)` and `%(file)` placeholders of a command
+/// with the (shell quoted) values of the selected item. The command is only scanned once,
+/// so a value that looks like a placeholder is left as it is.
+fn expand(command: &str, target: Option<&TargetData>) -> Res<String> {
+    let mut expanded = String::new();
+    let mut rest = command;
+
+    while let Some(start) = rest.find("%(") {
+        expanded.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let placeholder = ["commit", "branch", "file"]
+            .into_iter()
+            .find(|placeholder| rest[2..].starts_with(&format!("{})", placeholder)));
+        let Some(placeholder) = placeholder else {
+            expanded.push_str("%(");
+            rest = &rest[2..];
+            continue;
+        };
+
+        let Some(value) = target.and_then(|target| value(target, placeholder)) else {
+            return Err(
+                format!("%({}) needs a {} to be selected", placeholder, placeholder).into(),
+            );
+        };
+
+        expanded.push_str(&shell_quote(&value));
+        rest = &rest[placeholder.len() + 3..];
+    }
+
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+fn value(target: &TargetData, placeholder: &str) -> Option<String> {
+    match (placeholder, target) {
+        (
+            "commit",
+            TargetData::Commit(rev)
+            | TargetData::Branch(rev)
+            | TargetData::Tag(rev)
+            | TargetData::WipSnapshot { commit: rev, id: _ }
+            | TargetData::Stash { commit: rev, .. },
+        ) => Some(rev.clone()),
+        ("branch", TargetData::Branch(branch)) => Some(branch.clone()),
+        (
+            "file",
+            TargetData::File(path) | TargetData::Unmerged(path) | TargetData::Conflict(path, _),
+        ) => Some(path.to_string_lossy().to_string()),
+        ("file", TargetData::Delta(delta)) => Some(delta.new_file.to_string_lossy().to_string()),
+        ("file", TargetData::Hunk(hunk) | TargetData::HunkLine(hunk, _)) => {
+            Some(hunk.new_file.to_string_lossy().to_string())
+        }
+        _ => None,
+    }
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+pub(crate) fn serialize_name<S: Serializer>(name: &str, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format!("{}{}", PREFIX, name))
+}
+
+pub(crate) fn deserialize_name<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<String, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    value
+        .strip_prefix(PREFIX)
+        .map(String::from)
+        .ok_or_else(|| serde::de::Error::custom(format!("expected {}<name>", PREFIX)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn expand_placeholders() {
+        assert_eq!(
+            expand(
+                "tig blame %(file)",
+                Some(&TargetData::File(PathBuf::from("it's.txt")))
+            )
+            .unwrap(),
+            r"tig blame 'it'\''s.txt'"
+        );
+        assert_eq!(
+            expand(
+                "git branch -m %(branch) %(branch)-old",
+                Some(&TargetData::Branch("main".into()))
+            )
+            .unwrap(),
+            "git branch -m 'main' 'main'-old"
+        );
+        assert_eq!(expand("git gc", None).unwrap(), "git gc");
+    }
+
+    #[test]
+    fn expand_placeholder_in_value() {
+        assert_eq!(
+            expand(
+                "git checkout %(branch) %(unknown)",
+                Some(&TargetData::Branch("x%(file)';rm -rf ~;'".into()))
+            )
+            .unwrap(),
+            r"git checkout 'x%(file)'\'';rm -rf ~;'\''' %(unknown)"
+        );
+    }
+
+    #[test]
+    fn expand_missing_placeholder() {
+        assert_eq!(
+            expand(
+                "git show %(commit)",
+                Some(&TargetData::File(PathBuf::from("file")))
+            )
+            .unwrap_err()
+            .to_string(),
+            "%(commit) needs a commit to be selected"
+        );
+    }
+}
@@ -1,9 +1,20 @@
-use super::{Action, OpTrait};
-use crate::{items::TargetData, menu::arg::Arg, state::State, term::Term};
+use super::{commit_picker, create_y_n_prompt, set_prompt, Action, OpTrait};
+use crate::{
+    config::Config,
+    git::{self, absorb::Absorb},
+    items::TargetData,
+    menu::{arg::Arg, PendingMenu},
+    screen,
+    state::State,
+    term::Term,
+    Res,
+};
 use derive_more::Display;
 use std::{
     ffi::{OsStr, OsString},
-    process::Command,
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
     rc::Rc,
 };
 
@@ -20,10 +31,22 @@ pub(crate) fn init_args() -> Vec<Arg> {
         ),
         // TODO -A Override the author (--author=)
         Arg::new_flag("--signoff", "Add Signed-off-by line", false),
+        Arg::new_flag("--gpg-sign", "Sign the commit", false),
+        Arg::new_flag("--no-gpg-sign", "Don't sign the commit", false),
         // TODO -C Reuse commit message (--reuse-message=)
     ]
 }
 
+/// Preselects `--gpg-sign` when `general.sign_commits` is enabled.
+pub(crate) fn load_sign_default(menu: &mut PendingMenu, config: &Config) {
+    if let (Some(arg), true) = (
+        menu.args.get_mut("--gpg-sign"),
+        config.general.sign_commits.enabled,
+    ) {
+        arg.set("").expect("Should succeed");
+    }
+}
+
 #[derive(Display)]
 #[display(fmt = "Commit")]
 pub(crate) struct Commit;
@@ -34,13 +57,97 @@ impl OpTrait for Commit {
             cmd.args(["commit"]);
             cmd.args(state.pending_menu.as_ref().unwrap().args());
 
+            let draft = draft_path(&state.repo);
+            if draft.exists() {
+                cmd.args(["--edit", "--file"]);
+                cmd.arg(&draft);
+            }
+
+            warn_whitespace_errors(state, &cmd);
             state.close_menu();
-            state.run_cmd_interactive(term, cmd)?;
-            Ok(())
+            run_commit_editor(state, term, cmd, true)
         }))
     }
 }
 
+/// Runs a `git commit` that may open an editor. Leaving the message empty (or the template
+/// unchanged) is how git lets the user abort, so that isn't reported as an error. If `keep_draft` is set,
+/// a message that didn't make it into a commit, like when the editor exits with an error,
+/// is kept for the next one.
+fn run_commit_editor(
+    state: &mut State,
+    term: &mut Term,
+    mut cmd: Command,
+    keep_draft: bool,
+) -> Res<()> {
+    set_gpg_tty(&mut cmd);
+
+    let message_path = state.repo.path().join("COMMIT_EDITMSG");
+    let draft = draft_path(&state.repo);
+    // Whatever is left there afterwards was written during this commit
+    let _ = fs::remove_file(&message_path);
+
+    let Err(error) = state.run_cmd_interactive(term, cmd) else {
+        if keep_draft {
+            discard_draft(&draft)?;
+        }
+        return Ok(());
+    };
+
+    let Ok(content) = fs::read_to_string(&message_path) else {
+        return Err(error);
+    };
+
+    let message = git::strip_commit_message(&state.repo, &content);
+    if message.is_empty() || Some(&message) == git::commit_template(&state.repo).as_ref() {
+        // Clearing a draft that was filled in is how it gets thrown away
+        if keep_draft && discard_draft(&draft)? {
+            state.display_info("Commit aborted, the draft is discarded".to_string());
+        } else {
+            state.display_info("Commit aborted".to_string());
+        }
+        return Ok(());
+    }
+
+    if keep_draft {
+        fs::write(draft, message)?;
+        state.display_info("The commit message is kept as a draft for the next commit".to_string());
+    }
+
+    Err(error)
+}
+
+/// Lets gpg's pinentry ask for a passphrase on gitu's terminal, which git doesn't tell it about.
+fn set_gpg_tty(cmd: &mut Command) {
+    if std::env::var_os("GPG_TTY").is_some() {
+        return;
+    }
+
+    let Ok(out) = Command::new("tty").stdin(Stdio::inherit()).output() else {
+        return;
+    };
+
+    if out.status.success() {
+        cmd.env("GPG_TTY", String::from_utf8_lossy(&out.stdout).trim_end());
+    }
+}
+
+/// Where a commit message is kept when its commit fails or the editor is cancelled.
+/// It stays there until it's committed or cleared.
+fn draft_path(repo: &git2::Repository) -> PathBuf {
+    repo.path().join("GITU_COMMIT_DRAFT")
+}
+
+/// Removes the draft, if there is one. Returns whether there was.
+fn discard_draft(draft: &Path) -> Res<bool> {
+    if !draft.exists() {
+        return Ok(false);
+    }
+
+    fs::remove_file(draft)?;
+    Ok(true)
+}
+
 #[derive(Display)]
 #[display(fmt = "Commit amend")]
 pub(crate) struct CommitAmend;
@@ -51,41 +158,291 @@ impl OpTrait for CommitAmend {
             cmd.args(["commit", "--amend"]);
             cmd.args(state.pending_menu.as_ref().unwrap().args());
 
+            warn_whitespace_errors(state, &cmd);
             state.close_menu();
-            state.run_cmd_interactive(term, cmd)?;
+            run_commit_editor(state, term, cmd, false)
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Commit conventional")]
+pub(crate) struct CommitConventional;
+impl OpTrait for CommitConventional {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            let args: Rc<[OsString]> = state.pending_menu.as_ref().unwrap().args().into();
+            let types = git::config_list(&state.repo, "gitu.conventionalTypes")
+                .unwrap_or_else(|| state.config.conventional_commits.types.clone());
+
+            set_prompt(
+                state,
+                "Type",
+                conventional_type,
+                Box::new(|_| None),
+                args,
+                true,
+            );
+            state.prompt.completions = types;
             Ok(())
         }))
     }
 }
 
+fn conventional_type(
+    state: &mut State,
+    _term: &mut Term,
+    commit_type: &str,
+    args: &Rc<[OsString]>,
+) -> Res<()> {
+    if commit_type.is_empty() {
+        state.close_menu();
+        return Err("No type given".into());
+    }
+
+    let scopes = git::config_list(&state.repo, "gitu.conventionalScopes")
+        .unwrap_or_else(|| state.config.conventional_commits.scopes.clone());
+
+    set_prompt(
+        state,
+        "Scope (empty for none)",
+        conventional_scope,
+        Box::new(|_| None),
+        (args.clone(), commit_type.to_string()),
+        true,
+    );
+    state.prompt.completions = scopes;
+    Ok(())
+}
+
+fn conventional_scope(
+    state: &mut State,
+    _term: &mut Term,
+    scope: &str,
+    (args, commit_type): &(Rc<[OsString]>, String),
+) -> Res<()> {
+    let prefix = if scope.is_empty() {
+        commit_type.clone()
+    } else {
+        format!("{}({})", commit_type, scope)
+    };
+
+    set_prompt(
+        state,
+        "Subject",
+        conventional_subject,
+        Box::new(|_| None),
+        (args.clone(), prefix),
+        true,
+    );
+    Ok(())
+}
+
+/// Commits with the assembled message, which the editor opens with to add a body.
+fn conventional_subject(
+    state: &mut State,
+    term: &mut Term,
+    subject: &str,
+    (args, prefix): &(Rc<[OsString]>, String),
+) -> Res<()> {
+    if subject.is_empty() {
+        state.close_menu();
+        return Err("No subject given".into());
+    }
+
+    let mut cmd = Command::new("git");
+    cmd.arg("commit");
+    cmd.args(args.iter());
+    cmd.args(["--edit", "--message"]);
+    cmd.arg(format!("{}: {}", prefix, subject));
+
+    warn_whitespace_errors(state, &cmd);
+    state.close_menu();
+    run_commit_editor(state, term, cmd, false)
+}
+
 #[derive(Display)]
 #[display(fmt = "Commit fixup")]
 pub(crate) struct CommitFixup;
 impl OpTrait for CommitFixup {
     fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
-        match target {
-            Some(TargetData::Commit(r)) => {
-                let rev = OsString::from(r);
+        targeting_commit("--fixup", target)
+    }
+}
 
-                Some(Rc::new(move |state: &mut State, term: &mut Term| {
-                    let args = state.pending_menu.as_ref().unwrap().args();
+fn commit_fixup_cmd(args: &[OsString], rev: &OsStr) -> Command {
+    commit_targeting_cmd("--fixup", args, rev)
+}
+
+#[derive(Display)]
+#[display(fmt = "Commit squash")]
+pub(crate) struct CommitSquash;
+impl OpTrait for CommitSquash {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        targeting_commit("--squash", target)
+    }
+}
+
+/// Targets the selected commit, or lets one be picked from the recent ones.
+fn targeting_commit(flag: &'static str, target: Option<&TargetData>) -> Option<Action> {
+    let rev = match target {
+        Some(TargetData::Commit(r)) => Some(OsString::from(r)),
+        _ => None,
+    };
 
-                    state.close_menu();
-                    state.run_cmd_interactive(term, commit_fixup_cmd(&args, &rev))
-                }))
+    Some(Rc::new(move |state: &mut State, term: &mut Term| {
+        let args = state.pending_menu.as_ref().unwrap().args();
+
+        match &rev {
+            Some(rev) => {
+                state.close_menu();
+                run_commit_editor(state, term, commit_targeting_cmd(flag, &args, rev), false)
             }
-            _ => None,
+            None => commit_picker::pick_target(state, flag, args, false),
         }
-    }
-    fn is_target_op(&self) -> bool {
-        true
-    }
+    }))
 }
 
-fn commit_fixup_cmd(args: &[OsString], rev: &OsStr) -> Command {
+/// A commit meant to be folded into `rev` by `git rebase --autosquash`.
+pub(crate) fn commit_targeting_cmd(flag: &str, args: &[OsString], rev: &OsStr) -> Command {
     let mut cmd = Command::new("git");
-    cmd.args(["commit", "--fixup"]);
+    cmd.args(["commit", flag]);
     cmd.arg(rev);
     cmd.args(args);
     cmd
 }
+
+#[derive(Display)]
+#[display(fmt = "Commit instant fixup")]
+pub(crate) struct CommitInstantFixup;
+impl OpTrait for CommitInstantFixup {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let rev = match target {
+            Some(TargetData::Commit(r)) => Some(OsString::from(r)),
+            _ => None,
+        };
+
+        Some(Rc::new(move |state: &mut State, term: &mut Term| {
+            let args = state.pending_menu.as_ref().unwrap().args();
+
+            let Some(rev) = &rev else {
+                return commit_picker::pick_target(state, "--fixup", args, true);
+            };
+
+            state.close_menu();
+            state.run_cmd(term, &[], commit_fixup_cmd(&args, rev))?;
+            state.run_cmd(term, &[], instant_fixup_rebase_cmd(rev))
+        }))
+    }
+}
+
+/// Squashes the fixup commit into `rev` right away, accepting the generated todo as-is.
+pub(crate) fn instant_fixup_rebase_cmd(rev: &OsStr) -> Command {
+    let mut base = rev.to_os_string();
+    base.push("^");
+
+    let mut cmd = Command::new("git");
+    cmd.args(["rebase", "-i", "--autosquash", "--autostash", "--quiet"]);
+    cmd.arg(base);
+    cmd.env("GIT_SEQUENCE_EDITOR", "true");
+    cmd
+}
+
+#[derive(Display)]
+#[display(fmt = "Absorb")]
+pub(crate) struct CommitAbsorb;
+impl OpTrait for CommitAbsorb {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            let absorb = Rc::new(git::absorb(&state.config, &state.repo)?);
+            if absorb.fixups.is_empty() {
+                state.close_menu();
+                state.display_error("No staged changes belong to a recent commit".to_string());
+                return Ok(());
+            }
+
+            // Only the absorbed hunks should end up in the fixups
+            let mut args = state.pending_menu.as_ref().unwrap().args();
+            args.retain(|arg| arg != "--all");
+
+            let size = state.screens.last().unwrap().size;
+            state.screens.push(screen::absorb::create(
+                Rc::clone(&state.config),
+                Rc::clone(&absorb),
+                size,
+            )?);
+            state.close_menu();
+
+            let mut prompt = create_y_n_prompt(
+                Rc::new(move |state: &mut State, term: &mut Term| {
+                    state.screens.pop();
+                    create_fixups(state, term, &absorb, &args)
+                }),
+                "Create these fixup commits?",
+            );
+            Rc::get_mut(&mut prompt).unwrap()(state, term)?;
+            state.prompt.on_abort = Some(Rc::new(|state: &mut State, _term: &mut Term| {
+                state.screens.pop();
+                Ok(())
+            }));
+            Ok(())
+        }))
+    }
+}
+
+/// Commits each group of hunks on its own, using a separate index so the rest stays staged.
+fn create_fixups(
+    state: &mut State,
+    term: &mut Term,
+    absorb: &Absorb,
+    args: &[OsString],
+) -> Res<()> {
+    let index_file = state.repo.path().join("gitu-absorb-index");
+
+    let result = absorb.fixups.iter().try_for_each(|fixup| {
+        let index_cmd = |args: &[&str]| {
+            let mut cmd = Command::new("git");
+            cmd.args(args);
+            cmd.env("GIT_INDEX_FILE", &index_file);
+            cmd
+        };
+
+        state.run_cmd(term, &[], index_cmd(&["read-tree", "HEAD"]))?;
+        state.run_cmd(
+            term,
+            fixup.diff.format_patch().as_bytes(),
+            index_cmd(&["apply", "--cached"]),
+        )?;
+
+        let mut cmd = commit_fixup_cmd(args, OsStr::new(&fixup.commit));
+        cmd.env("GIT_INDEX_FILE", &index_file);
+        state.run_cmd(term, &[], cmd)
+    });
+
+    if index_file.exists() {
+        fs::remove_file(&index_file)?;
+    }
+    result
+}
+
+/// Summarizes whitespace errors in the changes about to be committed, like `git diff --check`.
+/// It's only advisory, so the commit goes ahead if the check itself fails.
+fn warn_whitespace_errors(state: &mut State, cmd: &Command) {
+    let all = cmd.get_args().any(|arg| arg == "--all");
+    let errors = match git::whitespace_errors(&state.repo, all) {
+        Ok(errors) => errors,
+        Err(e) => {
+            log::warn!("Couldn't check for whitespace errors: {}", e);
+            return;
+        }
+    };
+
+    if !errors.is_empty() {
+        state.display_error(format!(
+            "{} whitespace {} in changes to commit: {}",
+            errors.len(),
+            if errors.len() == 1 { "error" } else { "errors" },
+            errors.join(", ")
+        ));
+    }
+}
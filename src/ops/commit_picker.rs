@@ -0,0 +1,155 @@
+use super::{
+    commit::{commit_targeting_cmd, instant_fixup_rebase_cmd},
+    set_prompt, Action, OpTrait,
+};
+use crate::{
+    git::{self, commit_picker::CommitPicker},
+    items::TargetData,
+    menu::{Menu, PendingMenu},
+    screen::{self, NavMode},
+    state::State,
+    term::Term,
+    Res,
+};
+use derive_more::Display;
+use std::{
+    cell::RefCell,
+    ffi::{OsStr, OsString},
+    rc::Rc,
+};
+
+/// Opens a picker of recent commits to target with `git commit <flag>`,
+/// an `instant` fixup is also squashed into the picked commit.
+pub(crate) fn pick_target(
+    state: &mut State,
+    flag: &'static str,
+    args: Vec<OsString>,
+    instant: bool,
+) -> Res<()> {
+    let mut picker = git::commit_picker(&state.config, &state.repo, flag, args)?;
+    picker.instant = instant;
+    let size = state.screens.last().unwrap().size;
+
+    state.screens.push(screen::commit_picker::create(
+        Rc::clone(&state.config),
+        Rc::new(RefCell::new(picker)),
+        size,
+    )?);
+    state.pending_menu = Some(PendingMenu::init(Menu::CommitPicker));
+    Ok(())
+}
+
+#[derive(Display)]
+#[display(fmt = "Up")]
+pub(crate) struct CommitPickerUp;
+impl OpTrait for CommitPickerUp {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state, _term| {
+            state.screen_mut().select_previous(NavMode::Normal);
+            Ok(())
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Down")]
+pub(crate) struct CommitPickerDown;
+impl OpTrait for CommitPickerDown {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state, _term| {
+            state.screen_mut().select_next(NavMode::Normal);
+            Ok(())
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Filter")]
+pub(crate) struct CommitPickerFilter;
+impl OpTrait for CommitPickerFilter {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let Some(TargetData::PickerCommit(picker, _)) = target else {
+            return None;
+        };
+        let picker = Rc::clone(picker);
+
+        Some(Rc::new(move |state, _term| {
+            set_prompt(
+                state,
+                "Filter commits",
+                filter_commits,
+                Box::new(|_| None),
+                Rc::clone(&picker),
+                true,
+            );
+            Ok(())
+        }))
+    }
+}
+
+fn filter_commits(
+    state: &mut State,
+    _term: &mut Term,
+    filter: &str,
+    picker: &Rc<RefCell<CommitPicker>>,
+) -> Res<()> {
+    picker.borrow_mut().filter = filter.to_string();
+    state.screen_mut().update()
+}
+
+#[derive(Display)]
+#[display(fmt = "Toggle all commits")]
+pub(crate) struct CommitPickerToggleAll;
+impl OpTrait for CommitPickerToggleAll {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let Some(TargetData::PickerCommit(picker, _)) = target else {
+            return None;
+        };
+        let picker = Rc::clone(picker);
+
+        Some(Rc::new(move |state, _term| {
+            picker.borrow_mut().show_all ^= true;
+            state.screen_mut().update()
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Pick")]
+pub(crate) struct CommitPickerPick;
+impl OpTrait for CommitPickerPick {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let Some(TargetData::PickerCommit(picker, Some(commit))) = target else {
+            return None;
+        };
+        let (picker, commit) = (Rc::clone(picker), commit.clone());
+
+        Some(Rc::new(move |state: &mut State, term: &mut Term| {
+            let picker = picker.borrow();
+            let rev = OsStr::new(&commit);
+            let cmd = commit_targeting_cmd(picker.flag, &picker.args, rev);
+
+            state.screens.pop();
+            state.close_menu();
+            if picker.instant {
+                state.run_cmd(term, &[], cmd)?;
+                state.run_cmd(term, &[], instant_fixup_rebase_cmd(rev))
+            } else {
+                state.run_cmd_interactive(term, cmd)
+            }
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Abort")]
+pub(crate) struct CommitPickerAbort;
+impl OpTrait for CommitPickerAbort {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            state.screens.pop();
+            state.close_menu();
+            Ok(())
+        }))
+    }
+}
@@ -0,0 +1,70 @@
+use super::{Action, OpTrait};
+use crate::{
+    git::{self, conflict::Side},
+    items::TargetData,
+};
+use derive_more::Display;
+use std::{path::PathBuf, process::Command, rc::Rc};
+
+#[derive(Display)]
+#[display(fmt = "Take ours")]
+pub(crate) struct ConflictTakeOurs;
+impl OpTrait for ConflictTakeOurs {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        take_side(target, Side::Ours)
+    }
+    fn is_target_op(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Take theirs")]
+pub(crate) struct ConflictTakeTheirs;
+impl OpTrait for ConflictTakeTheirs {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        take_side(target, Side::Theirs)
+    }
+    fn is_target_op(&self) -> bool {
+        true
+    }
+}
+
+fn take_side(target: Option<&TargetData>, side: Side) -> Option<Action> {
+    let Some(TargetData::Conflict(path, index)) = target else {
+        return None;
+    };
+    let (path, index) = (path.clone(), *index);
+
+    Some(Rc::new(move |state, _term| {
+        state.close_menu();
+        git::resolve_conflict(&state.repo, &path, index, side)?;
+        state.screen_mut().update()
+    }))
+}
+
+#[derive(Display)]
+#[display(fmt = "Open mergetool")]
+pub(crate) struct ConflictMergetool;
+impl OpTrait for ConflictMergetool {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let path: PathBuf = match target {
+            Some(TargetData::Unmerged(path) | TargetData::Conflict(path, _)) => path.clone(),
+            Some(TargetData::Delta(d)) if d.status == git2::Delta::Conflicted => d.new_file.clone(),
+            _ => return None,
+        };
+
+        Some(Rc::new(move |state, term| {
+            let mut cmd = Command::new("git");
+            cmd.args(["mergetool", "--no-prompt", "--"]);
+            cmd.arg(&path);
+
+            state.close_menu();
+            state.run_cmd_interactive(term, cmd)?;
+            Ok(())
+        }))
+    }
+    fn is_target_op(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,270 @@
+use super::{create_prompt_with_default, selected_rev, set_prompt, Action, OpTrait};
+use crate::{
+    git::{self, diff::DiffOptions, RevDiff},
+    items::TargetData,
+    menu::{arg::Arg, PendingMenu},
+    screen,
+    state::State,
+    term::Term,
+    Res,
+};
+use derive_more::Display;
+use std::rc::Rc;
+
+pub(crate) fn init_args() -> Vec<Arg> {
+    vec![
+        Arg::new_flag("--ignore-all-space", "Ignore whitespace", false),
+        Arg::new_flag("--ignore-blank-lines", "Ignore blank lines", false),
+        Arg::new_flag("--find-renames", "Find renames", false),
+    ]
+}
+
+/// The diff flags of `options` as menu args, so the menu shows what diffs are currently using.
+pub(crate) fn load_diff_flags(menu: &mut PendingMenu, options: &DiffOptions) {
+    for (arg, enabled) in [
+        ("--ignore-all-space", options.ignore_all_space),
+        ("--ignore-blank-lines", options.ignore_blank_lines),
+        ("--find-renames", options.find_renames),
+    ] {
+        if let (Some(arg), true) = (menu.args.get_mut(arg), enabled) {
+            arg.set("").expect("Should succeed");
+        }
+    }
+}
+
+/// Keeps the menu args as diff flags for the rest of the session and regenerates the diffs.
+pub(crate) fn store_diff_flags(state: &mut State) -> Res<()> {
+    let Some(menu) = &state.pending_menu else {
+        return Ok(());
+    };
+
+    let is_active = |arg: &str| menu.args.get(arg).is_some_and(Arg::is_active);
+    let mut options = state.diff_options.get();
+    options.ignore_all_space = is_active("--ignore-all-space");
+    options.ignore_blank_lines = is_active("--ignore-blank-lines");
+    options.find_renames = is_active("--find-renames");
+    state.diff_options.set(options);
+
+    state.screen_mut().update()
+}
+
+#[derive(Display)]
+#[display(fmt = "Diff since branching from upstream")]
+pub(crate) struct DiffUpstreamMergeBase;
+impl OpTrait for DiffUpstreamMergeBase {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            let Some(upstream) = git::head_upstream(&state.repo) else {
+                state.close_menu();
+                state.display_error("No upstream is set for the current branch".to_string());
+                return Ok(());
+            };
+
+            goto_merge_base_screen(state, upstream)
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Diff since branching from ref")]
+pub(crate) struct DiffMergeBase;
+impl OpTrait for DiffMergeBase {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt_with_default(
+            "Diff since branching from",
+            diff_merge_base,
+            selected_rev,
+            true,
+        ))
+    }
+}
+
+fn diff_merge_base(state: &mut State, _term: &mut Term, rev: &str) -> Res<()> {
+    goto_merge_base_screen(state, rev.to_string())
+}
+
+#[derive(Display)]
+#[display(fmt = "Range-diff")]
+pub(crate) struct DiffRange;
+impl OpTrait for DiffRange {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt_with_default(
+            "Range-diff old",
+            prompt_range_diff_new,
+            |state| git::head_upstream(&state.repo),
+            true,
+        ))
+    }
+}
+
+fn prompt_range_diff_new(state: &mut State, _term: &mut Term, old: &str) -> Res<()> {
+    set_prompt(
+        state,
+        "Range-diff new",
+        range_diff,
+        Box::new(|state| state.repo.head().ok()?.shorthand().map(String::from)),
+        old.to_string(),
+        true,
+    );
+    Ok(())
+}
+
+fn range_diff(state: &mut State, _term: &mut Term, new: &str, old: &String) -> Res<()> {
+    let size = state.screens.last().unwrap().size;
+    let screen = screen::range_diff::create(
+        Rc::clone(&state.config),
+        Rc::clone(&state.repo),
+        size,
+        String::from(old),
+        new.to_string(),
+    )?;
+
+    state.close_menu();
+    state.screens.drain(1..);
+    state.screens.push(screen);
+    Ok(())
+}
+
+#[derive(Display)]
+#[display(fmt = "Diff worktree against rev")]
+pub(crate) struct DiffWorktree;
+impl OpTrait for DiffWorktree {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        let mut prompt =
+            create_prompt_with_default("Diff worktree against", diff_worktree, selected_rev, true);
+
+        Some(Rc::new(move |state: &mut State, term: &mut Term| {
+            Rc::get_mut(&mut prompt).unwrap()(state, term)?;
+            state.prompt.completions = git::ref_names(&state.repo)?;
+            Ok(())
+        }))
+    }
+}
+
+fn diff_worktree(state: &mut State, _term: &mut Term, rev: &str) -> Res<()> {
+    goto_rev_diff_screen(state, RevDiff::Worktree(rev.to_string()))
+}
+
+#[derive(Display)]
+#[display(fmt = "Diff index against rev")]
+pub(crate) struct DiffIndex;
+impl OpTrait for DiffIndex {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        let mut prompt =
+            create_prompt_with_default("Diff index against", diff_index, selected_rev, true);
+
+        Some(Rc::new(move |state: &mut State, term: &mut Term| {
+            Rc::get_mut(&mut prompt).unwrap()(state, term)?;
+            state.prompt.completions = git::ref_names(&state.repo)?;
+            Ok(())
+        }))
+    }
+}
+
+fn diff_index(state: &mut State, _term: &mut Term, rev: &str) -> Res<()> {
+    goto_rev_diff_screen(state, RevDiff::Index(rev.to_string()))
+}
+
+#[derive(Display)]
+#[display(fmt = "Diff against marked file")]
+pub(crate) struct DiffFiles;
+impl OpTrait for DiffFiles {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let path = match target? {
+            TargetData::File(path) | TargetData::Unmerged(path) => path.clone(),
+            TargetData::Delta(delta) if delta.status != git2::Delta::Deleted => {
+                delta.new_file.clone()
+            }
+            _ => return None,
+        };
+
+        Some(Rc::new(
+            move |state: &mut State, _term: &mut Term| match state.marked_file.take() {
+                None => {
+                    state.close_menu();
+                    state.display_info(format!(
+                        "Marked {}, select another file to diff against it",
+                        path.display()
+                    ));
+                    state.marked_file = Some(path.clone());
+                    Ok(())
+                }
+                Some(marked) if marked == path => {
+                    state.close_menu();
+                    state.display_info(format!("Unmarked {}", path.display()));
+                    Ok(())
+                }
+                Some(marked) => goto_rev_diff_screen(state, RevDiff::Files(marked, path.clone())),
+            },
+        ))
+    }
+    fn is_target_op(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Diff revs")]
+pub(crate) struct DiffRevs;
+impl OpTrait for DiffRevs {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        let mut prompt =
+            create_prompt_with_default("Diff from", prompt_diff_revs_new, selected_rev, true);
+
+        Some(Rc::new(move |state: &mut State, term: &mut Term| {
+            Rc::get_mut(&mut prompt).unwrap()(state, term)?;
+            state.prompt.completions = git::ref_names(&state.repo)?;
+            Ok(())
+        }))
+    }
+}
+
+fn prompt_diff_revs_new(state: &mut State, _term: &mut Term, old: &str) -> Res<()> {
+    set_prompt(
+        state,
+        "Diff to",
+        diff_revs,
+        Box::new(|state| state.repo.head().ok()?.shorthand().map(String::from)),
+        old.to_string(),
+        true,
+    );
+    state.prompt.completions = git::ref_names(&state.repo)?;
+    Ok(())
+}
+
+fn diff_revs(state: &mut State, _term: &mut Term, new: &str, old: &String) -> Res<()> {
+    goto_rev_diff_screen(state, RevDiff::Range(String::from(old), new.to_string()))
+}
+
+pub(crate) fn goto_rev_diff_screen(state: &mut State, rev_diff: RevDiff) -> Res<()> {
+    let size = state.screens.last().unwrap().size;
+    let screen = screen::diff::create_rev_diff(
+        Rc::clone(&state.config),
+        Rc::clone(&state.repo),
+        Rc::clone(&state.diff_options),
+        size,
+        rev_diff,
+    )?;
+
+    state.close_menu();
+    state.screens.drain(1..);
+    state.screens.push(screen);
+    Ok(())
+}
+
+fn goto_merge_base_screen(state: &mut State, rev: String) -> Res<()> {
+    // Creating the screen fails on a bad ref, so do it before leaving the current one
+    let size = state.screens.last().unwrap().size;
+    let screen = screen::diff::create_merge_base(
+        Rc::clone(&state.config),
+        Rc::clone(&state.repo),
+        Rc::clone(&state.diff_options),
+        size,
+        rev,
+    )?;
+
+    state.close_menu();
+    state.screens.drain(1..);
+    state.screens.push(screen);
+    Ok(())
+}
@@ -1,7 +1,7 @@
 use super::{Action, OpTrait};
 use crate::{git::diff::Hunk, items::TargetData};
 use derive_more::Display;
-use std::{path::PathBuf, process::Command, rc::Rc};
+use std::{fs, path::PathBuf, process::Command, rc::Rc};
 
 #[derive(Display)]
 #[display(fmt = "Discard")]
@@ -10,12 +10,26 @@ impl OpTrait for Discard {
     fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
         let action = match target.cloned() {
             Some(TargetData::Branch(branch)) => discard_branch(branch),
-            Some(TargetData::File(file)) => clean_file(file),
+            Some(TargetData::Tag(tag)) => {
+                return Some(super::create_y_n_prompt(
+                    super::tag::delete_tag(tag),
+                    "Really delete tag?",
+                ))
+            }
+            Some(TargetData::AllUntracked(files)) => clean_files(files),
+            Some(TargetData::File(file)) => clean_files(vec![file]),
+            Some(TargetData::UnstagedFiles(files)) => checkout_unstaged_files(files),
             Some(TargetData::Delta(d)) => match d.status {
                 git2::Delta::Added => remove_file(d.new_file),
                 _ => checkout_file(d.old_file),
             },
             Some(TargetData::Hunk(h)) => discard_unstaged_patch(h),
+            Some(TargetData::IndexLock(lock)) => {
+                return Some(super::create_y_n_prompt(
+                    remove_index_lock(lock),
+                    "Really remove the index lock?",
+                ))
+            }
             _ => return None,
         };
 
@@ -27,34 +41,53 @@ impl OpTrait for Discard {
     }
 }
 
+fn remove_index_lock(lock: PathBuf) -> Action {
+    Rc::new(move |state, _term| {
+        state.close_menu();
+        fs::remove_file(&lock)?;
+        state.display_info("Removed the index lock".to_owned());
+        state.screen_mut().update()
+    })
+}
+
 fn discard_branch(branch: String) -> Action {
+    Rc::new(move |state, term| super::checkout::delete_branch(state, term, branch.clone()))
+}
+
+fn clean_files(files: Vec<PathBuf>) -> Action {
     Rc::new(move |state, term| {
         let mut cmd = Command::new("git");
-        cmd.args(["branch", "-d"]);
-        cmd.arg(&branch);
+        cmd.args(["clean", "--force"]);
+        cmd.args(&files);
 
+        super::wip_snapshot_untracked(state, "Before discarding untracked files", &files, false)?;
         state.close_menu();
         state.run_cmd(term, &[], cmd)
     })
 }
 
-fn clean_file(file: PathBuf) -> Action {
+fn remove_file(file: PathBuf) -> Action {
     Rc::new(move |state, term| {
         let mut cmd = Command::new("git");
-        cmd.args(["clean", "--force"]);
+        cmd.args(["rm", "--force"]);
         cmd.arg(&file);
 
+        super::wip_snapshot(
+            state,
+            &format!("Before discarding {}", file.to_string_lossy()),
+        )?;
         state.close_menu();
         state.run_cmd(term, &[], cmd)
     })
 }
 
-fn remove_file(file: PathBuf) -> Action {
+fn checkout_unstaged_files(files: Vec<PathBuf>) -> Action {
     Rc::new(move |state, term| {
         let mut cmd = Command::new("git");
-        cmd.args(["rm", "--force"]);
-        cmd.arg(&file);
+        cmd.args(["checkout", "--"]);
+        cmd.args(&files);
 
+        super::wip_snapshot(state, "Before discarding unstaged changes")?;
         state.close_menu();
         state.run_cmd(term, &[], cmd)
     })
@@ -66,6 +99,10 @@ fn checkout_file(file: PathBuf) -> Action {
         cmd.args(["checkout", "HEAD", "--"]);
         cmd.arg(&file);
 
+        super::wip_snapshot(
+            state,
+            &format!("Before discarding {}", file.to_string_lossy()),
+        )?;
         state.close_menu();
         state.run_cmd(term, &[], cmd)
     })
@@ -76,6 +113,12 @@ fn discard_unstaged_patch(h: Rc<Hunk>) -> Action {
         let mut cmd = Command::new("git");
         cmd.args(["apply", "--reverse"]);
 
+        super::ensure_hunks_apply(state)?;
+        let message = format!(
+            "Before discarding a hunk of {}",
+            h.new_file.to_string_lossy()
+        );
+        super::wip_snapshot(state, &message)?;
         state.close_menu();
         state.run_cmd(term, &h.format_patch().into_bytes(), cmd)
     })
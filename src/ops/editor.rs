@@ -1,14 +1,17 @@
 use super::{set_prompt, Action, OpTrait};
 use crate::{
+    git,
     items::TargetData,
-    menu::PendingMenu,
+    menu::{Menu, PendingMenu},
+    prompt::PromptData,
     screen::NavMode,
     state::{root_menu, State},
     term::Term,
     Res,
 };
 use derive_more::Display;
-use std::rc::Rc;
+use std::{fmt, process::Command, rc::Rc};
+use tui_prompts::State as _;
 
 #[derive(Display)]
 #[display(fmt = "Quit/Close")]
@@ -28,7 +31,9 @@ impl OpTrait for Quit {
                         Ok(())
                     });
 
-                    let mut action = if state.config.general.confirm_quit.enabled {
+                    let mut action = if let Some(unfinished) = unfinished(state)? {
+                        confirm_quit_unfinished(unfinished)
+                    } else if state.config.general.confirm_quit.enabled {
                         super::create_y_n_prompt(quit, "Really quit?")
                     } else {
                         quit
@@ -51,6 +56,91 @@ impl OpTrait for Quit {
     }
 }
 
+/// Something that quitting would leave half done.
+enum Unfinished {
+    /// A command running in the background, or queued to run
+    Command,
+    /// A rebase, merge, revert or cherry-pick, by the name of its git command
+    Operation(&'static str),
+}
+
+fn unfinished(state: &State) -> Res<Option<Unfinished>> {
+    // Nothing the user started is left undone by a background fetch
+    let user_cmd = state.pending_cmd.is_some() && !state.is_auto_fetching();
+    if user_cmd || !state.queued_cmds.is_empty() {
+        return Ok(Some(Unfinished::Command));
+    }
+
+    let repo = &state.repo;
+    let operation = if git::rebase_status(repo)?.is_some() {
+        "rebase"
+    } else if git::merge_status(repo)?.is_some() {
+        "merge"
+    } else if git::revert_status(repo)?.is_some() {
+        "revert"
+    } else if git::cherry_pick_status(repo)?.is_some() {
+        "cherry-pick"
+    } else {
+        return Ok(None);
+    };
+
+    Ok(Some(Unfinished::Operation(operation)))
+}
+
+/// Asks whether to abort what's unfinished and quit, to quit and leave it be, or to stay.
+fn confirm_quit_unfinished(unfinished: Unfinished) -> Action {
+    let prompt_text = match &unfinished {
+        Unfinished::Command => "A command is still running.".to_string(),
+        Unfinished::Operation(operation) => format!("A {} is in progress.", operation),
+    };
+
+    let update_fn = Rc::new(move |state: &mut State, term: &mut Term| {
+        if state.prompt.state.status().is_pending() {
+            match state.prompt.state.value() {
+                "a" => {
+                    state.prompt.reset(term)?;
+                    abort(state, term, &unfinished)?;
+                    state.quit = true;
+                }
+                "d" => {
+                    state.prompt.reset(term)?;
+                    state.quit = true;
+                }
+                "" => (),
+                _ => state.prompt.reset(term)?,
+            }
+        }
+        Ok(())
+    });
+
+    Rc::new(move |state: &mut State, _term: &mut Term| {
+        state.prompt.set(PromptData {
+            prompt_text: format!("{} Quit and (a)bort it, (d)etach or (c)ancel?", prompt_text)
+                .into(),
+            update_fn: update_fn.clone(),
+        });
+        Ok(())
+    })
+}
+
+fn abort(state: &mut State, term: &mut Term, unfinished: &Unfinished) -> Res<()> {
+    match unfinished {
+        Unfinished::Command => {
+            state.queued_cmds.clear();
+            if let Some((child, _)) = &mut state.pending_cmd {
+                child.kill()?;
+            }
+        }
+        Unfinished::Operation(operation) => {
+            let mut cmd = Command::new("git");
+            cmd.args([*operation, "--abort"]);
+            state.run_cmd(term, &[], cmd)?;
+        }
+    }
+
+    Ok(())
+}
+
 #[derive(Display)]
 #[display(fmt = "Submenu")]
 pub(crate) struct OpenMenu(pub crate::menu::Menu);
@@ -58,7 +148,15 @@ impl OpTrait for OpenMenu {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
         let submenu = self.0;
         Some(Rc::new(move |state, _term| {
-            state.pending_menu = Some(PendingMenu::init(submenu));
+            let mut menu = PendingMenu::init(submenu);
+            match submenu {
+                Menu::Diff => super::diff::load_diff_flags(&mut menu, &state.diff_options.get()),
+                Menu::Commit => super::commit::load_sign_default(&mut menu, &state.config),
+                _ => (),
+            }
+            menu.load_hooks(&state.repo);
+
+            state.pending_menu = Some(menu);
             Ok(())
         }))
     }
@@ -71,7 +169,7 @@ impl OpTrait for Refresh {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
         Some(Rc::new(|state, _term| {
             state.close_menu();
-            state.screen_mut().update()
+            state.screen_mut().reload()
         }))
     }
 }
@@ -116,6 +214,14 @@ impl OpTrait for ToggleArg {
                 );
             }
 
+            if state
+                .pending_menu
+                .as_ref()
+                .is_some_and(|menu| menu.menu == Menu::Diff)
+            {
+                super::diff::store_diff_flags(state)?;
+            }
+
             Ok(())
         }))
     }
@@ -145,6 +251,63 @@ impl OpTrait for ToggleSection {
     }
 }
 
+#[derive(Display)]
+#[display(fmt = "Toggle hunks in file")]
+pub(crate) struct ToggleFileHunks;
+impl OpTrait for ToggleFileHunks {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state, _term| {
+            state.close_menu();
+            state.screen_mut().toggle_file_hunks();
+            Ok(())
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Toggle side-by-side diff")]
+pub(crate) struct ToggleSideBySideDiff;
+impl OpTrait for ToggleSideBySideDiff {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state, _term| {
+            state.close_menu();
+            state.screen_mut().toggle_side_by_side()
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Show more diff context")]
+pub(crate) struct IncreaseDiffContext;
+impl OpTrait for IncreaseDiffContext {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state, _term| {
+            set_diff_context(state, |lines| lines + 1)
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Show less diff context")]
+pub(crate) struct DecreaseDiffContext;
+impl OpTrait for DecreaseDiffContext {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state, _term| {
+            // Hunks without any context lines can't be applied, so staging them would fail
+            set_diff_context(state, |lines| lines.saturating_sub(1).max(1))
+        }))
+    }
+}
+
+fn set_diff_context(state: &mut State, f: fn(usize) -> usize) -> Res<()> {
+    let mut options = state.diff_options.get();
+    options.context_lines = f(options.context_lines);
+    state.diff_options.set(options);
+
+    state.close_menu();
+    state.screen_mut().update_keeping_diff_position()
+}
+
 #[derive(Display)]
 #[display(fmt = "Up")]
 pub(crate) struct MoveUp;
@@ -270,3 +433,53 @@ impl OpTrait for HalfPageDown {
         }))
     }
 }
+
+/// Expands sections up to a level and collapses the rest, see `Screen::show_level`.
+pub(crate) struct ShowLevel(pub usize);
+impl OpTrait for ShowLevel {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        let level = self.0;
+        Some(Rc::new(move |state, _term| {
+            state.close_menu();
+            state.screen_mut().show_level(level);
+            Ok(())
+        }))
+    }
+}
+
+impl fmt::Display for ShowLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self.0 {
+            1 => "Collapse all",
+            2 => "Expand to files",
+            3 => "Expand to hunks",
+            _ => "Expand all",
+        })
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Scroll left")]
+pub(crate) struct ScrollLeft;
+impl OpTrait for ScrollLeft {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state, _term| {
+            state.close_menu();
+            state.screen_mut().scroll_left();
+            Ok(())
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Scroll right")]
+pub(crate) struct ScrollRight;
+impl OpTrait for ScrollRight {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state, _term| {
+            state.close_menu();
+            state.screen_mut().scroll_right();
+            Ok(())
+        }))
+    }
+}
@@ -0,0 +1,34 @@
+use super::{set_prompt, Action, OpTrait};
+use crate::{items::TargetData, state::State, term::Term, Res};
+use derive_more::Display;
+use std::{fs, rc::Rc};
+
+#[derive(Display)]
+#[display(fmt = "Export keybindings")]
+pub(crate) struct ExportKeys;
+impl OpTrait for ExportKeys {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state, _term| {
+            let keys = state.bindings.to_toml()?;
+
+            set_prompt(
+                state,
+                "Export keybindings to",
+                export_keys,
+                Box::new(|_| Some("gitu-keys.toml".to_string())),
+                keys,
+                true,
+            );
+            Ok(())
+        }))
+    }
+}
+
+fn export_keys(state: &mut State, _term: &mut Term, file: &str, keys: &String) -> Res<()> {
+    let path = state.repo.workdir().expect("No workdir").join(file);
+    fs::write(&path, keys)?;
+
+    state.close_menu();
+    state.display_info(format!("Keybindings exported to {}", file));
+    Ok(())
+}
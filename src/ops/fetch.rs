@@ -1,5 +1,5 @@
-use super::{create_prompt, Action, OpTrait};
-use crate::{items::TargetData, menu::arg::Arg, state::State, term::Term, Res};
+use super::{create_prompt, create_prompt_with_default, Action, OpTrait};
+use crate::{git, items::TargetData, menu::arg::Arg, screen, state::State, term::Term, Res};
 use derive_more::Display;
 use std::{process::Command, rc::Rc};
 
@@ -17,7 +17,7 @@ impl OpTrait for FetchAll {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
         Some(Rc::new(|state, term| {
             let mut cmd = Command::new("git");
-            cmd.args(["fetch", "--all", "--jobs", "10"]);
+            cmd.args(["fetch", "--progress", "--all", "--jobs", "10"]);
             cmd.args(state.pending_menu.as_ref().unwrap().args());
 
             state.close_menu();
@@ -38,7 +38,7 @@ impl OpTrait for FetchElsewhere {
 
 fn push_elsewhere(state: &mut State, term: &mut Term, remote: &str) -> Res<()> {
     let mut cmd = Command::new("git");
-    cmd.args(["fetch"]);
+    cmd.args(["fetch", "--progress"]);
     cmd.args(state.pending_menu.as_ref().unwrap().args());
     cmd.arg(remote);
 
@@ -46,3 +46,36 @@ fn push_elsewhere(state: &mut State, term: &mut Term, remote: &str) -> Res<()> {
     state.run_cmd_async(term, &[], cmd)?;
     Ok(())
 }
+
+#[derive(Display)]
+#[display(fmt = "Show refs at remote")]
+pub(crate) struct ShowRemoteRefs;
+impl OpTrait for ShowRemoteRefs {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt_with_default(
+            "Show refs at remote",
+            show_remote_refs,
+            |state| git::head_remote(&state.repo),
+            true,
+        ))
+    }
+}
+
+pub(crate) fn goto_remote_refs(remote: String) -> Option<Action> {
+    Some(Rc::new(move |state, term| {
+        show_remote_refs(state, term, &remote)
+    }))
+}
+
+fn show_remote_refs(state: &mut State, term: &mut Term, remote: &str) -> Res<()> {
+    let screen = screen::remote_refs::create(
+        Rc::clone(&state.config),
+        Rc::clone(&state.repo),
+        term.size()?,
+        remote.to_string(),
+    )?;
+
+    state.close_menu();
+    state.screens.push(screen);
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use super::{create_prompt, Action, OpTrait};
+use crate::{items::TargetData, screen, state::State, term::Term, Res};
+use derive_more::Display;
+use std::rc::Rc;
+
+#[derive(Display)]
+#[display(fmt = "Filter files")]
+pub(crate) struct FilterFiles;
+impl OpTrait for FilterFiles {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt(
+            "Filter files (empty to clear)",
+            filter_files,
+            true,
+        ))
+    }
+}
+
+fn filter_files(state: &mut State, term: &mut Term, filter: &str) -> Res<()> {
+    state.close_menu();
+    state.screens = vec![screen::status::create(
+        Rc::clone(&state.config),
+        Rc::clone(&state.repo),
+        Rc::clone(&state.diff_options),
+        Rc::clone(&state.recent_commits_count),
+        term.size()?,
+        state.now,
+        Some(filter)
+            .filter(|filter| !filter.is_empty())
+            .map(String::from),
+    )?];
+    Ok(())
+}
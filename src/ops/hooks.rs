@@ -0,0 +1,26 @@
+use super::{Action, OpTrait};
+use crate::{git, items::TargetData, state::State, term::Term};
+use derive_more::Display;
+use std::rc::Rc;
+
+#[derive(Display)]
+#[display(fmt = "Show hooks")]
+pub(crate) struct ShowHooks;
+impl OpTrait for ShowHooks {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            let dir = git::hooks_dir(&state.repo);
+            let workdir = state.repo.workdir().expect("No workdir");
+            let dir = dir.strip_prefix(workdir).unwrap_or(&dir);
+            let hooks = git::active_hooks(&state.repo);
+
+            state.close_menu();
+            state.display_info(if hooks.is_empty() {
+                format!("No active hooks in {}", dir.display())
+            } else {
+                format!("Active hooks in {}: {}", dir.display(), hooks.join(", "))
+            });
+            Ok(())
+        }))
+    }
+}
@@ -0,0 +1,26 @@
+use super::{Action, OpTrait};
+use crate::{git, items::TargetData};
+use derive_more::Display;
+use std::rc::Rc;
+
+#[derive(Display)]
+#[display(fmt = "Ignore")]
+pub(crate) struct Ignore;
+impl OpTrait for Ignore {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let files = match target {
+            Some(TargetData::File(file)) => vec![file.clone()],
+            Some(TargetData::AllUntracked(files)) => files.clone(),
+            _ => return None,
+        };
+
+        Some(Rc::new(move |state, _term| {
+            state.close_menu();
+            git::ignore(&state.repo, &files)?;
+            state.screen_mut().update()
+        }))
+    }
+    fn is_target_op(&self) -> bool {
+        true
+    }
+}
@@ -1,7 +1,8 @@
-use super::{create_prompt_with_default, selected_rev, Action, OpTrait};
+use super::{create_prompt_with_default, selected_rev, set_prompt, Action, OpTrait};
 use crate::{
-    items::TargetData,
-    menu::arg::{any_regex, positive_number, Arg},
+    git,
+    items::{LogFilter, TargetData},
+    menu::arg::{any_regex, any_string, positive_number, Arg},
     screen,
     state::State,
     term::Term,
@@ -10,7 +11,10 @@ use crate::{
 use derive_more::Display;
 use git2::Oid;
 use regex::Regex;
-use std::rc::Rc;
+use std::{
+    path::{Path, PathBuf},
+    rc::Rc,
+};
 
 pub(crate) fn init_args() -> Vec<Arg> {
     vec![
@@ -21,6 +25,9 @@ pub(crate) fn init_args() -> Vec<Arg> {
             positive_number,
         ),
         Arg::new_arg("--grep", "Search messages", None, any_regex),
+        Arg::new_arg("--author", "Search authors", None, any_regex),
+        Arg::new_arg("--", "Limit to files", None, any_string),
+        Arg::new_flag("--graph", "Show graph", false),
         // Arg::new_str("-S", "Search occurences"), // TOOD: Implement search
     ]
 }
@@ -29,10 +36,20 @@ pub(crate) fn init_args() -> Vec<Arg> {
 #[display(fmt = "Log current")]
 pub(crate) struct LogCurrent;
 impl OpTrait for LogCurrent {
-    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
-        Some(Rc::new(|state: &mut State, _term: &mut Term| {
-            goto_log_screen(state, None);
-            Ok(())
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        // On the refs screen, log the selected branch or tag instead of HEAD
+        let selected_ref = match target {
+            Some(TargetData::Branch(r) | TargetData::Tag(r)) => Some(r.clone()),
+            _ => None,
+        };
+
+        Some(Rc::new(move |state: &mut State, _term: &mut Term| {
+            let rev = match &selected_ref {
+                Some(r) => Some(state.repo.revparse_single(r)?.peel_to_commit()?.id()),
+                None => None,
+            };
+
+            goto_log_screen(state, rev)
         }))
     }
 }
@@ -42,13 +59,109 @@ impl OpTrait for LogCurrent {
 pub(crate) struct LogOther;
 impl OpTrait for LogOther {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
-        Some(create_prompt_with_default(
-            "Log rev",
-            log_other,
-            selected_rev,
-            true,
-        ))
+        let mut prompt = create_prompt_with_default("Log rev", log_other, selected_rev, true);
+
+        Some(Rc::new(move |state: &mut State, term: &mut Term| {
+            Rc::get_mut(&mut prompt).unwrap()(state, term)?;
+            state.prompt.completions = git::ref_names(&state.repo)?;
+            Ok(())
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Log file")]
+pub(crate) struct LogFile;
+impl OpTrait for LogFile {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        // Untracked files have no history to show
+        let Some(TargetData::Delta(delta)) = target else {
+            return None;
+        };
+        let path = delta.new_file.clone();
+
+        Some(Rc::new(move |state: &mut State, _term: &mut Term| {
+            state.screens.drain(1..);
+            let size = state.screens.last().unwrap().size;
+            let (limit, filter) = log_args(state);
+            state.close_menu();
+
+            state.screens.push(screen::log::create_file(
+                Rc::clone(&state.config),
+                Rc::clone(&state.repo),
+                size,
+                limit,
+                path.clone(),
+                filter,
+            )?);
+            Ok(())
+        }))
+    }
+    fn is_target_op(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Log lines")]
+pub(crate) struct LogLines;
+impl OpTrait for LogLines {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        match target {
+            Some(TargetData::Hunk(hunk)) => {
+                let path = hunk.new_file.clone();
+                let touched = hunk.old_lines_touched();
+                let range = format!("{},{}", touched.iter().min()?, touched.iter().max()?);
+
+                Some(Rc::new(move |state: &mut State, term: &mut Term| {
+                    log_lines(state, term, &range, &path)
+                }))
+            }
+            // Any region of a file, as a line range or a function name
+            Some(TargetData::Delta(delta)) if delta.status != git2::Delta::Added => {
+                let path = delta.new_file.clone();
+
+                Some(Rc::new(move |state: &mut State, _term: &mut Term| {
+                    set_prompt(
+                        state,
+                        "Log lines (<start>,<end> or :<funcname>)",
+                        |state, term, range, path: &PathBuf| log_lines(state, term, range, path),
+                        Box::new(|_| None),
+                        path.clone(),
+                        true,
+                    );
+                    Ok(())
+                }))
+            }
+            _ => None,
+        }
+    }
+    fn is_target_op(&self) -> bool {
+        true
+    }
+}
+
+fn log_lines(state: &mut State, _term: &mut Term, range: &str, path: &Path) -> Res<()> {
+    if range.is_empty() {
+        state.close_menu();
+        return Err("No line range given".into());
     }
+
+    state.screens.drain(1..);
+    let size = state.screens.last().unwrap().size;
+    let (limit, filter) = log_args(state);
+    state.close_menu();
+
+    state.screens.push(screen::log::create_lines(
+        Rc::clone(&state.config),
+        Rc::clone(&state.repo),
+        size,
+        limit,
+        path.to_path_buf(),
+        range.to_string(),
+        filter,
+    )?);
+    Ok(())
 }
 
 fn log_other(state: &mut State, _term: &mut Term, result: &str) -> Res<()> {
@@ -63,38 +176,47 @@ fn log_other(state: &mut State, _term: &mut Term, result: &str) -> Res<()> {
 
     let oid = oid_result?;
 
-    goto_log_screen(state, Some(oid));
-    Ok(())
+    goto_log_screen(state, Some(oid))
 }
 
-fn goto_log_screen(state: &mut State, rev: Option<Oid>) {
-    state.screens.drain(1..);
+fn goto_log_screen(state: &mut State, rev: Option<Oid>) -> Res<()> {
     let size = state.screens.last().unwrap().size;
-    let limit = *state
+    let (limit, filter) = log_args(state);
+    let graph = state
         .pending_menu
         .as_ref()
-        .and_then(|m| m.args.get("-n"))
-        .and_then(|arg| arg.value_as::<u32>())
-        .unwrap_or(&u32::MAX);
+        .and_then(|m| m.args.get("--graph"))
+        .is_some_and(|arg| arg.is_active());
 
-    let msg_regex_menu = state
-        .pending_menu
-        .as_ref()
-        .and_then(|m| m.args.get("--grep"));
-
-    let msg_regex = msg_regex_menu.and_then(|arg| arg.value_as::<Regex>().cloned());
+    // Created before leaving the current screens, which are kept if it fails
+    let screen = screen::log::create(
+        Rc::clone(&state.config),
+        Rc::clone(&state.repo),
+        size,
+        limit,
+        rev,
+        filter,
+        graph,
+    )?;
 
     state.close_menu();
+    state.screens.drain(1..);
+    state.screens.push(screen);
+    Ok(())
+}
+
+fn log_args(state: &State) -> (usize, LogFilter) {
+    let arg = |name| state.pending_menu.as_ref().and_then(|m| m.args.get(name));
+
+    let limit = *arg("-n")
+        .and_then(|arg| arg.value_as::<u32>())
+        .unwrap_or(&u32::MAX);
+
+    let filter = LogFilter {
+        message: arg("--grep").and_then(|arg| arg.value_as::<Regex>().cloned()),
+        author: arg("--author").and_then(|arg| arg.value_as::<Regex>().cloned()),
+        path: arg("--").and_then(|arg| arg.value_as::<String>().cloned()),
+    };
 
-    state.screens.push(
-        screen::log::create(
-            Rc::clone(&state.config),
-            Rc::clone(&state.repo),
-            size,
-            limit as usize,
-            rev,
-            msg_regex,
-        )
-        .expect("Couldn't create screen"),
-    );
+    (limit as usize, filter)
 }
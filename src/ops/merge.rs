@@ -0,0 +1,69 @@
+use super::{create_prompt_with_default, selected_rev, Action, OpTrait};
+use crate::{items::TargetData, menu::arg::Arg, state::State, term::Term, Res};
+use derive_more::Display;
+use std::{process::Command, rc::Rc};
+
+pub(crate) fn init_args() -> Vec<Arg> {
+    vec![
+        Arg::new_flag("--ff-only", "Fast-forward only", false),
+        Arg::new_flag("--no-ff", "No fast-forward", false),
+        Arg::new_flag("--squash", "Squash", false),
+    ]
+}
+
+#[derive(Display)]
+#[display(fmt = "Merge continue")]
+pub(crate) struct MergeContinue;
+impl OpTrait for MergeContinue {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            let mut cmd = Command::new("git");
+            cmd.args(["merge", "--continue"]);
+
+            state.close_menu();
+            state.run_cmd_with_editor(term, cmd)?;
+            Ok(())
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Merge abort")]
+pub(crate) struct MergeAbort;
+impl OpTrait for MergeAbort {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            let mut cmd = Command::new("git");
+            cmd.args(["merge", "--abort"]);
+
+            state.close_menu();
+            state.run_cmd(term, &[], cmd)?;
+            Ok(())
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Merge")]
+pub(crate) struct Merge;
+impl OpTrait for Merge {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt_with_default(
+            "Merge",
+            merge,
+            selected_rev,
+            true,
+        ))
+    }
+}
+
+fn merge(state: &mut State, term: &mut Term, rev: &str) -> Res<()> {
+    let mut cmd = Command::new("git");
+    cmd.arg("merge");
+    cmd.args(state.pending_menu.as_ref().unwrap().args());
+    cmd.arg(rev);
+
+    state.close_menu();
+    state.run_cmd_with_editor(term, cmd)?;
+    Ok(())
+}
@@ -2,28 +2,57 @@ use serde::{Deserialize, Serialize};
 use tui_prompts::State as _;
 
 use crate::{
-    cmd_log::CmdLogEntry, items::TargetData, menu::Menu, prompt::PromptData, state::State,
-    term::Term, Res,
+    cmd_log::CmdLogEntry,
+    git::{self, rebase_todo::RebaseAction},
+    items::TargetData,
+    menu::Menu,
+    prompt::PromptData,
+    state::State,
+    term::Term,
+    Res,
 };
-use std::{fmt::Display, rc::Rc};
+use std::{fmt::Display, path::PathBuf, rc::Rc};
 
+pub(crate) mod apply;
+pub(crate) mod bisect;
 pub(crate) mod checkout;
+pub(crate) mod cherry_pick;
+pub(crate) mod clean;
+pub(crate) mod command;
 pub(crate) mod commit;
-pub(crate) mod copy_hash;
+pub(crate) mod commit_picker;
+pub(crate) mod conflict;
+pub(crate) mod diff;
 pub(crate) mod discard;
 pub(crate) mod editor;
+pub(crate) mod export_keys;
 pub(crate) mod fetch;
+pub(crate) mod filter_files;
+pub(crate) mod hooks;
+pub(crate) mod ignore;
+pub(crate) mod info;
 pub(crate) mod log;
+pub(crate) mod merge;
+pub(crate) mod open_in_forge;
+pub(crate) mod patch;
 pub(crate) mod pull;
+pub(crate) mod pull_request;
 pub(crate) mod push;
 pub(crate) mod rebase;
+pub(crate) mod rebase_todo;
 pub(crate) mod reset;
+pub(crate) mod reverse;
 pub(crate) mod revert;
+pub(crate) mod search;
 pub(crate) mod show;
 pub(crate) mod show_refs;
 pub(crate) mod stage;
 pub(crate) mod stash;
+pub(crate) mod submodule;
+pub(crate) mod tag;
 pub(crate) mod unstage;
+pub(crate) mod worktree;
+pub(crate) mod yank;
 
 pub(crate) type Action = Rc<dyn FnMut(&mut State, &mut Term) -> Res<()>>;
 
@@ -42,21 +71,49 @@ pub(crate) trait OpTrait: Display {
 #[derive(Clone, PartialOrd, Ord, PartialEq, Eq, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub(crate) enum Op {
+    ShowBisect,
+    BisectStart,
+    BisectGood,
+    BisectBad,
+    BisectSkip,
+    BisectReset,
     Checkout,
     CheckoutNewBranch,
+    DeleteGoneBranches,
+    SetUpstream,
+    SwitchBranch,
+    CheckoutTag,
+    RenameBranch,
+    DeleteBranch,
+    CompareBranches,
+    CheckoutPullRequest,
     Commit,
     CommitAmend,
+    CommitConventional,
     FetchAll,
     FetchElsewhere,
+    ShowRemoteRefs,
     LogCurrent,
     Pull,
     PullElsewhere,
     Push,
     PushElsewhere,
+    PushSetUpstream,
+    PushTag,
+    PushForReview,
+    CreatePullRequest,
     RebaseAbort,
     RebaseContinue,
+    RebaseSkip,
     RebaseElsewhere,
+    RebaseOnto,
     ShowRefs,
+    ShowRev,
+    FilterTags,
+    FilterFiles,
+    ExportKeys,
+    ShowHooks,
+    ShowInfo,
     Stash,
     StashApply,
     StashIndex,
@@ -64,24 +121,93 @@ pub(crate) enum Op {
     StashKeepIndex,
     StashPop,
     StashDrop,
+    ShowWipSnapshots,
     CommitFixup,
+    CommitInstantFixup,
+    CommitSquash,
+    CommitAbsorb,
+    CommitPickerUp,
+    CommitPickerDown,
+    CommitPickerFilter,
+    CommitPickerToggleAll,
+    CommitPickerPick,
+    CommitPickerAbort,
+    DiffUpstreamMergeBase,
+    DiffMergeBase,
+    DiffRange,
+    DiffWorktree,
+    DiffIndex,
+    DiffRevs,
+    DiffFiles,
     LogOther,
+    LogFile,
+    LogLines,
+    Merge,
+    MergeAbort,
+    MergeContinue,
+    ConflictTakeOurs,
+    ConflictTakeTheirs,
+    ConflictMergetool,
     RebaseAutosquash,
     RebaseInteractive,
+    RebaseTodoUp,
+    RebaseTodoDown,
+    RebaseTodoMoveUp,
+    RebaseTodoMoveDown,
+    RebaseTodoPick,
+    RebaseTodoReword,
+    RebaseTodoEdit,
+    RebaseTodoSquash,
+    RebaseTodoFixup,
+    RebaseTodoDrop,
+    RebaseTodoStart,
+    RebaseTodoAbort,
+    Clean,
+    CleanPreviewUp,
+    CleanPreviewDown,
+    CleanPreviewToggle,
+    CleanPreviewStart,
+    CleanPreviewAbort,
     ResetSoft,
     ResetMixed,
     ResetHard,
     RevertAbort,
     RevertContinue,
     RevertCommit,
+    CherryPickAbort,
+    CherryPickContinue,
+    CherryPickSkip,
+    CherryPickCommit,
+    SubmoduleUpdate,
+    SubmoduleSync,
+    SubmoduleAdd,
+    SubmoduleDeinit,
+    ShowWorktrees,
+    WorktreeAdd,
+    WorktreeRemove,
+    WorktreeLock,
+    WorktreeUnlock,
+    WorktreePrune,
 
     Stage,
     Unstage,
+    Ignore,
     Show,
     Discard,
-    CopyHash,
+    Reverse,
+    Apply,
+    #[serde(alias = "copy_hash")]
+    Yank,
+    OpenInForge,
+    ShowPullRequests,
+    CopyPatch,
+    SavePatch,
 
     ToggleSection,
+    ToggleFileHunks,
+    ToggleSideBySideDiff,
+    IncreaseDiffContext,
+    DecreaseDiffContext,
     MoveUp,
     MoveDown,
     MoveUpLine,
@@ -91,12 +217,27 @@ pub(crate) enum Op {
     MoveParentSection,
     HalfPageUp,
     HalfPageDown,
+    ScrollLeft,
+    ScrollRight,
+    CollapseAll,
+    ExpandToFiles,
+    ExpandToHunks,
+    ExpandAll,
+    Search,
+    SearchNext,
+    SearchPrevious,
 
     Refresh,
     Quit,
 
     #[serde(untagged)]
     OpenMenu(Menu),
+    #[serde(
+        untagged,
+        serialize_with = "command::serialize_name",
+        deserialize_with = "command::deserialize_name"
+    )]
+    RunCommand(String),
     #[serde(untagged)]
     ToggleArg(String),
 }
@@ -108,7 +249,12 @@ impl Op {
             Op::OpenMenu(menu) => Box::new(editor::OpenMenu(menu)),
             Op::Refresh => Box::new(editor::Refresh),
             Op::ToggleArg(name) => Box::new(editor::ToggleArg(name)),
+            Op::RunCommand(name) => Box::new(command::RunCommand(name)),
             Op::ToggleSection => Box::new(editor::ToggleSection),
+            Op::ToggleFileHunks => Box::new(editor::ToggleFileHunks),
+            Op::ToggleSideBySideDiff => Box::new(editor::ToggleSideBySideDiff),
+            Op::IncreaseDiffContext => Box::new(editor::IncreaseDiffContext),
+            Op::DecreaseDiffContext => Box::new(editor::DecreaseDiffContext),
             Op::MoveDown => Box::new(editor::MoveDown),
             Op::MoveUp => Box::new(editor::MoveUp),
             Op::MoveDownLine => Box::new(editor::MoveDownLine),
@@ -118,22 +264,59 @@ impl Op {
             Op::MoveParentSection => Box::new(editor::MoveParentSection),
             Op::HalfPageUp => Box::new(editor::HalfPageUp),
             Op::HalfPageDown => Box::new(editor::HalfPageDown),
+            Op::ScrollLeft => Box::new(editor::ScrollLeft),
+            Op::ScrollRight => Box::new(editor::ScrollRight),
+            Op::CollapseAll => Box::new(editor::ShowLevel(1)),
+            Op::ExpandToFiles => Box::new(editor::ShowLevel(2)),
+            Op::ExpandToHunks => Box::new(editor::ShowLevel(3)),
+            Op::ExpandAll => Box::new(editor::ShowLevel(4)),
+            Op::Search => Box::new(search::Search),
+            Op::SearchNext => Box::new(search::SearchNext),
+            Op::SearchPrevious => Box::new(search::SearchPrevious),
 
+            Op::ShowBisect => Box::new(bisect::ShowBisect),
+            Op::BisectStart => Box::new(bisect::BisectStart),
+            Op::BisectGood => Box::new(bisect::BisectGood),
+            Op::BisectBad => Box::new(bisect::BisectBad),
+            Op::BisectSkip => Box::new(bisect::BisectSkip),
+            Op::BisectReset => Box::new(bisect::BisectReset),
             Op::Checkout => Box::new(checkout::Checkout),
             Op::CheckoutNewBranch => Box::new(checkout::CheckoutNewBranch),
+            Op::DeleteGoneBranches => Box::new(checkout::DeleteGoneBranches),
+            Op::SetUpstream => Box::new(checkout::SetUpstream),
+            Op::SwitchBranch => Box::new(checkout::SwitchBranch),
+            Op::CheckoutTag => Box::new(tag::CheckoutTag),
+            Op::RenameBranch => Box::new(checkout::RenameBranch),
+            Op::DeleteBranch => Box::new(checkout::DeleteBranch),
+            Op::CompareBranches => Box::new(checkout::CompareBranches),
+            Op::CheckoutPullRequest => Box::new(pull_request::CheckoutPullRequest),
             Op::Commit => Box::new(commit::Commit),
             Op::CommitAmend => Box::new(commit::CommitAmend),
+            Op::CommitConventional => Box::new(commit::CommitConventional),
             Op::FetchAll => Box::new(fetch::FetchAll),
             Op::FetchElsewhere => Box::new(fetch::FetchElsewhere),
+            Op::ShowRemoteRefs => Box::new(fetch::ShowRemoteRefs),
             Op::LogCurrent => Box::new(log::LogCurrent),
             Op::Pull => Box::new(pull::Pull),
             Op::PullElsewhere => Box::new(pull::PullElsewhere),
             Op::Push => Box::new(push::Push),
             Op::PushElsewhere => Box::new(push::PushElsewhere),
+            Op::PushSetUpstream => Box::new(push::PushSetUpstream),
+            Op::PushTag => Box::new(tag::PushTag),
+            Op::PushForReview => Box::new(push::PushForReview),
+            Op::CreatePullRequest => Box::new(pull_request::CreatePullRequest),
             Op::RebaseAbort => Box::new(rebase::RebaseAbort),
             Op::RebaseContinue => Box::new(rebase::RebaseContinue),
+            Op::RebaseSkip => Box::new(rebase::RebaseSkip),
             Op::RebaseElsewhere => Box::new(rebase::RebaseElsewhere),
+            Op::RebaseOnto => Box::new(rebase::RebaseOnto),
             Op::ShowRefs => Box::new(show_refs::ShowRefs),
+            Op::ShowRev => Box::new(show::ShowRev),
+            Op::FilterTags => Box::new(show_refs::FilterTags),
+            Op::FilterFiles => Box::new(filter_files::FilterFiles),
+            Op::ExportKeys => Box::new(export_keys::ExportKeys),
+            Op::ShowHooks => Box::new(hooks::ShowHooks),
+            Op::ShowInfo => Box::new(info::ShowInfo),
             Op::Stash => Box::new(stash::Stash),
             Op::StashApply => Box::new(stash::StashApply),
             Op::StashIndex => Box::new(stash::StashIndex),
@@ -141,22 +324,90 @@ impl Op {
             Op::StashKeepIndex => Box::new(stash::StashKeepIndex),
             Op::StashPop => Box::new(stash::StashPop),
             Op::StashDrop => Box::new(stash::StashDrop),
+            Op::ShowWipSnapshots => Box::new(stash::ShowWipSnapshots),
 
             Op::CommitFixup => Box::new(commit::CommitFixup),
+            Op::CommitInstantFixup => Box::new(commit::CommitInstantFixup),
+            Op::CommitSquash => Box::new(commit::CommitSquash),
+            Op::CommitAbsorb => Box::new(commit::CommitAbsorb),
+            Op::CommitPickerUp => Box::new(commit_picker::CommitPickerUp),
+            Op::CommitPickerDown => Box::new(commit_picker::CommitPickerDown),
+            Op::CommitPickerFilter => Box::new(commit_picker::CommitPickerFilter),
+            Op::CommitPickerToggleAll => Box::new(commit_picker::CommitPickerToggleAll),
+            Op::CommitPickerPick => Box::new(commit_picker::CommitPickerPick),
+            Op::CommitPickerAbort => Box::new(commit_picker::CommitPickerAbort),
+            Op::DiffUpstreamMergeBase => Box::new(diff::DiffUpstreamMergeBase),
+            Op::DiffMergeBase => Box::new(diff::DiffMergeBase),
+            Op::DiffRange => Box::new(diff::DiffRange),
+            Op::DiffWorktree => Box::new(diff::DiffWorktree),
+            Op::DiffIndex => Box::new(diff::DiffIndex),
+            Op::DiffRevs => Box::new(diff::DiffRevs),
+            Op::DiffFiles => Box::new(diff::DiffFiles),
             Op::Discard => Box::new(discard::Discard),
             Op::LogOther => Box::new(log::LogOther),
+            Op::LogFile => Box::new(log::LogFile),
+            Op::LogLines => Box::new(log::LogLines),
+            Op::Merge => Box::new(merge::Merge),
+            Op::MergeAbort => Box::new(merge::MergeAbort),
+            Op::MergeContinue => Box::new(merge::MergeContinue),
+            Op::ConflictTakeOurs => Box::new(conflict::ConflictTakeOurs),
+            Op::ConflictTakeTheirs => Box::new(conflict::ConflictTakeTheirs),
+            Op::ConflictMergetool => Box::new(conflict::ConflictMergetool),
             Op::RebaseAutosquash => Box::new(rebase::RebaseAutosquash),
             Op::RebaseInteractive => Box::new(rebase::RebaseInteractive),
+            Op::RebaseTodoUp => Box::new(rebase_todo::RebaseTodoUp),
+            Op::RebaseTodoDown => Box::new(rebase_todo::RebaseTodoDown),
+            Op::RebaseTodoMoveUp => Box::new(rebase_todo::RebaseTodoMoveUp),
+            Op::RebaseTodoMoveDown => Box::new(rebase_todo::RebaseTodoMoveDown),
+            Op::RebaseTodoPick => Box::new(rebase_todo::RebaseTodoSetAction(RebaseAction::Pick)),
+            Op::RebaseTodoReword => {
+                Box::new(rebase_todo::RebaseTodoSetAction(RebaseAction::Reword))
+            }
+            Op::RebaseTodoEdit => Box::new(rebase_todo::RebaseTodoSetAction(RebaseAction::Edit)),
+            Op::RebaseTodoSquash => {
+                Box::new(rebase_todo::RebaseTodoSetAction(RebaseAction::Squash))
+            }
+            Op::RebaseTodoFixup => Box::new(rebase_todo::RebaseTodoSetAction(RebaseAction::Fixup)),
+            Op::RebaseTodoDrop => Box::new(rebase_todo::RebaseTodoSetAction(RebaseAction::Drop)),
+            Op::RebaseTodoStart => Box::new(rebase_todo::RebaseTodoStart),
+            Op::RebaseTodoAbort => Box::new(rebase_todo::RebaseTodoAbort),
+            Op::Clean => Box::new(clean::Clean),
+            Op::CleanPreviewUp => Box::new(clean::CleanPreviewUp),
+            Op::CleanPreviewDown => Box::new(clean::CleanPreviewDown),
+            Op::CleanPreviewToggle => Box::new(clean::CleanPreviewToggle),
+            Op::CleanPreviewStart => Box::new(clean::CleanPreviewStart),
+            Op::CleanPreviewAbort => Box::new(clean::CleanPreviewAbort),
             Op::ResetSoft => Box::new(reset::ResetSoft),
             Op::ResetMixed => Box::new(reset::ResetMixed),
             Op::ResetHard => Box::new(reset::ResetHard),
             Op::RevertAbort => Box::new(revert::RevertAbort),
             Op::RevertContinue => Box::new(revert::RevertContinue),
             Op::RevertCommit => Box::new(revert::RevertCommit),
+            Op::CherryPickAbort => Box::new(cherry_pick::CherryPickAbort),
+            Op::CherryPickContinue => Box::new(cherry_pick::CherryPickContinue),
+            Op::CherryPickSkip => Box::new(cherry_pick::CherryPickSkip),
+            Op::CherryPickCommit => Box::new(cherry_pick::CherryPickCommit),
+            Op::SubmoduleUpdate => Box::new(submodule::SubmoduleUpdate),
+            Op::SubmoduleSync => Box::new(submodule::SubmoduleSync),
+            Op::SubmoduleAdd => Box::new(submodule::SubmoduleAdd),
+            Op::SubmoduleDeinit => Box::new(submodule::SubmoduleDeinit),
+            Op::ShowWorktrees => Box::new(worktree::ShowWorktrees),
+            Op::WorktreeAdd => Box::new(worktree::WorktreeAdd),
+            Op::WorktreeRemove => Box::new(worktree::WorktreeRemove),
+            Op::WorktreeLock => Box::new(worktree::WorktreeLock),
+            Op::WorktreeUnlock => Box::new(worktree::WorktreeUnlock),
+            Op::WorktreePrune => Box::new(worktree::WorktreePrune),
             Op::Show => Box::new(show::Show),
             Op::Stage => Box::new(stage::Stage),
             Op::Unstage => Box::new(unstage::Unstage),
-            Op::CopyHash => Box::new(copy_hash::CopyHash),
+            Op::Ignore => Box::new(ignore::Ignore),
+            Op::Reverse => Box::new(reverse::Reverse),
+            Op::Apply => Box::new(apply::Apply),
+            Op::Yank => Box::new(yank::Yank),
+            Op::OpenInForge => Box::new(open_in_forge::OpenInForge),
+            Op::ShowPullRequests => Box::new(pull_request::ShowPullRequests),
+            Op::CopyPatch => Box::new(patch::CopyPatch),
+            Op::SavePatch => Box::new(patch::SavePatch),
         }
     }
 }
@@ -165,17 +416,29 @@ impl Display for Menu {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_str(match self {
             Menu::Root => "Root",
+            Menu::Bisect => "Bisect",
             Menu::Branch => "Branch",
+            Menu::CherryPick => "Cherry-pick",
+            Menu::Clean => "Clean",
+            Menu::CleanPreview => "Clean preview",
             Menu::Commit => "Commit",
+            Menu::CommitPicker => "Commit picker",
+            Menu::Diff => "Diff",
             Menu::Fetch => "Fetch",
+            Menu::FollowUp => "Follow up",
             Menu::Help => "Help",
             Menu::Log => "Log",
+            Menu::Merge => "Merge",
+            Menu::Patch => "Patch",
             Menu::Pull => "Pull",
             Menu::Push => "Push",
             Menu::Rebase => "Rebase",
+            Menu::RebaseTodo => "Rebase todo",
             Menu::Reset => "Reset",
             Menu::Revert => "Revert",
             Menu::Stash => "Stash",
+            Menu::Submodule => "Submodule",
+            Menu::Worktree => "Worktree",
         })
     }
 }
@@ -185,15 +448,20 @@ pub(crate) fn create_y_n_prompt(mut action: Action, prompt: &'static str) -> Act
         if state.prompt.state.status().is_pending() {
             match state.prompt.state.value() {
                 "y" => {
-                    Rc::get_mut(&mut action).unwrap()(state, term)?;
+                    // Reset first, the action may open another prompt
                     state.prompt.reset(term)?;
+                    Rc::get_mut(&mut action).unwrap()(state, term)?;
                 }
                 "" => (),
                 _ => {
                     state
                         .current_cmd_log
                         .push(CmdLogEntry::Error("Aborted".to_string()));
+                    let on_abort = state.prompt.on_abort.take();
                     state.prompt.reset(term)?;
+                    if let Some(mut on_abort) = on_abort {
+                        Rc::get_mut(&mut on_abort).unwrap()(state, term)?;
+                    }
                 }
             }
         }
@@ -282,7 +550,8 @@ pub(crate) fn set_prompt<T: 'static>(
 
                 callback(state, term, value, &context)?;
 
-                if hide_menu {
+                // The callback may have opened another prompt
+                if hide_menu && !state.prompt.state.is_focused() {
                     state.unhide_menu();
                 }
             }
@@ -291,9 +560,40 @@ pub(crate) fn set_prompt<T: 'static>(
     });
 }
 
+/// Records the uncommitted changes before an operation that could lose them,
+/// if `general.wip_snapshots` is enabled.
+pub(crate) fn wip_snapshot(state: &State, message: &str) -> Res<()> {
+    if state.config.general.wip_snapshots.enabled {
+        git::wip_snapshot(&state.repo, message)?;
+    }
+    Ok(())
+}
+
+/// Like `wip_snapshot`, but also records untracked `files` that are about to be removed.
+pub(crate) fn wip_snapshot_untracked(
+    state: &State,
+    message: &str,
+    files: &[PathBuf],
+    ignored: bool,
+) -> Res<()> {
+    if state.config.general.wip_snapshots.enabled {
+        git::wip_snapshot_untracked(&state.repo, message, files, ignored)?;
+    }
+    Ok(())
+}
+
+/// Hunks of a diff that leaves out ignored changes aren't patches of the files,
+/// so they can't be staged, unstaged or discarded.
+pub(crate) fn ensure_hunks_apply(state: &State) -> Res<()> {
+    if state.diff_options.get().ignores_changes() {
+        return Err("Can't apply hunks while whitespace or blank lines are ignored".into());
+    }
+    Ok(())
+}
+
 pub(crate) fn selected_rev(state: &State) -> Option<String> {
     match &state.screen().get_selected_item().target_data {
-        Some(TargetData::Branch(branch)) => Some(branch.to_owned()),
+        Some(TargetData::Branch(branch) | TargetData::Tag(branch)) => Some(branch.to_owned()),
         Some(TargetData::Commit(commit)) => Some(commit.to_owned()),
         _ => None,
     }
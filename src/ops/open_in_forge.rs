@@ -0,0 +1,97 @@
+use super::{Action, OpTrait};
+use crate::{
+    git::{self, diff::Hunk},
+    items::TargetData,
+    Res,
+};
+use derive_more::Display;
+use std::{path::PathBuf, process::Command, rc::Rc};
+
+#[derive(Display)]
+#[display(fmt = "Open in forge")]
+pub(crate) struct OpenInForge;
+impl OpTrait for OpenInForge {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let link = match target? {
+            TargetData::Commit(hash) => Link::Commit(hash.clone()),
+            TargetData::File(path) => Link::File(path.clone(), None),
+            TargetData::Delta(delta) => Link::File(delta.new_file.clone(), None),
+            TargetData::Hunk(hunk) => Link::File(hunk.new_file.clone(), Some(line_number(hunk, 0))),
+            TargetData::HunkLine(hunk, i) => {
+                Link::File(hunk.new_file.clone(), Some(line_number(hunk, *i)))
+            }
+            _ => return None,
+        };
+
+        Some(Rc::new(move |state, _term| {
+            let forge = git::origin_forge(&state.repo)?;
+            let url = match &link {
+                Link::Commit(hash) => forge.commit_url(hash),
+                Link::File(path, line) => {
+                    // Local commits aren't on the forge, but the upstream's are
+                    let upstream = git::head_upstream(&state.repo)
+                        .ok_or("No upstream is set for the current branch")?;
+                    let rev = state
+                        .repo
+                        .revparse_single(&upstream)?
+                        .peel_to_commit()?
+                        .id();
+                    forge.file_url(&rev.to_string(), &path.to_string_lossy(), *line)
+                }
+            };
+
+            let cmd = browser_command(state.config.general.browser.as_deref(), &url)?;
+
+            state.close_menu();
+            state.run_cmd_detached(cmd)
+        }))
+    }
+    fn is_target_op(&self) -> bool {
+        true
+    }
+}
+
+enum Link {
+    Commit(String),
+    File(PathBuf, Option<u32>),
+}
+
+/// The line of a hunk as numbered on the forge. Added lines only exist in the new file,
+/// the others are numbered as in the old one.
+fn line_number(hunk: &Hunk, i: usize) -> u32 {
+    let is_added = hunk.content.lines[i]
+        .spans
+        .first()
+        .is_some_and(|span| span.content.starts_with('+'));
+
+    if is_added {
+        hunk.new_line_number(i)
+    } else {
+        hunk.old_line_number(i)
+    }
+}
+
+pub(crate) fn browser_command(browser: Option<&str>, url: &str) -> Res<Command> {
+    let mut cmd = match browser {
+        Some(browser) => {
+            let args = browser.split_whitespace().collect::<Vec<_>>();
+            let Some((program, args)) = args.split_first() else {
+                return Err("The configured browser is empty".into());
+            };
+
+            let mut cmd = Command::new(program);
+            cmd.args(args);
+            cmd
+        }
+        None if cfg!(target_os = "macos") => Command::new("open"),
+        None if cfg!(windows) => {
+            let mut cmd = Command::new("cmd");
+            cmd.args(["/C", "start", ""]);
+            cmd
+        }
+        None => Command::new("xdg-open"),
+    };
+
+    cmd.arg(url);
+    Ok(cmd)
+}
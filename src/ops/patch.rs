@@ -0,0 +1,120 @@
+use super::{set_prompt, Action, OpTrait};
+use crate::{git, items::TargetData, menu::arg::Arg, state::State, term::Term, Res};
+use derive_more::Display;
+use std::{fs, process::Command, rc::Rc};
+
+pub(crate) fn init_args() -> Vec<Arg> {
+    vec![]
+}
+
+#[derive(Display)]
+#[display(fmt = "Copy patch")]
+pub(crate) struct CopyPatch;
+impl OpTrait for CopyPatch {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let source = PatchSource::from_target(target)?;
+
+        Some(Rc::new(move |state, term| {
+            let patch = source.format(state)?;
+
+            state.close_menu();
+            state.copy_to_clipboard(term, patch, "Patch")
+        }))
+    }
+    fn is_target_op(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Save patch")]
+pub(crate) struct SavePatch;
+impl OpTrait for SavePatch {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let source = PatchSource::from_target(target)?;
+
+        Some(Rc::new(move |state, _term| {
+            let patch = source.format(state)?;
+            let default_file = source.default_file_name();
+
+            set_prompt(
+                state,
+                "Save patch to",
+                save_patch,
+                Box::new(move |_| Some(default_file.clone())),
+                patch,
+                true,
+            );
+            Ok(())
+        }))
+    }
+    fn is_target_op(&self) -> bool {
+        true
+    }
+}
+
+fn save_patch(state: &mut State, _term: &mut Term, file: &str, patch: &String) -> Res<()> {
+    let path = state.repo.workdir().expect("No workdir").join(file);
+    fs::write(&path, patch)?;
+
+    state.close_menu();
+    state.display_info(format!("Patch saved to {}", file));
+    Ok(())
+}
+
+/// What to produce a patch from. Commits are exported with `git format-patch`,
+/// everything else as a plain unified diff.
+#[derive(Clone)]
+enum PatchSource {
+    Diff(String),
+    Staged,
+    Unstaged,
+    Commit(String),
+}
+
+impl PatchSource {
+    fn from_target(target: Option<&TargetData>) -> Option<Self> {
+        match target {
+            Some(TargetData::AllStaged) => Some(Self::Staged),
+            Some(TargetData::AllUnstaged) => Some(Self::Unstaged),
+            Some(TargetData::Delta(d)) => Some(Self::Diff(d.format_patch())),
+            Some(TargetData::Hunk(h)) => Some(Self::Diff(h.format_patch())),
+            Some(TargetData::Commit(r)) => Some(Self::Commit(r.clone())),
+            _ => None,
+        }
+    }
+
+    fn format(&self, state: &State) -> Res<String> {
+        let options = state.diff_options.get();
+        match self {
+            Self::Diff(patch) => Ok(patch.clone()),
+            Self::Staged => {
+                Ok(git::diff_staged(&state.config, &options, &state.repo)?.format_patch())
+            }
+            Self::Unstaged => {
+                Ok(git::diff_unstaged(&state.config, &options, &state.repo)?.format_patch())
+            }
+            Self::Commit(rev) => format_commit_patch(state, rev),
+        }
+    }
+
+    fn default_file_name(&self) -> String {
+        match self {
+            Self::Commit(rev) => format!("{}.patch", &rev[..rev.len().min(7)]),
+            _ => "gitu.patch".to_string(),
+        }
+    }
+}
+
+fn format_commit_patch(state: &State, rev: &str) -> Res<String> {
+    let out = Command::new("git")
+        .args(["format-patch", "-1", "--stdout", rev])
+        .current_dir(state.repo.workdir().expect("No workdir"))
+        .output()?;
+
+    if !out.status.success() {
+        return Err(String::from_utf8_lossy(&out.stderr).into_owned().into());
+    }
+
+    Ok(String::from_utf8(out.stdout)?)
+}
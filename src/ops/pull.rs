@@ -14,7 +14,7 @@ impl OpTrait for Pull {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
         Some(Rc::new(|state: &mut State, term: &mut Term| {
             let mut cmd = Command::new("git");
-            cmd.arg("pull");
+            cmd.args(["pull", "--progress"]);
             cmd.args(state.pending_menu.as_ref().unwrap().args());
 
             state.close_menu();
@@ -35,7 +35,7 @@ impl OpTrait for PullElsewhere {
 
 fn pull_elsewhere(state: &mut State, term: &mut Term, remote: &str) -> Res<()> {
     let mut cmd = Command::new("git");
-    cmd.args(["pull"]);
+    cmd.args(["pull", "--progress"]);
     cmd.args(state.pending_menu.as_ref().unwrap().args());
     cmd.arg(remote);
 
@@ -43,3 +43,15 @@ fn pull_elsewhere(state: &mut State, term: &mut Term, remote: &str) -> Res<()> {
     state.run_cmd_async(term, &[], cmd)?;
     Ok(())
 }
+
+/// Catches the current branch up with `upstream` when it has no commits of its own.
+pub(crate) fn fast_forward(upstream: String) -> Option<Action> {
+    Some(Rc::new(move |state, term| {
+        let mut cmd = Command::new("git");
+        cmd.args(["merge", "--ff-only"]);
+        cmd.arg(&upstream);
+
+        state.close_menu();
+        state.run_cmd(term, &[], cmd)
+    }))
+}
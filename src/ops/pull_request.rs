@@ -0,0 +1,117 @@
+use super::{create_prompt, open_in_forge::browser_command, Action, OpTrait};
+use crate::{git, items::TargetData, screen, state::State, term::Term, Res};
+use derive_more::Display;
+use std::{process::Command, rc::Rc};
+
+#[derive(Display)]
+#[display(fmt = "Show pull requests")]
+pub(crate) struct ShowPullRequests;
+impl OpTrait for ShowPullRequests {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            state.screens.drain(1..);
+            let size = state.screens.last().unwrap().size;
+            state.close_menu();
+            state.screens.push(screen::pull_requests::create(
+                Rc::clone(&state.config),
+                Rc::clone(&state.repo),
+                size,
+            )?);
+            Ok(())
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Checkout pull request")]
+pub(crate) struct CheckoutPullRequest;
+impl OpTrait for CheckoutPullRequest {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let Some(TargetData::PullRequest(pull_request)) = target else {
+            return Some(create_prompt(
+                "Checkout pull request #",
+                checkout_pull_request_number,
+                true,
+            ));
+        };
+        let number = pull_request.number;
+
+        Some(Rc::new(move |state, term| {
+            checkout_pull_request(state, term, number)
+        }))
+    }
+}
+
+fn checkout_pull_request_number(state: &mut State, term: &mut Term, input: &str) -> Res<()> {
+    let number = input
+        .trim()
+        .trim_start_matches('#')
+        .parse()
+        .map_err(|_| format!("Not a pull request number: {}", input))?;
+
+    checkout_pull_request(state, term, number)
+}
+
+/// Fetches the head of a pull request into a branch of its own, as one named like the pull
+/// request's could be a local branch too. The checkout is queued after it. The branch follows
+/// force-pushes to the pull request, even while it's checked out.
+fn checkout_pull_request(state: &mut State, term: &mut Term, number: u64) -> Res<()> {
+    let forge = git::origin_forge(&state.repo)?;
+    let pull_ref = forge.pull_request_ref(number);
+    let branch = format!("pr-{}", number);
+    let on_branch = state.repo.head().is_ok_and(|head| {
+        head.is_branch() && head.name() == Some(&format!("refs/heads/{}", branch))
+    });
+
+    let mut fetch = Command::new("git");
+    fetch.args(["fetch", "--progress", "origin"]);
+    let mut checkout = Command::new("git");
+    if on_branch {
+        // git refuses to fetch into the checked out branch, as the work tree would fall behind
+        fetch.arg(&pull_ref);
+        checkout.args(["reset", "--keep", "FETCH_HEAD"]);
+    } else {
+        fetch.arg(format!("+{}:refs/heads/{}", pull_ref, branch));
+        checkout.args(["checkout", &branch]);
+    }
+
+    state.close_menu();
+    state.run_cmd_async(term, &[], fetch)?;
+    state.run_cmd_async(term, &[], checkout)
+}
+
+#[derive(Display)]
+#[display(fmt = "Create pull request")]
+pub(crate) struct CreatePullRequest;
+impl OpTrait for CreatePullRequest {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            let forge = git::origin_forge(&state.repo)?;
+            let head = state.repo.head()?;
+            let Some(branch) = head
+                .is_branch()
+                .then(|| head.shorthand().map(String::from))
+                .flatten()
+            else {
+                return Err("Not on a branch".into());
+            };
+            drop(head);
+
+            if state
+                .repo
+                .find_reference(&format!("refs/remotes/origin/{}", branch))
+                .is_err()
+            {
+                return Err(format!("Push {} to origin first", branch).into());
+            }
+
+            let cmd = browser_command(
+                state.config.general.browser.as_deref(),
+                &forge.new_pull_request_url(&branch),
+            )?;
+
+            state.close_menu();
+            state.run_cmd_detached(cmd)
+        }))
+    }
+}
@@ -1,5 +1,7 @@
-use super::{create_prompt, Action, OpTrait};
-use crate::{items::TargetData, menu::arg::Arg, state::State, term::Term, Res};
+use super::{
+    create_prompt, create_prompt_with_default, create_y_n_prompt, set_prompt, Action, OpTrait,
+};
+use crate::{git, items::TargetData, menu::arg::Arg, screen, state::State, term::Term, Res};
 use derive_more::Display;
 use std::{process::Command, rc::Rc};
 
@@ -19,11 +21,47 @@ impl OpTrait for Push {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
         Some(Rc::new(|state: &mut State, term: &mut Term| {
             let mut cmd = Command::new("git");
-            cmd.args(["push"]);
+            cmd.args(["push", "--progress"]);
             cmd.args(state.pending_menu.as_ref().unwrap().args());
 
+            let outgoing = if state.config.general.confirm_push.enabled {
+                Some(git::outgoing(
+                    &state.config,
+                    &state.diff_options.get(),
+                    &state.repo,
+                )?)
+                .filter(|outgoing| !outgoing.commits.is_empty())
+            } else {
+                None
+            };
+
+            let Some(outgoing) = outgoing else {
+                state.close_menu();
+                state.run_cmd_async(term, &[], cmd)?;
+                return Ok(());
+            };
+
+            let size = state.screens.last().unwrap().size;
+            state.screens.push(screen::outgoing::create(
+                Rc::clone(&state.config),
+                Rc::new(outgoing),
+                size,
+            )?);
             state.close_menu();
-            state.run_cmd_async(term, &[], cmd)?;
+
+            let mut cmd = Some(cmd);
+            let mut prompt = create_y_n_prompt(
+                Rc::new(move |state: &mut State, term: &mut Term| {
+                    state.screens.pop();
+                    state.run_cmd_async(term, &[], cmd.take().unwrap())
+                }),
+                "Push these commits?",
+            );
+            Rc::get_mut(&mut prompt).unwrap()(state, term)?;
+            state.prompt.on_abort = Some(Rc::new(|state: &mut State, _term: &mut Term| {
+                state.screens.pop();
+                Ok(())
+            }));
             Ok(())
         }))
     }
@@ -38,9 +76,38 @@ impl OpTrait for PushElsewhere {
     }
 }
 
+#[derive(Display)]
+#[display(fmt = "Push and set upstream")]
+pub(crate) struct PushSetUpstream;
+impl OpTrait for PushSetUpstream {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt_with_default(
+            "Push and set upstream at",
+            push_set_upstream,
+            |state| {
+                git::head_remote(&state.repo).or_else(|| {
+                    let remotes = state.repo.remotes().ok()?;
+                    remotes.get(0).map(String::from)
+                })
+            },
+            true,
+        ))
+    }
+}
+
+fn push_set_upstream(state: &mut State, term: &mut Term, remote: &str) -> Res<()> {
+    let mut cmd = Command::new("git");
+    cmd.args(["push", "--progress", "--set-upstream", remote, "HEAD"]);
+    cmd.args(state.pending_menu.as_ref().unwrap().args());
+
+    state.close_menu();
+    state.run_cmd_async(term, &[], cmd)?;
+    Ok(())
+}
+
 fn push_elsewhere(state: &mut State, term: &mut Term, remote: &str) -> Res<()> {
     let mut cmd = Command::new("git");
-    cmd.args(["push"]);
+    cmd.args(["push", "--progress"]);
     cmd.arg(format!("--repo={}", remote));
     cmd.args(state.pending_menu.as_ref().unwrap().args());
 
@@ -48,3 +115,87 @@ fn push_elsewhere(state: &mut State, term: &mut Term, remote: &str) -> Res<()> {
     state.run_cmd_async(term, &[], cmd)?;
     Ok(())
 }
+
+#[derive(Display)]
+#[display(fmt = "Push for review")]
+pub(crate) struct PushForReview;
+impl OpTrait for PushForReview {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt_with_default(
+            "Push for review to branch",
+            prompt_review_topic,
+            |state| {
+                let upstream = git::head_upstream(&state.repo)?;
+                let remote = git::head_remote(&state.repo)?;
+                upstream
+                    .strip_prefix(&format!("{}/", remote))
+                    .map(String::from)
+            },
+            true,
+        ))
+    }
+}
+
+fn prompt_review_topic(state: &mut State, _term: &mut Term, branch: &str) -> Res<()> {
+    set_prompt(
+        state,
+        "Topic (empty for none)",
+        |state, term, topic, branch: &String| push_for_review(state, term, topic, branch),
+        Box::new(|_| None),
+        branch.to_string(),
+        true,
+    );
+    Ok(())
+}
+
+fn push_for_review(state: &mut State, term: &mut Term, topic: &str, branch: &str) -> Res<()> {
+    let gerrit = &state.config.gerrit;
+    let Some(remote) = gerrit
+        .remote
+        .clone()
+        .or_else(|| git::head_remote(&state.repo))
+    else {
+        return Err("No gerrit.remote configured or upstream remote to push to".into());
+    };
+
+    let mut cmd = Command::new("git");
+    cmd.args(["push", "--progress"]);
+    cmd.args(state.pending_menu.as_ref().unwrap().args());
+    cmd.arg(remote);
+    cmd.arg(format!(
+        "HEAD:{}",
+        review_ref(branch, topic, &gerrit.reviewers)
+    ));
+
+    state.close_menu();
+    state.run_cmd_async(term, &[], cmd)
+}
+
+/// Gerrit's magic ref for creating changes, with the topic and reviewers as push options.
+fn review_ref(branch: &str, topic: &str, reviewers: &[String]) -> String {
+    let options = (!topic.is_empty())
+        .then(|| format!("topic={}", topic))
+        .into_iter()
+        .chain(reviewers.iter().map(|reviewer| format!("r={}", reviewer)))
+        .collect::<Vec<_>>();
+
+    if options.is_empty() {
+        format!("refs/for/{}", branch)
+    } else {
+        format!("refs/for/{}%{}", branch, options.join(","))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::review_ref;
+
+    #[test]
+    fn review_refs() {
+        assert_eq!(review_ref("main", "", &[]), "refs/for/main");
+        assert_eq!(
+            review_ref("main", "fix-x", &["a@example.com".into(), "b".into()]),
+            "refs/for/main%topic=fix-x,r=a@example.com,r=b"
+        );
+    }
+}
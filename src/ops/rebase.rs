@@ -1,5 +1,5 @@
-use super::{create_prompt_with_default, selected_rev, Action, OpTrait};
-use crate::{items::TargetData, menu::arg::Arg, state::State, term::Term, Res};
+use super::{create_prompt_with_default, selected_rev, set_prompt, Action, OpTrait};
+use crate::{git, items::TargetData, menu::arg::Arg, state::State, term::Term, Res};
 use derive_more::Display;
 use std::{
     ffi::{OsStr, OsString},
@@ -18,6 +18,7 @@ pub(crate) fn init_args() -> Vec<Arg> {
         ),
         Arg::new_flag("--autosquash", "Autosquash", false),
         Arg::new_flag("--autostash", "Autostash", true),
+        Arg::new_flag("--fork-point", "Fork point", false),
         Arg::new_flag("--interactive", "Interactive", false),
         Arg::new_flag("--no-verify", "Disable hooks", false),
     ]
@@ -33,7 +34,23 @@ impl OpTrait for RebaseContinue {
             cmd.args(["rebase", "--continue"]);
 
             state.close_menu();
-            state.run_cmd_interactive(term, cmd)?;
+            state.run_cmd_with_editor(term, cmd)?;
+            Ok(())
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Rebase skip")]
+pub(crate) struct RebaseSkip;
+impl OpTrait for RebaseSkip {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            let mut cmd = Command::new("git");
+            cmd.args(["rebase", "--skip"]);
+
+            state.close_menu();
+            state.run_cmd(term, &[], cmd)?;
             Ok(())
         }))
     }
@@ -63,7 +80,8 @@ impl OpTrait for RebaseElsewhere {
         Some(create_prompt_with_default(
             "Rebase onto",
             rebase_elsewhere,
-            selected_rev,
+            // Like `git pull --rebase`, unless something else is selected
+            |state| selected_rev(state).or_else(|| git::head_upstream(&state.repo)),
             true,
         ))
     }
@@ -75,8 +93,50 @@ fn rebase_elsewhere(state: &mut State, term: &mut Term, rev: &str) -> Res<()> {
     cmd.args(state.pending_menu.as_ref().unwrap().args());
     cmd.arg(rev);
 
+    super::wip_snapshot(state, &format!("Before rebase onto {}", rev))?;
+    state.close_menu();
+    state.run_cmd_with_editor(term, cmd)?;
+    Ok(())
+}
+
+#[derive(Display)]
+#[display(fmt = "Rebase onto")]
+pub(crate) struct RebaseOnto;
+impl OpTrait for RebaseOnto {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt_with_default(
+            "Rebase onto",
+            prompt_onto_upstream,
+            selected_rev,
+            true,
+        ))
+    }
+}
+
+fn prompt_onto_upstream(state: &mut State, _term: &mut Term, new_base: &str) -> Res<()> {
+    set_prompt(
+        state,
+        "Rebase commits after",
+        rebase_onto,
+        // `git rebase` falls back to the upstream too
+        Box::new(|state| git::head_upstream(&state.repo)),
+        new_base.to_string(),
+        true,
+    );
+    Ok(())
+}
+
+fn rebase_onto(state: &mut State, term: &mut Term, upstream: &str, new_base: &String) -> Res<()> {
+    let mut cmd = Command::new("git");
+    cmd.arg("rebase");
+    cmd.args(state.pending_menu.as_ref().unwrap().args());
+    cmd.arg("--onto");
+    cmd.arg(new_base);
+    cmd.arg(upstream);
+
+    super::wip_snapshot(state, &format!("Before rebase onto {}", new_base))?;
     state.close_menu();
-    state.run_cmd_interactive(term, cmd)?;
+    state.run_cmd_with_editor(term, cmd)?;
     Ok(())
 }
 
@@ -86,12 +146,11 @@ pub(crate) struct RebaseInteractive;
 impl OpTrait for RebaseInteractive {
     fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
         let action = match target {
-            Some(TargetData::Commit(r) | TargetData::Branch(r)) => {
-                let rev = OsString::from(r);
-                Rc::new(move |state: &mut State, term: &mut Term| {
+            Some(TargetData::Commit(r) | TargetData::Branch(r) | TargetData::Tag(r)) => {
+                let base = format!("{}^", r);
+                Rc::new(move |state: &mut State, _term: &mut Term| {
                     let args = state.pending_menu.as_ref().unwrap().args();
-                    state.close_menu();
-                    state.run_cmd_interactive(term, rebase_interactive_cmd(&args, &rev))
+                    super::rebase_todo::edit_todo(state, &base, args)
                 })
             }
             _ => return None,
@@ -104,32 +163,19 @@ impl OpTrait for RebaseInteractive {
     }
 }
 
-fn rebase_interactive_cmd(args: &[OsString], rev: &OsStr) -> Command {
-    let mut cmd = Command::new("git");
-    cmd.args(["rebase", "-i"]);
-    cmd.args(args);
-    cmd.arg(&parent(rev));
-    cmd
-}
-
-fn parent(reference: &OsStr) -> OsString {
-    let mut parent = reference.to_os_string();
-    parent.push("^");
-    parent
-}
-
 #[derive(Display)]
 #[display(fmt = "Rebase autosquash")]
 pub(crate) struct RebaseAutosquash;
 impl OpTrait for RebaseAutosquash {
     fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
         let action = match target {
-            Some(TargetData::Commit(r) | TargetData::Branch(r)) => {
+            Some(TargetData::Commit(r) | TargetData::Branch(r) | TargetData::Tag(r)) => {
                 let rev = OsString::from(r);
                 Rc::new(move |state: &mut State, term: &mut Term| {
                     let args = state.pending_menu.as_ref().unwrap().args();
+                    super::wip_snapshot(state, "Before rebase autosquash")?;
                     state.close_menu();
-                    state.run_cmd_interactive(term, rebase_autosquash_cmd(&args, &rev))
+                    state.run_cmd_with_editor(term, rebase_autosquash_cmd(&args, &rev))
                 })
             }
             _ => return None,
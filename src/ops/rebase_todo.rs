@@ -0,0 +1,168 @@
+use super::{Action, OpTrait};
+use crate::{
+    git::{self, rebase_todo::RebaseAction},
+    items::TargetData,
+    menu::{Menu, PendingMenu},
+    screen::{self, NavMode},
+    state::State,
+    term::Term,
+    Res,
+};
+use derive_more::Display;
+use std::{cell::RefCell, ffi::OsString, fs, process::Command, rc::Rc};
+
+/// Opens the todo editor for an interactive rebase onto `base`, instead of `$EDITOR`.
+pub(crate) fn edit_todo(state: &mut State, base: &str, args: Vec<OsString>) -> Res<()> {
+    let todo = git::rebase_todo(&state.repo, base, args)?;
+    let size = state.screens.last().unwrap().size;
+
+    state.screens.push(screen::rebase_todo::create(
+        Rc::clone(&state.config),
+        Rc::new(RefCell::new(todo)),
+        size,
+    )?);
+    state.pending_menu = Some(PendingMenu::init(Menu::RebaseTodo));
+    Ok(())
+}
+
+#[derive(Display)]
+#[display(fmt = "Up")]
+pub(crate) struct RebaseTodoUp;
+impl OpTrait for RebaseTodoUp {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state, _term| {
+            state.screen_mut().select_previous(NavMode::Normal);
+            Ok(())
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Down")]
+pub(crate) struct RebaseTodoDown;
+impl OpTrait for RebaseTodoDown {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state, _term| {
+            state.screen_mut().select_next(NavMode::Normal);
+            Ok(())
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Move commit up")]
+pub(crate) struct RebaseTodoMoveUp;
+impl OpTrait for RebaseTodoMoveUp {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let Some(TargetData::RebaseTodoLine(todo, i)) = target else {
+            return None;
+        };
+        let (todo, i) = (Rc::clone(todo), *i);
+
+        Some(Rc::new(move |state, _term| {
+            if i > 0 {
+                todo.borrow_mut().lines.swap(i - 1, i);
+                state.screen_mut().update()?;
+                state.screen_mut().select_previous(NavMode::Normal);
+            }
+            Ok(())
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Move commit down")]
+pub(crate) struct RebaseTodoMoveDown;
+impl OpTrait for RebaseTodoMoveDown {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let Some(TargetData::RebaseTodoLine(todo, i)) = target else {
+            return None;
+        };
+        let (todo, i) = (Rc::clone(todo), *i);
+
+        Some(Rc::new(move |state, _term| {
+            if i + 1 < todo.borrow().lines.len() {
+                todo.borrow_mut().lines.swap(i, i + 1);
+                state.screen_mut().update()?;
+                state.screen_mut().select_next(NavMode::Normal);
+            }
+            Ok(())
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Set {}", _0)]
+pub(crate) struct RebaseTodoSetAction(pub RebaseAction);
+impl OpTrait for RebaseTodoSetAction {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let Some(TargetData::RebaseTodoLine(todo, i)) = target else {
+            return None;
+        };
+        let (todo, i, action) = (Rc::clone(todo), *i, self.0);
+
+        Some(Rc::new(move |state, _term| {
+            todo.borrow_mut().lines[i].action = action;
+            state.screen_mut().update()?;
+            state.screen_mut().select_next(NavMode::Normal);
+            Ok(())
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Start rebase")]
+pub(crate) struct RebaseTodoStart;
+impl OpTrait for RebaseTodoStart {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let Some(TargetData::RebaseTodoLine(todo, _)) = target else {
+            return None;
+        };
+        let todo = Rc::clone(todo);
+
+        Some(Rc::new(move |state: &mut State, term: &mut Term| {
+            let todo = todo.borrow();
+            if let Some(file) = &todo.file {
+                fs::write(file, todo.format())?;
+                state.screens.pop();
+                state.close_menu();
+                return state.finish_edit(true);
+            }
+
+            let todo_file = state.repo.path().join("gitu-rebase-todo");
+            fs::write(&todo_file, todo.format())?;
+
+            let mut cmd = Command::new("git");
+            cmd.args(["rebase", "-i"]);
+            cmd.args(&todo.args);
+            cmd.arg(&todo.base);
+            // Git invokes the sequence editor with the path of its own todo, which is overwritten.
+            // The path is passed via the environment, so that it needs no quoting.
+            cmd.env("GITU_REBASE_TODO", &todo_file);
+            cmd.env("GIT_SEQUENCE_EDITOR", "cp \"$GITU_REBASE_TODO\"");
+
+            super::wip_snapshot(
+                state,
+                &format!("Before interactive rebase from {}", todo.base),
+            )?;
+            state.screens.pop();
+            state.close_menu();
+            let result = state.run_cmd_interactive(term, cmd);
+            fs::remove_file(&todo_file)?;
+            result
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Abort")]
+pub(crate) struct RebaseTodoAbort;
+impl OpTrait for RebaseTodoAbort {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            state.screens.pop();
+            state.close_menu();
+            state.finish_edit(false)
+        }))
+    }
+}
@@ -75,6 +75,7 @@ fn reset_hard(state: &mut State, term: &mut Term, input: &str) -> Res<()> {
     cmd.args(state.pending_menu.as_ref().unwrap().args());
     cmd.arg(input);
 
+    super::wip_snapshot(state, &format!("Before hard reset to {}", input))?;
     state.close_menu();
     state.run_cmd(term, &[], cmd)
 }
@@ -0,0 +1,17 @@
+use super::{apply::apply_patch, Action, OpTrait};
+use crate::{git::diff::PatchMode, items::TargetData};
+use derive_more::Display;
+
+#[derive(Display)]
+#[display(fmt = "Reverse")]
+pub(crate) struct Reverse;
+impl OpTrait for Reverse {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let action = apply_patch(target, &["--reverse"], PatchMode::Reverse)?;
+        Some(super::create_y_n_prompt(action, "Really reverse?"))
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+}
@@ -40,7 +40,7 @@ impl OpTrait for RevertContinue {
             cmd.args(["revert", "--continue"]);
 
             state.close_menu();
-            state.run_cmd_interactive(term, cmd)?;
+            state.run_cmd_with_editor(term, cmd)?;
             Ok(())
         }))
     }
@@ -67,5 +67,5 @@ fn revert_commit(state: &mut State, term: &mut Term, input: &str) -> Res<()> {
     cmd.arg(input);
 
     state.close_menu();
-    state.run_cmd_interactive(term, cmd)
+    state.run_cmd_with_editor(term, cmd)
 }
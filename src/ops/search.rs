@@ -0,0 +1,59 @@
+use super::{Action, OpTrait};
+use crate::{items::TargetData, prompt::PromptData, state::State, term::Term};
+use derive_more::Display;
+use std::rc::Rc;
+use tui_prompts::State as _;
+
+#[derive(Display)]
+#[display(fmt = "Search")]
+pub(crate) struct Search;
+impl OpTrait for Search {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            state.close_menu();
+            let start = state.screen().selected_item_index();
+
+            state.prompt.set(PromptData {
+                prompt_text: "Search".into(),
+                // Searches again from where it started as the query is typed
+                update_fn: Rc::new(move |state: &mut State, term: &mut Term| {
+                    let query = state.prompt.state.value().to_string();
+                    let found = state.screen_mut().search(&query, start);
+
+                    if state.prompt.state.status().is_done() {
+                        state.prompt.reset(term)?;
+                        if !found && !query.is_empty() {
+                            return Err(format!("No matches for '{}'", query).into());
+                        }
+                    }
+                    Ok(())
+                }),
+            });
+            Ok(())
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Next match")]
+pub(crate) struct SearchNext;
+impl OpTrait for SearchNext {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            state.close_menu();
+            state.screen_mut().search_next(true)
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Previous match")]
+pub(crate) struct SearchPrevious;
+impl OpTrait for SearchPrevious {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            state.close_menu();
+            state.screen_mut().search_next(false)
+        }))
+    }
+}
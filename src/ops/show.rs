@@ -1,7 +1,18 @@
-use super::OpTrait;
-use crate::{items::TargetData, screen, Action};
+use super::{create_prompt, OpTrait};
+use crate::{
+    git::{self, RevDiff},
+    items::TargetData,
+    screen,
+    state::State,
+    term::Term,
+    Action, Res,
+};
 use derive_more::Display;
-use std::{path::Path, process::Command, rc::Rc};
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+    rc::Rc,
+};
 
 #[derive(Default, Clone, Copy, Debug, Display)]
 #[display(fmt = "Show")]
@@ -9,11 +20,31 @@ pub(crate) struct Show;
 impl OpTrait for Show {
     fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
         match target {
-            Some(TargetData::Commit(r) | TargetData::Branch(r)) => goto_show_screen(r.clone()),
-            Some(TargetData::File(u)) => editor(u.as_path(), None),
-            Some(TargetData::Delta(d)) => editor(d.new_file.as_path(), None),
+            Some(
+                TargetData::Commit(r)
+                | TargetData::Branch(r)
+                | TargetData::Tag(r)
+                | TargetData::WipSnapshot { commit: r, id: _ },
+            ) => goto_show_screen(r.clone()),
+            Some(TargetData::File(u)) => viewer(u.as_path()),
+            Some(TargetData::Unmerged(path)) => goto_conflict_screen(path.clone()),
+            Some(TargetData::Conflict(path, _)) => editor(path.as_path(), None),
+            Some(TargetData::Delta(d)) if d.status != git2::Delta::Deleted => {
+                viewer(d.new_file.as_path())
+            }
             Some(TargetData::Hunk(h)) => editor(h.new_file.as_path(), Some(h.first_diff_line())),
-            Some(TargetData::Stash { id: _, commit }) => goto_show_screen(commit.clone()),
+            Some(TargetData::HunkLine(h, i)) => {
+                editor(h.new_file.as_path(), Some(h.new_line_number(*i)))
+            }
+            // Uncommitted changes get a screen of their own, where they can still be (un)staged
+            Some(TargetData::AllUnstaged) => goto_rev_diff_screen(RevDiff::Unstaged),
+            Some(TargetData::AllStaged) => goto_rev_diff_screen(RevDiff::Staged),
+            Some(TargetData::Stash { id, commit }) => goto_stash_screen(*id, commit.clone()),
+            Some(TargetData::Submodule(path)) => super::submodule::visit_submodule(path.clone()),
+            Some(TargetData::FastForward(upstream)) => super::pull::fast_forward(upstream.clone()),
+            Some(TargetData::Worktree(path)) => super::worktree::visit_worktree(path.clone()),
+            Some(TargetData::Remote { name, .. }) => super::fetch::goto_remote_refs(name.clone()),
+            Some(TargetData::MoreRecentCommits) => show_more_recent_commits(),
             _ => None,
         }
     }
@@ -22,6 +53,40 @@ impl OpTrait for Show {
     }
 }
 
+#[derive(Display)]
+#[display(fmt = "Show rev")]
+pub(crate) struct ShowRev;
+impl OpTrait for ShowRev {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        let mut prompt = create_prompt("Show rev", show_rev, true);
+
+        Some(Rc::new(move |state: &mut State, term: &mut Term| {
+            Rc::get_mut(&mut prompt).unwrap()(state, term)?;
+            state.prompt.completions = git::ref_names(&state.repo)?;
+            Ok(())
+        }))
+    }
+}
+
+fn show_rev(state: &mut State, term: &mut Term, rev: &str) -> Res<()> {
+    state.close_menu();
+
+    let commit = state
+        .repo
+        .revparse_single(rev)
+        .and_then(|object| object.peel_to_commit())
+        .map_err(|err| format!("Failed due to: {:?}", err.code()))?;
+
+    state.screens.push(screen::show::create(
+        Rc::clone(&state.config),
+        Rc::clone(&state.repo),
+        Rc::clone(&state.diff_options),
+        term.size()?,
+        commit.id().to_string(),
+    )?);
+    Ok(())
+}
+
 fn goto_show_screen(r: String) -> Option<Action> {
     Some(Rc::new(move |state, term| {
         state.close_menu();
@@ -29,6 +94,7 @@ fn goto_show_screen(r: String) -> Option<Action> {
             screen::show::create(
                 Rc::clone(&state.config),
                 Rc::clone(&state.repo),
+                Rc::clone(&state.diff_options),
                 term.size()?,
                 r.clone(),
             )
@@ -38,14 +104,75 @@ fn goto_show_screen(r: String) -> Option<Action> {
     }))
 }
 
+fn show_more_recent_commits() -> Option<Action> {
+    Some(Rc::new(|state, _term| {
+        let count = &state.recent_commits_count;
+        count.set(count.get().max(1) * 2);
+
+        state.close_menu();
+        state.screen_mut().update()
+    }))
+}
+
+fn goto_rev_diff_screen(rev_diff: RevDiff) -> Option<Action> {
+    Some(Rc::new(move |state, _term| {
+        super::diff::goto_rev_diff_screen(state, rev_diff.clone())
+    }))
+}
+
+fn goto_stash_screen(id: usize, commit: String) -> Option<Action> {
+    Some(Rc::new(move |state, term| {
+        state.close_menu();
+        state.screens.push(screen::stash::create(
+            Rc::clone(&state.config),
+            Rc::clone(&state.repo),
+            Rc::clone(&state.diff_options),
+            term.size()?,
+            id,
+            commit.clone(),
+        )?);
+        Ok(())
+    }))
+}
+
+fn goto_conflict_screen(path: PathBuf) -> Option<Action> {
+    Some(Rc::new(move |state, term| {
+        state.close_menu();
+        state.screens.push(screen::conflict::create(
+            Rc::clone(&state.config),
+            Rc::clone(&state.repo),
+            term.size()?,
+            path.clone(),
+        )?);
+        Ok(())
+    }))
+}
+
+/// Visits a file with `general.file_viewer`, falling back to the editor.
+fn viewer(file: &Path) -> Option<Action> {
+    open_file(file, None, true)
+}
+
 fn editor(file: &Path, maybe_line: Option<u32>) -> Option<Action> {
+    open_file(file, maybe_line, false)
+}
+
+fn open_file(file: &Path, maybe_line: Option<u32>, use_viewer: bool) -> Option<Action> {
     let file = file.to_str().unwrap().to_string();
 
     Some(Rc::new(move |state, term| {
         const EDITOR_VARS: [&str; 3] = ["GIT_EDITOR", "VISUAL", "EDITOR"];
-        let configured_editor = EDITOR_VARS
-            .into_iter()
-            .find_map(|var| std::env::var(var).ok());
+        let configured_viewer = state
+            .config
+            .general
+            .file_viewer
+            .clone()
+            .filter(|_| use_viewer);
+        let configured_editor = configured_viewer.or_else(|| {
+            EDITOR_VARS
+                .into_iter()
+                .find_map(|var| std::env::var(var).ok())
+        });
 
         let Some(editor) = configured_editor else {
             return Err(format!(
@@ -74,10 +201,18 @@ fn parse_editor_command(editor: &str, file: &str, maybe_line: Option<u32>) -> Co
     let lower = args[0].to_lowercase();
 
     if let Some(line) = maybe_line {
-        if lower.ends_with("vi")
-            || lower.ends_with("vim")
-            || lower.ends_with("nvim")
-            || lower.ends_with("nano")
+        if [
+            "vi",
+            "vim",
+            "nvim",
+            "nano",
+            "emacs",
+            "emacsclient",
+            "micro",
+            "kak",
+        ]
+        .iter()
+        .any(|name| lower.ends_with(name))
         {
             cmd.args([&format!("+{}", line), file]);
         } else {
@@ -102,4 +237,19 @@ mod tests {
             &["-f", "+42", "README.md"]
         );
     }
+
+    #[test]
+    fn parse_editor_command_emacsclient() {
+        let cmd = super::parse_editor_command("emacsclient -t", "src/main.rs", Some(7));
+        assert_eq!(
+            &cmd.get_args().collect::<Vec<_>>(),
+            &["-t", "+7", "src/main.rs"]
+        );
+    }
+
+    #[test]
+    fn parse_editor_command_other() {
+        let cmd = super::parse_editor_command("hx", "src/main.rs", Some(7));
+        assert_eq!(&cmd.get_args().collect::<Vec<_>>(), &["src/main.rs:7"]);
+    }
 }
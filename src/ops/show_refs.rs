@@ -1,5 +1,5 @@
-use super::{Action, OpTrait};
-use crate::{items::TargetData, screen, state::State, term::Term};
+use super::{create_prompt, Action, OpTrait};
+use crate::{items::TargetData, screen, state::State, term::Term, Res};
 use derive_more::Display;
 use std::rc::Rc;
 
@@ -9,18 +9,36 @@ pub(crate) struct ShowRefs;
 impl OpTrait for ShowRefs {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
         Some(Rc::new(|state: &mut State, _term: &mut Term| {
-            goto_refs_screen(state);
-            Ok(())
+            goto_refs_screen(state, None)
         }))
     }
 }
 
-fn goto_refs_screen(state: &mut State) {
-    state.screens.drain(1..);
+#[derive(Display)]
+#[display(fmt = "Filter tags")]
+pub(crate) struct FilterTags;
+impl OpTrait for FilterTags {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt("Filter tags", filter_tags, true))
+    }
+}
+
+fn filter_tags(state: &mut State, _term: &mut Term, filter: &str) -> Res<()> {
+    goto_refs_screen(state, Some(filter).filter(|filter| !filter.is_empty()))
+}
+
+fn goto_refs_screen(state: &mut State, tag_filter: Option<&str>) -> Res<()> {
     let size = state.screens.last().unwrap().size;
+    // Created before leaving the current screens, which are kept if it fails
+    let screen = screen::show_refs::create(
+        Rc::clone(&state.config),
+        Rc::clone(&state.repo),
+        size,
+        tag_filter.map(String::from),
+    )?;
+
     state.close_menu();
-    state.screens.push(
-        screen::show_refs::create(Rc::clone(&state.config), Rc::clone(&state.repo), size)
-            .expect("Couldn't create screen"),
-    );
+    state.screens.drain(1..);
+    state.screens.push(screen);
+    Ok(())
 }
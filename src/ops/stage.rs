@@ -7,7 +7,7 @@ use crate::{
     Action,
 };
 use derive_more::Display;
-use std::{ffi::OsString, process::Command, rc::Rc};
+use std::{ffi::OsString, path::PathBuf, process::Command, rc::Rc};
 
 #[derive(Display)]
 #[display(fmt = "Stage")]
@@ -17,7 +17,8 @@ impl OpTrait for Stage {
         let action = match target.cloned() {
             Some(TargetData::AllUnstaged) => stage_unstaged(),
             Some(TargetData::AllUntracked(untracked)) => stage_untracked(untracked),
-            Some(TargetData::File(u)) => stage_file(u.into()),
+            Some(TargetData::UnstagedFiles(files)) => stage_unstaged_files(files),
+            Some(TargetData::File(u) | TargetData::Unmerged(u)) => stage_file(u.into()),
             Some(TargetData::Delta(d)) => stage_file(d.new_file.into()),
             Some(TargetData::Hunk(h)) => stage_patch(h),
             Some(TargetData::HunkLine(h, i)) => stage_line(h, i),
@@ -41,7 +42,18 @@ fn stage_unstaged() -> Action {
     })
 }
 
-fn stage_untracked(untracked: Vec<std::path::PathBuf>) -> Action {
+fn stage_unstaged_files(files: Vec<PathBuf>) -> Action {
+    Rc::new(move |state: &mut State, term: &mut Term| {
+        let mut cmd = Command::new("git");
+        cmd.args(["add", "-u", "--"]);
+        cmd.args(files.clone());
+
+        state.close_menu();
+        state.run_cmd(term, &[], cmd)
+    })
+}
+
+fn stage_untracked(untracked: Vec<PathBuf>) -> Action {
     Rc::new(move |state: &mut State, term: &mut Term| {
         let mut cmd = Command::new("git");
         cmd.arg("add");
@@ -68,6 +80,7 @@ fn stage_patch(h: Rc<Hunk>) -> Action {
         let mut cmd = Command::new("git");
         cmd.args(["apply", "--cached"]);
 
+        super::ensure_hunks_apply(state)?;
         state.close_menu();
         state.run_cmd(term, &h.format_patch().into_bytes(), cmd)
     })
@@ -82,6 +95,7 @@ fn stage_line(h: Rc<Hunk>, i: usize) -> Action {
             .format_line_patch(i..(i + 1), PatchMode::Normal)
             .into_bytes();
 
+        super::ensure_hunks_apply(state)?;
         state.close_menu();
         state.run_cmd(term, &input, cmd)
     })
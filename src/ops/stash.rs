@@ -1,5 +1,5 @@
-use super::{create_prompt, create_prompt_with_default, Action, OpTrait};
-use crate::{items::TargetData, menu::arg::Arg, state::State, term::Term, Res};
+use super::{create_prompt, create_prompt_with_default, create_y_n_prompt, Action, OpTrait};
+use crate::{git, items::TargetData, menu::arg::Arg, screen, state::State, term::Term, Res};
 use derive_more::Display;
 use git2::{Repository, Status, StatusOptions};
 use std::{process::Command, rc::Rc};
@@ -177,13 +177,8 @@ impl OpTrait for StashPop {
 }
 
 fn stash_pop(state: &mut State, term: &mut Term, input: &str) -> Res<()> {
-    let mut cmd = Command::new("git");
-    cmd.args(["stash", "pop", "-q"]);
-    cmd.arg(input);
-
     state.close_menu();
-    state.run_cmd(term, &[], cmd)?;
-    Ok(())
+    unstash_checking_conflicts(state, term, "pop", input)
 }
 
 #[derive(Display)]
@@ -201,13 +196,56 @@ impl OpTrait for StashApply {
 }
 
 fn stash_apply(state: &mut State, term: &mut Term, input: &str) -> Res<()> {
-    let mut cmd = Command::new("git");
-    cmd.args(["stash", "apply", "-q"]);
-    cmd.arg(input);
-
     state.close_menu();
-    state.run_cmd(term, &[], cmd)?;
-    Ok(())
+    unstash_checking_conflicts(state, term, "apply", input)
+}
+
+/// Runs `git stash <subcommand> <stash>` right away if the stash applies cleanly.
+/// Otherwise lists the files expected to conflict and asks first.
+fn unstash_checking_conflicts(
+    state: &mut State,
+    term: &mut Term,
+    subcommand: &'static str,
+    stash: &str,
+) -> Res<()> {
+    let conflicts = git::stash_conflicts(&state.repo, stash)?;
+    let stash = stash.to_string();
+    let unstash = move |state: &mut State, term: &mut Term| {
+        // `git stash pop` only accepts entries of `refs/stash`
+        let pop_snapshot = subcommand == "pop" && is_wip_snapshot(&stash);
+        let mut cmd = Command::new("git");
+        cmd.args([
+            "stash",
+            if pop_snapshot { "apply" } else { subcommand },
+            "-q",
+        ]);
+        cmd.arg(&stash);
+        state.run_cmd(term, &[], cmd)?;
+
+        if pop_snapshot {
+            state.run_cmd(term, &[], drop_cmd(&stash))?;
+        }
+
+        Ok(())
+    };
+
+    if conflicts.is_empty() {
+        return unstash(state, term);
+    }
+
+    state.display_error(format!(
+        "The stash doesn't apply cleanly, expect conflicts in: {}",
+        conflicts.join(", ")
+    ));
+
+    let mut prompt = create_y_n_prompt(
+        Rc::new(unstash),
+        match subcommand {
+            "pop" => "Pop anyway?",
+            _ => "Apply anyway?",
+        },
+    );
+    Rc::get_mut(&mut prompt).unwrap()(state, term)
 }
 
 #[derive(Display)]
@@ -225,18 +263,51 @@ impl OpTrait for StashDrop {
 }
 
 fn stash_drop(state: &mut State, term: &mut Term, input: &str) -> Res<()> {
+    state.close_menu();
+    state.run_cmd(term, &[], drop_cmd(input))?;
+    Ok(())
+}
+
+fn drop_cmd(input: &str) -> Command {
     let mut cmd = Command::new("git");
-    cmd.args(["stash", "drop"]);
+    if is_wip_snapshot(input) {
+        // `git stash drop` only accepts entries of `refs/stash`
+        cmd.args(["reflog", "delete", "--updateref", "--rewrite"]);
+    } else {
+        cmd.args(["stash", "drop"]);
+    }
     cmd.arg(input);
+    cmd
+}
 
-    state.close_menu();
-    state.run_cmd(term, &[], cmd)?;
-    Ok(())
+fn is_wip_snapshot(input: &str) -> bool {
+    input.starts_with(git::WIP_REF)
 }
 
 fn selected_stash(state: &State) -> Option<String> {
-    match state.screen().get_selected_item().target_data {
+    match &state.screen().get_selected_item().target_data {
         Some(TargetData::Stash { id, commit: _ }) => Some(id.to_string()),
+        // The reflog entry rather than the commit, so that pop and drop remove the snapshot
+        Some(TargetData::WipSnapshot { commit: _, id }) => {
+            Some(format!("{}@{{{}}}", git::WIP_REF, id))
+        }
         _ => Some("0".to_string()),
     }
 }
+
+#[derive(Display)]
+#[display(fmt = "Show WIP snapshots")]
+pub(crate) struct ShowWipSnapshots;
+impl OpTrait for ShowWipSnapshots {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            state.close_menu();
+            state.screens.push(screen::wip::create(
+                Rc::clone(&state.config),
+                Rc::clone(&state.repo),
+                term.size()?,
+            )?);
+            Ok(())
+        }))
+    }
+}
@@ -0,0 +1,114 @@
+use super::{create_prompt, create_prompt_with_default, Action, OpTrait};
+use crate::{items::TargetData, menu::arg::Arg, state::State, term::Term, Res};
+use derive_more::Display;
+use std::{path::PathBuf, process::Command, rc::Rc};
+
+pub(crate) fn init_args() -> Vec<Arg> {
+    vec![Arg::new_flag("--force", "Force", false)]
+}
+
+#[derive(Display)]
+#[display(fmt = "Update")]
+pub(crate) struct SubmoduleUpdate;
+impl OpTrait for SubmoduleUpdate {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let path = selected_submodule_path(target);
+
+        Some(Rc::new(move |state: &mut State, term: &mut Term| {
+            let mut cmd = Command::new("git");
+            cmd.args(["submodule", "update", "--init", "--recursive"]);
+            cmd.args(state.pending_menu.as_ref().unwrap().args());
+            if let Some(path) = &path {
+                cmd.arg("--");
+                cmd.arg(path);
+            }
+
+            state.close_menu();
+            state.run_cmd_async(term, &[], cmd)
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Sync")]
+pub(crate) struct SubmoduleSync;
+impl OpTrait for SubmoduleSync {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let path = selected_submodule_path(target);
+
+        Some(Rc::new(move |state: &mut State, term: &mut Term| {
+            let mut cmd = Command::new("git");
+            cmd.args(["submodule", "sync", "--recursive"]);
+            if let Some(path) = &path {
+                cmd.arg("--");
+                cmd.arg(path);
+            }
+
+            state.close_menu();
+            state.run_cmd(term, &[], cmd)
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Add")]
+pub(crate) struct SubmoduleAdd;
+impl OpTrait for SubmoduleAdd {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt("Add submodule from url", submodule_add, true))
+    }
+}
+
+fn submodule_add(state: &mut State, term: &mut Term, url: &str) -> Res<()> {
+    let mut cmd = Command::new("git");
+    cmd.args(["submodule", "add"]);
+    cmd.args(state.pending_menu.as_ref().unwrap().args());
+    cmd.arg(url);
+
+    state.close_menu();
+    state.run_cmd_async(term, &[], cmd)
+}
+
+#[derive(Display)]
+#[display(fmt = "Deinit")]
+pub(crate) struct SubmoduleDeinit;
+impl OpTrait for SubmoduleDeinit {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt_with_default(
+            "Deinit submodule",
+            submodule_deinit,
+            selected_submodule,
+            true,
+        ))
+    }
+}
+
+fn submodule_deinit(state: &mut State, term: &mut Term, path: &str) -> Res<()> {
+    let mut cmd = Command::new("git");
+    cmd.args(["submodule", "deinit"]);
+    cmd.args(state.pending_menu.as_ref().unwrap().args());
+    cmd.arg("--");
+    cmd.arg(path);
+
+    state.close_menu();
+    state.run_cmd(term, &[], cmd)
+}
+
+pub(crate) fn visit_submodule(path: PathBuf) -> Option<Action> {
+    Some(Rc::new(move |state, term| {
+        let full_path = state.repo.workdir().expect("No workdir").join(&path);
+        super::worktree::open_repository(state, term, &full_path)
+    }))
+}
+
+fn selected_submodule_path(target: Option<&TargetData>) -> Option<PathBuf> {
+    match target {
+        Some(TargetData::Submodule(path)) => Some(path.clone()),
+        _ => None,
+    }
+}
+
+fn selected_submodule(state: &State) -> Option<String> {
+    selected_submodule_path(state.screen().get_selected_item().target_data.as_ref())
+        .map(|path| path.to_string_lossy().to_string())
+}
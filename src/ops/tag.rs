@@ -0,0 +1,82 @@
+use super::{set_prompt, Action, OpTrait};
+use crate::{git, items::TargetData, state::State, term::Term, Res};
+use derive_more::Display;
+use std::{process::Command, rc::Rc};
+
+#[derive(Display)]
+#[display(fmt = "Checkout tag (detached)")]
+pub(crate) struct CheckoutTag;
+impl OpTrait for CheckoutTag {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let Some(TargetData::Tag(tag)) = target else {
+            return None;
+        };
+        let tag = tag.clone();
+
+        Some(Rc::new(move |state: &mut State, term: &mut Term| {
+            let mut cmd = Command::new("git");
+            cmd.args(["checkout", "--detach"]);
+            cmd.arg(&tag);
+
+            state.close_menu();
+            state.run_cmd(term, &[], cmd)
+        }))
+    }
+    fn is_target_op(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Push tag")]
+pub(crate) struct PushTag;
+impl OpTrait for PushTag {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let Some(TargetData::Tag(tag)) = target else {
+            return None;
+        };
+        let tag = tag.clone();
+
+        Some(Rc::new(move |state: &mut State, _term: &mut Term| {
+            set_prompt(
+                state,
+                "Push tag to",
+                push_tag,
+                Box::new(|state| {
+                    git::head_remote(&state.repo).or_else(|| {
+                        let remotes = state.repo.remotes().ok()?;
+                        remotes.get(0).map(String::from)
+                    })
+                }),
+                tag.clone(),
+                true,
+            );
+            Ok(())
+        }))
+    }
+    fn is_target_op(&self) -> bool {
+        true
+    }
+}
+
+fn push_tag(state: &mut State, term: &mut Term, remote: &str, tag: &String) -> Res<()> {
+    let mut cmd = Command::new("git");
+    cmd.args(["push", "--progress"]);
+    cmd.args(state.pending_menu.as_ref().unwrap().args());
+    cmd.arg(remote);
+    cmd.arg(format!("refs/tags/{}", tag));
+
+    state.close_menu();
+    state.run_cmd_async(term, &[], cmd)
+}
+
+pub(crate) fn delete_tag(tag: String) -> Action {
+    Rc::new(move |state: &mut State, term: &mut Term| {
+        let mut cmd = Command::new("git");
+        cmd.args(["tag", "--delete"]);
+        cmd.arg(&tag);
+
+        state.close_menu();
+        state.run_cmd(term, &[], cmd)
+    })
+}
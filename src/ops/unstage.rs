@@ -52,6 +52,7 @@ fn unstage_patch(input: Vec<u8>) -> Action {
         let mut cmd = Command::new("git");
         cmd.args(["apply", "--cached", "--reverse"]);
 
+        super::ensure_hunks_apply(state)?;
         state.close_menu();
         state.run_cmd(term, &input, cmd)
     })
@@ -62,6 +63,7 @@ fn unstage_line(input: Vec<u8>) -> Action {
         let mut cmd = Command::new("git");
         cmd.args(["apply", "--cached", "--reverse", "--recount"]);
 
+        super::ensure_hunks_apply(state)?;
         state.close_menu();
         state.run_cmd(term, &input, cmd)
     })
@@ -0,0 +1,171 @@
+use super::{create_prompt, create_prompt_with_default, selected_rev, Action, OpTrait};
+use crate::{items::TargetData, menu::arg::Arg, screen, state::State, term::Term, Res};
+use derive_more::Display;
+use git2::Repository;
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+    rc::Rc,
+};
+
+pub(crate) fn init_args() -> Vec<Arg> {
+    vec![Arg::new_flag("--force", "Force", false)]
+}
+
+#[derive(Display)]
+#[display(fmt = "Show worktrees")]
+pub(crate) struct ShowWorktrees;
+impl OpTrait for ShowWorktrees {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            state.screens.drain(1..);
+            let size = state.screens.last().unwrap().size;
+            state.close_menu();
+            state.screens.push(screen::worktrees::create(
+                Rc::clone(&state.config),
+                Rc::clone(&state.repo),
+                size,
+            )?);
+            Ok(())
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Add worktree")]
+pub(crate) struct WorktreeAdd;
+impl OpTrait for WorktreeAdd {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt("Add worktree at", worktree_add, true))
+    }
+}
+
+fn worktree_add(state: &mut State, term: &mut Term, path: &str) -> Res<()> {
+    let mut cmd = Command::new("git");
+    cmd.args(["worktree", "add"]);
+    cmd.args(state.pending_menu.as_ref().unwrap().args());
+    cmd.arg(path);
+    if let Some(rev) = selected_rev(state) {
+        cmd.arg(rev);
+    }
+
+    state.close_menu();
+    state.run_cmd(term, &[], cmd)
+}
+
+#[derive(Display)]
+#[display(fmt = "Remove worktree")]
+pub(crate) struct WorktreeRemove;
+impl OpTrait for WorktreeRemove {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt_with_default(
+            "Remove worktree",
+            worktree_remove,
+            selected_worktree,
+            true,
+        ))
+    }
+}
+
+fn worktree_remove(state: &mut State, term: &mut Term, path: &str) -> Res<()> {
+    let mut cmd = Command::new("git");
+    cmd.args(["worktree", "remove"]);
+    cmd.args(state.pending_menu.as_ref().unwrap().args());
+    cmd.arg(path);
+
+    state.close_menu();
+    state.run_cmd(term, &[], cmd)
+}
+
+#[derive(Display)]
+#[display(fmt = "Lock worktree")]
+pub(crate) struct WorktreeLock;
+impl OpTrait for WorktreeLock {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt_with_default(
+            "Lock worktree",
+            worktree_lock,
+            selected_worktree,
+            true,
+        ))
+    }
+}
+
+fn worktree_lock(state: &mut State, term: &mut Term, path: &str) -> Res<()> {
+    let mut cmd = Command::new("git");
+    cmd.args(["worktree", "lock"]);
+    cmd.arg(path);
+
+    state.close_menu();
+    state.run_cmd(term, &[], cmd)
+}
+
+#[derive(Display)]
+#[display(fmt = "Unlock worktree")]
+pub(crate) struct WorktreeUnlock;
+impl OpTrait for WorktreeUnlock {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt_with_default(
+            "Unlock worktree",
+            worktree_unlock,
+            selected_worktree,
+            true,
+        ))
+    }
+}
+
+fn worktree_unlock(state: &mut State, term: &mut Term, path: &str) -> Res<()> {
+    let mut cmd = Command::new("git");
+    cmd.args(["worktree", "unlock"]);
+    cmd.arg(path);
+
+    state.close_menu();
+    state.run_cmd(term, &[], cmd)
+}
+
+#[derive(Display)]
+#[display(fmt = "Prune worktrees")]
+pub(crate) struct WorktreePrune;
+impl OpTrait for WorktreePrune {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            let mut cmd = Command::new("git");
+            cmd.args(["worktree", "prune", "--verbose"]);
+
+            state.close_menu();
+            state.run_cmd(term, &[], cmd)
+        }))
+    }
+}
+
+/// Switches Gitu over to another worktree, starting over at its status screen.
+pub(crate) fn visit_worktree(path: PathBuf) -> Option<Action> {
+    Some(Rc::new(move |state, term| {
+        open_repository(state, term, &path)
+    }))
+}
+
+/// Replaces the current repository with the one at `path` and shows its status.
+pub(crate) fn open_repository(state: &mut State, term: &mut Term, path: &Path) -> Res<()> {
+    let repo = Repository::open(path)?;
+
+    state.close_menu();
+    state.repo = Rc::new(repo);
+    state.screens = vec![screen::status::create(
+        Rc::clone(&state.config),
+        Rc::clone(&state.repo),
+        Rc::clone(&state.diff_options),
+        Rc::clone(&state.recent_commits_count),
+        term.size()?,
+        state.now,
+        None,
+    )?];
+    Ok(())
+}
+
+fn selected_worktree(state: &State) -> Option<String> {
+    match &state.screen().get_selected_item().target_data {
+        Some(TargetData::Worktree(path)) => Some(path.to_string_lossy().to_string()),
+        _ => None,
+    }
+}
@@ -0,0 +1,32 @@
+use super::{Action, OpTrait};
+use crate::items::TargetData;
+use derive_more::Display;
+use std::rc::Rc;
+
+#[derive(Display)]
+#[display(fmt = "Copy")]
+pub(crate) struct Yank;
+impl OpTrait for Yank {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let (text, description) = match target? {
+            TargetData::Commit(hash) => (hash.clone(), "Commit hash"),
+            TargetData::Branch(branch) => (branch.clone(), "Branch name"),
+            TargetData::Tag(tag) => (tag.clone(), "Tag name"),
+            TargetData::File(path) | TargetData::Unmerged(path) => {
+                (path.to_string_lossy().to_string(), "File path")
+            }
+            TargetData::Delta(delta) => (delta.new_file.to_string_lossy().to_string(), "File path"),
+            TargetData::Hunk(hunk) => (hunk.format(), "Hunk"),
+            TargetData::Remote { url, .. } => (url.clone(), "Remote URL"),
+            _ => return None,
+        };
+
+        Some(Rc::new(move |state, term| {
+            state.close_menu();
+            state.copy_to_clipboard(term, text.clone(), description)
+        }))
+    }
+    fn is_target_op(&self) -> bool {
+        true
+    }
+}
@@ -12,6 +12,12 @@ pub(crate) struct PromptData {
 pub(crate) struct Prompt {
     pub(crate) data: Option<PromptData>,
     pub(crate) state: TextState<'static>,
+    /// Candidates for completing the input with <tab>
+    pub(crate) completions: Vec<String>,
+    /// Run when the prompt is aborted with `<esc>`
+    pub(crate) on_abort: Option<Action>,
+    /// Shows the input as `*`s, like for passwords
+    pub(crate) hide_input: bool,
 }
 
 impl Prompt {
@@ -19,17 +25,58 @@ impl Prompt {
         Prompt {
             data: None,
             state: TextState::new(),
+            completions: vec![],
+            on_abort: None,
+            hide_input: false,
         }
     }
 
     pub(crate) fn set(&mut self, data: PromptData) {
         self.data = Some(data);
+        self.completions.clear();
+        self.on_abort = None;
+        self.hide_input = false;
         self.state.focus();
     }
 
+    /// Extends the input to the longest prefix shared by the completions it's a prefix of.
+    /// Returns the matching completions if there's more than one.
+    pub(crate) fn complete(&mut self) -> Vec<String> {
+        let input = self.state.value();
+        let matches = self
+            .completions
+            .iter()
+            .filter(|completion| completion.starts_with(input))
+            .collect::<Vec<_>>();
+
+        let Some(first) = matches.first() else {
+            return vec![];
+        };
+
+        let prefix_len = matches.iter().fold(first.len(), |len, completion| {
+            first[..len]
+                .char_indices()
+                .zip(completion.chars())
+                .find(|((_, a), b)| a != b)
+                .map_or(len.min(completion.len()), |((i, _), _)| i)
+        });
+
+        *self.state.value_mut() = first[..prefix_len].to_string();
+        self.state.move_end();
+
+        if matches.len() > 1 {
+            matches.into_iter().cloned().collect()
+        } else {
+            vec![]
+        }
+    }
+
     pub(crate) fn reset<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> Res<()> {
         self.data = None;
         self.state = TextState::new();
+        self.completions.clear();
+        self.on_abort = None;
+        self.hide_input = false;
         terminal.hide_cursor()?;
         Ok(())
     }
@@ -0,0 +1,69 @@
+use std::{iter, rc::Rc};
+
+use super::Screen;
+use crate::{
+    config::Config,
+    git::absorb::Absorb,
+    items::{self, Item, TargetData},
+    Res,
+};
+use ratatui::{
+    prelude::Rect,
+    text::{Line, Span},
+};
+
+pub(crate) fn create(config: Rc<Config>, absorb: Rc<Absorb>, size: Rect) -> Res<Screen> {
+    Screen::new(
+        Rc::clone(&config),
+        size,
+        Box::new(move || {
+            let style = &config.style;
+
+            let fixups = absorb.fixups.iter().flat_map(|fixup| {
+                iter::once(items::blank_line())
+                    .chain(iter::once(Item {
+                        id: fixup.commit.clone().into(),
+                        display: Line::from(vec![
+                            Span::styled("Fixup ", &style.section_header),
+                            Span::styled(fixup.hash.clone(), &style.hash),
+                            Span::raw(" "),
+                            Span::styled(fixup.summary.clone(), &style.section_header),
+                        ]),
+                        section: true,
+                        depth: 0,
+                        target_data: Some(TargetData::Commit(fixup.commit.clone())),
+                        ..Default::default()
+                    }))
+                    .chain(items::create_diff_items(
+                        Rc::clone(&config),
+                        &fixup.diff,
+                        &1,
+                        false,
+                    ))
+            });
+
+            let unabsorbed = if absorb.unabsorbed.deltas.is_empty() {
+                vec![]
+            } else {
+                iter::once(items::blank_line())
+                    .chain(iter::once(Item {
+                        id: "unabsorbed".into(),
+                        display: Line::styled("Not absorbed", &style.section_header),
+                        section: true,
+                        depth: 0,
+                        ..Default::default()
+                    }))
+                    .chain(items::create_diff_items(
+                        Rc::clone(&config),
+                        &absorb.unabsorbed,
+                        &1,
+                        false,
+                    ))
+                    .collect()
+            };
+
+            // Sections are separated by a blank line, but there's none before the first
+            Ok(fixups.chain(unabsorbed).skip(1).collect())
+        }),
+    )
+}
@@ -0,0 +1,92 @@
+use std::rc::Rc;
+
+use super::{plural, Screen};
+use crate::{
+    config::Config,
+    git,
+    items::{self, Item},
+    Res,
+};
+use git2::{Oid, Repository};
+use ratatui::{prelude::Rect, text::Line};
+
+pub(crate) fn create(config: Rc<Config>, repo: Rc<Repository>, size: Rect) -> Res<Screen> {
+    Screen::new(
+        Rc::clone(&config),
+        size,
+        Box::new(move || {
+            let style = &config.style;
+
+            let Some(bisect) = git::bisect_status(&repo)? else {
+                return Ok(vec![Item {
+                    id: "bisect_status".into(),
+                    display: Line::styled("Not bisecting", &style.section_header),
+                    ..Default::default()
+                }]);
+            };
+
+            let (heading, commit, progress) = match &bisect.first_bad {
+                Some(first_bad) => (
+                    "Found first bad commit",
+                    first_bad,
+                    "Bisecting done".to_string(),
+                ),
+                None => (
+                    "Candidate",
+                    &bisect.candidate,
+                    match &bisect.remaining {
+                        Some(remaining) => format!(
+                            "Bisecting: {} left to test after this (roughly {})",
+                            plural(remaining.revisions, "revision"),
+                            plural(remaining.steps, "step"),
+                        ),
+                        None => "Bisecting: waiting for both good and bad revisions".to_string(),
+                    },
+                ),
+            };
+
+            Ok([
+                Item {
+                    id: "bisect_status".into(),
+                    display: Line::styled(progress, &style.section_header),
+                    ..Default::default()
+                },
+                items::blank_line(),
+                Item {
+                    id: "bisect_candidate".into(),
+                    display: Line::styled(heading, &style.section_header),
+                    section: true,
+                    depth: 0,
+                    ..Default::default()
+                },
+            ]
+            .into_iter()
+            .chain(items::log(
+                &config,
+                &repo,
+                1,
+                Some(Oid::from_str(commit)?),
+                Default::default(),
+                false,
+                false,
+            )?)
+            .chain([
+                items::blank_line(),
+                Item {
+                    id: "bisect_log".into(),
+                    display: Line::styled("Bisect log", &style.section_header),
+                    section: true,
+                    depth: 0,
+                    ..Default::default()
+                },
+            ])
+            .chain(bisect.log.iter().map(|line| Item {
+                display: Line::raw(line.clone()),
+                depth: 1,
+                unselectable: true,
+                ..Default::default()
+            }))
+            .collect())
+        }),
+    )
+}
@@ -0,0 +1,57 @@
+use std::{cell::RefCell, iter, rc::Rc};
+
+use super::Screen;
+use crate::{
+    config::Config,
+    git::clean::Clean,
+    items::{Item, TargetData},
+    Res,
+};
+use ratatui::{
+    prelude::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+};
+
+pub(crate) fn create(config: Rc<Config>, clean: Rc<RefCell<Clean>>, size: Rect) -> Res<Screen> {
+    Screen::new(
+        Rc::clone(&config),
+        size,
+        Box::new(move || {
+            let style = &config.style;
+            let entries = clean.borrow().entries.clone();
+            let selected = entries.iter().filter(|entry| entry.selected).count();
+
+            Ok(iter::once(Item {
+                id: "clean".into(),
+                display: Line::styled(
+                    format!("Remove {} of {} untracked files", selected, entries.len()),
+                    &style.section_header,
+                ),
+                depth: 0,
+                unselectable: true,
+                ..Default::default()
+            })
+            .chain(entries.into_iter().enumerate().map(|(i, entry)| {
+                let (mark, path_style) = if entry.selected {
+                    ("[x]", Style::new())
+                } else {
+                    ("[ ]", Style::new().add_modifier(Modifier::CROSSED_OUT))
+                };
+
+                Item {
+                    id: entry.path.clone().into(),
+                    display: Line::from(vec![
+                        Span::styled(mark, &style.hotkey),
+                        Span::raw(" "),
+                        Span::styled(entry.path, path_style),
+                    ]),
+                    depth: 1,
+                    target_data: Some(TargetData::CleanEntry(Rc::clone(&clean), i)),
+                    ..Default::default()
+                }
+            }))
+            .collect())
+        }),
+    )
+}
@@ -0,0 +1,108 @@
+use std::{cell::RefCell, iter, rc::Rc};
+
+use super::Screen;
+use crate::{
+    config::Config,
+    git::commit_picker::{CommitPicker, PickerCommit},
+    items::{self, Item, TargetData},
+    Res,
+};
+use ratatui::{
+    prelude::Rect,
+    text::{Line, Span},
+};
+
+pub(crate) fn create(
+    config: Rc<Config>,
+    picker: Rc<RefCell<CommitPicker>>,
+    size: Rect,
+) -> Res<Screen> {
+    Screen::new(
+        Rc::clone(&config),
+        size,
+        Box::new(move || {
+            let style = &config.style;
+            let borrowed = picker.borrow();
+
+            let mut header = if borrowed.instant {
+                "Pick a commit to move the staged changes into".to_string()
+            } else {
+                format!(
+                    "Pick a commit to {}",
+                    borrowed.flag.trim_start_matches("--")
+                )
+            };
+            if !borrowed.show_all {
+                header.push_str(", touching staged files");
+            }
+            if !borrowed.filter.is_empty() {
+                header.push_str(&format!(", matching '{}'", borrowed.filter));
+            }
+
+            let commit_item = |section: &str, commit: &PickerCommit| Item {
+                id: format!("{}_{}", section, commit.commit).into(),
+                display: Line::from(vec![
+                    Span::styled(commit.hash.clone(), &style.hash),
+                    Span::raw(" "),
+                    Span::raw(commit.summary.clone()),
+                ]),
+                depth: 1,
+                target_data: Some(TargetData::PickerCommit(
+                    Rc::clone(&picker),
+                    Some(commit.commit.clone()),
+                )),
+                ..Default::default()
+            };
+            let subheader = |text: &'static str| Item {
+                id: text.into(),
+                display: Line::styled(text, &style.section_header),
+                depth: 0,
+                unselectable: true,
+                ..Default::default()
+            };
+
+            // Listed first, so the cursor starts at the likeliest target
+            let suggested = borrowed
+                .visible()
+                .filter(|commit| commit.suggested)
+                .map(|commit| commit_item("suggested", commit))
+                .collect::<Vec<_>>();
+
+            let mut commits = borrowed
+                .visible()
+                .map(|commit| commit_item("recent", commit))
+                .peekable();
+
+            // Keeps the picker's ops reachable, to change the filter
+            let none_found = commits.peek().is_none().then(|| Item {
+                id: "no_commits".into(),
+                display: Line::raw("No matching commits"),
+                depth: 1,
+                target_data: Some(TargetData::PickerCommit(Rc::clone(&picker), None)),
+                ..Default::default()
+            });
+
+            let sections = if suggested.is_empty() {
+                vec![]
+            } else {
+                iter::once(subheader("Suggested by blame"))
+                    .chain(suggested)
+                    .chain([items::blank_line(), subheader("Recent commits")])
+                    .collect()
+            };
+
+            Ok(iter::once(Item {
+                id: "commit_picker".into(),
+                display: Line::styled(header, &style.section_header),
+                depth: 0,
+                unselectable: true,
+                ..Default::default()
+            })
+            .chain([items::blank_line()])
+            .chain(sections)
+            .chain(commits)
+            .chain(none_found)
+            .collect())
+        }),
+    )
+}
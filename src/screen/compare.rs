@@ -0,0 +1,154 @@
+use std::{iter, rc::Rc};
+
+use super::{plural, Screen};
+use crate::{
+    config::Config,
+    git::{self, compare::CompareSide},
+    items::{self, Item},
+    Res,
+};
+use git2::Repository;
+use ratatui::{
+    prelude::Rect,
+    text::{Line, Span},
+};
+
+/// Commits listed for each side, at most
+const LIMIT: usize = 100;
+/// Widest the `+++--` bar of a file gets
+const BAR_WIDTH: usize = 30;
+
+pub(crate) fn create(
+    config: Rc<Config>,
+    repo: Rc<Repository>,
+    size: Rect,
+    ours: String,
+    theirs: String,
+) -> Res<Screen> {
+    Screen::new(
+        Rc::clone(&config),
+        size,
+        Box::new(move || {
+            let compare = git::compare(&repo, &ours, &theirs)?;
+            let base = repo.find_commit(compare.merge_base)?;
+
+            let mut items = vec![Item {
+                id: "compare".into(),
+                display: Line::from(vec![
+                    Span::styled(
+                        format!("Compare {} with {}, diverged at ", ours, theirs),
+                        &config.style.section_header,
+                    ),
+                    Span::styled(git::short_id(&base), &config.style.hash),
+                ]),
+                depth: 0,
+                unselectable: true,
+                ..Default::default()
+            }];
+
+            for (id, side, other) in [
+                ("compare_ours", &compare.ours, &compare.theirs),
+                ("compare_theirs", &compare.theirs, &compare.ours),
+            ] {
+                items.push(items::blank_line());
+                items.extend(side_items(&config, &repo, id, side, other.id)?);
+            }
+
+            Ok(items)
+        }),
+    )
+}
+
+/// The commits only `side` has, then what they changed.
+fn side_items(
+    config: &Config,
+    repo: &Repository,
+    id: &'static str,
+    side: &CompareSide,
+    other: git2::Oid,
+) -> Res<Vec<Item>> {
+    let style = &config.style;
+    let header = Item {
+        id: id.into(),
+        display: Line::styled(
+            format!("Only on {} ({})", side.name, side.count),
+            &style.section_header,
+        ),
+        section: true,
+        depth: 0,
+        ..Default::default()
+    };
+
+    let path_width = side
+        .stats
+        .iter()
+        .map(|stat| stat.path.chars().count())
+        .max()
+        .unwrap_or(0);
+    let most_lines = side
+        .stats
+        .iter()
+        .map(|stat| stat.insertions + stat.deletions)
+        .max()
+        .unwrap_or(0);
+
+    let stat_items = side.stats.iter().map(|stat| {
+        let lines = stat.insertions + stat.deletions;
+        // Scaled down like `git diff --stat` when a file has more lines than fit
+        let scale = |count: usize| {
+            if most_lines > BAR_WIDTH {
+                (count * BAR_WIDTH).div_ceil(most_lines)
+            } else {
+                count
+            }
+        };
+
+        Item {
+            id: format!("{}_{}", id, stat.path).into(),
+            display: Line::from(vec![
+                Span::raw(format!(
+                    "{:<path_width$} | {:>5} ",
+                    stat.path,
+                    lines,
+                    path_width = path_width
+                )),
+                Span::styled(
+                    "+".repeat(scale(stat.insertions)),
+                    &style.diff_highlight.tag_new,
+                ),
+                Span::styled(
+                    "-".repeat(scale(stat.deletions)),
+                    &style.diff_highlight.tag_old,
+                ),
+            ]),
+            depth: 1,
+            unselectable: true,
+            ..Default::default()
+        }
+    });
+
+    let summary = Item {
+        id: format!("{}_summary", id).into(),
+        display: Line::raw(format!(
+            "{} changed, {}(+), {}(-)",
+            plural(side.stats.len(), "file"),
+            plural(
+                side.stats.iter().map(|stat| stat.insertions).sum(),
+                "insertion"
+            ),
+            plural(
+                side.stats.iter().map(|stat| stat.deletions).sum(),
+                "deletion"
+            ),
+        )),
+        depth: 1,
+        unselectable: true,
+        ..Default::default()
+    };
+
+    Ok(iter::once(header)
+        .chain(items::range_log(config, repo, LIMIT, other, side.id)?)
+        .chain(stat_items)
+        .chain(iter::once(summary))
+        .collect())
+}
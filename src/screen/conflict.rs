@@ -0,0 +1,105 @@
+use std::{iter, path::PathBuf, rc::Rc};
+
+use super::Screen;
+use crate::{
+    config::Config,
+    git,
+    items::{Item, TargetData},
+    Res,
+};
+use git2::Repository;
+use ratatui::{
+    prelude::Rect,
+    style::Style,
+    text::{Line, Span},
+};
+
+/// The conflicts of an unmerged file, split up into the sides of each.
+pub(crate) fn create(
+    config: Rc<Config>,
+    repo: Rc<Repository>,
+    size: Rect,
+    path: PathBuf,
+) -> Res<Screen> {
+    Screen::new(
+        Rc::clone(&config),
+        size,
+        Box::new(move || {
+            let style = &config.style;
+            let conflicts = git::conflicts(repo.as_ref(), &path)?;
+            let file = path.to_string_lossy().to_string();
+
+            let header = Item {
+                id: format!("conflicts_{}", file).into(),
+                display: Line::from(vec![
+                    Span::styled("Conflicts in ", &style.section_header),
+                    Span::styled(file.clone(), &style.file_header),
+                    Span::styled(format!(" ({})", conflicts.len()), &style.section_header),
+                ]),
+                section: true,
+                depth: 0,
+                ..Default::default()
+            };
+
+            let conflict_items = conflicts.into_iter().enumerate().flat_map(|(i, conflict)| {
+                let id = format!("conflict_{}_{}", file, i);
+
+                iter::once(Item {
+                    id: id.clone().into(),
+                    display: Line::styled(
+                        format!("Conflict at line {}", conflict.start_line),
+                        &style.section_header,
+                    ),
+                    section: true,
+                    depth: 1,
+                    target_data: Some(TargetData::Conflict(path.clone(), i)),
+                    ..Default::default()
+                })
+                .chain(side_items(
+                    &config,
+                    &id,
+                    format!("ours {}", conflict.ours_label),
+                    conflict.ours,
+                    (&style.diff_highlight.changed_old).into(),
+                ))
+                .chain(conflict.base.into_iter().flat_map(|(label, lines)| {
+                    side_items(&config, &id, format!("base {}", label), lines, Style::new())
+                }))
+                .chain(side_items(
+                    &config,
+                    &id,
+                    format!("theirs {}", conflict.theirs_label),
+                    conflict.theirs,
+                    (&style.diff_highlight.changed_new).into(),
+                ))
+                .collect::<Vec<_>>()
+            });
+
+            Ok(iter::once(header).chain(conflict_items).collect())
+        }),
+    )
+}
+
+fn side_items(
+    config: &Config,
+    id: &str,
+    label: String,
+    lines: Vec<String>,
+    line_style: Style,
+) -> Vec<Item> {
+    iter::once(Item {
+        id: id.to_string().into(),
+        display: Line::styled(label, &config.style.hunk_header),
+        depth: 2,
+        unselectable: true,
+        ..Default::default()
+    })
+    .chain(lines.into_iter().map(|line| Item {
+        id: id.to_string().into(),
+        display: Line::styled(line, line_style),
+        depth: 2,
+        unselectable: true,
+        ..Default::default()
+    }))
+    .collect()
+}
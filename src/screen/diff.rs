@@ -0,0 +1,100 @@
+use std::{cell::Cell, iter, rc::Rc};
+
+use super::Screen;
+use crate::{
+    config::Config,
+    git::{self, diff::DiffOptions, RevDiff},
+    items::{self, Item},
+    Res,
+};
+use git2::Repository;
+use ratatui::{
+    prelude::Rect,
+    text::{Line, Span},
+};
+
+/// Changes since HEAD branched off `rev`, including uncommitted ones.
+pub(crate) fn create_merge_base(
+    config: Rc<Config>,
+    repo: Rc<Repository>,
+    diff_options: Rc<Cell<DiffOptions>>,
+    size: Rect,
+    rev: String,
+) -> Res<Screen> {
+    Screen::new(
+        Rc::clone(&config),
+        size,
+        Box::new(move || {
+            let options = diff_options.get();
+            let style = &config.style;
+            let (merge_base, diff) = git::diff_merge_base(&config, &options, repo.as_ref(), &rev)?;
+
+            Ok(iter::once(Item {
+                id: format!("merge_base_{}", rev).into(),
+                display: Line::from(vec![
+                    Span::styled(
+                        format!("Changes since branching from {} at ", rev),
+                        &style.section_header,
+                    ),
+                    Span::styled(merge_base, &style.hash),
+                ]),
+                section: true,
+                depth: 0,
+                ..Default::default()
+            })
+            .chain([items::blank_line()])
+            .chain(items::create_diff_items(
+                Rc::clone(&config),
+                &diff,
+                &0,
+                false,
+            ))
+            .collect())
+        }),
+    )
+}
+
+/// The changes between arbitrary revisions, the working tree or the index.
+pub(crate) fn create_rev_diff(
+    config: Rc<Config>,
+    repo: Rc<Repository>,
+    diff_options: Rc<Cell<DiffOptions>>,
+    size: Rect,
+    rev_diff: RevDiff,
+) -> Res<Screen> {
+    Screen::new(
+        Rc::clone(&config),
+        size,
+        Box::new(move || {
+            let options = diff_options.get();
+            let style = &config.style;
+            let diff = git::diff_revs(&config, &options, repo.as_ref(), &rev_diff)?;
+            let header = match &rev_diff {
+                RevDiff::Unstaged => "Unstaged changes".to_string(),
+                RevDiff::Staged => "Staged changes".to_string(),
+                RevDiff::Worktree(rev) => format!("Changes in the working tree since {}", rev),
+                RevDiff::Index(rev) => format!("Staged changes since {}", rev),
+                RevDiff::Range(old, new) => format!("Changes from {} to {}", old, new),
+                RevDiff::Files(old, new) | RevDiff::NoIndex(old, new) => {
+                    format!("Changes from {} to {}", old.display(), new.display())
+                }
+            };
+
+            Ok(iter::once(Item {
+                id: header.clone().into(),
+                display: Line::styled(header, &style.section_header),
+                section: true,
+                depth: 0,
+                ..Default::default()
+            })
+            .chain([items::blank_line()])
+            .chain(items::create_diff_items(
+                Rc::clone(&config),
+                &diff,
+                &0,
+                false,
+            ))
+            .collect())
+        }),
+    )
+}
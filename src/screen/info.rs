@@ -0,0 +1,157 @@
+use std::rc::Rc;
+
+use super::{plural, Screen};
+use crate::{
+    config::Config,
+    git,
+    items::{self, Item, TargetData},
+    Res,
+};
+use git2::Repository;
+use ratatui::{
+    prelude::Rect,
+    text::{Line, Span},
+};
+
+/// Width the labels in front of values are padded to
+const LABEL_WIDTH: usize = 12;
+
+pub(crate) fn create(config: Rc<Config>, repo: Rc<Repository>, size: Rect) -> Res<Screen> {
+    Screen::new(
+        Rc::clone(&config),
+        size,
+        Box::new(move || {
+            let style = &config.style;
+            let info = git::repo_info(&repo)?;
+            let path = |path: &std::path::Path| {
+                Span::styled(path.to_string_lossy().to_string(), &style.file_header)
+            };
+
+            let hooks_dir = info
+                .hooks_dir
+                .strip_prefix(&info.workdir)
+                .unwrap_or(&info.hooks_dir);
+            let hooks = if info.hooks.is_empty() {
+                "none active".to_string()
+            } else {
+                info.hooks.join(", ")
+            };
+
+            let mut items = vec![
+                header("info_paths", "Repository", &config),
+                row("workdir", "Worktree", vec![path(&info.workdir)], None),
+                row("git_dir", "Git dir", vec![path(&info.git_dir)], None),
+                row(
+                    "hooks",
+                    "Hooks",
+                    vec![path(hooks_dir), Span::raw(format!(" ({})", hooks))],
+                    None,
+                ),
+                items::blank_line(),
+                header("info_branch", "Branch", &config),
+                match info.branch {
+                    Some(branch) => row(
+                        "branch",
+                        "Checked out",
+                        vec![Span::styled(branch.clone(), &style.branch)],
+                        Some(TargetData::Branch(branch)),
+                    ),
+                    None => row(
+                        "branch",
+                        "Checked out",
+                        vec![Span::raw("detached HEAD")],
+                        None,
+                    ),
+                },
+                match info.upstream {
+                    Some(upstream) => row(
+                        "upstream",
+                        "Upstream",
+                        vec![Span::styled(upstream.clone(), &style.remote)],
+                        Some(TargetData::Branch(upstream)),
+                    ),
+                    None => row("upstream", "Upstream", vec![Span::raw("none")], None),
+                },
+                items::blank_line(),
+                header("info_remotes", "Remotes", &config),
+            ];
+
+            if info.remotes.is_empty() {
+                items.push(row("no_remotes", "none", vec![], None));
+            }
+
+            items.extend(info.remotes.into_iter().map(|remote| {
+                row(
+                    &format!("remote_{}", remote.name),
+                    &remote.name,
+                    vec![Span::raw(remote.url.clone())],
+                    Some(TargetData::Remote {
+                        name: remote.name.clone(),
+                        url: remote.url,
+                    }),
+                )
+            }));
+
+            let objects = info.objects;
+            items.extend([
+                items::blank_line(),
+                header("info_objects", "Objects", &config),
+                row(
+                    "loose",
+                    "Loose",
+                    vec![Span::raw(format!(
+                        "{} ({} KiB)",
+                        objects.loose, objects.loose_size
+                    ))],
+                    None,
+                ),
+                row(
+                    "packed",
+                    "Packed",
+                    vec![Span::raw(format!(
+                        "{} in {} ({} KiB)",
+                        objects.packed,
+                        plural(objects.packs, "pack"),
+                        objects.pack_size
+                    ))],
+                    None,
+                ),
+                items::blank_line(),
+                header("info_features", "Features", &config),
+                feature("lfs", "LFS", info.lfs),
+                feature("sparse", "Sparse", info.sparse),
+                feature("shallow", "Shallow", info.shallow),
+            ]);
+
+            Ok(items)
+        }),
+    )
+}
+
+fn header(id: &'static str, title: &'static str, config: &Config) -> Item {
+    Item {
+        id: id.into(),
+        display: Line::styled(title, &config.style.section_header),
+        section: true,
+        depth: 0,
+        ..Default::default()
+    }
+}
+
+fn row(id: &str, label: &str, value: Vec<Span<'static>>, target_data: Option<TargetData>) -> Item {
+    let mut spans = vec![Span::raw(format!("{:<LABEL_WIDTH$} ", label))];
+    spans.extend(value);
+
+    Item {
+        id: id.to_string().into(),
+        display: Line::from(spans),
+        depth: 1,
+        target_data,
+        ..Default::default()
+    }
+}
+
+fn feature(id: &str, label: &str, enabled: bool) -> Item {
+    let value = if enabled { "enabled" } else { "disabled" };
+    row(id, label, vec![Span::raw(value)], None)
+}
@@ -1,9 +1,16 @@
 use super::Screen;
-use crate::{config::Config, items::log, Res};
+use crate::{
+    config::Config,
+    items::{file_log, line_log, LogFilter, LogPages},
+    Res,
+};
 use git2::{Oid, Repository};
 use ratatui::prelude::Rect;
-use regex::Regex;
-use std::rc::Rc;
+use std::{
+    cell::{Cell, RefCell},
+    path::PathBuf,
+    rc::Rc,
+};
 
 pub(crate) fn create(
     config: Rc<Config>,
@@ -11,11 +18,69 @@ pub(crate) fn create(
     size: Rect,
     limit: usize,
     rev: Option<Oid>,
-    msg_regex: Option<Regex>,
+    filter: LogFilter,
+    graph: bool,
+) -> Res<Screen> {
+    // Walking all of a large history up front would take seconds
+    let page_size = config.general.log_page_size.max(1);
+    let loaded = Rc::new(Cell::new(page_size.min(limit)));
+    let more_left = Rc::new(Cell::new(false));
+    let pages = RefCell::new(LogPages::new(rev, filter, graph));
+    // Set when a page is loaded, any other update walks from the start again
+    let continued = Rc::new(Cell::new(false));
+
+    let screen = Screen::new(Rc::clone(&config), size, {
+        let loaded = Rc::clone(&loaded);
+        let more_left = Rc::clone(&more_left);
+        let continued = Rc::clone(&continued);
+        Box::new(move || {
+            let mut pages = pages.borrow_mut();
+            if !continued.replace(false) {
+                pages.restart();
+            }
+
+            let items = pages.items(&config, &repo, loaded.get())?;
+            more_left.set(items.len() >= loaded.get() && loaded.get() < limit);
+            Ok(items)
+        })
+    })?;
+
+    Ok(screen.with_load_more(Box::new(move || {
+        if more_left.get() {
+            loaded.set((loaded.get() + page_size).min(limit));
+            continued.set(true);
+        }
+        more_left.get()
+    })))
+}
+
+pub(crate) fn create_file(
+    config: Rc<Config>,
+    repo: Rc<Repository>,
+    size: Rect,
+    limit: usize,
+    path: PathBuf,
+    filter: LogFilter,
+) -> Res<Screen> {
+    Screen::new(
+        Rc::clone(&config),
+        size,
+        Box::new(move || file_log(&config, &repo, limit, &path, filter.clone())),
+    )
+}
+
+pub(crate) fn create_lines(
+    config: Rc<Config>,
+    repo: Rc<Repository>,
+    size: Rect,
+    limit: usize,
+    path: PathBuf,
+    range: String,
+    filter: LogFilter,
 ) -> Res<Screen> {
     Screen::new(
         Rc::clone(&config),
         size,
-        Box::new(move || log(&config, &repo, limit, rev, msg_regex.clone())),
+        Box::new(move || line_log(&config, &repo, limit, &path, &range, filter.clone())),
     )
 }
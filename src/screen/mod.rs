@@ -1,14 +1,34 @@
 use ratatui::prelude::*;
 
-use crate::{config::Config, items::TargetData, Res};
+use crate::{
+    config::Config,
+    items::{self, TargetData},
+    Res,
+};
 
 use super::Item;
-use std::{borrow::Cow, collections::HashSet, rc::Rc};
-
+use std::{borrow::Cow, collections::HashSet, iter, mem, rc::Rc};
+
+pub(crate) mod absorb;
+pub(crate) mod bisect;
+pub(crate) mod clean;
+pub(crate) mod commit_picker;
+pub(crate) mod compare;
+pub(crate) mod conflict;
+pub(crate) mod diff;
+pub(crate) mod info;
 pub(crate) mod log;
+pub(crate) mod outgoing;
+pub(crate) mod pull_requests;
+pub(crate) mod range_diff;
+pub(crate) mod rebase_todo;
+pub(crate) mod remote_refs;
 pub(crate) mod show;
 pub(crate) mod show_refs;
+pub(crate) mod stash;
 pub(crate) mod status;
+pub(crate) mod wip;
+pub(crate) mod worktrees;
 
 const BOTTOM_CONTEXT_LINES: usize = 2;
 
@@ -23,11 +43,22 @@ pub(crate) struct Screen {
     pub(crate) size: Rect,
     cursor: usize,
     scroll: usize,
+    /// Columns scrolled to the right, to see the end of long lines
+    scroll_x: u16,
     config: Rc<Config>,
     refresh_items: Box<dyn Fn() -> Res<Vec<Item>>>,
     items: Vec<Item>,
     line_index: Vec<usize>,
     collapsed: HashSet<Cow<'static, str>>,
+    side_by_side: bool,
+    /// Lowercase text to highlight and jump between
+    search: Option<String>,
+    /// The item last found, and the one selected for it, which is another if it can't be
+    search_match: Option<(usize, usize)>,
+    /// Has `refresh_items` return more items next time, if there are any left
+    load_more: Option<Rc<dyn Fn() -> bool>>,
+    /// Drops what `refresh_items` cached, so an explicit refresh gets it anew
+    reload: Option<Rc<dyn Fn()>>,
 }
 
 impl Screen {
@@ -36,19 +67,30 @@ impl Screen {
         size: Rect,
         refresh_items: Box<dyn Fn() -> Res<Vec<Item>>>,
     ) -> Res<Self> {
+        let side_by_side = config.general.side_by_side_diff.enabled;
         let mut screen = Self {
             cursor: 0,
             scroll: 0,
+            scroll_x: 0,
             size,
             config,
             refresh_items,
             items: vec![],
             line_index: vec![],
             collapsed: HashSet::new(),
+            side_by_side,
+            search: None,
+            search_match: None,
+            load_more: None,
+            reload: None,
         };
 
         screen.update()?;
 
+        if let Some(level) = screen.config.general.default_expansion_level {
+            screen.collapsed = screen.sections_below(level);
+        }
+
         // TODO Maybe this should be done on update. Better keep track of toggled sections rather than collapsed then.
         screen
             .items
@@ -67,6 +109,39 @@ impl Screen {
         Ok(screen)
     }
 
+    /// For screens that load their items a page at a time.
+    pub(crate) fn with_load_more(mut self, load_more: Box<dyn Fn() -> bool>) -> Self {
+        self.load_more = Some(load_more.into());
+        self
+    }
+
+    /// For screens that cache what's slow to get, like API responses, between updates.
+    pub(crate) fn with_reload(mut self, reload: Box<dyn Fn()>) -> Self {
+        self.reload = Some(reload.into());
+        self
+    }
+
+    /// Like `update`, but without anything cached.
+    pub(crate) fn reload(&mut self) -> Res<()> {
+        if let Some(reload) = &self.reload {
+            reload();
+        }
+        self.update()
+    }
+
+    /// Loads more items once the cursor is within a screen of the last ones.
+    pub(crate) fn load_more_near_end(&mut self) -> Res<()> {
+        let Some(load_more) = self.load_more.clone() else {
+            return Ok(());
+        };
+
+        while self.cursor + self.size.height as usize >= self.line_index.len() && load_more() {
+            self.update()?;
+        }
+
+        Ok(())
+    }
+
     fn find_first_hunk(&mut self) -> Option<usize> {
         (0..self.line_index.len()).find(|&line_i| {
             !self.at_line(line_i).unselectable
@@ -178,6 +253,28 @@ impl Screen {
         self.update_cursor(nav_mode);
     }
 
+    pub(crate) fn scroll_left(&mut self) {
+        let half_screen = self.size.width / 2;
+        self.scroll_x = self.scroll_x.saturating_sub(half_screen);
+    }
+
+    /// Scrolls by half a screen, but no further than to the end of the longest line shown.
+    pub(crate) fn scroll_right(&mut self) {
+        let half_screen = self.size.width / 2;
+        let widest = self
+            .line_index
+            .iter()
+            .map(|&item_index| &self.items[item_index])
+            .flat_map(|item| iter::once(&item.display).chain(&item.display_new))
+            .map(Line::width)
+            .max()
+            .unwrap_or(0);
+        let max_scroll = (widest + 1).saturating_sub(self.size.width as usize);
+
+        self.scroll_x =
+            (self.scroll_x + half_screen).min(max_scroll.try_into().unwrap_or(u16::MAX));
+    }
+
     pub(crate) fn toggle_section(&mut self) {
         let selected = &self.items[self.line_index[self.cursor]];
 
@@ -192,14 +289,242 @@ impl Screen {
         self.update_line_index();
     }
 
+    /// Collapses the hunks of the selected file, or expands them if they all are collapsed.
+    pub(crate) fn toggle_file_hunks(&mut self) {
+        let selected = self.line_index[self.cursor];
+        let Some(file) = self.find_file(selected) else {
+            return;
+        };
+
+        let file_depth = self.items[file].depth;
+        let hunk_ids = self.items[file + 1..]
+            .iter()
+            .take_while(|item| item.depth > file_depth)
+            .filter(|item| matches!(item.target_data, Some(TargetData::Hunk(_))))
+            .map(|item| item.id.clone())
+            .collect::<Vec<_>>();
+
+        if hunk_ids.iter().all(|id| self.collapsed.contains(id)) {
+            hunk_ids.iter().for_each(|id| {
+                self.collapsed.remove(id);
+            });
+        } else {
+            self.collapsed.extend(hunk_ids);
+        }
+        // For the hunks to be seen at all
+        self.collapsed.remove(&self.items[file].id);
+
+        self.update_line_index();
+        self.select_closest_visible(selected);
+    }
+
+    /// Expands the sections up to `level`, 1 being the top level, and collapses the rest.
+    pub(crate) fn show_level(&mut self, level: usize) {
+        let selected = self.line_index[self.cursor];
+        self.collapsed = self.sections_below(level);
+        self.update_line_index();
+        self.select_closest_visible(selected);
+    }
+
+    fn sections_below(&self, level: usize) -> HashSet<Cow<'static, str>> {
+        self.items
+            .iter()
+            .filter(|item| item.section && item.depth + 1 >= level)
+            .map(|item| item.id.clone())
+            .collect()
+    }
+
+    /// Selects the item, or the section it's hidden in.
+    fn select_closest_visible(&mut self, item_index: usize) {
+        self.cursor = self
+            .line_index
+            .iter()
+            .rposition(|&i| i <= item_index)
+            .unwrap_or(0);
+        self.scroll_fit_end();
+        self.scroll_fit_start();
+    }
+
+    pub(crate) fn selected_item_index(&self) -> usize {
+        self.line_index.get(self.cursor).copied().unwrap_or(0)
+    }
+
+    /// Highlights `query`, ignoring case, and selects the first item from `start` on that
+    /// contains it. An empty query clears the search and selects `start` again.
+    pub(crate) fn search(&mut self, query: &str, start: usize) -> bool {
+        self.search = Some(query.to_lowercase()).filter(|query| !query.is_empty());
+        self.search_match = None;
+        if self.search.is_none() {
+            self.select_closest_visible(start);
+            return false;
+        }
+
+        let len = self.items.len();
+        match (0..len)
+            .map(|i| (start + i) % len)
+            .find(|&i| self.is_match(i))
+        {
+            Some(found) => {
+                self.reveal(found);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Selects the next (or previous) item matching the search, wrapping around at the end.
+    pub(crate) fn search_next(&mut self, forward: bool) -> Res<()> {
+        let Some(query) = &self.search else {
+            return Err("No search to repeat".into());
+        };
+
+        let len = self.items.len();
+        let selected = self.selected_item_index();
+        // Goes on from the last match, unless something else has been selected since
+        let from = match self.search_match {
+            Some((found, selected_for_it)) if selected_for_it == selected => found,
+            _ => selected,
+        };
+        let found = (1..=len)
+            .map(|i| {
+                if forward {
+                    (from + i) % len
+                } else {
+                    (from + len - i) % len
+                }
+            })
+            .find(|&i| self.is_match(i));
+
+        match found {
+            Some(found) => {
+                self.reveal(found);
+                Ok(())
+            }
+            None => Err(format!("No matches for '{}'", query).into()),
+        }
+    }
+
+    fn is_match(&self, item_index: usize) -> bool {
+        let item = &self.items[item_index];
+        let Some(query) = &self.search else {
+            return false;
+        };
+
+        iter::once(&item.display)
+            .chain(&item.display_new)
+            .any(|line| line_text(line).to_lowercase().contains(query))
+    }
+
+    /// Expands the sections that the item is in, and selects it. Or the closest item that
+    /// can be, for lines like those of a commit message.
+    fn reveal(&mut self, item_index: usize) {
+        let mut depth = self.items[item_index].depth;
+        for i in (0..item_index).rev() {
+            if self.items[i].depth < depth {
+                depth = self.items[i].depth;
+                self.collapsed.remove(&self.items[i].id);
+            }
+        }
+
+        self.update_line_index();
+        self.select_closest_visible(item_index);
+        self.move_from_unselectable(NavMode::IncludeHunkLines);
+        self.search_match = Some((item_index, self.selected_item_index()));
+    }
+
+    /// The file (delta) that the item is in, or is itself.
+    fn find_file(&self, mut item_index: usize) -> Option<usize> {
+        loop {
+            if let Some(TargetData::Delta(_)) = self.items[item_index].target_data {
+                return Some(item_index);
+            }
+
+            let depth = self.items[item_index].depth;
+            item_index = (0..item_index)
+                .rev()
+                .find(|&i| self.items[i].depth < depth)?;
+        }
+    }
+
     pub(crate) fn update(&mut self) -> Res<()> {
         let nav_mode = self.selected_item_nav_mode();
-        self.items = (self.refresh_items)()?;
+        let items = (self.refresh_items)()?;
+        let items = if self.side_by_side {
+            items::side_by_side(items)
+        } else {
+            items
+        };
+        let width = self.size.width.saturating_sub(1) as usize;
+        self.items = wrap_items(items, width);
         self.update_line_index();
         self.update_cursor(nav_mode);
         Ok(())
     }
 
+    pub(crate) fn toggle_side_by_side(&mut self) -> Res<()> {
+        self.side_by_side = !self.side_by_side;
+        self.update()
+    }
+
+    /// Lines are wrapped again if the width changed.
+    pub(crate) fn resize(&mut self, size: Rect) -> Res<()> {
+        let rewrap = size.width != self.size.width && self.items.iter().any(|item| item.wrap);
+        self.size = size;
+
+        if rewrap {
+            self.update()?;
+        }
+
+        Ok(())
+    }
+
+    /// Like `update`, but keeps the cursor on the same file, hunk or diff line
+    /// when the hunks have been split up or merged.
+    pub(crate) fn update_keeping_diff_position(&mut self) -> Res<()> {
+        let nav_mode = self.selected_item_nav_mode();
+        let cursor = self.cursor;
+        let selected = (!self.items.is_empty())
+            .then(|| self.get_selected_item().target_data.clone())
+            .flatten();
+
+        self.update()?;
+
+        let is_same_position = |target_data: &TargetData| match (&selected, target_data) {
+            (Some(TargetData::Delta(old)), TargetData::Delta(new)) => {
+                old.file_header == new.file_header
+            }
+            (Some(TargetData::Hunk(old)), TargetData::Hunk(new)) => {
+                old.file_header == new.file_header
+                    && new.new_line_range().contains(&old.first_diff_line())
+            }
+            (Some(TargetData::HunkLine(old, i)), TargetData::HunkLine(new, j)) => {
+                old.file_header == new.file_header
+                    && old.new_line_number(*i) == new.new_line_number(*j)
+                    && old.content.lines[*i].spans.first() == new.content.lines[*j].spans.first()
+            }
+            _ => false,
+        };
+
+        // The same file can show up more than once, like when both staged and unstaged
+        let closest = (0..self.line_index.len())
+            .filter(|&line_i| {
+                self.at_line(line_i)
+                    .target_data
+                    .as_ref()
+                    .is_some_and(is_same_position)
+            })
+            .min_by_key(|&line_i| line_i.abs_diff(cursor));
+
+        if let Some(line_i) = closest {
+            self.cursor = line_i;
+            self.move_from_unselectable(nav_mode);
+            self.scroll_fit_end();
+            self.scroll_fit_start();
+        }
+
+        Ok(())
+    }
+
     fn update_cursor(&mut self, nav_mode: NavMode) {
         self.clamp_cursor();
         if self.is_cursor_off_screen() {
@@ -336,13 +661,33 @@ impl Widget for &Screen {
                 }
             }
 
-            line.display.render(indented_line_area, buf);
-            let overflow = line.display.width() > line_area.width as usize;
+            if let Some(display_new) = &line.item.display_new {
+                let columns_area = Rect {
+                    width: line_area.width.saturating_sub(1),
+                    ..indented_line_area
+                };
+                render_columns(line.display, display_new, self.scroll_x, columns_area, buf);
+            } else {
+                render_scrolled(line.display, self.scroll_x, indented_line_area, buf);
+                if let Some(query) = &self.search {
+                    let match_style: Style = (&style.search_match).into();
+                    highlight_matches(
+                        line.display,
+                        query,
+                        self.scroll_x,
+                        indented_line_area,
+                        buf,
+                        match_style,
+                    );
+                }
+                let width = line.display.width().saturating_sub(self.scroll_x as usize);
+                let overflow = width > line_area.width as usize;
 
-            if self.is_collapsed(line.item) && line.display.width() > 0 || overflow {
-                let line_end =
-                    (indented_line_area.x + line.display.width() as u16).min(area.width - 1);
-                buf.get_mut(line_end, line_index as u16).set_char('…');
+                if self.is_collapsed(line.item) && width > 0 || overflow {
+                    let line_end =
+                        (indented_line_area.x as usize + width).min(area.width as usize - 1) as u16;
+                    buf.get_mut(line_end, line_index as u16).set_char('…');
+                }
             }
 
             if self.line_index[self.cursor] == line.item_index {
@@ -353,3 +698,185 @@ impl Widget for &Screen {
         }
     }
 }
+
+/// Renders the line from column `scroll_x` on.
+fn render_scrolled(line: &Line, scroll_x: u16, area: Rect, buf: &mut Buffer) {
+    if scroll_x == 0 {
+        line.render(area, buf);
+        return;
+    }
+
+    let mut skipped = 0;
+    let spans = line
+        .styled_graphemes(Style::default())
+        .skip_while(|grapheme| {
+            let skip = skipped < scroll_x as usize;
+            skipped += Span::raw(grapheme.symbol).width();
+            skip
+        })
+        .map(|grapheme| Span::styled(grapheme.symbol.to_string(), grapheme.style))
+        .collect::<Vec<_>>();
+
+    Line::from(spans).render(area, buf);
+}
+
+fn line_text(line: &Line) -> String {
+    line.spans
+        .iter()
+        .map(|span| span.content.as_ref())
+        .collect()
+}
+
+/// Styles where `query` shows up in the line, as rendered from column `scroll_x` on.
+fn highlight_matches(
+    line: &Line,
+    query: &str,
+    scroll_x: u16,
+    area: Rect,
+    buf: &mut Buffer,
+    style: Style,
+) {
+    // The lowercase text, and where each grapheme of it starts and is rendered
+    let mut text = String::new();
+    let mut graphemes = vec![];
+    let mut column = 0;
+    for grapheme in line.styled_graphemes(Style::default()) {
+        let width = Span::raw(grapheme.symbol).width();
+        graphemes.push((text.len(), column..column + width));
+        text.push_str(&grapheme.symbol.to_lowercase());
+        column += width;
+    }
+
+    for (start, matched) in text.match_indices(query) {
+        let matched_bytes = start..start + matched.len();
+        let columns = graphemes
+            .iter()
+            .filter(|(offset, _)| matched_bytes.contains(offset))
+            .flat_map(|(_, columns)| columns.clone());
+
+        for column in columns {
+            let Some(x) = column.checked_sub(scroll_x as usize) else {
+                continue;
+            };
+            let x = area.x as usize + x;
+            if x < buf.area.right() as usize {
+                buf.get_mut(x as u16, area.y).set_style(style);
+            }
+        }
+    }
+}
+
+fn wrap_items(items: Vec<Item>, width: usize) -> Vec<Item> {
+    items
+        .into_iter()
+        .flat_map(|item| {
+            if !item.wrap || item.display.width() <= width {
+                return vec![item];
+            }
+
+            wrap_line(&item.display, width)
+                .into_iter()
+                .map(|display| Item {
+                    display,
+                    ..item.clone()
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Breaks a line between words to fit `width`, lining up the rest of it under the text
+/// after its indentation and list marker, like "- " or "1. ".
+/// The lines take the style of the first span.
+fn wrap_line(line: &Line, width: usize) -> Vec<Line<'static>> {
+    let style = line
+        .spans
+        .first()
+        .map(|span| span.style)
+        .unwrap_or_default();
+    let text = line
+        .spans
+        .iter()
+        .map(|span| span.content.as_ref())
+        .collect::<String>();
+    let (prefix, rest) = text.split_at(wrap_prefix_len(&text));
+    let hanging_indent = " ".repeat(Span::raw(prefix).width());
+
+    let mut lines = vec![];
+    let mut current = prefix.to_string();
+    let mut has_words = false;
+
+    for word in rest.split_whitespace() {
+        if has_words && Span::raw(current.as_str()).width() + 1 + Span::raw(word).width() > width {
+            lines.push(Line::styled(mem::take(&mut current), style));
+            current = format!("{}{}", hanging_indent, word);
+        } else {
+            if has_words {
+                current.push(' ');
+            }
+            current.push_str(word);
+        }
+        has_words = true;
+    }
+
+    lines.push(Line::styled(current, style));
+    lines
+}
+
+/// The length of the indentation and list marker at the start of a line.
+fn wrap_prefix_len(text: &str) -> usize {
+    let indent = text.len() - text.trim_start().len();
+    let rest = &text[indent..];
+    let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+
+    let marker = if rest.starts_with(['-', '*', '+']) {
+        1
+    } else if digits > 0 && rest[digits..].starts_with(['.', ')']) {
+        digits + 1
+    } else {
+        0
+    };
+
+    if marker > 0 && rest[marker..].starts_with(' ') {
+        indent + marker + 1
+    } else {
+        indent
+    }
+}
+
+/// Like "1 commit" or "2 commits".
+pub(crate) fn plural(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("{} {}", count, noun)
+    } else {
+        format!("{} {}s", count, noun)
+    }
+}
+
+/// Splits the area into two columns, marking lines that don't fit.
+fn render_columns(old: &Line, new: &Line, scroll_x: u16, area: Rect, buf: &mut Buffer) {
+    let old_area = Rect {
+        width: area.width.saturating_sub(1) / 2,
+        ..area
+    };
+    let separator_x = old_area.x + old_area.width;
+    let new_area = Rect {
+        x: separator_x + 1,
+        width: area.width.saturating_sub(old_area.width + 1),
+        ..area
+    };
+
+    for (line, column) in [(old, old_area), (new, new_area)] {
+        render_scrolled(line, scroll_x, column, buf);
+        if column.width > 0
+            && line.width().saturating_sub(scroll_x as usize) > column.width as usize
+        {
+            buf.get_mut(column.x + column.width - 1, column.y)
+                .set_char('…');
+        }
+    }
+
+    if new_area.width > 0 {
+        buf.get_mut(separator_x, area.y).set_char('│');
+    }
+}
@@ -0,0 +1,54 @@
+use std::{iter, rc::Rc};
+
+use super::Screen;
+use crate::{
+    config::Config,
+    git::outgoing::Outgoing,
+    items::{self, Item, TargetData},
+    Res,
+};
+use ratatui::{
+    prelude::Rect,
+    text::{Line, Span},
+};
+
+pub(crate) fn create(config: Rc<Config>, outgoing: Rc<Outgoing>, size: Rect) -> Res<Screen> {
+    Screen::new(
+        Rc::clone(&config),
+        size,
+        Box::new(move || {
+            let style = &config.style;
+            let header = match &outgoing.upstream {
+                Some(upstream) => format!("Commits to push to {}", upstream),
+                None => "Commits not on any remote".to_string(),
+            };
+
+            Ok(iter::once(Item {
+                id: "outgoing_commits".into(),
+                display: Line::styled(header, &style.section_header),
+                section: true,
+                depth: 0,
+                ..Default::default()
+            })
+            .chain(outgoing.commits.iter().map(|commit| Item {
+                id: commit.commit.clone().into(),
+                display: Line::from(vec![
+                    Span::styled(commit.hash.clone(), &style.hash),
+                    Span::raw(" "),
+                    Span::raw(commit.summary.clone()),
+                ]),
+                depth: 1,
+                target_data: Some(TargetData::Commit(commit.commit.clone())),
+                ..Default::default()
+            }))
+            .chain([items::blank_line()])
+            .chain(items::create_diff_items(
+                Rc::clone(&config),
+                &outgoing.diff,
+                &0,
+                false,
+            ))
+            .collect())
+        }),
+    )
+}
@@ -0,0 +1,117 @@
+use std::{cell::RefCell, iter, rc::Rc};
+
+use super::Screen;
+use crate::{
+    config::Config,
+    git::{
+        self,
+        forge::{CiState, ForgeKind, PullRequest},
+    },
+    items::{self, Item, TargetData},
+    Res,
+};
+use git2::Repository;
+use ratatui::{
+    prelude::Rect,
+    text::{Line, Span},
+};
+
+/// The open pull requests of the forge `origin` is hosted on, fetched through its API.
+/// They're fetched once, and again only on an explicit refresh, not after every command.
+pub(crate) fn create(config: Rc<Config>, repo: Rc<Repository>, size: Rect) -> Res<Screen> {
+    let cache = Rc::new(RefCell::new(None));
+    let reload_cache = Rc::clone(&cache);
+
+    Ok(Screen::new(
+        Rc::clone(&config),
+        size,
+        Box::new(move || {
+            let style = &config.style;
+            let forge = git::origin_forge(repo.as_ref())?;
+            let pull_requests = match cache.borrow().clone() {
+                Some(pull_requests) => pull_requests,
+                None => fetch_pull_requests(&config, repo.as_ref())?,
+            };
+            cache.replace(Some(pull_requests.clone()));
+
+            Ok(iter::once(Item {
+                id: "pull_requests".into(),
+                display: Line::from(vec![
+                    Span::styled("Pull requests at ", &style.section_header),
+                    Span::styled(forge.base_url.clone(), &style.remote),
+                ]),
+                depth: 0,
+                unselectable: true,
+                ..Default::default()
+            })
+            .chain([
+                items::blank_line(),
+                Item {
+                    id: "pull_requests_open".into(),
+                    display: Line::styled(
+                        format!("Open ({})", pull_requests.len()),
+                        &style.section_header,
+                    ),
+                    section: true,
+                    depth: 0,
+                    ..Default::default()
+                },
+            ])
+            .chain(pull_requests.into_iter().map(|pull_request| {
+                let (ci, ci_style) = match pull_request.ci {
+                    CiState::None => (" ", &style.hash),
+                    CiState::Pending => ("●", &style.section_header),
+                    CiState::Success => ("✓", &style.diff_highlight.tag_new),
+                    CiState::Failure => ("✗", &style.diff_highlight.tag_old),
+                };
+
+                Item {
+                    id: format!("pull_request_{}", pull_request.number).into(),
+                    display: Line::from(vec![
+                        Span::styled(format!("#{}", pull_request.number), &style.hash),
+                        Span::raw(" "),
+                        Span::styled(ci, ci_style),
+                        Span::raw(" "),
+                        Span::styled(pull_request.branch.clone(), &style.branch),
+                        Span::raw(" "),
+                        Span::raw(pull_request.title.clone()),
+                        Span::styled(format!(" @{}", pull_request.author), &style.remote),
+                    ]),
+                    depth: 1,
+                    target_data: Some(TargetData::PullRequest(pull_request)),
+                    ..Default::default()
+                }
+            }))
+            .collect())
+        }),
+    )?
+    .with_reload(Box::new(move || {
+        reload_cache.replace(None);
+    })))
+}
+
+fn fetch_pull_requests(config: &Config, repo: &Repository) -> Res<Vec<PullRequest>> {
+    let forge = git::origin_forge(repo)?;
+    let api_url = match &config.forge.api_url {
+        Some(api_url) => api_url.clone(),
+        None => forge.api_url()?,
+    };
+    let token = match forge.kind {
+        ForgeKind::GitLab => config
+            .forge
+            .gitlab_token
+            .clone()
+            .or_else(|| env("GITLAB_TOKEN")),
+        _ => config
+            .forge
+            .github_token
+            .clone()
+            .or_else(|| env("GITHUB_TOKEN")),
+    };
+
+    forge.pull_requests(&api_url, token.as_deref())
+}
+
+fn env(var: &str) -> Option<String> {
+    std::env::var(var).ok().filter(|value| !value.is_empty())
+}
@@ -0,0 +1,90 @@
+use std::{iter, rc::Rc};
+
+use super::Screen;
+use crate::{
+    config::Config,
+    git,
+    items::{Item, TargetData},
+    Res,
+};
+use git2::Repository;
+use ratatui::{
+    prelude::Rect,
+    style::Style,
+    text::{Line, Span},
+};
+
+pub(crate) fn create(
+    config: Rc<Config>,
+    repo: Rc<Repository>,
+    size: Rect,
+    old: String,
+    new: String,
+) -> Res<Screen> {
+    Screen::new(
+        Rc::clone(&config),
+        size,
+        Box::new(move || {
+            let style = &config.style;
+            let pairs = git::range_diff(repo.as_ref(), &old, &new)?;
+            let missing = || "-------".to_string();
+
+            let pair_items = pairs.into_iter().enumerate().flat_map(|(i, pair)| {
+                let status_style: Style = match pair.status {
+                    '<' => (&style.diff_highlight.tag_old).into(),
+                    '>' => (&style.diff_highlight.tag_new).into(),
+                    '!' => (&style.section_header).into(),
+                    _ => Style::new(),
+                };
+                let commit = pair.new_hash.clone().or(pair.old_hash.clone());
+
+                iter::once(Item {
+                    id: format!("range_diff_{}", i).into(),
+                    display: Line::from(vec![
+                        Span::styled(pair.old_hash.unwrap_or_else(missing), &style.hash),
+                        Span::raw(" "),
+                        Span::styled(pair.status.to_string(), status_style),
+                        Span::raw(" "),
+                        Span::styled(pair.new_hash.unwrap_or_else(missing), &style.hash),
+                        Span::raw(" "),
+                        Span::raw(pair.summary),
+                    ]),
+                    section: !pair.diff.is_empty(),
+                    depth: 1,
+                    target_data: commit.map(TargetData::Commit),
+                    ..Default::default()
+                })
+                .chain(pair.diff.into_iter().map(move |line| {
+                    // Lines of the patches are prefixed with whether they were removed or added
+                    let line_style: Style = match line.chars().next() {
+                        Some('-') => (&style.diff_highlight.changed_old).into(),
+                        Some('+') => (&style.diff_highlight.changed_new).into(),
+                        Some('@') => (&style.hunk_header).into(),
+                        _ => Style::new(),
+                    };
+
+                    Item {
+                        id: format!("range_diff_{}", i).into(),
+                        display: Line::styled(line, line_style),
+                        depth: 2,
+                        unselectable: true,
+                        ..Default::default()
+                    }
+                }))
+            });
+
+            Ok(iter::once(Item {
+                id: "range_diff".into(),
+                display: Line::styled(
+                    format!("Range-diff {}...{}", old, new),
+                    &style.section_header,
+                ),
+                depth: 0,
+                unselectable: true,
+                ..Default::default()
+            })
+            .chain(pair_items)
+            .collect())
+        }),
+    )
+}
@@ -0,0 +1,58 @@
+use std::{cell::RefCell, iter, rc::Rc};
+
+use super::Screen;
+use crate::{
+    config::Config,
+    git::rebase_todo::{RebaseAction, RebaseTodo},
+    items::{Item, TargetData},
+    Res,
+};
+use ratatui::{
+    prelude::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+};
+
+pub(crate) fn create(config: Rc<Config>, todo: Rc<RefCell<RebaseTodo>>, size: Rect) -> Res<Screen> {
+    Screen::new(
+        Rc::clone(&config),
+        size,
+        Box::new(move || {
+            let style = &config.style;
+            let lines = todo.borrow().lines.clone();
+
+            Ok(iter::once(Item {
+                id: "rebase_todo".into(),
+                display: Line::styled(
+                    format!("Rebase onto {}", todo.borrow().base),
+                    &style.section_header,
+                ),
+                depth: 0,
+                unselectable: true,
+                ..Default::default()
+            })
+            .chain(lines.into_iter().enumerate().map(|(i, line)| {
+                let line_style = if line.action == RebaseAction::Drop {
+                    Style::new().add_modifier(Modifier::CROSSED_OUT)
+                } else {
+                    Style::new()
+                };
+
+                Item {
+                    id: line.hash.clone().into(),
+                    display: Line::from(vec![
+                        Span::styled(format!("{:<6}", line.action.to_string()), &style.hotkey),
+                        Span::raw(" "),
+                        Span::styled(line.hash, &style.hash),
+                        Span::raw(" "),
+                        Span::styled(line.summary, line_style),
+                    ]),
+                    depth: 1,
+                    target_data: Some(TargetData::RebaseTodoLine(Rc::clone(&todo), i)),
+                    ..Default::default()
+                }
+            }))
+            .collect())
+        }),
+    )
+}
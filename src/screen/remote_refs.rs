@@ -0,0 +1,112 @@
+use std::{iter, rc::Rc};
+
+use super::Screen;
+use crate::{
+    config::{Config, StyleConfigEntry},
+    git::{self, remote_refs::RemoteRef},
+    items::{self, Item, TargetData},
+    Res,
+};
+use git2::Repository;
+use ratatui::{
+    prelude::Rect,
+    text::{Line, Span},
+};
+
+/// The refs a remote has, as reported by `git ls-remote`, grouped into branches, tags and others.
+pub(crate) fn create(
+    config: Rc<Config>,
+    repo: Rc<Repository>,
+    size: Rect,
+    remote: String,
+) -> Res<Screen> {
+    Screen::new(
+        Rc::clone(&config),
+        size,
+        Box::new(move || {
+            let style = &config.style;
+            let refs = git::remote_refs(repo.as_ref(), &remote)?;
+
+            let (branches, rest): (Vec<_>, Vec<_>) = refs
+                .into_iter()
+                .partition(|remote_ref| remote_ref.name.starts_with("refs/heads/"));
+            let (tags, others): (Vec<_>, Vec<_>) = rest
+                .into_iter()
+                .partition(|remote_ref| remote_ref.name.starts_with("refs/tags/"));
+
+            Ok(iter::once(Item {
+                id: "remote_refs".into(),
+                display: Line::from(vec![
+                    Span::styled("Refs at ", &style.section_header),
+                    Span::styled(remote.clone(), &style.remote),
+                ]),
+                depth: 0,
+                unselectable: true,
+                ..Default::default()
+            })
+            .chain(section(
+                &config,
+                "Branches",
+                "refs/heads/",
+                &style.branch,
+                branches,
+            ))
+            .chain(section(&config, "Tags", "refs/tags/", &style.tag, tags))
+            .chain(section(&config, "Other", "", &style.remote, others))
+            .collect())
+        }),
+    )
+}
+
+fn section(
+    config: &Config,
+    header: &str,
+    prefix: &str,
+    ref_style: &StyleConfigEntry,
+    refs: Vec<RemoteRef>,
+) -> Vec<Item> {
+    if refs.is_empty() {
+        return vec![];
+    }
+
+    let style = &config.style;
+    [
+        items::blank_line(),
+        Item {
+            id: format!("remote_refs_{}", header).into(),
+            display: Line::styled(
+                format!("{} ({})", header, refs.len()),
+                &style.section_header,
+            ),
+            section: true,
+            depth: 0,
+            ..Default::default()
+        },
+    ]
+    .into_iter()
+    .chain(refs.into_iter().map(|remote_ref| {
+        let name = remote_ref
+            .name
+            .strip_prefix(prefix)
+            .unwrap_or(&remote_ref.name)
+            .to_string();
+
+        Item {
+            id: remote_ref.name.clone().into(),
+            display: Line::from(vec![
+                Span::styled(remote_ref.hash[..7].to_string(), &style.hash),
+                Span::raw(" "),
+                Span::styled(name, ref_style),
+                Span::raw(if remote_ref.missing {
+                    " (not fetched)"
+                } else {
+                    ""
+                }),
+            ]),
+            depth: 1,
+            target_data: (!remote_ref.missing).then_some(TargetData::Commit(remote_ref.hash)),
+            ..Default::default()
+        }
+    }))
+    .collect()
+}
@@ -1,15 +1,15 @@
-use std::{iter, rc::Rc};
+use std::{cell::Cell, iter, rc::Rc};
 
 use crate::{
     config::Config,
-    git,
+    git::{self, diff::DiffOptions},
     items::{self, Item},
     Res,
 };
-use git2::Repository;
+use git2::{Oid, Repository};
 use ratatui::{
     prelude::Rect,
-    text::{Line, Text},
+    text::{Line, Span, Text},
 };
 
 use super::Screen;
@@ -17,6 +17,7 @@ use super::Screen;
 pub(crate) fn create(
     config: Rc<Config>,
     repo: Rc<Repository>,
+    diff_options: Rc<Cell<DiffOptions>>,
     size: Rect,
     reference: String,
 ) -> Res<Screen> {
@@ -24,23 +25,38 @@ pub(crate) fn create(
         Rc::clone(&config),
         size,
         Box::new(move || {
+            let options = diff_options.get();
             let style = &config.style;
             let commit = git::show_summary(repo.as_ref(), &reference)?;
-            let show = git::show(&config, repo.as_ref(), &reference)?;
+            let show = git::show(&config, &options, repo.as_ref(), &reference)?;
             let details = Text::from(commit.details).lines;
+            let rewrites = git::history_rewrites(repo.as_ref())
+                .describe(Oid::from_str(&commit.hash)?)
+                .into_iter()
+                .map(|description| Line::styled(description, &style.history_rewrite));
+
+            let mut header = Line::styled(format!("commit {}", commit.hash), &style.section_header);
+            if let Some(mark) = items::signature_mark(&config, commit.signature) {
+                header.spans.extend([
+                    Span::raw(" "),
+                    mark,
+                    Span::raw(format!(" {}", commit.signature.describe())),
+                ]);
+            }
 
             Ok(iter::once(Item {
                 id: format!("commit_section_{}", commit.hash).into(),
-                display: Line::styled(format!("commit {}", commit.hash), &style.section_header),
+                display: header,
                 section: true,
                 depth: 0,
                 ..Default::default()
             })
-            .chain(details.into_iter().map(|line| Item {
+            .chain(rewrites.chain(details).map(|line| Item {
                 id: format!("commit_{}", commit.hash).into(),
                 display: line,
                 depth: 1,
                 unselectable: true,
+                wrap: true,
                 ..Default::default()
             }))
             .chain([items::blank_line()])
@@ -7,16 +7,23 @@ use std::{
 use super::Screen;
 use crate::{
     config::{Config, StyleConfigEntry},
+    git,
     items::{self, Item, TargetData},
     Res,
 };
 use git2::{Reference, Repository};
+use itertools::Itertools;
 use ratatui::{
     prelude::Rect,
     text::{Line, Span},
 };
 
-pub(crate) fn create(config: Rc<Config>, repo: Rc<Repository>, size: Rect) -> Res<Screen> {
+pub(crate) fn create(
+    config: Rc<Config>,
+    repo: Rc<Repository>,
+    size: Rect,
+    tag_filter: Option<String>,
+) -> Res<Screen> {
     Screen::new(
         Rc::clone(&config),
         size,
@@ -31,31 +38,30 @@ pub(crate) fn create(config: Rc<Config>, repo: Rc<Repository>, size: Rect) -> Re
                 ..Default::default()
             })
             .chain(create_references_section(
+                &config,
                 &repo,
                 Reference::is_branch,
                 &style.branch,
             )?)
             .chain(create_remotes_sections(
+                &config,
                 &repo,
                 &style.section_header,
                 &style.remote,
             )?)
-            .chain(create_tags_section(
-                &repo,
-                &style.section_header,
-                &style.tag,
-            )?)
+            .chain(create_tags_section(&config, &repo, tag_filter.as_deref())?)
             .collect())
         }),
     )
 }
 
 fn create_remotes_sections<'a>(
+    config: &'a Config,
     repo: &'a Repository,
     header_style: &'a StyleConfigEntry,
     item_style: &'a StyleConfigEntry,
 ) -> Res<impl Iterator<Item = Item> + 'a> {
-    let all_remotes = create_references_section(repo, Reference::is_remote, item_style)?;
+    let all_remotes = create_references_section(config, repo, Reference::is_remote, item_style)?;
     let mut remotes = BTreeMap::new();
     for remote in all_remotes {
         let name = String::from_utf8_lossy(&repo.branch_remote_name(&remote.id)?).to_string();
@@ -87,31 +93,53 @@ fn create_remotes_sections<'a>(
     }))
 }
 
-fn create_tags_section<'a>(
-    repo: &'a Repository,
-    header_style: &'a StyleConfigEntry,
-    item_style: &'a StyleConfigEntry,
-) -> Res<impl Iterator<Item = Item> + 'a> {
-    let mut tags = create_references_section(repo, Reference::is_tag, item_style)?;
-    Ok(match tags.next() {
-        Some(item) => vec![
-            items::blank_line(),
-            Item {
-                id: "tags".into(),
-                display: Line::styled("Tags".to_string(), header_style),
-                section: true,
-                depth: 0,
-                ..Default::default()
-            },
-            item,
-        ],
-        None => vec![],
+fn create_tags_section(config: &Config, repo: &Repository, filter: Option<&str>) -> Res<Vec<Item>> {
+    let style = &config.style;
+    let tags = git::tags(repo, config.general.tag_sort.as_deref(), filter)?;
+    if tags.is_empty() && filter.is_none() {
+        return Ok(vec![]);
     }
+
+    let header = match filter {
+        Some(filter) => format!("Tags matching '{}'", filter),
+        None => "Tags".to_string(),
+    };
+    let name_width = tags.iter().map(|tag| tag.name.len()).max().unwrap_or(0);
+
+    Ok([
+        items::blank_line(),
+        Item {
+            id: "tags".into(),
+            display: Line::styled(header, &style.section_header),
+            section: true,
+            depth: 0,
+            ..Default::default()
+        },
+    ]
     .into_iter()
-    .chain(tags))
+    .chain(tags.into_iter().filter_map(|tag| {
+        let reference = repo
+            .find_reference(&format!("refs/tags/{}", tag.name))
+            .ok()?;
+
+        Some(Item {
+            id: reference.name().unwrap().to_owned().into(),
+            display: Line::from(vec![
+                create_prefix(repo, &reference),
+                Span::raw(" "),
+                Span::styled(format!("{:<name_width$}", tag.name), &style.tag),
+                Span::raw(format!(" {} {}", tag.date, tag.subject)),
+            ]),
+            depth: 1,
+            target_data: Some(TargetData::Tag(tag.name)),
+            ..Default::default()
+        })
+    }))
+    .collect())
 }
 
 fn create_references_section<'a, F>(
+    config: &'a Config,
     repo: &'a Repository,
     filter: F,
     style: &'a StyleConfigEntry,
@@ -125,13 +153,50 @@ where
         .filter(filter)
         .map(move |reference| {
             let shorthand = reference.shorthand().unwrap().to_owned();
+            let gone = reference
+                .is_branch()
+                .then(|| git::gone_upstream(repo, reference.name().unwrap()))
+                .flatten()
+                .map(|upstream| Span::raw(format!("[{}: gone]", upstream)));
+            let (upstream, ahead_behind) = reference
+                .is_branch()
+                .then(|| git::upstream_ahead_behind(repo, reference.name().unwrap()))
+                .flatten()
+                .map_or((vec![], vec![]), |(upstream, ahead, behind)| {
+                    let counts = [("ahead", ahead), ("behind", behind)]
+                        .into_iter()
+                        .filter(|(_, count)| *count > 0)
+                        .map(|(direction, count)| format!("{} {}", direction, count))
+                        .join(", ");
+
+                    (
+                        vec![Span::styled(upstream, &config.style.remote)],
+                        Some(counts)
+                            .filter(|counts| !counts.is_empty())
+                            .map(Span::raw)
+                            .into_iter()
+                            .collect(),
+                    )
+                });
+            let commit_fields = reference
+                .peel_to_commit()
+                .ok()
+                .and_then(|commit| items::commit_fields(config, &commit).ok())
+                .unwrap_or_default();
+            let fields = [
+                ("head", vec![create_prefix(repo, &reference)]),
+                ("name", vec![Span::styled(shorthand.clone(), style)]),
+                ("upstream", upstream),
+                ("ahead_behind", ahead_behind),
+                ("gone", gone.into_iter().collect()),
+            ];
 
             Item {
                 id: reference.name().unwrap().to_owned().into(),
-                display: Line::from(vec![
-                    create_prefix(repo, &reference),
-                    Span::styled(shorthand.clone(), style),
-                ]),
+                display: items::format_row(
+                    &config.format.branch,
+                    &[&fields[..], &commit_fields[..]].concat(),
+                ),
                 depth: 1,
                 target_data: Some(TargetData::Branch(shorthand)),
                 ..Default::default()
@@ -144,13 +209,13 @@ fn create_prefix(repo: &Repository, reference: &Reference) -> Span<'static> {
 
     Span::raw(if repo.head_detached().unwrap_or(false) {
         if reference.target() == head.as_ref().and_then(Reference::target) {
-            "? "
+            "?"
         } else {
-            "  "
+            " "
         }
     } else if reference.name() == head.as_ref().and_then(Reference::name) {
-        "* "
+        "*"
     } else {
-        "  "
+        " "
     })
 }
@@ -0,0 +1,80 @@
+use std::{cell::Cell, iter, rc::Rc};
+
+use super::Screen;
+use crate::{
+    config::Config,
+    git::{self, diff::DiffOptions},
+    items::{self, Item},
+    Res,
+};
+use git2::Repository;
+use ratatui::{
+    prelude::Rect,
+    text::{Line, Span},
+};
+
+/// The changes of `stash@{id}`, like `git stash show -p`, plus any untracked files it saved.
+pub(crate) fn create(
+    config: Rc<Config>,
+    repo: Rc<Repository>,
+    diff_options: Rc<Cell<DiffOptions>>,
+    size: Rect,
+    id: usize,
+    commit: String,
+) -> Res<Screen> {
+    Screen::new(
+        Rc::clone(&config),
+        size,
+        Box::new(move || {
+            let options = diff_options.get();
+            let style = &config.style;
+            let message = repo
+                .find_commit(git2::Oid::from_str(&commit)?)?
+                .summary()
+                .unwrap_or("")
+                .to_string();
+            let show = git::show(&config, &options, repo.as_ref(), &commit)?;
+            let untracked = git::stash_untracked(&config, &options, repo.as_ref(), &commit)?
+                .filter(|untracked| !untracked.deltas.is_empty());
+
+            Ok(iter::once(Item {
+                id: format!("stash_section_{}", commit).into(),
+                display: Line::from(vec![
+                    Span::styled(format!("stash@{{{}}}", id), &style.hash),
+                    Span::raw(" "),
+                    Span::styled(message, &style.section_header),
+                ]),
+                section: true,
+                depth: 0,
+                ..Default::default()
+            })
+            .chain([items::blank_line()])
+            .chain(items::create_diff_items(
+                Rc::clone(&config),
+                &show,
+                &0,
+                false,
+            ))
+            .chain(untracked.iter().flat_map(|untracked| {
+                [
+                    items::blank_line(),
+                    Item {
+                        id: format!("untracked_{}", commit).into(),
+                        display: Line::styled("Untracked files", &style.section_header),
+                        section: true,
+                        depth: 0,
+                        ..Default::default()
+                    },
+                ]
+                .into_iter()
+                .chain(items::create_diff_items(
+                    Rc::clone(&config),
+                    untracked,
+                    &1,
+                    false,
+                ))
+            }))
+            .collect())
+        }),
+    )
+}
@@ -1,30 +1,65 @@
-use super::Screen;
+use super::{plural, Screen};
 use crate::{
     config::Config,
-    git::{self, diff::Diff},
+    git::{
+        self,
+        diff::{Diff, DiffOptions},
+    },
     git2_opts,
     items::{self, Item, TargetData},
+    menu::Menu,
+    ops::Op,
     Res,
 };
-use git2::Repository;
+use git2::{Pathspec, PathspecFlags, Repository, SubmoduleIgnore, SubmoduleStatus};
 use ratatui::{
     prelude::Rect,
     text::{Line, Span},
 };
-use std::{path::PathBuf, rc::Rc};
+use std::{
+    cell::Cell,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+/// `now` returns the current time in seconds since the epoch, to show how old things are.
+/// With a `file_filter` (a pathspec like `*.rs`), only matching untracked files and
+/// unstaged changes are shown.
+pub(crate) fn create(
+    config: Rc<Config>,
+    repo: Rc<Repository>,
+    diff_options: Rc<Cell<DiffOptions>>,
+    recent_commits_count: Rc<Cell<usize>>,
+    size: Rect,
+    now: fn() -> i64,
+    file_filter: Option<String>,
+) -> Res<Screen> {
+    let pathspec = file_filter
+        .as_ref()
+        .map(|filter| Pathspec::new([filter]))
+        .transpose()?;
+    let matches = move |path: &Path| {
+        pathspec
+            .as_ref()
+            .is_none_or(|pathspec| pathspec.matches_path(path, PathspecFlags::DEFAULT))
+    };
 
-pub(crate) fn create(config: Rc<Config>, repo: Rc<Repository>, size: Rect) -> Res<Screen> {
     Screen::new(
         Rc::clone(&config),
         size,
         Box::new(move || {
+            let options = diff_options.get();
             let style = &config.style;
-            let statuses = repo.statuses(Some(&mut git2_opts::status(&repo)?))?;
+            let mut status_opts = git2_opts::status(&repo)?;
+            // Match files inside untracked directories, rather than the directories
+            status_opts.recurse_untracked_dirs(file_filter.is_some());
+            let statuses = repo.statuses(Some(&mut status_opts))?;
 
             let untracked_files = statuses
                 .iter()
                 .filter(|status| status.status().is_wt_new())
                 .map(|status| PathBuf::from(status.path().unwrap()))
+                .filter(|path| matches(path))
                 .collect::<Vec<_>>();
 
             let unmerged_files = statuses
@@ -33,42 +68,64 @@ pub(crate) fn create(config: Rc<Config>, repo: Rc<Repository>, size: Rect) -> Re
                 .map(|status| PathBuf::from(status.path().unwrap()))
                 .collect::<Vec<_>>();
 
-            let untracked = items_list(&config, untracked_files.clone());
-            let unmerged = items_list(&config, unmerged_files);
-
-            let items = if let Some(rebase) = git::rebase_status(&repo)? {
-                vec![Item {
-                    id: "rebase_status".into(),
-                    display: Line::styled(
-                        format!("Rebasing {} onto {}", rebase.head_name, &rebase.onto),
-                        &style.section_header,
-                    ),
-                    ..Default::default()
-                }]
-                .into_iter()
-            } else if let Some(merge) = git::merge_status(&repo)? {
-                vec![Item {
-                    id: "merge_status".into(),
-                    display: Line::styled(
-                        format!("Merging {}", &merge.head),
-                        &style.section_header,
-                    ),
-                    ..Default::default()
-                }]
-                .into_iter()
-            } else if let Some(revert) = git::revert_status(&repo)? {
-                vec![Item {
-                    id: "revert_status".into(),
-                    display: Line::styled(
-                        format!("Reverting {}", &revert.head),
-                        &style.section_header,
-                    ),
-                    ..Default::default()
-                }]
-                .into_iter()
-            } else {
-                branch_status_items(&config, &repo)?.into_iter()
+            let mut unstaged = git::diff_unstaged(&config, &options, repo.as_ref())?;
+            unstaged.deltas.retain(|delta| matches(&delta.new_file));
+            let unstaged_files = unstaged
+                .deltas
+                .iter()
+                .map(|delta| delta.new_file.clone())
+                .collect::<Vec<_>>();
+
+            let untracked = items_list(&config, untracked_files.clone(), TargetData::File);
+            let unmerged = items_list(&config, unmerged_files, TargetData::Unmerged);
+
+            let index_lock = git::index_lock(&repo).map(|lock| {
+                let workdir = repo.workdir().expect("No workdir");
+                let display = lock
+                    .strip_prefix(workdir)
+                    .unwrap_or(&lock)
+                    .to_string_lossy();
+
+                vec![
+                    Item {
+                        id: "index_lock".into(),
+                        display: Line::from(vec![
+                            Span::styled("Index locked ", &style.section_header),
+                            Span::styled(display.to_string(), &style.file_header),
+                        ]),
+                        section: true,
+                        depth: 0,
+                        target_data: Some(TargetData::IndexLock(lock.clone())),
+                        ..Default::default()
+                    },
+                    Item {
+                        id: "index_lock_hint".into(),
+                        display: Line::raw(
+                            "Retry once the other git process is done, or discard a stale lock",
+                        ),
+                        depth: 1,
+                        unselectable: true,
+                        ..Default::default()
+                    },
+                    items::blank_line(),
+                ]
+            });
+
+            let items = match in_progress_items(&config, &repo)? {
+                Some(items) => items,
+                None => branch_status_items(&config, &repo, now())?,
             }
+            .into_iter()
+            .chain(file_filter.iter().map(|filter| Item {
+                id: "file_filter".into(),
+                display: Line::from(vec![
+                    Span::styled("Files matching ", &style.section_header),
+                    Span::styled(filter.clone(), &style.file_header),
+                ]),
+                depth: 0,
+                unselectable: true,
+                ..Default::default()
+            }))
             .chain(if untracked.is_empty() {
                 vec![]
             } else {
@@ -78,6 +135,7 @@ pub(crate) fn create(config: Rc<Config>, repo: Rc<Repository>, size: Rect) -> Re
                         id: "untracked".into(),
                         display: Line::styled("Untracked files", &style.section_header),
                         section: true,
+                        default_collapsed: is_collapsed_by_config(&config, "Untracked files"),
                         depth: 0,
                         target_data: Some(TargetData::AllUntracked(untracked_files)),
                         ..Default::default()
@@ -92,44 +150,67 @@ pub(crate) fn create(config: Rc<Config>, repo: Rc<Repository>, size: Rect) -> Re
                     items::blank_line(),
                     Item {
                         id: "unmerged".into(),
-                        display: Line::styled("Unmerged", &style.section_header),
+                        display: Line::styled(
+                            format!("Unmerged ({})", unmerged.len()),
+                            &style.section_header,
+                        ),
                         section: true,
+                        default_collapsed: is_collapsed_by_config(&config, "Unmerged"),
                         depth: 0,
                         ..Default::default()
                     },
                 ]
             })
             .chain(unmerged)
+            .chain(create_submodule_section_items(&config, &repo)?)
             .chain(create_status_section_items(
                 Rc::clone(&config),
                 "Unstaged changes",
-                Some(TargetData::AllUnstaged),
-                &git::diff_unstaged(&config, repo.as_ref())?,
+                Some(if file_filter.is_some() {
+                    TargetData::UnstagedFiles(unstaged_files)
+                } else {
+                    TargetData::AllUnstaged
+                }),
+                &unstaged,
             ))
             .chain(create_status_section_items(
                 Rc::clone(&config),
                 "Staged changes",
                 Some(TargetData::AllStaged),
-                &git::diff_staged(&config, repo.as_ref())?,
+                &git::diff_staged(&config, &options, repo.as_ref())?,
             ))
             .chain(create_stash_list_section_items(
                 Rc::clone(&config),
                 repo.as_ref(),
                 "Stashes",
             ))
+            .chain(create_upstream_sections(&config, repo.as_ref())?)
             .chain(create_log_section_items(
                 Rc::clone(&config),
                 repo.as_ref(),
+                recent_commits_count.get(),
                 "Recent commits",
             ))
-            .collect();
+            .collect::<Vec<_>>();
 
-            Ok(items)
+            Ok(index_lock.into_iter().flatten().chain(items).collect())
         }),
     )
 }
 
-fn items_list(config: &Config, files: Vec<PathBuf>) -> Vec<Item> {
+fn is_collapsed_by_config(config: &Config, header: &str) -> bool {
+    config
+        .general
+        .collapsed_sections
+        .iter()
+        .any(|section| section == header)
+}
+
+fn items_list(
+    config: &Config,
+    files: Vec<PathBuf>,
+    target_data: fn(PathBuf) -> TargetData,
+) -> Vec<Item> {
     let style = &config.style;
     files
         .into_iter()
@@ -137,13 +218,209 @@ fn items_list(config: &Config, files: Vec<PathBuf>) -> Vec<Item> {
             id: path.to_string_lossy().to_string().into(),
             display: Line::styled(path.to_string_lossy().to_string(), &style.file_header),
             depth: 1,
-            target_data: Some(items::TargetData::File(path)),
+            target_data: Some(target_data(path)),
             ..Default::default()
         })
         .collect::<Vec<_>>()
 }
 
-fn branch_status_items(config: &Config, repo: &Repository) -> Res<Vec<Item>> {
+fn create_submodule_section_items(config: &Config, repo: &Repository) -> Res<Vec<Item>> {
+    let style = &config.style;
+    let submodules = repo
+        .submodules()?
+        .iter()
+        .filter_map(|submodule| {
+            let status = repo
+                .submodule_status(submodule.name()?, SubmoduleIgnore::None)
+                .ok()?;
+
+            let descriptions = [
+                (SubmoduleStatus::WD_UNINITIALIZED, "uninitialized"),
+                (SubmoduleStatus::WD_MODIFIED, "new commits"),
+                (
+                    SubmoduleStatus::WD_INDEX_MODIFIED | SubmoduleStatus::WD_WD_MODIFIED,
+                    "modified content",
+                ),
+                (SubmoduleStatus::WD_UNTRACKED, "untracked content"),
+            ]
+            .into_iter()
+            .filter(|(flags, _)| status.intersects(*flags))
+            .map(|(_, description)| description)
+            .collect::<Vec<_>>();
+
+            if descriptions.is_empty() {
+                return None;
+            }
+
+            let path = submodule.path().to_path_buf();
+            Some(Item {
+                id: format!("submodule_{}", path.to_string_lossy()).into(),
+                display: Line::from(vec![
+                    Span::styled(path.to_string_lossy().to_string(), &style.file_header),
+                    format!(" ({})", descriptions.join(", ")).into(),
+                ]),
+                depth: 1,
+                target_data: Some(TargetData::Submodule(path)),
+                ..Default::default()
+            })
+        })
+        .collect::<Vec<_>>();
+
+    if submodules.is_empty() {
+        return Ok(vec![]);
+    }
+
+    Ok([
+        items::blank_line(),
+        Item {
+            id: "submodules".into(),
+            display: Line::styled("Submodules", &style.section_header),
+            section: true,
+            default_collapsed: is_collapsed_by_config(config, "Submodules"),
+            depth: 0,
+            ..Default::default()
+        },
+    ]
+    .into_iter()
+    .chain(submodules)
+    .collect())
+}
+
+/// A banner for a rebase, merge, revert, cherry-pick or bisect in progress,
+/// listing the keys to continue or abort it.
+fn in_progress_items(config: &Config, repo: &Repository) -> Res<Option<Vec<Item>>> {
+    let style = &config.style;
+
+    let (id, heading, stopped_at, hints) = if let Some(rebase) = git::rebase_status(repo)? {
+        (
+            "rebase_status",
+            format!("Rebasing {} onto {}", rebase.head_name, &rebase.onto),
+            rebase.stopped_at,
+            key_hints(
+                config,
+                Menu::Rebase,
+                &[
+                    (Op::RebaseContinue, "continue"),
+                    (Op::RebaseSkip, "skip"),
+                    (Op::RebaseAbort, "abort"),
+                ],
+            ),
+        )
+    } else if let Some(merge) = git::merge_status(repo)? {
+        (
+            "merge_status",
+            format!("Merging {}", &merge.head),
+            None,
+            key_hints(
+                config,
+                Menu::Merge,
+                &[(Op::MergeContinue, "continue"), (Op::MergeAbort, "abort")],
+            ),
+        )
+    } else if let Some(revert) = git::revert_status(repo)? {
+        (
+            "revert_status",
+            format!("Reverting {}", &revert.head),
+            None,
+            key_hints(
+                config,
+                Menu::Revert,
+                &[(Op::RevertContinue, "continue"), (Op::RevertAbort, "abort")],
+            ),
+        )
+    } else if let Some(cherry_pick) = git::cherry_pick_status(repo)? {
+        (
+            "cherry_pick_status",
+            format!("Cherry-picking {}", &cherry_pick.head),
+            None,
+            key_hints(
+                config,
+                Menu::CherryPick,
+                &[
+                    (Op::CherryPickContinue, "continue"),
+                    (Op::CherryPickSkip, "skip"),
+                    (Op::CherryPickAbort, "abort"),
+                ],
+            ),
+        )
+    } else if git::bisect_status(repo)?.is_some() {
+        (
+            "bisect_status",
+            "Bisecting".to_string(),
+            None,
+            key_hints(
+                config,
+                Menu::Bisect,
+                &[
+                    (Op::BisectGood, "good"),
+                    (Op::BisectBad, "bad"),
+                    (Op::BisectSkip, "skip"),
+                    (Op::BisectReset, "reset"),
+                ],
+            ),
+        )
+    } else {
+        return Ok(None);
+    };
+
+    let details = stopped_at
+        .map(|(hash, summary)| {
+            Line::from(vec![
+                Span::raw("Stopped at "),
+                Span::styled(hash, &style.hash),
+                Span::raw(" "),
+                Span::raw(summary),
+            ])
+        })
+        .into_iter()
+        .chain(hints);
+
+    Ok(Some(
+        [Item {
+            id: id.into(),
+            display: Line::styled(heading, &style.section_header),
+            section: true,
+            depth: 0,
+            ..Default::default()
+        }]
+        .into_iter()
+        .chain(details.map(|display| Item {
+            id: id.into(),
+            display,
+            depth: 1,
+            unselectable: true,
+            ..Default::default()
+        }))
+        .collect(),
+    ))
+}
+
+/// The keys of `ops` in `menu` opened from the root, like "rc continue, ra abort".
+fn key_hints(config: &Config, menu: Menu, ops: &[(Op, &str)]) -> Option<Line<'static>> {
+    let style = &config.style;
+    let first_key = |menu: Menu, op: &Op| config.bindings.get(&menu)?.get(op)?.first().cloned();
+    let menu_key = first_key(Menu::Root, &Op::OpenMenu(menu))?;
+
+    let mut spans = vec![];
+    for (op, name) in ops {
+        let Some(key) = first_key(menu, op) else {
+            continue;
+        };
+        if !spans.is_empty() {
+            spans.push(Span::raw(", "));
+        }
+        spans.push(Span::styled(format!("{}{}", menu_key, key), &style.hotkey));
+        spans.push(Span::raw(format!(" {}", name)));
+    }
+
+    if spans.is_empty() {
+        None
+    } else {
+        Some(Line::from(spans))
+    }
+}
+
+fn branch_status_items(config: &Config, repo: &Repository, now: i64) -> Res<Vec<Item>> {
     let style = &config.style;
     let Ok(head) = repo.head() else {
         return Ok(vec![Item {
@@ -177,7 +454,7 @@ fn branch_status_items(config: &Config, repo: &Repository) -> Res<Vec<Item>> {
 
     let Ok(upstream_id) = repo.refname_to_id(&upstream_name) else {
         items.push(Item {
-            id: "branch_status".into(),
+            id: "branch_status_gone".into(),
             display: format!(
                 "Your branch is based on '{}', but the upstream is gone.",
                 upstream_shortname
@@ -192,23 +469,84 @@ fn branch_status_items(config: &Config, repo: &Repository) -> Res<Vec<Item>> {
 
     let (ahead, behind) = repo.graph_ahead_behind(head.target().unwrap(), upstream_id)?;
 
+    // Each line has its own id, as the cursor and what's collapsed are kept by id
+    let status_lines = if ahead == 0 && behind == 0 {
+        vec![(
+            "branch_status_up_to_date",
+            format!("Your branch is up to date with '{}'.", upstream_shortname),
+        )]
+    } else if ahead > 0 && behind == 0 {
+        vec![(
+            "branch_status_ahead",
+            format!(
+                "Your branch is ahead of '{}' by {}.",
+                upstream_shortname,
+                plural(ahead, "commit")
+            ),
+        )]
+    } else if ahead == 0 && behind > 0 {
+        vec![(
+            "branch_status_behind",
+            format!(
+                "Your branch is behind '{}' by {}.",
+                upstream_shortname,
+                plural(behind, "commit")
+            ),
+        )]
+    } else {
+        vec![
+            (
+                "branch_status_ahead",
+                format!("Your branch and '{}' have diverged,", upstream_shortname),
+            ),
+            (
+                "branch_status_behind",
+                format!(
+                    "and have {} and {} different commits each, respectively.",
+                    ahead, behind
+                ),
+            ),
+        ]
+    };
+
+    items.extend(status_lines.into_iter().map(|(id, line)| Item {
+        id: id.into(),
+        display: Line::raw(line),
+        depth: 1,
+        unselectable: true,
+        ..Default::default()
+    }));
+
+    if ahead == 0 && behind > 0 {
+        items.push(Item {
+            id: "branch_status_fast_forward".into(),
+            display: Line::raw(format!("Fast-forward to '{}'", upstream_shortname)),
+            depth: 1,
+            target_data: Some(TargetData::FastForward(upstream_shortname.clone())),
+            ..Default::default()
+        });
+    }
+
+    let upstream_commit = repo.find_commit(upstream_id)?;
     items.push(Item {
-        id: "branch_status".into(),
-        display: if ahead == 0 && behind == 0 {
-            Line::raw(format!("Your branch is up to date with '{}'.", upstream_shortname))
-        } else if ahead > 0 && behind == 0 {
-            Line::raw(format!(
-                "Your branch is ahead of '{}' by {} commit.",
-                upstream_shortname, ahead
-            ))
-        } else if ahead == 0 && behind > 0 {
-            Line::raw(format!(
-                "Your branch is behind '{}' by {} commit.",
-                upstream_shortname, behind
-            ))
-        } else {
-            Line::raw(format!("Your branch and '{}' have diverged,\nand have {} and {} different commits each, respectively.", upstream_shortname, ahead, behind))
-        },
+        id: "branch_status_upstream".into(),
+        display: Line::from(vec![
+            Span::raw("Upstream: "),
+            Span::styled(
+                upstream_commit
+                    .as_object()
+                    .short_id()?
+                    .as_str()
+                    .unwrap()
+                    .to_string(),
+                &style.hash,
+            ),
+            Span::raw(format!(
+                " {} ({} ago)",
+                upstream_commit.summary().unwrap_or(""),
+                age(now - upstream_commit.time().seconds())
+            )),
+        ]),
         depth: 1,
         unselectable: true,
         ..Default::default()
@@ -217,6 +555,26 @@ fn branch_status_items(config: &Config, repo: &Repository) -> Res<Vec<Item>> {
     Ok(items)
 }
 
+/// A rough, human-readable duration, like the relative dates `git log` shows.
+fn age(seconds: i64) -> String {
+    const MINUTE: i64 = 60;
+    const HOUR: i64 = 60 * MINUTE;
+    const DAY: i64 = 24 * HOUR;
+
+    let seconds = seconds.max(0);
+    let (count, unit) = match seconds {
+        s if s < MINUTE => (s, "second"),
+        s if s < HOUR => (s / MINUTE, "minute"),
+        s if s < DAY => (s / HOUR, "hour"),
+        s if s < 14 * DAY => (s / DAY, "day"),
+        s if s < 60 * DAY => (s / (7 * DAY), "week"),
+        s if s < 365 * DAY => (s / (30 * DAY), "month"),
+        s => (s / (365 * DAY), "year"),
+    };
+
+    format!("{} {}{}", count, unit, if count == 1 { "" } else { "s" })
+}
+
 fn create_status_section_items<'a>(
     config: Rc<Config>,
     header: &str,
@@ -241,6 +599,7 @@ fn create_status_section_items<'a>(
                     format!(" ({})", diff.deltas.len()).into(),
                 ]),
                 section: true,
+                default_collapsed: is_collapsed_by_config(&config, header),
                 depth: 0,
                 target_data: header_data,
                 ..Default::default()
@@ -267,6 +626,7 @@ fn create_stash_list_section_items<'a>(
                 id: header.to_string().into(),
                 display: Line::styled(header.to_string(), &style.section_header),
                 section: true,
+                default_collapsed: is_collapsed_by_config(&config, header),
                 depth: 0,
                 ..Default::default()
             },
@@ -276,12 +636,86 @@ fn create_stash_list_section_items<'a>(
     .chain(stashes)
 }
 
+/// Commits that are only on the upstream or push branch, or only on the checked out one.
+fn create_upstream_sections(config: &Config, repo: &Repository) -> Res<Vec<Item>> {
+    if !config.general.upstream_commits.enabled {
+        return Ok(vec![]);
+    }
+
+    let Ok(head) = repo.head() else {
+        return Ok(vec![]);
+    };
+    let (Some(head_name), Some(head_id)) = (head.name(), head.target()) else {
+        return Ok(vec![]);
+    };
+    let upstream = repo
+        .branch_upstream_name(head_name)
+        .ok()
+        .and_then(|name| name.as_str().map(String::from));
+    // Pushing to the upstream is already covered
+    let push_ref = git::head_push_ref(repo).filter(|push_ref| Some(push_ref) != upstream.as_ref());
+
+    let mut sections = vec![];
+    for (refname, kind, headers) in [
+        (upstream, "upstream", ["Unpulled from", "Unmerged into"]),
+        (push_ref, "pushremote", ["Unpulled from", "Unpushed to"]),
+    ] {
+        let Some(refname) = refname else {
+            continue;
+        };
+        let Ok(ref_id) = repo.refname_to_id(&refname) else {
+            continue;
+        };
+        let shortname = refname.strip_prefix("refs/remotes/").unwrap_or(&refname);
+        let (ahead, behind) = repo.graph_ahead_behind(head_id, ref_id)?;
+
+        for (header, count, from, to) in [
+            (headers[0], behind, head_id, ref_id),
+            (headers[1], ahead, ref_id, head_id),
+        ] {
+            if count == 0 {
+                continue;
+            }
+
+            // Also what it's called in `collapsed_sections`
+            let name = format!("{} {}", header, kind);
+            sections.push(items::blank_line());
+            sections.push(Item {
+                display: Line::styled(
+                    format!("{} {} ({})", header, shortname, count),
+                    &config.style.section_header,
+                ),
+                section: true,
+                default_collapsed: is_collapsed_by_config(config, &name),
+                id: name.into(),
+                depth: 0,
+                ..Default::default()
+            });
+            sections.extend(items::range_log(config, repo, 100, from, to)?);
+        }
+    }
+
+    Ok(sections)
+}
+
 fn create_log_section_items<'a>(
     config: Rc<Config>,
     repo: &Repository,
+    count: usize,
     header: &str,
 ) -> impl Iterator<Item = Item> + 'a {
     let style = &config.style;
+    // One more than shown, to tell if there are more
+    let mut commits = items::recent_log(
+        &config,
+        repo,
+        count + 1,
+        config.general.recent_commits.unmerged_branches,
+    )
+    .unwrap();
+    let more = commits.len() > count;
+    commits.truncate(count);
+
     [
         Item {
             display: Line::raw(""),
@@ -293,10 +727,18 @@ fn create_log_section_items<'a>(
             id: header.to_string().into(),
             display: Line::styled(header.to_string(), &style.section_header),
             section: true,
+            default_collapsed: is_collapsed_by_config(&config, header),
             depth: 0,
             ..Default::default()
         },
     ]
     .into_iter()
-    .chain(items::log(&config, repo, 10, None, None).unwrap())
+    .chain(commits)
+    .chain(more.then(|| Item {
+        id: "more_recent_commits".into(),
+        display: Line::raw("Show more"),
+        depth: 1,
+        target_data: Some(TargetData::MoreRecentCommits),
+        ..Default::default()
+    }))
 }
@@ -0,0 +1,57 @@
+use std::{iter, rc::Rc};
+
+use super::Screen;
+use crate::{
+    config::Config,
+    git,
+    items::{Item, TargetData},
+    Res,
+};
+use git2::Repository;
+use ratatui::{
+    prelude::Rect,
+    text::{Line, Span},
+};
+
+/// The snapshots of uncommitted changes taken before destructive operations, newest first.
+pub(crate) fn create(config: Rc<Config>, repo: Rc<Repository>, size: Rect) -> Res<Screen> {
+    Screen::new(
+        Rc::clone(&config),
+        size,
+        Box::new(move || {
+            let style = &config.style;
+            let snapshots = git::wip_snapshots(repo.as_ref())?;
+
+            Ok(iter::once(Item {
+                id: "wip_snapshots".into(),
+                display: Line::styled(
+                    format!("WIP snapshots ({})", snapshots.len()),
+                    &style.section_header,
+                ),
+                section: true,
+                depth: 0,
+                ..Default::default()
+            })
+            .chain(
+                snapshots
+                    .into_iter()
+                    .enumerate()
+                    .map(|(id, snapshot)| Item {
+                        id: snapshot.commit.clone().into(),
+                        display: Line::from(vec![
+                            Span::styled(snapshot.hash, &style.hash),
+                            Span::raw(" "),
+                            Span::raw(snapshot.message),
+                        ]),
+                        depth: 1,
+                        target_data: Some(TargetData::WipSnapshot {
+                            commit: snapshot.commit,
+                            id,
+                        }),
+                        ..Default::default()
+                    }),
+            )
+            .collect())
+        }),
+    )
+}
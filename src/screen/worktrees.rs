@@ -0,0 +1,66 @@
+use std::{iter, rc::Rc};
+
+use super::Screen;
+use crate::{
+    config::Config,
+    git,
+    items::{Item, TargetData},
+    Res,
+};
+use git2::Repository;
+use ratatui::{
+    prelude::Rect,
+    text::{Line, Span},
+};
+
+pub(crate) fn create(config: Rc<Config>, repo: Rc<Repository>, size: Rect) -> Res<Screen> {
+    Screen::new(
+        Rc::clone(&config),
+        size,
+        Box::new(move || {
+            let style = &config.style;
+            let current = repo
+                .workdir()
+                .and_then(|workdir| workdir.canonicalize().ok());
+
+            Ok(iter::once(Item {
+                id: "worktrees".into(),
+                display: Line::styled("Worktrees".to_string(), &style.section_header),
+                section: true,
+                depth: 0,
+                ..Default::default()
+            })
+            .chain(git::worktrees(&repo)?.into_iter().map(|worktree| {
+                let is_current = worktree.path.canonicalize().ok() == current;
+                let path = worktree.path.to_string_lossy().to_string();
+
+                let mut spans = vec![
+                    Span::raw(if is_current { "* " } else { "  " }),
+                    Span::styled(path.clone(), &style.file_header),
+                    Span::raw(" "),
+                    match worktree.branch {
+                        Some(branch) => Span::styled(branch, &style.branch),
+                        None => Span::raw("(detached)"),
+                    },
+                ];
+
+                if worktree.locked {
+                    spans.push(Span::raw(" locked"));
+                }
+
+                if worktree.prunable {
+                    spans.push(Span::raw(" prunable"));
+                }
+
+                Item {
+                    id: path.into(),
+                    display: Line::from(spans),
+                    depth: 1,
+                    target_data: Some(TargetData::Worktree(worktree.path)),
+                    ..Default::default()
+                }
+            }))
+            .collect())
+        }),
+    )
+}
@@ -1,12 +1,21 @@
+use std::cell::Cell;
+use std::collections::VecDeque;
 use std::error::Error;
+use std::fs;
+use std::io;
 use std::io::Read;
 use std::ops::DerefMut;
+use std::path::PathBuf;
 use std::process::Child;
 use std::process::Command;
 use std::process::Stdio;
 use std::rc::Rc;
 use std::sync::Arc;
 use std::sync::RwLock;
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use std::time::Instant;
 
 use arboard::Clipboard;
 use crossterm::event;
@@ -21,16 +30,21 @@ use tui_prompts::Status;
 
 use crate::bindings::Bindings;
 use crate::cli;
+use crate::cmd_log;
 use crate::cmd_log::CmdLog;
 use crate::cmd_log::CmdLogEntry;
 use crate::config::Config;
+use crate::credentials;
+use crate::edit_server;
+use crate::error::GitError;
+use crate::git;
+use crate::git::diff::DiffOptions;
 use crate::menu::Menu;
 use crate::menu::PendingMenu;
 use crate::ops::Op;
 use crate::prompt;
 use crate::screen;
 use crate::screen::Screen;
-use crate::term;
 use crate::term::Term;
 use crate::ui;
 
@@ -41,14 +55,39 @@ pub(crate) struct State {
     pub config: Rc<Config>,
     pub bindings: Bindings,
     pending_keys: Vec<(KeyModifiers, KeyCode)>,
+    pub now: fn() -> i64,
+    /// Shared with the screens showing diffs
+    pub diff_options: Rc<Cell<DiffOptions>>,
+    /// How many recent commits the status screen shows, raised with "Show more"
+    pub recent_commits_count: Rc<Cell<usize>>,
     pub quit: bool,
     pub screens: Vec<Screen>,
     pub pending_menu: Option<PendingMenu>,
     pub pending_cmd: Option<(Child, Arc<RwLock<CmdLogEntry>>)>,
+    /// Commands started while another was running, with their input
+    pub queued_cmds: VecDeque<(Command, Vec<u8>)>,
+    /// A copy of the pending command, to run again if it turns out to need credentials
+    pending_cmd_retry: Option<(Command, Vec<u8>)>,
+    /// When the latest command was started, to tell how long it took
+    cmd_started: Instant,
+    /// Reads the pending command's stderr as it runs, to show its progress
+    pending_cmd_stderr: Option<JoinHandle<io::Result<Vec<u8>>>>,
+    /// Whether the pending command has its editor requests served by gitu
+    serving_edits: bool,
+    /// Whether git waits for an edit to be done within gitu
+    editing: bool,
+    /// Whether the pending command is a fetch started by `general.auto_fetch`
+    auto_fetching: bool,
+    /// A file marked to be diffed against the next one
+    pub marked_file: Option<PathBuf>,
     enable_async_cmds: bool,
     pub current_cmd_log: CmdLog,
     pub prompt: prompt::Prompt,
+    pub popup: Option<ui::Popup>,
     pub clipboard: Option<Clipboard>,
+    next_auto_fetch: Instant,
+    /// Whether the terminal has focus, as far as it reports it
+    focused: bool,
 }
 
 impl State {
@@ -57,27 +96,54 @@ impl State {
         size: Rect,
         args: &cli::Args,
         config: Rc<Config>,
+        now: fn() -> i64,
         enable_async_cmds: bool,
     ) -> Res<Self> {
+        let diff_options = Rc::new(Cell::new(DiffOptions::new(&config)));
+        let recent_commits_count = Rc::new(Cell::new(config.general.recent_commits.count));
         let screens = match args.command {
             Some(cli::Commands::Show { ref reference }) => {
                 vec![screen::show::create(
                     Rc::clone(&config),
                     Rc::clone(&repo),
+                    Rc::clone(&diff_options),
                     size,
                     reference.clone(),
                 )?]
             }
-            None => vec![screen::status::create(
+            Some(cli::Commands::Diff {
+                ref old, ref new, ..
+            }) => vec![screen::diff::create_rev_diff(
                 Rc::clone(&config),
                 Rc::clone(&repo),
+                Rc::clone(&diff_options),
                 size,
+                git::RevDiff::NoIndex(old.clone(), new.clone()),
+            )?],
+            Some(cli::Commands::Keys) | None => vec![screen::status::create(
+                Rc::clone(&config),
+                Rc::clone(&repo),
+                Rc::clone(&diff_options),
+                Rc::clone(&recent_commits_count),
+                size,
+                now,
+                None,
             )?],
         };
 
         let bindings = Bindings::from(&config.bindings);
         let pending_menu = root_menu(&config).map(PendingMenu::init);
 
+        let conflicts = bindings.conflicts();
+        let popup = if conflicts.is_empty() {
+            None
+        } else {
+            Some(ui::Popup {
+                title: "Conflicting keybindings, these can't be triggered".to_string(),
+                lines: conflicts,
+            })
+        };
+
         let clipboard = Clipboard::new()
             .inspect_err(|e| log::warn!("Couldn't initialize clipboard: {}", e))
             .ok();
@@ -87,14 +153,28 @@ impl State {
             config,
             bindings,
             pending_keys: vec![],
+            now,
+            diff_options,
+            recent_commits_count,
             enable_async_cmds,
             quit: false,
             screens,
             pending_cmd: None,
+            queued_cmds: VecDeque::new(),
+            pending_cmd_retry: None,
+            cmd_started: Instant::now(),
+            pending_cmd_stderr: None,
+            serving_edits: false,
+            editing: false,
+            auto_fetching: false,
+            marked_file: None,
             pending_menu,
             current_cmd_log: CmdLog::new(),
             prompt: prompt::Prompt::new(),
+            popup,
             clipboard,
+            next_auto_fetch: Instant::now(),
+            focused: true,
         })
     }
 
@@ -103,12 +183,20 @@ impl State {
             match *event {
                 Event::Resize(w, h) => {
                     for screen in self.screens.iter_mut() {
-                        screen.size = Rect::new(0, 0, w, h);
+                        screen.resize(Rect::new(0, 0, w, h))?;
                     }
                 }
                 Event::Key(key) => {
                     if self.prompt.state.is_focused() {
-                        self.prompt.state.handle_key_event(key)
+                        if key.code == KeyCode::Tab && key.kind == KeyEventKind::Press {
+                            self.complete_prompt();
+                        } else {
+                            self.prompt.state.handle_key_event(key)
+                        }
+                    } else if self.popup.is_some() {
+                        if key.kind == KeyEventKind::Press {
+                            self.popup = None;
+                        }
                     } else if key.kind == KeyEventKind::Press {
                         if self.pending_cmd.is_none() {
                             self.current_cmd_log.clear();
@@ -117,18 +205,30 @@ impl State {
                         self.handle_key_input(term, key)?;
                     }
                 }
+                Event::FocusGained => self.focused = true,
+                Event::FocusLost => self.focused = false,
                 _ => (),
             }
 
             self.update_prompt(term)?;
         }
 
-        let handle_pending_cmd_result = self.handle_pending_cmd();
+        if !events.is_empty() {
+            if let Some(screen) = self.screens.last_mut() {
+                screen.load_more_near_end()?;
+            }
+        }
+
+        let auto_fetch_result = self.auto_fetch(term);
+        self.handle_result(auto_fetch_result);
+
+        let handle_pending_cmd_result = self.handle_pending_cmd(term);
         let pending_cmd_done = self
             .handle_result(handle_pending_cmd_result)
             .unwrap_or(true);
 
-        let needs_redraw = !events.is_empty() || pending_cmd_done;
+        // A running command may have made progress
+        let needs_redraw = !events.is_empty() || pending_cmd_done || self.pending_cmd.is_some();
 
         if needs_redraw && self.screens.last_mut().is_some() {
             term.draw(|frame| ui::ui(frame, self))?;
@@ -137,15 +237,86 @@ impl State {
         Ok(())
     }
 
+    /// Fetches in the background every `general.auto_fetch.interval` seconds, unless a command is running.
+    fn auto_fetch(&mut self, term: &mut Term) -> Res<()> {
+        let config = Rc::clone(&self.config);
+        let auto_fetch = &config.general.auto_fetch;
+        // Like when diffing paths outside of any repository
+        let no_remotes = self
+            .repo
+            .remotes()
+            .map(|remotes| remotes.is_empty())
+            .unwrap_or(true);
+        if !auto_fetch.enabled
+            || auto_fetch.interval == 0
+            || no_remotes
+            || self.pending_cmd.is_some()
+            || Instant::now() < self.next_auto_fetch
+        {
+            return Ok(());
+        }
+
+        self.next_auto_fetch = Instant::now() + Duration::from_secs(auto_fetch.interval);
+
+        let mut cmd = Command::new("git");
+        cmd.args(["fetch", "--progress"]);
+        if auto_fetch.prune {
+            cmd.arg("--prune");
+        }
+        if auto_fetch.remotes.is_empty() {
+            cmd.arg("--all");
+        } else {
+            cmd.arg("--multiple");
+            cmd.args(&auto_fetch.remotes);
+        }
+        self.run_cmd_async(term, &[], cmd)?;
+        self.auto_fetching = true;
+        // There's no one to answer a credential prompt in the background
+        self.pending_cmd_retry = None;
+        Ok(())
+    }
+
+    /// Whether the pending command is a background fetch, rather than one the user started.
+    pub(crate) fn is_auto_fetching(&self) -> bool {
+        self.auto_fetching
+    }
+
+    /// Lets a background fetch finish before a command the user started, rather than failing it.
+    /// Commands the user queued behind it still keep others from running.
+    fn await_auto_fetch(&mut self, term: &mut Term) -> Res<()> {
+        if !self.auto_fetching || !self.queued_cmds.is_empty() {
+            return Ok(());
+        }
+
+        self.await_pending_cmd()?;
+        // How the fetch went is logged, but has no bearing on the command that waited for it
+        let result = self.handle_pending_cmd(term);
+        self.handle_result(result);
+        Ok(())
+    }
+
+    fn complete_prompt(&mut self) {
+        let matches = self.prompt.complete();
+        self.current_cmd_log.clear();
+        if !matches.is_empty() {
+            self.display_info(matches.join("  "));
+        }
+    }
+
     fn update_prompt(&mut self, term: &mut Term) -> Res<()> {
         if self.prompt.state.status() == Status::Aborted {
+            let on_abort = self.prompt.on_abort.take();
             self.prompt.reset(term)?;
+            if let Some(mut on_abort) = on_abort {
+                (Rc::get_mut(&mut on_abort).unwrap())(self, term)?;
+            }
         } else if let Some(mut prompt_data) = self.prompt.data.take() {
             let result = (Rc::get_mut(&mut prompt_data.update_fn).unwrap())(self, term);
 
             match result {
                 Ok(()) => {
-                    if self.prompt.state.is_focused() {
+                    // Keep the prompt unless it was closed, or replaced by a new one
+                    if self.prompt.state.is_focused() && self.prompt.data.is_none() {
                         self.prompt.data = Some(prompt_data);
                     }
                 }
@@ -169,6 +340,11 @@ impl State {
         let matching_bindings = self
             .bindings
             .match_bindings(&menu, &self.pending_keys)
+            .filter(|binding| {
+                self.pending_menu
+                    .as_ref()
+                    .is_none_or(|pending| pending.offers(&binding.op))
+            })
             .collect::<Vec<_>>();
 
         match matching_bindings[..] {
@@ -219,6 +395,53 @@ impl State {
         self.screens.last().expect("No screen")
     }
 
+    /// Copies `text` to the system clipboard. Without one (like over SSH), the terminal is
+    /// asked to set its clipboard with an OSC 52 escape sequence instead.
+    pub fn copy_to_clipboard(
+        &mut self,
+        term: &mut Term,
+        text: String,
+        description: &str,
+    ) -> Res<()> {
+        let copied = match &mut self.clipboard {
+            Some(clipboard) => clipboard
+                .set_text(text.clone())
+                .inspect_err(|e| log::warn!("Couldn't set clipboard: {}", e))
+                .is_ok(),
+            None => false,
+        };
+
+        if !copied {
+            term.backend_mut().set_clipboard(&text)?;
+        }
+
+        self.display_info(format!("{} copied to clipboard", description));
+        Ok(())
+    }
+
+    /// Commands fail when another git process holds the index, add that to the error.
+    fn explain_index_lock(&self, err: Box<dyn Error>) -> Box<dyn Error> {
+        if git::index_lock(&self.repo).is_some() {
+            format!("{} (the index is locked, see the status screen)", err).into()
+        } else {
+            err
+        }
+    }
+
+    /// Says what went wrong and opens a menu of what to do about it, if anything applies.
+    fn offer_follow_ups(&mut self, git_error: GitError) {
+        self.display_info(git_error.hint().to_string());
+
+        let follow_ups = git_error.follow_ups(&self.repo);
+        if follow_ups.is_empty() {
+            return;
+        }
+
+        let mut menu = PendingMenu::init(Menu::FollowUp);
+        menu.follow_ups = follow_ups;
+        self.pending_menu = Some(menu);
+    }
+
     /// Displays an `Info` message to the CmdLog.
     pub fn display_info(&mut self, message: String) {
         self.current_cmd_log.push(CmdLogEntry::Info(message));
@@ -232,17 +455,23 @@ impl State {
     /// Runs a `Command` and handles its output.
     /// Will block awaiting its completion.
     pub fn run_cmd(&mut self, term: &mut Term, input: &[u8], cmd: Command) -> Res<()> {
+        self.await_auto_fetch(term)?;
+        if self.pending_cmd.is_some() {
+            return Err("A command is already running".into());
+        }
+
         self.run_cmd_async(term, input, cmd)?;
         self.await_pending_cmd()?;
-        self.handle_pending_cmd()?;
+        self.handle_pending_cmd(term)?;
         Ok(())
     }
 
     /// Runs a `Command` and handles its output asynchronously (if async commands are enabled).
-    /// Will return `Ok(())` if one is already running.
+    /// If one is already running, it's queued to run once that's done.
     pub fn run_cmd_async(&mut self, term: &mut Term, input: &[u8], mut cmd: Command) -> Res<()> {
         if self.pending_cmd.is_some() {
-            return Err("A command is already running".into());
+            self.queued_cmds.push_back((cmd, input.to_vec()));
+            return Ok(());
         }
 
         cmd.current_dir(self.repo.workdir().expect("No workdir"));
@@ -250,12 +479,21 @@ impl State {
         cmd.stdin(Stdio::piped());
         cmd.stdout(Stdio::piped());
         cmd.stderr(Stdio::piped());
+        credentials::disable_terminal_prompt(&mut cmd);
+        self.pending_cmd_retry = Some((credentials::copy(&cmd), input.to_vec()));
 
         let log_entry = self.current_cmd_log.push_cmd(&cmd);
         term.draw(|frame| ui::ui(frame, self))?;
 
+        self.cmd_started = Instant::now();
         let mut child = cmd.spawn()?;
 
+        let stderr = child.stderr.take().unwrap();
+        let progress_log = Arc::clone(&log_entry);
+        self.pending_cmd_stderr = Some(thread::spawn(move || {
+            cmd_log::read_progress(stderr, &progress_log)
+        }));
+
         use std::io::Write;
         child.stdin.take().unwrap().write_all(input)?;
 
@@ -268,6 +506,49 @@ impl State {
         Ok(())
     }
 
+    /// Runs a command that may open an editor. With `general.edit_in_gitu`, it runs in the
+    /// background and what it opens is edited within gitu. Otherwise the terminal is handed over.
+    pub fn run_cmd_with_editor(&mut self, term: &mut Term, mut cmd: Command) -> Res<()> {
+        if !self.config.general.edit_in_gitu.enabled || !self.enable_async_cmds {
+            return self.run_cmd_interactive(term, cmd);
+        }
+
+        self.await_auto_fetch(term)?;
+        if self.pending_cmd.is_some() {
+            return Err("A command is already running".into());
+        }
+
+        edit_server::serve(&mut cmd, &edit_server::dir(&self.repo))?;
+        self.run_cmd_async(term, &[], cmd)?;
+        self.serving_edits = true;
+        Ok(())
+    }
+
+    /// Tells git the file it asked to have edited is done, or to abort if not `done`.
+    /// Does nothing unless git is waiting for one.
+    pub(crate) fn finish_edit(&mut self, done: bool) -> Res<()> {
+        if !self.editing {
+            return Ok(());
+        }
+
+        self.editing = false;
+        edit_server::respond(&edit_server::dir(&self.repo), done)
+    }
+
+    /// Opens the file the pending command asked to have edited, if it did.
+    fn serve_edit_request(&mut self) -> Res<()> {
+        if !self.serving_edits || self.editing {
+            return Ok(());
+        }
+
+        let Some(file) = edit_server::take_request(&edit_server::dir(&self.repo))? else {
+            return Ok(());
+        };
+
+        self.editing = true;
+        edit_server::open(self, file)
+    }
+
     fn await_pending_cmd(&mut self) -> Res<()> {
         if let Some((child, _)) = &mut self.pending_cmd {
             child.wait()?;
@@ -275,9 +556,12 @@ impl State {
         Ok(())
     }
 
-    /// Handles any pending_cmd in State without blocking. Returns `true` if a cmd was handled.
-    pub fn handle_pending_cmd(&mut self) -> Res<bool> {
-        let Some((ref mut child, ref mut log_rwlock)) = self.pending_cmd else {
+    /// Handles any pending_cmd in State without blocking, then starts the next queued one.
+    /// Returns `true` if a cmd was handled.
+    pub fn handle_pending_cmd(&mut self, term: &mut Term) -> Res<bool> {
+        self.serve_edit_request()?;
+
+        let Some((ref mut child, _)) = self.pending_cmd else {
             return Ok(false);
         };
 
@@ -285,17 +569,68 @@ impl State {
             return Ok(false);
         };
 
+        let (mut child, mut log_rwlock) = self.pending_cmd.take().expect("pending_cmd is set");
         log::debug!("pending cmd finished with {:?}", status);
 
-        let result = write_child_output_to_log(log_rwlock, child, status);
-        self.pending_cmd = None;
+        let stderr = self
+            .pending_cmd_stderr
+            .take()
+            .expect("pending_cmd always has a stderr reader")
+            .join()
+            .expect("Couldn't read cmd output");
+        let result = write_child_output_to_log(&mut log_rwlock, &mut child, stderr, status);
+        let credentials_url = credentials::needed_for(&log_rwlock.read().unwrap());
+        let git_error = GitError::from_log(&log_rwlock.read().unwrap());
+        let toast = self.toast(&log_rwlock.read().unwrap(), result.is_ok());
+        // Nobody asked for a background fetch, so it's not worth interrupting them over
+        if self.config.general.desktop_notifications.enabled && !self.focused && !self.auto_fetching
+        {
+            cmd_log::notify_desktop(&log_rwlock.read().unwrap(), result.is_ok());
+        }
+        self.auto_fetching = false;
+        if self.serving_edits {
+            self.serving_edits = false;
+            self.editing = false;
+            let _ = fs::remove_dir_all(edit_server::dir(&self.repo));
+        }
+        let retry = self.pending_cmd_retry.take();
         self.screen_mut().update()?;
-        result?;
+
+        if let (Err(_), Some(url), Some((cmd, input))) = (&result, credentials_url, retry) {
+            // Queued commands wait for the retry, which is run once the credentials are entered
+            credentials::prompt_and_retry(self, url, cmd, input);
+            return Ok(true);
+        }
+
+        if let Some(toast) = toast {
+            self.current_cmd_log.push(toast);
+        }
+
+        if let Err(err) = result {
+            let err = self.explain_index_lock(err);
+            if let Some(git_error) = git_error {
+                self.offer_follow_ups(git_error);
+            }
+
+            if self.queued_cmds.is_empty() {
+                return Err(err);
+            }
+
+            // Later commands likely depend on this one, like a push after a fetch
+            let cancelled = self.queued_cmds.len();
+            self.queued_cmds.clear();
+            return Err(format!("{}, cancelled {} queued command(s)", err, cancelled).into());
+        }
+
+        if let Some((cmd, input)) = self.queued_cmds.pop_front() {
+            self.run_cmd_async(term, &input, cmd)?;
+        }
 
         Ok(true)
     }
 
     pub fn run_cmd_interactive(&mut self, term: &mut Term, mut cmd: Command) -> Res<()> {
+        self.await_auto_fetch(term)?;
         if self.pending_cmd.is_some() {
             return Err("A command is already running".into());
         }
@@ -303,38 +638,63 @@ impl State {
         cmd.current_dir(self.repo.workdir().expect("No workdir"));
 
         cmd.stdin(Stdio::piped());
-        let child = cmd.spawn()?;
-
-        let out = child.wait_with_output()?;
+        term.backend_mut().suspend()?;
+        self.cmd_started = Instant::now();
+        let child = cmd.spawn();
+        let out = child.and_then(|child| child.wait_with_output());
+        term.backend_mut().resume()?;
+        let out = out?;
         let out_utf8 = String::from_utf8(out.stderr.clone())
             .expect("Error turning command output to String")
             .into();
 
-        self.current_cmd_log.push_cmd_with_output(&cmd, out_utf8);
+        let log_entry = self.current_cmd_log.push_cmd_with_output(&cmd, out_utf8);
+        if let Some(toast) = self.toast(&log_entry.read().unwrap(), out.status.success()) {
+            self.current_cmd_log.push(toast);
+        }
 
         // Prevents cursor flash when exiting editor
         term.hide_cursor()?;
-
-        // In case the command left the alternate screen (editors would)
-        term::enter_alternate_screen()?;
-
         term.clear()?;
         self.screen_mut().update()?;
 
         if !out.status.success() {
-            return Err(format!(
-                "exited with code: {}",
-                out.status
-                    .code()
-                    .map(|c| c.to_string())
-                    .unwrap_or("".to_string())
-            )
-            .into());
+            return Err(self.explain_index_lock(
+                format!(
+                    "exited with code: {}",
+                    out.status
+                        .code()
+                        .map(|c| c.to_string())
+                        .unwrap_or("".to_string())
+                )
+                .into(),
+            ));
         }
 
         Ok(())
     }
 
+    /// A note on how the command that was started last went, however it was run.
+    fn toast(&self, log: &CmdLogEntry, success: bool) -> Option<CmdLogEntry> {
+        cmd_log::toast(&self.config, log, success, self.cmd_started.elapsed())
+    }
+
+    /// Starts a `Command` that keeps running on its own, like a browser, without waiting for it.
+    pub fn run_cmd_detached(&mut self, mut cmd: Command) -> Res<()> {
+        cmd.current_dir(self.repo.workdir().expect("No workdir"));
+
+        cmd.stdin(Stdio::null());
+        cmd.stdout(Stdio::null());
+        cmd.stderr(Stdio::null());
+
+        let mut child = cmd.spawn()?;
+        self.current_cmd_log.push_cmd_with_output(&cmd, "".into());
+
+        // Reaps the process once it exits
+        thread::spawn(move || child.wait());
+        Ok(())
+    }
+
     pub fn hide_menu(&mut self) {
         if let Some(ref mut menu) = self.pending_menu {
             menu.is_hidden = true;
@@ -359,25 +719,24 @@ pub(crate) fn root_menu(config: &Config) -> Option<Menu> {
 fn write_child_output_to_log(
     log_rwlock: &mut Arc<RwLock<CmdLogEntry>>,
     child: &mut Child,
+    stderr: io::Result<Vec<u8>>,
     status: std::process::ExitStatus,
 ) -> Result<(), Box<dyn Error>> {
     let mut log = log_rwlock.write().unwrap();
 
-    let CmdLogEntry::Cmd { args, out: out_log } = log.deref_mut() else {
+    let CmdLogEntry::Cmd {
+        args,
+        out: out_log,
+        progress,
+    } = log.deref_mut()
+    else {
         unreachable!("pending_cmd is always CmdLogEntry::Cmd variant");
     };
 
     drop(child.stdin.take());
+    *progress = None;
 
-    let mut out_bytes = vec![];
-    log::debug!("Reading stderr");
-
-    child
-        .stderr
-        .take()
-        .unwrap()
-        .read_to_end(&mut out_bytes)
-        .map_err(|e| format!("Couldn't read cmd output: {}", e))?;
+    let mut out_bytes = stderr.map_err(|e| format!("Couldn't read cmd output: {}", e))?;
 
     child
         .stdout
@@ -386,7 +745,10 @@ fn write_child_output_to_log(
         .read_to_end(&mut out_bytes)
         .map_err(|e| format!("Couldn't read cmd output: {}", e))?;
 
-    let out_string = String::from_utf8(out_bytes.clone())?;
+    let mut out_string = String::from_utf8(out_bytes)?;
+    if args.split(' ').any(|arg| arg == "--progress") {
+        out_string = cmd_log::strip_progress(&out_string);
+    }
     *out_log = Some(out_string.into());
 
     if !status.success() {
@@ -3,7 +3,7 @@ use crate::config::Config;
 use itertools::Itertools;
 use ratatui::style::Style;
 use std::{cell::RefCell, collections::HashMap, iter, ops::Range, path::Path};
-use tree_sitter::Language;
+use tree_sitter::{Language, Parser};
 use tree_sitter_highlight::{Highlight, HighlightConfiguration, HighlightEvent, Highlighter};
 
 const HIGHLIGHT_NAMES: &[&str] = &[
@@ -246,3 +246,63 @@ pub(crate) fn highlight<'a>(
         })
     })
 }
+
+/// Words in the node kinds of the grammars that make up a scope worth naming,
+/// like `function_item`, `class_definition` or `method_declaration`.
+const SCOPE_WORDS: &[&str] = &[
+    "function",
+    "method",
+    "class",
+    "struct",
+    "impl",
+    "trait",
+    "interface",
+    "enum",
+    "module",
+    "mod",
+    "namespace",
+];
+
+fn is_scope(kind: &str) -> bool {
+    let is_part =
+        kind.ends_with("_body") || kind.ends_with("_list") || kind.ends_with("expression");
+    !is_part && kind.split('_').any(|word| SCOPE_WORDS.contains(&word))
+}
+
+/// The rows spanned by functions, types and the like, which hunk headers are named after.
+/// Like git's `xfuncname`, but using the language's grammar.
+pub(crate) fn scopes(path: &Path, content: &str) -> Vec<Range<usize>> {
+    let Some(lang) = determine_lang(path) else {
+        return vec![];
+    };
+
+    let mut parser = Parser::new();
+    if parser.set_language(lang).is_err() {
+        return vec![];
+    }
+    let Some(tree) = parser.parse(content, None) else {
+        return vec![];
+    };
+
+    let mut scopes = vec![];
+    let mut cursor = tree.walk();
+    'walk: loop {
+        let node = cursor.node();
+        let (start, end) = (node.start_position().row, node.end_position().row);
+        if start < end && is_scope(node.kind()) {
+            scopes.push(start..end + 1);
+        }
+
+        if cursor.goto_first_child() {
+            continue;
+        }
+
+        while !cursor.goto_next_sibling() {
+            if !cursor.goto_parent() {
+                break 'walk;
+            }
+        }
+    }
+
+    scopes
+}
@@ -1,4 +1,6 @@
 use crate::Res;
+use crossterm::event::DisableFocusChange;
+use crossterm::event::EnableFocusChange;
 use crossterm::terminal::disable_raw_mode;
 use crossterm::terminal::enable_raw_mode;
 use crossterm::terminal::EnterAlternateScreen;
@@ -18,14 +20,12 @@ use std::io::Stderr;
 
 pub type Term = Terminal<TermBackend>;
 
-pub fn enter_alternate_screen() -> Res<()> {
-    stderr().execute(EnterAlternateScreen)?;
-    Ok(())
-}
-
+/// Also reports focus changes, so gitu knows when it isn't looked at.
 pub fn alternate_screen<T, F: Fn() -> Res<T>>(fun: F) -> Res<T> {
     stderr().execute(EnterAlternateScreen)?;
+    stderr().execute(EnableFocusChange)?;
     let result = fun();
+    stderr().execute(DisableFocusChange)?;
     stderr().execute(LeaveAlternateScreen)?;
     result
 }
@@ -38,6 +38,7 @@ pub fn raw_mode<T, F: Fn() -> Res<T>>(fun: F) -> Res<T> {
 }
 
 pub fn cleanup_alternate_screen() {
+    print_err(stderr().execute(DisableFocusChange));
     print_err(stderr().execute(LeaveAlternateScreen));
 }
 
@@ -58,8 +59,68 @@ pub fn backend() -> TermBackend {
 
 pub enum TermBackend {
     Crossterm(CrosstermBackend<Stderr>),
+    /// Keeps what the terminal's clipboard was last set to, instead of setting it
     #[allow(dead_code)]
-    Test(TestBackend),
+    Test(TestBackend, Option<String>),
+}
+
+impl TermBackend {
+    /// Hands the terminal over to a child process, like an editor or gpg's pinentry,
+    /// by leaving the alternate screen and raw mode.
+    pub fn suspend(&mut self) -> Res<()> {
+        if let TermBackend::Crossterm(t) = self {
+            t.execute(LeaveAlternateScreen)?;
+            disable_raw_mode()?;
+        }
+        Ok(())
+    }
+
+    /// Takes the terminal back after `suspend`.
+    pub fn resume(&mut self) -> Res<()> {
+        if let TermBackend::Crossterm(t) = self {
+            enable_raw_mode()?;
+            t.execute(EnterAlternateScreen)?;
+        }
+        Ok(())
+    }
+
+    /// Sets the terminal's clipboard with an OSC 52 escape sequence.
+    /// Supported by most terminal emulators, and works over SSH.
+    pub fn set_clipboard(&mut self, text: &str) -> io::Result<()> {
+        match self {
+            TermBackend::Crossterm(t) => {
+                let osc52 = format!("\x1b]52;c;{}\x07", base64(text.as_bytes()));
+                io::Write::write_all(t, osc52.as_bytes())?;
+                io::Write::flush(t)
+            }
+            TermBackend::Test(_, clipboard) => {
+                *clipboard = Some(text.to_string());
+                Ok(())
+            }
+        }
+    }
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    bytes
+        .chunks(3)
+        .flat_map(|chunk| {
+            let n = chunk
+                .iter()
+                .enumerate()
+                .fold(0u32, |n, (i, &byte)| n | (byte as u32) << (16 - 8 * i));
+
+            (0..4).map(move |i| {
+                if i <= chunk.len() {
+                    ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char
+                } else {
+                    '='
+                }
+            })
+        })
+        .collect()
 }
 
 impl Backend for TermBackend {
@@ -69,63 +130,75 @@ impl Backend for TermBackend {
     {
         match self {
             TermBackend::Crossterm(t) => t.draw(content),
-            TermBackend::Test(t) => t.draw(content),
+            TermBackend::Test(t, _) => t.draw(content),
         }
     }
 
     fn hide_cursor(&mut self) -> io::Result<()> {
         match self {
             TermBackend::Crossterm(t) => t.hide_cursor(),
-            TermBackend::Test(t) => t.hide_cursor(),
+            TermBackend::Test(t, _) => t.hide_cursor(),
         }
     }
 
     fn show_cursor(&mut self) -> io::Result<()> {
         match self {
             TermBackend::Crossterm(t) => t.show_cursor(),
-            TermBackend::Test(t) => t.show_cursor(),
+            TermBackend::Test(t, _) => t.show_cursor(),
         }
     }
 
     fn get_cursor(&mut self) -> io::Result<(u16, u16)> {
         match self {
             TermBackend::Crossterm(t) => t.get_cursor(),
-            TermBackend::Test(t) => t.get_cursor(),
+            TermBackend::Test(t, _) => t.get_cursor(),
         }
     }
 
     fn set_cursor(&mut self, x: u16, y: u16) -> io::Result<()> {
         match self {
             TermBackend::Crossterm(t) => t.set_cursor(x, y),
-            TermBackend::Test(t) => t.set_cursor(x, y),
+            TermBackend::Test(t, _) => t.set_cursor(x, y),
         }
     }
 
     fn clear(&mut self) -> io::Result<()> {
         match self {
             TermBackend::Crossterm(t) => t.clear(),
-            TermBackend::Test(t) => t.clear(),
+            TermBackend::Test(t, _) => t.clear(),
         }
     }
 
     fn size(&self) -> io::Result<Rect> {
         match self {
             TermBackend::Crossterm(t) => t.size(),
-            TermBackend::Test(t) => t.size(),
+            TermBackend::Test(t, _) => t.size(),
         }
     }
 
     fn window_size(&mut self) -> io::Result<WindowSize> {
         match self {
             TermBackend::Crossterm(t) => t.window_size(),
-            TermBackend::Test(t) => t.window_size(),
+            TermBackend::Test(t, _) => t.window_size(),
         }
     }
 
     fn flush(&mut self) -> io::Result<()> {
         match self {
             TermBackend::Crossterm(t) => t.flush(),
-            TermBackend::Test(t) => t.flush(),
+            TermBackend::Test(t, _) => t.flush(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn base64() {
+        assert_eq!(super::base64(b""), "");
+        assert_eq!(super::base64(b"f"), "Zg==");
+        assert_eq!(super::base64(b"fo"), "Zm8=");
+        assert_eq!(super::base64(b"foo"), "Zm9v");
+        assert_eq!(super::base64(b"main\n"), "bWFpbgo=");
+    }
+}
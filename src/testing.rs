@@ -0,0 +1,125 @@
+//! A scriptable virtual terminal, for integration testing gitu and customizations of it
+//! (keybinds, styles, formats) without a real terminal.
+//!
+//! Enabled with the `test-backend` feature.
+//!
+//! ```no_run
+//! use gitu::testing::VirtualTerminal;
+//!
+//! let mut vt = VirtualTerminal::new(".".as_ref(), "bindings.root.show_refs = [\"R\"]", 80, 20)?;
+//! vt.keys("R")?;
+//! assert!(vt.contains("Branches"));
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::{
+    cli::Args,
+    config,
+    key_parser::parse_keys,
+    state::State,
+    term::{Term, TermBackend},
+    Res,
+};
+use crossterm::event::{Event, KeyEvent};
+use git2::Repository;
+use ratatui::{backend::TestBackend, buffer::Buffer, layout::Rect, Terminal};
+use std::{path::Path, rc::Rc};
+use unicode_width::UnicodeWidthStr;
+
+/// Gitu running on a repository, rendering to an in-memory buffer.
+/// Commands run to completion before a key is handled, so what they print is on screen
+/// once `keys` returns.
+pub struct VirtualTerminal {
+    term: Term,
+    state: State,
+}
+
+impl VirtualTerminal {
+    /// Opens gitu on the repository at `path`, with `config` merged over the defaults
+    /// like a user's `config.toml` would be.
+    pub fn new(path: &Path, config: &str, width: u16, height: u16) -> Res<Self> {
+        let mut term = Terminal::new(TermBackend::Test(TestBackend::new(width, height), None))?;
+        let mut state = State::create(
+            Rc::new(Repository::open(path)?),
+            Rect::new(0, 0, width, height),
+            &Args::default(),
+            Rc::new(config::init_config_from(config)?),
+            || chrono::Utc::now().timestamp(),
+            false,
+        )?;
+
+        // Render the first frame, like the focus event gitu gets on startup would
+        state.update(&mut term, &[Event::FocusGained])?;
+        Ok(Self { term, state })
+    }
+
+    /// Presses keys, written like in the config: `"Pp"`, `"<ctrl+d>"` or `"<enter>"`.
+    pub fn keys(&mut self, keys: &str) -> Res<()> {
+        let Ok(("", keys)) = parse_keys(keys) else {
+            return Err(format!("Invalid keys: {}", keys).into());
+        };
+
+        let events = keys
+            .into_iter()
+            .map(|(mods, key)| Event::Key(KeyEvent::new(key, mods)))
+            .collect::<Vec<_>>();
+        self.events(&events)
+    }
+
+    /// Feeds any terminal events, such as resizes or pastes.
+    pub fn events(&mut self, events: &[Event]) -> Res<()> {
+        for event in events {
+            if let (Event::Resize(width, height), TermBackend::Test(backend, _)) =
+                (event, self.term.backend_mut())
+            {
+                backend.resize(*width, *height);
+            }
+        }
+
+        self.state.update(&mut self.term, events)
+    }
+
+    /// Whether gitu was told to quit.
+    pub fn has_quit(&self) -> bool {
+        self.state.quit
+    }
+
+    /// The last rendered frame, with styles.
+    pub fn buffer(&self) -> &Buffer {
+        let TermBackend::Test(backend, _) = self.term.backend() else {
+            unreachable!();
+        };
+        backend.buffer()
+    }
+
+    /// The text of each row of the last frame, without trailing whitespace.
+    pub fn lines(&self) -> Vec<String> {
+        let buffer = self.buffer();
+        buffer
+            .content
+            .chunks(buffer.area.width as usize)
+            .map(|row| {
+                let mut line = String::new();
+                let mut skip = 0;
+                for cell in row {
+                    // Cells covered by a wide symbol before them hold a placeholder
+                    if skip == 0 {
+                        line.push_str(cell.symbol());
+                    }
+                    skip = std::cmp::max(skip, cell.symbol().width()).saturating_sub(1);
+                }
+                line.trim_end().to_string()
+            })
+            .collect()
+    }
+
+    /// The text of the last frame, one row per line.
+    pub fn contents(&self) -> String {
+        self.lines().join("\n")
+    }
+
+    /// Whether `text` is shown on a single row of the last frame.
+    pub fn contains(&self, text: &str) -> bool {
+        self.lines().iter().any(|line| line.contains(text))
+    }
+}
@@ -0,0 +1,25 @@
+use super::*;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "testfile", "testing\ntesttest\n");
+    run(ctx.dir.path(), &["git", "checkout", "-b", "other"]);
+    commit(ctx.dir.path(), "testfile", "test\ntesttest\nmore\n");
+    run(ctx.dir.path(), &["git", "checkout", "main"]);
+    ctx
+}
+
+#[test]
+fn apply_hunk_from_other_commit() {
+    snapshot!(setup(), "loother<enter><enter>aqq");
+}
+
+#[test]
+fn apply_file_from_other_commit() {
+    snapshot!(setup(), "loother<enter><enter>kaqq");
+}
+
+#[test]
+fn apply_line_from_other_commit() {
+    snapshot!(setup(), "loother<enter><enter><ctrl+j><ctrl+j><ctrl+j>aqq");
+}
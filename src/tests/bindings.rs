@@ -0,0 +1,108 @@
+use super::*;
+use crate::{cmd_log::CmdLogEntry, config::CommandConfig, menu::Menu, ops::Op};
+
+#[test]
+fn default_bindings_have_no_conflicts() {
+    let ctx = TestContext::setup_init();
+    snapshot!(ctx, "");
+}
+
+#[test]
+fn conflicting_bindings_are_reported() {
+    let mut ctx = TestContext::setup_init();
+    let root = ctx.config().bindings.get_mut(&Menu::Root).unwrap();
+    root.insert(Op::Discard, vec!["s".to_string()]);
+    root.insert(Op::ShowRefs, vec!["gr".to_string()]);
+
+    snapshot!(ctx, "");
+}
+
+#[test]
+fn conflicting_bindings_popup_closes_on_key() {
+    let mut ctx = TestContext::setup_init();
+    let root = ctx.config().bindings.get_mut(&Menu::Root).unwrap();
+    root.insert(Op::Discard, vec!["s".to_string()]);
+
+    snapshot!(ctx, "q");
+}
+
+#[test]
+fn export_keys_prompt() {
+    snapshot!(TestContext::setup_init(), "E");
+}
+
+#[test]
+fn export_keys() {
+    let mut ctx = TestContext::setup_init();
+    ctx.config()
+        .bindings
+        .get_mut(&Menu::Root)
+        .unwrap()
+        .insert(Op::Quit, vec!["Q".to_string()]);
+
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("E<enter>")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+
+    let exported: toml::Table =
+        toml::from_str(&fs::read_to_string(ctx.dir.child("gitu-keys.toml")).unwrap()).unwrap();
+    let bindings = &exported["bindings"];
+    assert_eq!(bindings["root"]["quit"], toml::Value::from(vec!["Q"]));
+    assert_eq!(
+        bindings["root"]["export_keys"],
+        toml::Value::from(vec!["E"])
+    );
+    assert_eq!(
+        bindings["commit_menu"]["--all"],
+        toml::Value::from(vec!["-a"])
+    );
+}
+
+fn setup_command(command: &str) -> TestContext {
+    let mut ctx = TestContext::setup_init();
+    run(ctx.dir.path(), &["touch", "new-file"]);
+    ctx.config().commands.insert(
+        "stage".to_string(),
+        CommandConfig {
+            run: command.to_string(),
+            interactive: false,
+        },
+    );
+    ctx.config()
+        .bindings
+        .get_mut(&Menu::Root)
+        .unwrap()
+        .insert(Op::RunCommand("stage".to_string()), vec!["!s".to_string()]);
+    ctx
+}
+
+#[test]
+fn run_command() {
+    snapshot!(setup_command("git add %(file)"), "jj!s");
+}
+
+#[test]
+fn run_command_without_target() {
+    snapshot!(setup_command("git add %(file)"), "!s");
+}
+
+#[test]
+fn run_interactive_command_toast() {
+    let mut ctx = setup_command("exit 1");
+    ctx.config().commands.get_mut("stage").unwrap().interactive = true;
+    ctx.config().general.command_toasts.enabled = true;
+
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("!s")).unwrap();
+
+    let toasts = state
+        .current_cmd_log
+        .entries
+        .iter()
+        .filter_map(|entry| match &*entry.read().unwrap() {
+            CmdLogEntry::Error(err) if err.starts_with("'sh -c exit 1' failed after ") => Some(()),
+            _ => None,
+        })
+        .count();
+    assert_eq!(toasts, 1);
+}
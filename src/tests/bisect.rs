@@ -0,0 +1,49 @@
+use super::*;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_init();
+    for i in 1..=5 {
+        commit(ctx.dir.path(), &format!("file-{}", i), "");
+    }
+    ctx
+}
+
+#[test]
+fn bisect_menu() {
+    snapshot!(setup(), "B");
+}
+
+#[test]
+fn not_bisecting() {
+    snapshot!(setup(), "Bv");
+}
+
+#[test]
+fn bisect_start_prompt() {
+    snapshot!(setup(), "lljjjjBB");
+}
+
+#[test]
+fn bisect_start() {
+    snapshot!(setup(), "lljjjjBB<enter>");
+}
+
+#[test]
+fn bisect_good() {
+    snapshot!(setup(), "lljjjjBB<enter>Bg");
+}
+
+#[test]
+fn bisect_found_first_bad() {
+    snapshot!(setup(), "lljjjjBB<enter>BgBb");
+}
+
+#[test]
+fn bisect_skip() {
+    snapshot!(setup(), "lljjjjBB<enter>Bs");
+}
+
+#[test]
+fn bisect_reset() {
+    snapshot!(setup(), "lljjjjBB<enter>Br");
+}
@@ -0,0 +1,90 @@
+use super::*;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), ".gitignore", "ignored-file\n");
+    fs::write(ctx.dir.child("ignored-file"), "").unwrap();
+    fs::write(ctx.dir.child("untracked-file"), "").unwrap();
+    fs::create_dir(ctx.dir.child("untracked-dir")).unwrap();
+    fs::write(ctx.dir.child("untracked-dir/file"), "").unwrap();
+    ctx
+}
+
+#[test]
+fn clean_menu() {
+    snapshot!(setup(), "C");
+}
+
+#[test]
+fn clean_preview() {
+    snapshot!(setup(), "Cc");
+}
+
+#[test]
+fn clean_preview_ignored() {
+    snapshot!(setup(), "C-xc");
+}
+
+#[test]
+fn clean_preview_toggle() {
+    snapshot!(setup(), "Cct");
+}
+
+#[test]
+fn clean_selected() {
+    let mut ctx = setup();
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("Cct<enter>")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+
+    assert!(ctx.dir.child("untracked-dir/file").exists());
+    assert!(!ctx.dir.child("untracked-file").exists());
+    assert!(ctx.dir.child("ignored-file").exists());
+}
+
+#[test]
+fn clean_selected_unusual_names() {
+    let mut ctx = TestContext::setup_clone();
+    fs::write(ctx.dir.child("*"), "").unwrap();
+    fs::write(ctx.dir.child("fü.txt"), "").unwrap();
+    fs::write(ctx.dir.child("other-file"), "").unwrap();
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("Ccjjt<enter>")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+
+    assert!(!ctx.dir.child("*").exists());
+    assert!(!ctx.dir.child("fü.txt").exists());
+    assert!(ctx.dir.child("other-file").exists());
+}
+
+#[test]
+fn clean_abort() {
+    let mut ctx = setup();
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("Ccq")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+
+    assert!(ctx.dir.child("untracked-file").exists());
+}
+
+#[test]
+fn nothing_to_clean() {
+    snapshot!(TestContext::setup_clone(), "Cc");
+}
+
+#[test]
+fn clean_ignored_then_apply_wip_snapshot() {
+    let mut ctx = setup();
+    ctx.config().general.wip_snapshots.enabled = true;
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("C-xc<enter>")).unwrap();
+    assert!(!ctx.dir.child("ignored-file").exists());
+    assert!(!ctx.dir.child("untracked-dir/file").exists());
+
+    state.update(&mut ctx.term, &keys("zWjza<enter>q")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+
+    assert!(ctx.dir.child("ignored-file").exists());
+    assert!(ctx.dir.child("untracked-file").exists());
+    assert!(ctx.dir.child("untracked-dir/file").exists());
+}
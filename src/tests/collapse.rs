@@ -0,0 +1,74 @@
+use super::*;
+
+#[test]
+fn collapsed_sections() {
+    let mut ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "new-file", "");
+    fs::write(ctx.dir.child("new-file"), "hello").unwrap();
+    ctx.config().general.collapsed_sections =
+        vec!["Unstaged changes".to_string(), "Recent commits".to_string()];
+
+    snapshot!(ctx, "");
+}
+
+#[test]
+fn collapse_diffs_larger_than() {
+    let mut ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "small-file", "one\n");
+    run(ctx.dir.path(), &["git", "add", "."]);
+    fs::write(ctx.dir.child("small-file"), "two\n").unwrap();
+    fs::write(ctx.dir.child("large-file"), "1\n2\n3\n4\n5\n6\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "."]);
+    run(ctx.dir.path(), &["git", "commit", "-m", "change files"]);
+    ctx.config().general.collapse_diffs_larger_than = Some(5);
+
+    snapshot!(ctx, "ll<enter>");
+}
+
+#[test]
+fn expanded_section_is_remembered_on_refresh() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.collapsed_sections = vec!["Recent commits".to_string()];
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("j<tab>")).unwrap();
+
+    commit(ctx.dir.path(), "new-file", "");
+    state.update(&mut ctx.term, &keys("g")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn toggle_file_hunks() {
+    let ctx = TestContext::setup_init();
+    let content = |first: &str, last: &str| {
+        let middle = (2..=19).map(|i| format!("{}\n", i)).collect::<String>();
+        format!("{}\n{}{}\n", first, middle, last)
+    };
+    commit(ctx.dir.path(), "testfile", &content("1", "20"));
+    fs::write(ctx.dir.child("testfile"), content("one", "twenty")).unwrap();
+
+    snapshot!(ctx, "jj<tab>j<ctrl+j><backtab>");
+}
+
+#[test]
+fn expand_to_hunks() {
+    let ctx = TestContext::setup_init();
+    let content = |first: &str, last: &str| {
+        let middle = (2..=19).map(|i| format!("{}\n", i)).collect::<String>();
+        format!("{}\n{}{}\n", first, middle, last)
+    };
+    commit(ctx.dir.path(), "testfile", &content("1", "20"));
+    fs::write(ctx.dir.child("testfile"), content("one", "twenty")).unwrap();
+
+    snapshot!(ctx, "<alt+3>");
+}
+
+#[test]
+fn default_expansion_level() {
+    let mut ctx = TestContext::setup_init();
+    commit(ctx.dir.path(), "testfile", "1\n");
+    fs::write(ctx.dir.child("testfile"), "one\n").unwrap();
+    ctx.config().general.default_expansion_level = Some(2);
+
+    snapshot!(ctx, "");
+}
@@ -0,0 +1,285 @@
+use super::*;
+use std::process::Command;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "file-1", "one");
+    commit(ctx.dir.path(), "file-2", "two");
+    fs::write(ctx.dir.child("file-1"), "one, fixed").unwrap();
+    run(ctx.dir.path(), &["git", "add", "file-1"]);
+    ctx
+}
+
+#[test]
+fn commit_menu() {
+    snapshot!(setup(), "llc");
+}
+
+#[test]
+fn commit_menu_signs_by_default() {
+    let mut ctx = setup();
+    ctx.config().general.sign_commits.enabled = true;
+    snapshot!(ctx, "llc");
+}
+
+#[test]
+fn fixup_from_recent_commits() {
+    snapshot!(setup(), "jjjjjcf");
+}
+
+#[test]
+fn squash_from_log() {
+    snapshot!(setup(), "lljcs");
+}
+
+#[test]
+fn fixup_picker() {
+    snapshot!(setup(), "cf");
+}
+
+#[test]
+fn fixup_picker_all_commits() {
+    snapshot!(setup(), "cfa");
+}
+
+#[test]
+fn fixup_picker_filter() {
+    snapshot!(setup(), "cfa/file-2<enter>");
+}
+
+#[test]
+fn fixup_picker_no_match() {
+    snapshot!(setup(), "cf/nothing<enter>");
+}
+
+#[test]
+fn fixup_picker_pick() {
+    snapshot!(setup(), "cf<enter>");
+}
+
+#[test]
+fn fixup_picker_suggests_each_blamed_commit() {
+    let ctx = TestContext::setup_clone();
+    let lines = (1..=12)
+        .map(|i| format!("line {}\n", i))
+        .collect::<String>();
+    commit(ctx.dir.path(), "file-1", &lines);
+    let lines = lines.replace("line 12\n", "line 12 changed\n");
+    fs::write(ctx.dir.child("file-1"), &lines).unwrap();
+    run(
+        ctx.dir.path(),
+        &["git", "commit", "-am", "change end of file-1"],
+    );
+    commit(ctx.dir.path(), "file-2", "two");
+
+    let lines = lines
+        .replace("line 1\n", "line 1 fixed\n")
+        .replace("line 12 changed\n", "line 12 fixed\n");
+    fs::write(ctx.dir.child("file-1"), lines).unwrap();
+    run(ctx.dir.path(), &["git", "add", "file-1"]);
+    snapshot!(ctx, "cf");
+}
+
+#[test]
+fn squash_picker() {
+    snapshot!(setup(), "cs");
+}
+
+#[test]
+fn instant_fixup() {
+    let mut ctx = setup();
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("lljcF")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+
+    let fixed = Command::new("git")
+        .args(["show", "HEAD~1:file-1"])
+        .current_dir(ctx.dir.path())
+        .output()
+        .unwrap();
+    assert_eq!(fixed.stdout, b"one, fixed");
+}
+
+#[test]
+fn instant_fixup_picker() {
+    snapshot!(setup(), "cF");
+}
+
+#[test]
+fn instant_fixup_from_picker() {
+    let mut ctx = setup();
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("cF<enter>")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+
+    let fixed = Command::new("git")
+        .args(["show", "HEAD~1:file-1"])
+        .current_dir(ctx.dir.path())
+        .output()
+        .unwrap();
+    assert_eq!(fixed.stdout, b"one, fixed");
+}
+
+fn setup_absorb() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "file-a", "a1\na2\na3\na4\na5\na6\na7\na8\n");
+    commit(ctx.dir.path(), "file-b", "b1\nb2\nb3\n");
+    fs::write(
+        ctx.dir.child("file-a"),
+        "a1\na2 changed\na3\na4\na5\na6\na7 changed\na8\n",
+    )
+    .unwrap();
+    fs::write(ctx.dir.child("file-b"), "b1\nb2\nb2.5\nb3\n").unwrap();
+    fs::write(ctx.dir.child("initial-file"), "hello, changed").unwrap();
+    run(ctx.dir.path(), &["git", "add", "."]);
+    ctx
+}
+
+#[test]
+fn absorb_preview() {
+    snapshot!(setup_absorb(), "cx");
+}
+
+#[test]
+fn absorb() {
+    let mut ctx = setup_absorb();
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("cxy")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+
+    let log = Command::new("git")
+        .args(["log", "--format=%s"])
+        .current_dir(ctx.dir.path())
+        .output()
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(log.stdout).unwrap(),
+        "fixup! add file-a\nfixup! add file-b\nadd file-b\nadd file-a\nadd initial-file\n"
+    );
+}
+
+#[test]
+fn absorb_declined() {
+    snapshot!(setup_absorb(), "cxn");
+}
+
+#[test]
+fn absorb_aborted() {
+    snapshot!(setup_absorb(), "cx<esc>");
+}
+
+#[test]
+fn absorb_nothing() {
+    let ctx = TestContext::setup_clone();
+    fs::write(ctx.dir.child("initial-file"), "hello, changed").unwrap();
+    run(ctx.dir.path(), &["git", "add", "."]);
+    snapshot!(ctx, "cx");
+}
+
+#[test]
+fn commit_empty_message_aborts() {
+    let ctx = setup();
+    run(ctx.dir.path(), &["git", "config", "core.editor", "true"]);
+    snapshot!(ctx, "cc");
+}
+
+fn reject_commit_message(ctx: &TestContext) {
+    let hooks = ctx.dir.path().join(".git/hooks");
+    fs::write(
+        hooks.join("prepare-commit-msg"),
+        "#!/bin/sh\necho Draft message > \"$1\"\n",
+    )
+    .unwrap();
+    fs::write(hooks.join("commit-msg"), "#!/bin/sh\nexit 1\n").unwrap();
+    run(&hooks, &["chmod", "+x", "prepare-commit-msg", "commit-msg"]);
+}
+
+#[test]
+fn commit_failure_keeps_draft() {
+    let ctx = setup();
+    reject_commit_message(&ctx);
+    snapshot!(ctx, "cc");
+}
+
+#[test]
+fn commit_retries_draft() {
+    let mut ctx = setup();
+    reject_commit_message(&ctx);
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("cc")).unwrap();
+
+    let hooks = ctx.dir.path().join(".git/hooks");
+    fs::remove_file(hooks.join("prepare-commit-msg")).unwrap();
+    fs::remove_file(hooks.join("commit-msg")).unwrap();
+    state.update(&mut ctx.term, &keys("cc")).unwrap();
+
+    assert!(!ctx.dir.path().join(".git/GITU_COMMIT_DRAFT").exists());
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn commit_cleared_draft_is_discarded() {
+    let mut ctx = setup();
+    let draft = ctx.dir.path().join(".git/GITU_COMMIT_DRAFT");
+    fs::write(&draft, "Draft message\n").unwrap();
+
+    // Stands in for the user emptying the message in the editor
+    let hooks = ctx.dir.path().join(".git/hooks");
+    fs::write(hooks.join("commit-msg"), "#!/bin/sh\n: > \"$1\"\n").unwrap();
+    run(&hooks, &["chmod", "+x", "commit-msg"]);
+
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("cc")).unwrap();
+
+    assert!(!draft.exists());
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn commit_unchanged_template_aborts() {
+    let mut ctx = setup();
+    fs::write(ctx.dir.path().join(".git/template"), "Template\n").unwrap();
+    run(
+        ctx.dir.path(),
+        &["git", "config", "commit.template", ".git/template"],
+    );
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("cc")).unwrap();
+
+    assert!(!ctx.dir.path().join(".git/GITU_COMMIT_DRAFT").exists());
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn conventional_type_complete() {
+    snapshot!(setup(), "cCf<tab>");
+}
+
+#[test]
+fn conventional_commit() {
+    let ctx = setup();
+    run(ctx.dir.path(), &["git", "config", "core.editor", "true"]);
+    snapshot!(ctx, "cCfix<enter>ui<enter>Handle empty input<enter>");
+}
+
+#[test]
+fn conventional_commit_without_scope() {
+    let ctx = setup();
+    run(ctx.dir.path(), &["git", "config", "core.editor", "true"]);
+    snapshot!(ctx, "cCdocs<enter><enter>Fix typo<enter>");
+}
+
+#[test]
+fn conventional_scopes_from_repo_config() {
+    let ctx = setup();
+    run(
+        ctx.dir.path(),
+        &["git", "config", "gitu.conventionalScopes", "parser cli"],
+    );
+    snapshot!(ctx, "cCfeat<enter>p<tab>");
+}
+
+#[test]
+fn conventional_commit_empty_subject() {
+    snapshot!(setup(), "cCfeat<enter><enter><enter>");
+}
@@ -0,0 +1,292 @@
+use super::*;
+use crate::{
+    cli::{Args, Commands},
+    state::State,
+};
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "file-1", "one");
+    run(ctx.dir.path(), &["git", "checkout", "-b", "other"]);
+    commit(ctx.dir.path(), "other-file", "other");
+    run(ctx.dir.path(), &["git", "checkout", "main"]);
+    commit(ctx.dir.path(), "file-2", "two");
+    fs::write(ctx.dir.child("file-1"), "one, changed").unwrap();
+    ctx
+}
+
+#[test]
+fn diff_menu() {
+    snapshot!(setup(), "d");
+}
+
+#[test]
+fn diff_upstream_merge_base() {
+    snapshot!(setup(), "du");
+}
+
+#[test]
+fn diff_merge_base_prompt() {
+    snapshot!(setup(), "dm");
+}
+
+#[test]
+fn diff_merge_base_other() {
+    snapshot!(setup(), "dmother<enter>");
+}
+
+#[test]
+fn diff_merge_base_bad_ref() {
+    snapshot!(setup(), "dmnonexistent<enter>");
+}
+
+#[test]
+fn diff_upstream_merge_base_no_upstream() {
+    let ctx = setup();
+    run(ctx.dir.path(), &["git", "checkout", "other"]);
+    snapshot!(ctx, "du");
+}
+
+#[test]
+fn diff_worktree_prompt() {
+    snapshot!(setup(), "dw");
+}
+
+#[test]
+fn diff_worktree() {
+    snapshot!(setup(), "dwother<enter>");
+}
+
+#[test]
+fn diff_index() {
+    snapshot!(setup(), "dimain~1<enter>");
+}
+
+#[test]
+fn diff_revs_new_prompt() {
+    snapshot!(setup(), "ddother<enter>");
+}
+
+#[test]
+fn diff_revs() {
+    snapshot!(setup(), "ddother<enter><enter>");
+}
+
+#[test]
+fn diff_revs_bad_ref() {
+    snapshot!(setup(), "ddother<enter>nonexistent<enter>");
+}
+
+fn setup_range_diff() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    let lines = (1..=20)
+        .map(|i| format!("line {}\n", i))
+        .collect::<String>();
+    commit(ctx.dir.path(), "file-1", &lines);
+    commit(ctx.dir.path(), "file-2", "two");
+    run(ctx.dir.path(), &["git", "push"]);
+
+    // Rewrite the pushed commits, as if by a rebase
+    run(ctx.dir.path(), &["git", "reset", "--hard", "HEAD~2"]);
+    commit(
+        ctx.dir.path(),
+        "file-1",
+        &lines.replace("line 5\n", "five\n"),
+    );
+    commit(ctx.dir.path(), "file-3", "three");
+    ctx
+}
+
+#[test]
+fn range_diff_prompt() {
+    snapshot!(setup_range_diff(), "dr");
+}
+
+#[test]
+fn range_diff_new_prompt() {
+    snapshot!(setup_range_diff(), "dr<enter>");
+}
+
+#[test]
+fn range_diff() {
+    snapshot!(setup_range_diff(), "dr<enter><enter>");
+}
+
+fn setup_side_by_side() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(
+        ctx.dir.path(),
+        "file",
+        "keep\nold 1\nold 2\nkeep\nremoved\n",
+    );
+    fs::write(
+        ctx.dir.child("file"),
+        "keep\nnew 1\nkeep\nadded\nan added line that is too long to fit in its column\n",
+    )
+    .unwrap();
+    ctx
+}
+
+#[test]
+fn side_by_side_diff() {
+    let mut ctx = setup_side_by_side();
+    ctx.config().general.side_by_side_diff.enabled = true;
+    snapshot!(ctx, "jj<tab>");
+}
+
+#[test]
+fn toggle_side_by_side_diff() {
+    snapshot!(setup_side_by_side(), "jj<tab>|");
+}
+
+#[test]
+fn toggle_side_by_side_diff_back() {
+    snapshot!(setup_side_by_side(), "jj<tab>||");
+}
+
+fn setup_diff_context() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    let lines = (1..=16)
+        .map(|i| format!("line {}\n", i))
+        .collect::<String>();
+    commit(ctx.dir.path(), "file", &lines);
+    fs::write(
+        ctx.dir.child("file"),
+        lines
+            .replace("line 3\n", "three\n")
+            .replace("line 14\n", "fourteen\n"),
+    )
+    .unwrap();
+    ctx
+}
+
+#[test]
+fn increase_diff_context() {
+    snapshot!(setup_diff_context(), "jj<tab>+");
+}
+
+#[test]
+fn increase_diff_context_merges_hunks() {
+    snapshot!(setup_diff_context(), "jj<tab>++");
+}
+
+#[test]
+fn decrease_diff_context() {
+    snapshot!(setup_diff_context(), "jj<tab>--");
+}
+
+#[test]
+fn decrease_diff_context_keeps_one_line() {
+    snapshot!(setup_diff_context(), "jj<tab>----");
+}
+
+#[test]
+fn diff_context_keeps_cursor_on_line() {
+    snapshot!(setup_diff_context(), "jj<tab>jj<ctrl+j><ctrl+j>-");
+}
+
+fn setup_diff_flags() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "code", "fn main() {\n    call();\n}\n");
+    commit(ctx.dir.path(), "other", "one\n");
+    fs::write(ctx.dir.child("code"), "fn main() {\n  call( );\n\n}\n").unwrap();
+    fs::write(ctx.dir.child("other"), "two\n").unwrap();
+    ctx
+}
+
+#[test]
+fn ignore_all_space() {
+    snapshot!(setup_diff_flags(), "jj<tab>d-wq");
+}
+
+#[test]
+fn ignore_all_space_and_blank_lines() {
+    snapshot!(setup_diff_flags(), "d-w-lq");
+}
+
+#[test]
+fn diff_flags_kept_for_session() {
+    snapshot!(setup_diff_flags(), "d-wqd");
+}
+
+#[test]
+fn find_renames() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "old-name", "one\ntwo\nthree\n");
+    run(ctx.dir.path(), &["git", "mv", "old-name", "new-name"]);
+    snapshot!(ctx, "d-Mq");
+}
+
+#[test]
+fn ignore_all_space_prevents_staging_hunks() {
+    snapshot!(setup_diff_flags(), "d-wqjj<tab>js");
+}
+
+fn setup_files() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    fs::write(ctx.dir.child("file-a"), "one\ntwo\nthree\n").unwrap();
+    fs::write(ctx.dir.child("file-b"), "one\n2\nthree\n").unwrap();
+    ctx
+}
+
+#[test]
+fn mark_file_to_diff() {
+    snapshot!(setup_files(), "jjdf");
+}
+
+#[test]
+fn diff_marked_file() {
+    snapshot!(setup_files(), "jjdfjdf");
+}
+
+#[test]
+fn unmark_file() {
+    snapshot!(setup_files(), "jjdfdf");
+}
+
+/// Starts gitu as `gitu diff --no-index <old> <new>`, on files outside of the repository.
+fn diff_no_index(ctx: &mut TestContext, old: &str, new: &str) -> State {
+    let outside = ctx.dir.child("outside");
+    let args = Args {
+        command: Some(Commands::Diff {
+            no_index: true,
+            old: outside.join(old),
+            new: outside.join(new),
+        }),
+        ..Default::default()
+    };
+
+    ctx.init_state_with_args(crate::detached_repo(&outside).unwrap(), &args)
+}
+
+fn setup_outside() -> TestContext {
+    let ctx = TestContext::setup_init();
+    let outside = ctx.dir.child("outside");
+    fs::create_dir_all(outside.join("old/dir")).unwrap();
+    fs::create_dir_all(outside.join("new/dir")).unwrap();
+    fs::write(outside.join("a"), "one\ntwo\nthree\n").unwrap();
+    fs::write(outside.join("b"), "one\n2\nthree\n").unwrap();
+    fs::write(outside.join("old/dir/changed"), "one\ntwo\nthree\n").unwrap();
+    fs::write(outside.join("new/dir/changed"), "one\n2\nthree\n").unwrap();
+    fs::write(outside.join("old/same"), "same\n").unwrap();
+    fs::write(outside.join("new/same"), "same\n").unwrap();
+    fs::write(outside.join("old/removed"), "gone\n").unwrap();
+    fs::write(outside.join("new/added"), "new\n").unwrap();
+    ctx
+}
+
+#[test]
+fn no_index_files() {
+    let mut ctx = setup_outside();
+    let mut state = diff_no_index(&mut ctx, "a", "b");
+    state.update(&mut ctx.term, &keys("")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn no_index_dirs() {
+    let mut ctx = setup_outside();
+    let mut state = diff_no_index(&mut ctx, "old", "new");
+    state.update(&mut ctx.term, &keys("")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
@@ -0,0 +1,85 @@
+use super::*;
+use crate::state::State;
+use tui_prompts::State as _;
+
+fn setup() -> TestContext {
+    let mut ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "file-1", "");
+    commit(ctx.dir.path(), "file-2", "");
+    ctx.config().general.edit_in_gitu.enabled = true;
+    ctx
+}
+
+/// Updates until git asks for a file to be edited, or is done.
+fn await_edit(ctx: &mut TestContext, state: &mut State) {
+    let screens = state.screens.len();
+    wait_for("an edit to be asked for", || {
+        if state.pending_cmd.is_none()
+            || state.prompt.state.is_focused()
+            || state.screens.len() != screens
+        {
+            return Some(());
+        }
+
+        state.update(&mut ctx.term, &[]).unwrap();
+        None
+    });
+}
+
+#[test]
+fn message_in_prompt() {
+    let mut ctx = setup();
+    let mut state = ctx.init_async_state();
+    state.update(&mut ctx.term, &keys("jjjVV<enter>")).unwrap();
+
+    await_edit(&mut ctx, &mut state);
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn message_edited() {
+    let mut ctx = setup();
+    let mut state = ctx.init_async_state();
+    state.update(&mut ctx.term, &keys("jjjVV<enter>")).unwrap();
+
+    await_edit(&mut ctx, &mut state);
+    state
+        .update(&mut ctx.term, &keys(", again<enter>"))
+        .unwrap();
+    await_cmds(&mut ctx, &mut state);
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn message_aborted() {
+    let mut ctx = setup();
+    let mut state = ctx.init_async_state();
+    state.update(&mut ctx.term, &keys("jjjVV<enter>")).unwrap();
+
+    await_edit(&mut ctx, &mut state);
+    state.update(&mut ctx.term, &keys("<esc>")).unwrap();
+    await_cmds(&mut ctx, &mut state);
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn rebase_todo_on_screen() {
+    let mut ctx = setup();
+    let mut state = ctx.init_async_state();
+    state.update(&mut ctx.term, &keys("lljjrf")).unwrap();
+
+    await_edit(&mut ctx, &mut state);
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn rebase_todo_edited() {
+    let mut ctx = setup();
+    let mut state = ctx.init_async_state();
+    state.update(&mut ctx.term, &keys("lljjrf")).unwrap();
+
+    await_edit(&mut ctx, &mut state);
+    state.update(&mut ctx.term, &keys("d<enter>")).unwrap();
+    await_cmds(&mut ctx, &mut state);
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
@@ -64,3 +64,24 @@ fn move_next_then_parent_section() {
         .unwrap();
     insta::assert_snapshot!(ctx.redact_buffer());
 }
+
+fn setup_long_line() -> TestContext {
+    let ctx = TestContext::setup_init();
+    commit(ctx.dir.path(), "testfile", "short\n");
+    fs::write(
+        ctx.dir.child("testfile"),
+        format!("short\n{}{}\n", "x".repeat(60), "y".repeat(60)),
+    )
+    .unwrap();
+    ctx
+}
+
+#[test]
+fn scroll_right() {
+    snapshot!(setup_long_line(), "jj<tab><right>");
+}
+
+#[test]
+fn scroll_right_stops_at_longest_line() {
+    snapshot!(setup_long_line(), "jj<tab><right><right>");
+}
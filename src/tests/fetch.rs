@@ -1,11 +1,142 @@
-use super::*;
-
-#[test]
-fn fetch_from_elsewhere_prompt() {
-    snapshot!(TestContext::setup_clone(), "fe");
-}
-
-#[test]
-fn fetch_from_elsewhere() {
-    snapshot!(TestContext::setup_clone(), "feorigin<enter>");
-}
+use super::*;
+use std::path::PathBuf;
+
+#[test]
+fn fetch_from_elsewhere_prompt() {
+    snapshot!(TestContext::setup_clone(), "fe");
+}
+
+#[test]
+fn fetch_from_elsewhere() {
+    snapshot!(TestContext::setup_clone(), "feorigin<enter>");
+}
+
+#[test]
+fn auto_fetch() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.auto_fetch.enabled = true;
+    clone_and_commit(&ctx.remote_dir, "remote-file", "hello");
+
+    ctx.init_state();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn auto_fetch_remotes() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.auto_fetch.enabled = true;
+    ctx.config().general.auto_fetch.remotes = vec!["origin".into()];
+    clone_and_commit(&ctx.remote_dir, "remote-file", "hello");
+
+    ctx.init_state();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn auto_fetch_zero_interval() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.auto_fetch.enabled = true;
+    ctx.config().general.auto_fetch.interval = 0;
+    clone_and_commit(&ctx.remote_dir, "remote-file", "hello");
+
+    ctx.init_state();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn auto_fetch_waits_for_interval() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.auto_fetch.enabled = true;
+    ctx.config().general.auto_fetch.interval = 300;
+    let mut state = ctx.init_state();
+    clone_and_commit(&ctx.remote_dir, "remote-file", "hello");
+
+    state.update(&mut ctx.term, &keys("g")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+/// Has fetches from origin wait until the returned file exists, so they're still running
+/// when keys are pressed.
+fn hold_fetches(ctx: &TestContext) -> PathBuf {
+    let release = ctx.dir.path().join(".git/release-fetch");
+    let upload_pack = ctx.dir.path().join(".git/upload-pack");
+    fs::write(
+        &upload_pack,
+        format!(
+            "#!/bin/sh\nwhile [ ! -e '{}' ]; do sleep 0.01; done\nexec git-upload-pack \"$@\"\n",
+            release.display()
+        ),
+    )
+    .unwrap();
+    run(ctx.dir.path(), &["chmod", "+x", ".git/upload-pack"]);
+    run(
+        ctx.dir.path(),
+        &[
+            "git",
+            "config",
+            "remote.origin.uploadpack",
+            upload_pack.to_str().unwrap(),
+        ],
+    );
+    release
+}
+
+#[test]
+fn stage_while_auto_fetching() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.auto_fetch.enabled = true;
+    clone_and_commit(&ctx.remote_dir, "remote-file", "hello");
+    fs::write(ctx.dir.child("new-file"), "").unwrap();
+    let release = hold_fetches(&ctx);
+
+    let mut state = ctx.init_async_state();
+    assert!(state.pending_cmd.is_some());
+    fs::write(&release, "").unwrap();
+
+    state.update(&mut ctx.term, &keys("js")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn quit_while_auto_fetching() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.auto_fetch.enabled = true;
+    let release = hold_fetches(&ctx);
+
+    let mut state = ctx.init_async_state();
+    assert!(state.pending_cmd.is_some());
+    state.update(&mut ctx.term, &keys("q")).unwrap();
+    fs::write(&release, "").unwrap();
+
+    assert!(state.quit);
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn show_remote_refs_prompt() {
+    snapshot!(TestContext::setup_clone(), "fl");
+}
+
+#[test]
+fn show_remote_refs() {
+    let ctx = TestContext::setup_clone();
+    clone_and_commit(&ctx.remote_dir, "remote-file", "hello");
+    run(
+        ctx.remote_dir.path(),
+        &[
+            "git",
+            "tag",
+            "--annotate",
+            "v1.0",
+            "--message",
+            "v1.0",
+            "main",
+        ],
+    );
+    snapshot!(ctx, "fl<enter>");
+}
+
+#[test]
+fn show_remote_refs_unknown_remote() {
+    snapshot!(TestContext::setup_clone(), "flnope<enter>");
+}
@@ -0,0 +1,83 @@
+use super::*;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "tracked.rs", "");
+    commit(ctx.dir.path(), "tracked.txt", "");
+    fs::write(ctx.dir.child("tracked.rs"), "changed\n").unwrap();
+    fs::write(ctx.dir.child("tracked.txt"), "changed\n").unwrap();
+    fs::create_dir(ctx.dir.child("src")).unwrap();
+    for file in ["new.rs", "new.txt", "src/nested.rs"] {
+        run(ctx.dir.path(), &["touch", file]);
+    }
+    ctx
+}
+
+#[test]
+fn filter_files_prompt() {
+    snapshot!(setup(), "=");
+}
+
+#[test]
+fn filter_files() {
+    snapshot!(setup(), "=*.rs<enter>");
+}
+
+#[test]
+fn clear_file_filter() {
+    snapshot!(setup(), "=*.rs<enter>=<enter>");
+}
+
+#[test]
+fn stage_filtered_untracked() {
+    snapshot!(setup(), "=*.rs<enter>js");
+}
+
+#[test]
+fn stage_filtered_unstaged() {
+    snapshot!(setup(), "=*.rs<enter>j<alt+j>s");
+}
+
+#[test]
+fn discard_filtered_unstaged() {
+    snapshot!(setup(), "=*.rs<enter>j<alt+j>Ky");
+}
+
+#[test]
+fn discard_filtered_untracked() {
+    snapshot!(setup(), "=*.rs<enter>jKy");
+}
+
+#[test]
+fn ignore_filtered_untracked() {
+    let mut ctx = setup();
+    fs::write(ctx.dir.child(".gitignore"), "target").unwrap();
+    run(ctx.dir.path(), &["git", "add", ".gitignore"]);
+
+    let mut state = ctx.init_state();
+    state
+        .update(&mut ctx.term, &keys("=*.rs<enter>ji"))
+        .unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+
+    assert_eq!(
+        fs::read_to_string(ctx.dir.child(".gitignore")).unwrap(),
+        "target\n/new.rs\n/src/nested.rs\n"
+    );
+}
+
+#[test]
+fn ignore_appends_to_gitignore() {
+    let mut ctx = setup();
+    fs::write(ctx.dir.child(".gitignore"), b"caf\xe9").unwrap();
+
+    let mut state = ctx.init_state();
+    state
+        .update(&mut ctx.term, &keys("=*.rs<enter>ji"))
+        .unwrap();
+
+    assert_eq!(
+        fs::read(ctx.dir.child(".gitignore")).unwrap(),
+        b"caf\xe9\n/new.rs\n/src/nested.rs\n"
+    );
+}
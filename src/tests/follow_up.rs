@@ -0,0 +1,71 @@
+use super::*;
+
+/// Presses `keys` once the failed command's output has been handled.
+fn after_failure(mut ctx: TestContext, failing: &str, keys_after: &str) -> TestContext {
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys(failing)).unwrap();
+    state.update(&mut ctx.term, &keys(keys_after)).unwrap();
+    ctx
+}
+
+fn setup_behind_remote() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    clone_and_commit(&ctx.remote_dir, "remote-file", "hello");
+    commit(ctx.dir.path(), "local-file", "");
+    ctx
+}
+
+#[test]
+fn push_rejected() {
+    snapshot!(setup_behind_remote(), "Pp");
+}
+
+#[test]
+fn push_rejected_pull() {
+    let ctx = setup_behind_remote();
+    run(ctx.dir.path(), &["git", "config", "pull.rebase", "true"]);
+    let ctx = after_failure(ctx, "Pp", "p");
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn push_rejected_only_offered_keys() {
+    let ctx = after_failure(setup_behind_remote(), "Pp", "c");
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn push_detached_head() {
+    let ctx = TestContext::setup_clone();
+    run(ctx.dir.path(), &["git", "checkout", "--detach"]);
+    snapshot!(ctx, "Pp");
+}
+
+fn setup_no_upstream() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    run(ctx.dir.path(), &["git", "checkout", "-b", "feature"]);
+    ctx
+}
+
+#[test]
+fn push_no_upstream() {
+    snapshot!(setup_no_upstream(), "Pp");
+}
+
+#[test]
+fn push_no_upstream_set_upstream() {
+    let ctx = after_failure(setup_no_upstream(), "Pp", "u<enter>");
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn pull_unmerged() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "new-file", "hello");
+    run(ctx.dir.path(), &["git", "checkout", "-b", "other-branch"]);
+    commit(ctx.dir.path(), "new-file", "hey");
+    run(ctx.dir.path(), &["git", "checkout", "main"]);
+    commit(ctx.dir.path(), "new-file", "hi");
+    run(ctx.dir.path(), &["git", "merge", "other-branch"]);
+    snapshot!(ctx, "Fp");
+}
@@ -1,6 +1,8 @@
 pub mod repo;
 #[macro_use]
 pub mod ui;
+pub mod wait;
 
 pub use repo::*;
 pub use ui::*;
+pub use wait::*;
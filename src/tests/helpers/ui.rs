@@ -8,7 +8,12 @@ use crate::{
 };
 use crossterm::event::{Event, KeyEvent};
 use git2::Repository;
-use ratatui::{backend::TestBackend, prelude::Rect, Terminal};
+use ratatui::{
+    backend::TestBackend,
+    buffer::{Buffer, Cell},
+    prelude::Rect,
+    Terminal,
+};
 use std::{path::PathBuf, rc::Rc};
 use temp_dir::TempDir;
 
@@ -39,7 +44,7 @@ impl TestContext {
     pub fn setup_init() -> Self {
         let width = 80;
         let height = 20;
-        let term = Terminal::new(TermBackend::Test(TestBackend::new(width, height))).unwrap();
+        let term = Terminal::new(TermBackend::Test(TestBackend::new(width, height), None)).unwrap();
         let repo_ctx = RepoTestContext::setup_init();
         Self {
             term,
@@ -53,7 +58,7 @@ impl TestContext {
     pub fn setup_clone() -> Self {
         let width = 80;
         let height = 20;
-        let term = Terminal::new(TermBackend::Test(TestBackend::new(width, height))).unwrap();
+        let term = Terminal::new(TermBackend::Test(TestBackend::new(width, height), None)).unwrap();
         let repo_ctx = RepoTestContext::setup_clone();
         Self {
             term,
@@ -73,12 +78,41 @@ impl TestContext {
     }
 
     pub fn init_state_at_path(&mut self, path: PathBuf) -> State {
+        self.create_state(path, false)
+    }
+
+    /// Like `init_state`, but commands run in the background like they do outside of tests.
+    pub fn init_async_state(&mut self) -> State {
+        self.create_state(self.dir.path().to_path_buf(), true)
+    }
+
+    /// Like `init_state`, but started as gitu would be with `args`, on `repo`.
+    pub fn init_state_with_args(&mut self, repo: Repository, args: &Args) -> State {
+        self.create_state_with(repo, args, false)
+    }
+
+    fn create_state(&mut self, path: PathBuf, enable_async_cmds: bool) -> State {
+        self.create_state_with(
+            Repository::open(path).unwrap(),
+            &Args::default(),
+            enable_async_cmds,
+        )
+    }
+
+    fn create_state_with(
+        &mut self,
+        repo: Repository,
+        args: &Args,
+        enable_async_cmds: bool,
+    ) -> State {
         let mut state = State::create(
-            Rc::new(Repository::open(path).unwrap()),
+            Rc::new(repo),
             self.size,
-            &Args::default(),
+            args,
             Rc::clone(&self.config),
-            false,
+            // Two hours after the committer date used in tests, so that ages don't change over time
+            || 1708261200 + 2 * 60 * 60,
+            enable_async_cmds,
         )
         .unwrap();
 
@@ -87,11 +121,23 @@ impl TestContext {
         state
     }
 
+    /// What the terminal's clipboard was last set to.
+    pub fn clipboard(&self) -> Option<&str> {
+        let TermBackend::Test(_, clipboard) = self.term.backend() else {
+            unreachable!();
+        };
+        clipboard.as_deref()
+    }
+
     pub fn redact_buffer(&self) -> String {
-        let TermBackend::Test(test_backend) = self.term.backend() else {
+        let TermBackend::Test(test_backend, _) = self.term.backend() else {
             unreachable!();
         };
-        let mut debug_output = format!("{:?}", TestBuffer(test_backend.buffer()));
+        let mut buffer = test_backend.buffer().clone();
+        redact_temp_dir(&self.dir, "<dir>", &mut buffer);
+        redact_temp_dir(&self.remote_dir, "<remote>", &mut buffer);
+
+        let mut debug_output = format!("{:?}", TestBuffer(&buffer));
 
         [&self.dir, &self.remote_dir]
             .iter()
@@ -111,16 +157,48 @@ impl TestContext {
                     });
             });
 
-        redact_temp_dir(&self.dir, &mut debug_output);
-        redact_temp_dir(&self.remote_dir, &mut debug_output);
-
         debug_output
     }
 }
 
-fn redact_temp_dir(temp_dir: &TempDir, debug_output: &mut String) {
-    let text = temp_dir.path().to_str().unwrap();
-    *debug_output = debug_output.replace(text, &" ".repeat(text.len()));
+/// Temp dir names vary in length, so they're replaced by a fixed placeholder.
+/// This is done on the cells themselves, keeping the styles hash stable as well.
+fn redact_temp_dir(temp_dir: &TempDir, placeholder: &str, buffer: &mut Buffer) {
+    let text = temp_dir
+        .path()
+        .to_str()
+        .unwrap()
+        .chars()
+        .map(String::from)
+        .collect::<Vec<_>>();
+
+    let width = buffer.area.width as usize;
+    for row in buffer.content.chunks_mut(width) {
+        while let Some(start) = (0..row.len().saturating_sub(text.len() - 1))
+            .find(|&i| row[i..(i + text.len())].iter().map(Cell::symbol).eq(&text))
+        {
+            let template = row[start].clone();
+            let blank = row[width - 1].clone();
+
+            let redacted = row[..start]
+                .iter()
+                .cloned()
+                .chain(placeholder.chars().map(|c| {
+                    let mut cell = template.clone();
+                    cell.set_char(c);
+                    cell
+                }))
+                .chain(row[(start + text.len())..].iter().cloned())
+                .chain(std::iter::repeat(blank).map(|mut cell| {
+                    cell.set_char(' ');
+                    cell
+                }))
+                .take(width)
+                .collect::<Vec<_>>();
+
+            row.clone_from_slice(&redacted);
+        }
+    }
 }
 
 pub fn keys(input: &str) -> Vec<Event> {
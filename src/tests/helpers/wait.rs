@@ -0,0 +1,34 @@
+use super::TestContext;
+use crate::state::State;
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Polls `poll` until it has a value, failing the test if `what` doesn't happen in time.
+pub fn wait_for<T>(what: &str, mut poll: impl FnMut() -> Option<T>) -> T {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    loop {
+        if let Some(value) = poll() {
+            return value;
+        }
+
+        if Instant::now() > deadline {
+            panic!("Timed out waiting for {}", what);
+        }
+
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+/// Updates the state until the running command, and any queued after it, are done.
+pub fn await_cmds(ctx: &mut TestContext, state: &mut State) {
+    wait_for("the commands to finish", || {
+        if state.pending_cmd.is_none() {
+            return Some(());
+        }
+
+        state.update(&mut ctx.term, &[]).unwrap();
+        None
+    });
+}
@@ -0,0 +1,61 @@
+use super::*;
+use std::{fs, os::unix::fs::PermissionsExt};
+
+fn add_hook(dir: &std::path::Path, hooks_dir: &str, name: &str) {
+    let path = dir.join(hooks_dir).join(name);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(&path, "#!/bin/sh\nsleep 10\n").unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    run(
+        ctx.dir.path(),
+        &["git", "config", "core.hooksPath", ".githooks"],
+    );
+    add_hook(ctx.dir.path(), ".githooks", "pre-commit");
+    add_hook(ctx.dir.path(), ".githooks", "pre-push");
+    ctx
+}
+
+#[test]
+fn show_no_hooks() {
+    snapshot!(TestContext::setup_clone(), "H");
+}
+
+#[test]
+fn show_hooks_in_hooks_path() {
+    snapshot!(setup(), "H");
+}
+
+#[test]
+fn samples_are_not_active() {
+    let ctx = TestContext::setup_clone();
+    add_hook(ctx.dir.path(), ".git/hooks", "pre-commit.sample");
+    add_hook(ctx.dir.path(), ".git/hooks", "commit-msg");
+    snapshot!(ctx, "H");
+}
+
+#[test]
+fn commit_menu_lists_hooks() {
+    snapshot!(setup(), "c");
+}
+
+#[test]
+fn commit_menu_hooks_skipped() {
+    snapshot!(setup(), "c-n");
+}
+
+#[test]
+fn push_menu_lists_hooks() {
+    snapshot!(setup(), "P");
+}
+
+#[test]
+fn commit_menu_hooks_not_skipped() {
+    let ctx = setup();
+    add_hook(ctx.dir.path(), ".githooks", "prepare-commit-msg");
+    add_hook(ctx.dir.path(), ".githooks", "post-commit");
+    snapshot!(ctx, "c-n");
+}
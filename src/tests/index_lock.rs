@@ -0,0 +1,33 @@
+use super::*;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    run(ctx.dir.path(), &["touch", "new-file"]);
+    fs::write(ctx.dir.child(".git/index.lock"), "").unwrap();
+    ctx
+}
+
+#[test]
+fn index_locked() {
+    snapshot!(setup(), "");
+}
+
+#[test]
+fn stage_with_index_locked() {
+    snapshot!(setup(), "jjjs");
+}
+
+#[test]
+fn remove_index_lock_prompt() {
+    snapshot!(setup(), "K");
+}
+
+#[test]
+fn remove_index_lock() {
+    let mut ctx = setup();
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("Ky")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+
+    assert!(!ctx.dir.child(".git/index.lock").exists());
+}
@@ -0,0 +1,41 @@
+use super::*;
+
+#[test]
+fn info_screen() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "file", "");
+    snapshot!(ctx, "I");
+}
+
+#[test]
+fn detached_head() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "file", "");
+    run(ctx.dir.path(), &["git", "checkout", "--detach"]);
+    snapshot!(ctx, "I");
+}
+
+#[test]
+fn features() {
+    let ctx = TestContext::setup_clone();
+    run(
+        ctx.dir.path(),
+        &["git", "config", "core.sparseCheckout", "true"],
+    );
+    commit(
+        ctx.dir.path(),
+        ".gitattributes",
+        "*.bin filter=lfs diff=lfs merge=lfs -text\n",
+    );
+    snapshot!(ctx, "I");
+}
+
+#[test]
+fn show_upstream() {
+    snapshot!(TestContext::setup_clone(), "Ijjjjjj<enter>");
+}
+
+#[test]
+fn show_remote_refs() {
+    snapshot!(TestContext::setup_clone(), "Ijjjjjjjj<enter>");
+}
@@ -1,4 +1,5 @@
 use super::*;
+use temp_dir::TempDir;
 
 fn setup() -> TestContext {
     let ctx = TestContext::setup_clone();
@@ -58,6 +59,29 @@ fn grep_second_other() {
     snapshot!(setup(), "l-Fsecond<enter>omain<enter>");
 }
 
+#[test]
+fn author_filter() {
+    let ctx = setup();
+    run(
+        ctx.dir.path(),
+        &[
+            "git",
+            "commit",
+            "--allow-empty",
+            "-m",
+            "someone else's commit",
+            "--author",
+            "Someone Else <else@example.com>",
+        ],
+    );
+    snapshot!(ctx, "l-Aelse@<enter>l");
+}
+
+#[test]
+fn path_filter() {
+    snapshot!(setup(), "l--second*<enter>l");
+}
+
 #[test]
 fn log_other_prompt() {
     snapshot!(setup(), "lljlo");
@@ -77,3 +101,276 @@ fn log_other_input() {
 fn log_other_invalid() {
     snapshot!(setup(), "lo <enter>");
 }
+
+#[test]
+fn log_selected_ref() {
+    let ctx = setup();
+    run(ctx.dir.path(), &["git", "branch", "other-branch", "HEAD~1"]);
+    snapshot!(ctx, "Yjjll");
+}
+
+#[test]
+fn log_other_complete() {
+    let ctx = setup();
+    run(ctx.dir.path(), &["git", "branch", "other-branch"]);
+    run(ctx.dir.path(), &["git", "branch", "other-feature"]);
+    snapshot!(ctx, "loot<tab>");
+}
+
+#[test]
+fn log_other_complete_unique() {
+    let ctx = setup();
+    run(ctx.dir.path(), &["git", "branch", "other-branch"]);
+    snapshot!(ctx, "loot<tab><enter>");
+}
+
+fn setup_file_history() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "old-name", "one");
+    commit(ctx.dir.path(), "unrelated", "");
+    run(ctx.dir.path(), &["git", "mv", "old-name", "new-name"]);
+    run(ctx.dir.path(), &["git", "commit", "-m", "rename old-name"]);
+    fs::write(ctx.dir.child("new-name"), "one, changed").unwrap();
+    ctx
+}
+
+#[test]
+fn log_file_menu() {
+    snapshot!(setup_file_history(), "jjl");
+}
+
+#[test]
+fn log_file_follows_renames() {
+    snapshot!(setup_file_history(), "jjlf");
+}
+
+#[test]
+fn log_file_show_commit() {
+    snapshot!(setup_file_history(), "jjlfj<enter>");
+}
+
+#[test]
+fn log_file_not_on_file() {
+    snapshot!(setup_file_history(), "lf");
+}
+
+fn setup_line_history() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    let lines = |changed: &[usize]| {
+        (1..=6)
+            .map(|i| {
+                if changed.contains(&i) {
+                    format!("line {} changed\n", i)
+                } else {
+                    format!("line {}\n", i)
+                }
+            })
+            .collect::<String>()
+    };
+
+    commit(ctx.dir.path(), "file", &lines(&[]));
+    fs::write(ctx.dir.child("file"), lines(&[2])).unwrap();
+    run(ctx.dir.path(), &["git", "commit", "-am", "change line 2"]);
+    fs::write(ctx.dir.child("file"), lines(&[2, 5])).unwrap();
+    run(ctx.dir.path(), &["git", "commit", "-am", "change line 5"]);
+    commit(ctx.dir.path(), "unrelated", "");
+    fs::write(
+        ctx.dir.child("file"),
+        lines(&[2]).replace("line 5", "line five"),
+    )
+    .unwrap();
+    ctx
+}
+
+#[test]
+fn log_lines() {
+    snapshot!(setup_line_history(), "jj<tab>jlL");
+}
+
+#[test]
+fn log_lines_of_file() {
+    snapshot!(setup_line_history(), "jjlL5,6<enter>");
+}
+
+#[test]
+fn log_lines_without_target() {
+    snapshot!(setup_line_history(), "lL");
+}
+
+/// Signs a commit with a trusted SSH key, then one with a key missing from the allowed signers.
+fn setup_signed() -> (TestContext, TempDir) {
+    let ctx = setup();
+    let keys = TempDir::new().unwrap();
+    run(
+        keys.path(),
+        &[
+            "ssh-keygen",
+            "-q",
+            "-t",
+            "ed25519",
+            "-N",
+            "",
+            "-f",
+            "trusted",
+        ],
+    );
+    run(
+        keys.path(),
+        &[
+            "ssh-keygen",
+            "-q",
+            "-t",
+            "ed25519",
+            "-N",
+            "",
+            "-f",
+            "stranger",
+        ],
+    );
+    let trusted = fs::read_to_string(keys.path().join("trusted.pub")).unwrap();
+    let allowed_signers = keys.path().join("allowed_signers");
+    fs::write(&allowed_signers, format!("committer@email.com {}", trusted)).unwrap();
+
+    let dir = ctx.dir.path();
+    run(dir, &["git", "config", "gpg.format", "ssh"]);
+    run(
+        dir,
+        &[
+            "git",
+            "config",
+            "gpg.ssh.allowedSignersFile",
+            allowed_signers.to_str().unwrap(),
+        ],
+    );
+    for (key, message) in [
+        ("trusted", "good signature"),
+        ("stranger", "untrusted signature"),
+    ] {
+        let key = keys.path().join(key);
+        run(
+            dir,
+            &[
+                "git",
+                "-c",
+                &format!("user.signingkey={}", key.to_str().unwrap()),
+                "commit",
+                "--allow-empty",
+                "-S",
+                "-m",
+                message,
+            ],
+        );
+    }
+
+    (ctx, keys)
+}
+
+#[test]
+fn signature_marks() {
+    let (ctx, _keys) = setup_signed();
+    snapshot!(ctx, "ll");
+}
+
+#[test]
+fn show_good_signature() {
+    let (ctx, _keys) = setup_signed();
+    snapshot!(ctx, "llj<enter>");
+}
+
+#[test]
+fn custom_format() {
+    let mut ctx = setup();
+    ctx.config().format.log = "{date} {hash} {author}: {subject}".to_string();
+    snapshot!(ctx, "ll");
+}
+
+#[test]
+fn graph() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "file-a", "");
+    run(ctx.dir.path(), &["git", "checkout", "-b", "feature"]);
+    commit(ctx.dir.path(), "file-b", "");
+    commit(ctx.dir.path(), "file-d", "");
+    run(ctx.dir.path(), &["git", "checkout", "main"]);
+    commit(ctx.dir.path(), "file-c", "");
+    run(
+        ctx.dir.path(),
+        &["git", "merge", "--no-ff", "feature", "-m", "merge feature"],
+    );
+
+    snapshot!(ctx, "l-gl");
+}
+
+#[test]
+fn marks_ahead() {
+    snapshot!(setup(), "ll");
+}
+
+#[test]
+fn marks_diverged() {
+    let ctx = setup();
+    clone_and_commit(&ctx.remote_dir, "remote-file", "hello");
+    run(ctx.dir.path(), &["git", "fetch"]);
+    snapshot!(ctx, "loorigin/main<enter>");
+}
+
+/// Replaces the third commit, grafts the second onto the initial one, skipping the first,
+/// and makes the initial one the boundary of a shallow clone.
+fn setup_history_rewrites() -> TestContext {
+    let ctx = setup();
+    let repo = git2::Repository::open(ctx.dir.path()).unwrap();
+    let oid = |rev| repo.revparse_single(rev).unwrap().id().to_string();
+    let (third, second, initial) = (oid("HEAD"), oid("HEAD~1"), oid("origin/main"));
+
+    run(ctx.dir.path(), &["git", "replace", &third, &initial]);
+    fs::write(
+        ctx.dir.child(".git/info/grafts"),
+        format!("{} {}\n", second, initial),
+    )
+    .unwrap();
+    fs::write(ctx.dir.child(".git/shallow"), format!("{}\n", initial)).unwrap();
+    ctx
+}
+
+#[test]
+fn history_rewrites() {
+    snapshot!(setup_history_rewrites(), "ll");
+}
+
+#[test]
+fn show_grafted() {
+    snapshot!(setup_history_rewrites(), "llj<enter>");
+}
+
+fn setup_long_history() -> TestContext {
+    let mut ctx = TestContext::setup_clone();
+    for i in 1..=40 {
+        commit(ctx.dir.path(), &format!("file-{:02}", i), "");
+    }
+    ctx.config().general.log_page_size = 5;
+    ctx
+}
+
+#[test]
+fn pages_fill_the_screen() {
+    snapshot!(setup_long_history(), "ll");
+}
+
+#[test]
+fn pages_load_when_scrolling() {
+    let mut ctx = setup_long_history();
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("ll")).unwrap();
+    for _ in 0..38 {
+        state.update(&mut ctx.term, &keys("j")).unwrap();
+    }
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn detached_head_decoration() {
+    let ctx = setup();
+    run(ctx.dir.path(), &["git", "checkout", "--detach", "HEAD~1"]);
+    run(ctx.dir.path(), &["git", "tag", "v1", "HEAD~1"]);
+    snapshot!(ctx, "ll");
+}
@@ -0,0 +1,123 @@
+use super::*;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    run(ctx.dir.path(), &["git", "checkout", "-b", "other-branch"]);
+    commit(ctx.dir.path(), "other-file", "hey");
+    run(ctx.dir.path(), &["git", "checkout", "main"]);
+    ctx
+}
+
+fn setup_conflict() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "new-file", "hello");
+    run(ctx.dir.path(), &["git", "checkout", "-b", "other-branch"]);
+    commit(ctx.dir.path(), "new-file", "hey");
+    run(ctx.dir.path(), &["git", "checkout", "main"]);
+    commit(ctx.dir.path(), "new-file", "hi");
+    ctx
+}
+
+#[test]
+fn merge_menu() {
+    snapshot!(setup(), "m");
+}
+
+#[test]
+fn merge_prompt() {
+    snapshot!(setup(), "mm");
+}
+
+#[test]
+fn merge_fast_forward() {
+    snapshot!(setup(), "mmother-branch<enter>");
+}
+
+#[test]
+fn merge_no_ff() {
+    snapshot!(setup(), "m-nmother-branch<enter>");
+}
+
+#[test]
+fn merge_squash() {
+    snapshot!(setup(), "m-smother-branch<enter>");
+}
+
+#[test]
+fn merge_ff_only_diverged() {
+    snapshot!(setup_conflict(), "m-fmother-branch<enter>");
+}
+
+#[test]
+fn merge_conflict() {
+    snapshot!(setup_conflict(), "mmother-branch<enter>");
+}
+
+#[test]
+fn merge_abort() {
+    snapshot!(setup_conflict(), "mmother-branch<enter>ma");
+}
+
+fn setup_conflicts() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    let lines = |a: &str, b: &str| format!("{}\n2\n3\n4\n5\n6\n7\n8\n9\n{}\n", a, b);
+    commit(ctx.dir.path(), "new-file", &lines("1", "10"));
+    run(ctx.dir.path(), &["git", "checkout", "-b", "other-branch"]);
+    commit(ctx.dir.path(), "new-file", &lines("theirs 1", "theirs 10"));
+    run(ctx.dir.path(), &["git", "checkout", "main"]);
+    commit(ctx.dir.path(), "new-file", &lines("ours 1", "ours 10"));
+    run(ctx.dir.path(), &["git", "merge", "other-branch"]);
+    ctx
+}
+
+#[test]
+fn show_conflicts() {
+    snapshot!(setup_conflicts(), "jj<enter>");
+}
+
+#[test]
+fn show_conflicts_diff3() {
+    let ctx = setup_conflicts();
+    run(
+        ctx.dir.path(),
+        &["git", "checkout", "--conflict=diff3", "new-file"],
+    );
+    snapshot!(ctx, "jj<enter>");
+}
+
+#[test]
+fn conflict_take_ours() {
+    snapshot!(setup_conflicts(), "jj<enter>jmo");
+}
+
+#[test]
+fn conflict_take_theirs() {
+    snapshot!(setup_conflicts(), "jj<enter>jjmt");
+}
+
+#[test]
+fn conflict_take_both() {
+    let mut ctx = setup_conflicts();
+    let mut state = ctx.init_state();
+    state
+        .update(&mut ctx.term, &keys("jj<enter>jmojmt"))
+        .unwrap();
+
+    assert_eq!(
+        fs::read_to_string(ctx.dir.path().join("new-file")).unwrap(),
+        "ours 1\n2\n3\n4\n5\n6\n7\n8\n9\ntheirs 10\n"
+    );
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn conflict_mergetool() {
+    let ctx = setup_conflicts();
+    let config = |key, value| run(ctx.dir.path(), &["git", "config", key, value]);
+    config("merge.tool", "theirs");
+    config("mergetool.theirs.cmd", "cp \"$REMOTE\" \"$MERGED\"");
+    config("mergetool.theirs.trustExitCode", "true");
+    config("mergetool.keepBackup", "false");
+
+    snapshot!(ctx, "jjmM");
+}
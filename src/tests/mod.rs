@@ -2,21 +2,46 @@ use std::fs;
 
 #[macro_use]
 mod helpers;
+mod apply;
 mod arg;
+mod bindings;
+mod bisect;
+mod clean;
+mod collapse;
+mod commit;
+mod diff;
 mod discard;
+mod edit_in_gitu;
 mod editor;
 mod fetch;
+mod filter_files;
+mod follow_up;
+mod hooks;
+mod index_lock;
+mod info;
 mod log;
+mod merge;
+mod open_in_forge;
+mod patch;
 mod pull;
+mod pull_requests;
 mod push;
 mod quit;
 mod rebase;
+mod recent_commits;
 mod reset;
+mod reverse;
+mod search;
 mod stage;
 mod stash;
+mod submodule;
 mod unstage;
+mod virtual_terminal;
+mod whitespace;
+mod worktree;
+mod yank;
 
-use helpers::{clone_and_commit, commit, keys, run, TestContext};
+use helpers::{await_cmds, clone_and_commit, commit, keys, run, wait_for, TestContext};
 
 #[test]
 fn no_repo() {
@@ -52,6 +77,23 @@ fn new_file() {
     insta::assert_snapshot!(ctx.redact_buffer());
 }
 
+#[test]
+fn visit_file_with_viewer() {
+    let mut ctx = TestContext::setup_init();
+    run(ctx.dir.path(), &["touch", "new-file"]);
+    ctx.config().general.file_viewer = Some("git add".into());
+    snapshot!(ctx, "jj<enter>");
+}
+
+#[test]
+fn visit_deleted_file() {
+    let mut ctx = TestContext::setup_init();
+    ctx.config().general.file_viewer = Some("touch".into());
+    commit(ctx.dir.path(), "deleted-file", "content\n");
+    run(ctx.dir.path(), &["rm", "deleted-file"]);
+    snapshot!(ctx, "jj<enter>");
+}
+
 #[test]
 fn unstaged_changes() {
     let ctx = TestContext::setup_init();
@@ -85,6 +127,46 @@ fn show() {
     snapshot!(ctx, "ll<enter>");
 }
 
+#[test]
+fn show_wraps_message() {
+    let ctx = TestContext::setup_clone();
+    fs::write(ctx.dir.child("file"), "hello\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "file"]);
+    run(
+        ctx.dir.path(),
+        &[
+            "git",
+            "commit",
+            "-m",
+            "add file",
+            "-m",
+            "This paragraph is long enough that it has to be wrapped to fit within the width of the terminal.\n\
+             - A list item that is long enough to be wrapped, with the rest of it lined up under its text.",
+        ],
+    );
+    snapshot!(ctx, "ll<enter>");
+}
+
+#[test]
+fn show_rev() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "firstfile", "This should be visible\n");
+    commit(ctx.dir.path(), "secondfile", "");
+    snapshot!(ctx, "Jmain~1<enter>");
+}
+
+#[test]
+fn show_rev_complete() {
+    let ctx = TestContext::setup_clone();
+    run(ctx.dir.path(), &["git", "tag", "a-tag"]);
+    snapshot!(ctx, "Ja-<tab>");
+}
+
+#[test]
+fn show_rev_invalid() {
+    snapshot!(TestContext::setup_clone(), "Jdoesntexist<enter>");
+}
+
 #[test]
 fn rebase_conflict() {
     let mut ctx = TestContext::setup_clone();
@@ -120,6 +202,64 @@ fn merge_conflict() {
     insta::assert_snapshot!(ctx.redact_buffer());
 }
 
+#[test]
+fn cherry_pick_conflict() {
+    let mut ctx = setup_cherry_pick_conflict();
+    ctx.init_state();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+fn setup_cherry_pick_conflict() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "new-file", "hello");
+
+    run(ctx.dir.path(), &["git", "checkout", "-b", "other-branch"]);
+    commit(ctx.dir.path(), "new-file", "hey");
+
+    run(ctx.dir.path(), &["git", "checkout", "main"]);
+    commit(ctx.dir.path(), "new-file", "hi");
+
+    run(ctx.dir.path(), &["git", "cherry-pick", "other-branch"]);
+    ctx
+}
+
+#[test]
+fn cherry_pick_abort() {
+    snapshot!(setup_cherry_pick_conflict(), "Aa");
+}
+
+#[test]
+fn cherry_pick_continue() {
+    let ctx = setup_cherry_pick_conflict();
+    fs::write(ctx.dir.child("new-file"), "hey").unwrap();
+    run(ctx.dir.path(), &["git", "add", "new-file"]);
+    snapshot!(ctx, "Ac");
+}
+
+#[test]
+fn cherry_pick_truncated_head() {
+    let mut ctx = TestContext::setup_clone();
+    fs::write(ctx.dir.child(".git/CHERRY_PICK_HEAD"), "abc\n").unwrap();
+
+    ctx.init_state();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn cherry_pick_menu() {
+    let ctx = TestContext::setup_clone();
+    snapshot!(ctx, "llA");
+}
+
+#[test]
+fn cherry_pick_commit() {
+    let ctx = TestContext::setup_clone();
+    run(ctx.dir.path(), &["git", "checkout", "-b", "other-branch"]);
+    commit(ctx.dir.path(), "picked-file", "");
+    run(ctx.dir.path(), &["git", "checkout", "main"]);
+    snapshot!(ctx, "AAother-branch<enter>");
+}
+
 #[test]
 fn revert_conflict() {
     let mut ctx = TestContext::setup_clone();
@@ -220,7 +360,12 @@ mod show_refs {
     #[test]
     fn show_refs_at_remote_branch() {
         let ctx = TestContext::setup_clone();
-        snapshot!(ctx, "Yjjjjbb<enter>Y");
+        // Checking out a remote branch from gitu creates a tracking branch instead
+        run(
+            ctx.dir.path(),
+            &["git", "checkout", "--detach", "origin/main"],
+        );
+        snapshot!(ctx, "Y");
     }
 
     #[test]
@@ -229,11 +374,117 @@ mod show_refs {
         run(ctx.dir.path(), &["git", "tag", "v1.0"]);
         snapshot!(ctx, "Yjjjjjjbb<enter>Y");
     }
+
+    #[test]
+    fn show_refs_checkout_tag() {
+        let ctx = TestContext::setup_clone();
+        run(ctx.dir.path(), &["git", "tag", "v1.0"]);
+        snapshot!(ctx, "Yjjjjjjbt");
+    }
+
+    #[test]
+    fn show_refs_delete_tag() {
+        let ctx = TestContext::setup_clone();
+        run(ctx.dir.path(), &["git", "tag", "v1.0"]);
+        snapshot!(ctx, "YjjjjjjKy");
+    }
+
+    #[test]
+    fn show_refs_push_tag() {
+        let ctx = TestContext::setup_clone();
+        run(ctx.dir.path(), &["git", "tag", "v1.0"]);
+        snapshot!(ctx, "YjjjjjjPt<enter>");
+    }
+
+    #[test]
+    fn show_refs_yank_tag() {
+        let ctx = TestContext::setup_clone();
+        run(ctx.dir.path(), &["git", "tag", "v1.0"]);
+        snapshot!(ctx, "Yjjjjjjy");
+    }
+
+    fn setup_tags() -> TestContext {
+        let ctx = TestContext::setup_clone();
+        run(ctx.dir.path(), &["git", "tag", "v1.10.0"]);
+        commit(ctx.dir.path(), "second-file", "");
+        run(ctx.dir.path(), &["git", "tag", "-m", "Release", "v1.9.0"]);
+        run(ctx.dir.path(), &["git", "tag", "v2.0.0"]);
+        ctx
+    }
+
+    #[test]
+    fn show_refs_tags() {
+        snapshot!(setup_tags(), "Y");
+    }
+
+    #[test]
+    fn show_refs_tags_sorted() {
+        let mut ctx = setup_tags();
+        ctx.config().general.tag_sort = Some("-v:refname".to_string());
+        snapshot!(ctx, "Y");
+    }
+
+    #[test]
+    fn show_refs_tags_invalid_sort() {
+        let mut ctx = setup_tags();
+        ctx.config().general.tag_sort = Some("no-such-key".to_string());
+        snapshot!(ctx, "Y");
+    }
+
+    #[test]
+    fn failed_screen_keeps_screens() {
+        let mut ctx = setup_tags();
+        ctx.config().general.tag_sort = Some("no-such-key".to_string());
+        snapshot!(ctx, "llY");
+    }
+
+    #[test]
+    fn filter_tags() {
+        snapshot!(setup_tags(), "Tv1.<enter>");
+    }
+
+    #[test]
+    fn show_refs_upstream_ahead_behind() {
+        let ctx = TestContext::setup_clone();
+        clone_and_commit(&ctx.remote_dir, "remote-file", "hello");
+        commit(ctx.dir.path(), "local-file", "");
+        run(ctx.dir.path(), &["git", "fetch"]);
+        run(
+            ctx.dir.path(),
+            &["git", "branch", "--track", "even", "origin/main"],
+        );
+        run(ctx.dir.path(), &["git", "branch", "untracked"]);
+        snapshot!(ctx, "Y");
+    }
 }
 
 mod checkout {
     use super::*;
 
+    fn setup_diverged() -> TestContext {
+        let ctx = TestContext::setup_clone();
+        clone_and_commit(&ctx.remote_dir, "remote-file", "hello\nworld\n");
+        run(ctx.dir.path(), &["git", "fetch"]);
+        commit(ctx.dir.path(), "local-file", "hi\n");
+        commit(ctx.dir.path(), "initial-file", "hello, changed\n");
+        ctx
+    }
+
+    #[test]
+    pub(crate) fn compare_branches_prompt() {
+        snapshot!(setup_diverged(), "bC");
+    }
+
+    #[test]
+    pub(crate) fn compare_branches_with_upstream() {
+        snapshot!(setup_diverged(), "bC<enter><enter>");
+    }
+
+    #[test]
+    pub(crate) fn compare_branches_invalid() {
+        snapshot!(setup_diverged(), "bC<enter>no-such-branch<enter>");
+    }
+
     #[test]
     pub(crate) fn checkout_menu() {
         let ctx = TestContext::setup_clone();
@@ -255,10 +506,153 @@ mod checkout {
         snapshot!(ctx, "Yjjbbhi<enter>");
     }
 
+    fn setup_other_branch() -> TestContext {
+        let ctx = TestContext::setup_clone();
+        run(ctx.dir.path(), &["git", "branch", "other-branch"]);
+        ctx
+    }
+
+    #[test]
+    pub(crate) fn switch_to_selected_branch() {
+        snapshot!(setup_other_branch(), "Yjjbs");
+    }
+
+    #[test]
+    pub(crate) fn rename_branch_prompt() {
+        snapshot!(setup_other_branch(), "Yjjbm");
+    }
+
+    #[test]
+    pub(crate) fn rename_branch() {
+        snapshot!(setup_other_branch(), "Yjjbmrenamed<enter>");
+    }
+
+    #[test]
+    pub(crate) fn delete_merged_branch() {
+        snapshot!(setup_other_branch(), "Yjjbky");
+    }
+
+    fn setup_unmerged_branch() -> TestContext {
+        let ctx = setup_other_branch();
+        run(ctx.dir.path(), &["git", "checkout", "other-branch"]);
+        commit(ctx.dir.path(), "unmerged-file", "");
+        run(ctx.dir.path(), &["git", "checkout", "main"]);
+        ctx
+    }
+
+    #[test]
+    pub(crate) fn delete_unmerged_branch_prompt() {
+        snapshot!(setup_unmerged_branch(), "Yjjbky");
+    }
+
+    #[test]
+    pub(crate) fn delete_unmerged_branch_force() {
+        snapshot!(setup_unmerged_branch(), "Yjjbkyy");
+    }
+
+    #[test]
+    pub(crate) fn delete_remote_branch() {
+        snapshot!(TestContext::setup_clone(), "Yjjjjjbky");
+    }
+
+    fn setup_remote_branch() -> TestContext {
+        let ctx = TestContext::setup_clone();
+        run(ctx.dir.path(), &["git", "push", "origin", "main:feature"]);
+        run(ctx.dir.path(), &["git", "fetch"]);
+        ctx
+    }
+
+    #[test]
+    pub(crate) fn checkout_remote_branch_prompt() {
+        snapshot!(setup_remote_branch(), "bborigin/feature<enter>");
+    }
+
+    #[test]
+    pub(crate) fn checkout_remote_branch() {
+        snapshot!(setup_remote_branch(), "bborigin/feature<enter><enter>");
+    }
+
+    #[test]
+    pub(crate) fn checkout_remote_branch_renamed() {
+        snapshot!(
+            setup_remote_branch(),
+            "bborigin/feature<enter>my-feature<enter>"
+        );
+    }
+
+    #[test]
+    pub(crate) fn switch_to_remote_branch() {
+        snapshot!(setup_remote_branch(), "Yjjjjbs<enter>");
+    }
+
+    #[test]
+    pub(crate) fn checkout_remote_branch_name_exists_prompt() {
+        snapshot!(setup_remote_branch(), "bborigin/main<enter>");
+    }
+
+    #[test]
+    pub(crate) fn checkout_remote_branch_name_exists() {
+        snapshot!(
+            setup_remote_branch(),
+            "bborigin/main<enter>main-copy<enter>"
+        );
+    }
+
     #[test]
     pub(crate) fn checkout_new_branch() {
         snapshot!(TestContext::setup_clone(), "bcf<esc>bcx<enter>");
     }
+
+    fn setup_gone_upstream() -> TestContext {
+        let ctx = TestContext::setup_clone();
+        run(ctx.dir.path(), &["git", "branch", "feature"]);
+        run(ctx.dir.path(), &["git", "push", "-u", "origin", "feature"]);
+        run(
+            ctx.dir.path(),
+            &["git", "push", "origin", "--delete", "feature"],
+        );
+        run(ctx.dir.path(), &["git", "branch", "local-only"]);
+        ctx
+    }
+
+    #[test]
+    pub(crate) fn show_refs_gone_upstream() {
+        snapshot!(setup_gone_upstream(), "Y");
+    }
+
+    #[test]
+    pub(crate) fn delete_gone_branches_prompt() {
+        snapshot!(setup_gone_upstream(), "bx");
+    }
+
+    #[test]
+    pub(crate) fn delete_gone_branches() {
+        snapshot!(setup_gone_upstream(), "bxyY");
+    }
+
+    #[test]
+    pub(crate) fn delete_gone_branches_keeps_unmerged() {
+        let ctx = setup_gone_upstream();
+        run(ctx.dir.path(), &["git", "checkout", "-b", "unmerged"]);
+        commit(ctx.dir.path(), "unmerged-file", "");
+        run(ctx.dir.path(), &["git", "push", "-u", "origin", "unmerged"]);
+        run(
+            ctx.dir.path(),
+            &["git", "push", "origin", "--delete", "unmerged"],
+        );
+        run(ctx.dir.path(), &["git", "checkout", "main"]);
+        snapshot!(ctx, "bxy");
+    }
+
+    #[test]
+    pub(crate) fn delete_gone_branches_none() {
+        snapshot!(TestContext::setup_clone(), "bx");
+    }
+
+    #[test]
+    pub(crate) fn set_upstream() {
+        snapshot!(setup_gone_upstream(), "Yjbuorigin/m<tab><enter>");
+    }
 }
 
 #[test]
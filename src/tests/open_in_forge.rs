@@ -0,0 +1,110 @@
+use super::*;
+use std::process::Command;
+
+fn setup_github_clone() -> TestContext {
+    let mut ctx = TestContext::setup_clone();
+    run(
+        ctx.dir.path(),
+        &[
+            "git",
+            "remote",
+            "set-url",
+            "origin",
+            "git@github.com:altsem/gitu.git",
+        ],
+    );
+
+    // Stands in for a browser, keeping the URL it's opened with
+    fs::write(
+        ctx.dir.path().join(".git/browser"),
+        "#!/bin/sh\necho \"$1\" > .git/opened-url\n",
+    )
+    .unwrap();
+    run(ctx.dir.path(), &["chmod", "+x", ".git/browser"]);
+    ctx.config().general.browser = Some(".git/browser".into());
+    ctx
+}
+
+/// The URL the browser was opened with, once it's been started in the background.
+fn opened_url(ctx: &TestContext) -> String {
+    let file = ctx.dir.path().join(".git/opened-url");
+    wait_for("the browser to be opened", || {
+        fs::read_to_string(&file)
+            .ok()
+            .filter(|url| url.ends_with('\n'))
+            .map(|url| url.trim_end().to_string())
+    })
+}
+
+#[test]
+fn open_commit() {
+    let ctx = setup_github_clone();
+    snapshot!(ctx, "jjO");
+}
+
+#[test]
+fn open_file() {
+    let ctx = setup_github_clone();
+    commit(ctx.dir.path(), "file", "one\n");
+    fs::write(ctx.dir.child("file"), "two\n").unwrap();
+    snapshot!(ctx, "jjO");
+}
+
+#[test]
+fn open_hunk_line() {
+    let mut ctx = setup_github_clone();
+    commit(ctx.dir.path(), "file", "one\ntwo\nthree\n");
+    fs::write(ctx.dir.child("file"), "one\nthree\n").unwrap();
+    let mut state = ctx.init_state();
+    state
+        .update(&mut ctx.term, &keys("jj<tab><ctrl+j><ctrl+j>O"))
+        .unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+    assert!(opened_url(&ctx).ends_with("/file#L2"));
+}
+
+#[test]
+fn open_without_forge_remote() {
+    let ctx = TestContext::setup_clone();
+    snapshot!(ctx, "jjO");
+}
+
+#[test]
+fn open_added_line_upstream() {
+    let mut ctx = setup_github_clone();
+    commit(ctx.dir.path(), "file", "one\ntwo\n");
+    // Like a push, which the fake remote URL doesn't allow
+    run(
+        ctx.dir.path(),
+        &["git", "update-ref", "refs/remotes/origin/main", "HEAD"],
+    );
+    commit(ctx.dir.path(), "file", "zero\none\ntwo\n");
+    fs::write(ctx.dir.child("file"), "zero\none\ntwo\nthree\n").unwrap();
+
+    let mut state = ctx.init_state();
+    state
+        .update(&mut ctx.term, &keys("jj<tab><ctrl+j><ctrl+j>O"))
+        .unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+
+    let upstream = Command::new("git")
+        .args(["rev-parse", "origin/main"])
+        .current_dir(ctx.dir.path())
+        .output()
+        .unwrap();
+    let upstream = String::from_utf8(upstream.stdout).unwrap();
+    assert_eq!(
+        opened_url(&ctx),
+        format!(
+            "https://github.com/altsem/gitu/blob/{}/file#L4",
+            upstream.trim()
+        )
+    );
+}
+
+#[test]
+fn open_with_empty_browser() {
+    let mut ctx = setup_github_clone();
+    ctx.config().general.browser = Some(" ".into());
+    snapshot!(ctx, "jjO");
+}
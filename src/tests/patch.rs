@@ -0,0 +1,56 @@
+use super::*;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "testfile", "testing\ntesttest\n");
+    fs::write(ctx.dir.child("testfile"), "test\ntesttest\n").unwrap();
+    ctx
+}
+
+#[test]
+fn patch_menu() {
+    snapshot!(setup(), "jjW");
+}
+
+#[test]
+fn save_patch_prompt() {
+    snapshot!(setup(), "jjWw");
+}
+
+#[test]
+fn save_file_patch() {
+    let mut ctx = setup();
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("jjWw<enter>")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+
+    let patch = fs::read_to_string(ctx.dir.child("gitu.patch")).unwrap();
+    insta::assert_snapshot!(patch);
+}
+
+#[test]
+fn save_staged_patch() {
+    let mut ctx = setup();
+    run(ctx.dir.path(), &["git", "add", "testfile"]);
+    let mut state = ctx.init_state();
+    state
+        .update(&mut ctx.term, &keys("jWwstaged.patch<enter>"))
+        .unwrap();
+
+    let patch = fs::read_to_string(ctx.dir.child("staged.patch")).unwrap();
+    insta::assert_snapshot!(patch);
+}
+
+#[test]
+fn save_commit_patch() {
+    let mut ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "firstfile", "hello\n");
+    let mut state = ctx.init_state();
+    state
+        .update(&mut ctx.term, &keys("llWwcommit.patch<enter>"))
+        .unwrap();
+
+    let patch = fs::read_to_string(ctx.dir.child("commit.patch")).unwrap();
+    assert!(patch.starts_with("From "));
+    assert!(patch.contains("Subject: [PATCH] add firstfile"));
+}
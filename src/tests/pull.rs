@@ -9,3 +9,34 @@ fn pull_from_elsewhere_prompt() {
 fn pull_from_elsewhere() {
     snapshot!(TestContext::setup_clone(), "Feorigin<enter>");
 }
+
+#[test]
+fn fast_forward() {
+    let ctx = TestContext::setup_clone();
+    clone_and_commit(&ctx.remote_dir, "remote-file", "hello");
+    run(ctx.dir.path(), &["git", "fetch"]);
+    snapshot!(ctx, "j<enter>");
+}
+
+#[test]
+fn fast_forward_not_offered_when_diverged() {
+    let mut ctx = TestContext::setup_clone();
+    clone_and_commit(&ctx.remote_dir, "remote-file", "hello");
+    run(ctx.dir.path(), &["git", "fetch"]);
+    commit(ctx.dir.path(), "local-file", "hi");
+
+    ctx.init_state();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn upstream_commits() {
+    let mut ctx = TestContext::setup_clone();
+    clone_and_commit(&ctx.remote_dir, "remote-file", "hello");
+    run(ctx.dir.path(), &["git", "fetch"]);
+    commit(ctx.dir.path(), "local-file", "hi");
+    ctx.config().general.upstream_commits.enabled = true;
+
+    ctx.init_state();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
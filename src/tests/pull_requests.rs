@@ -0,0 +1,332 @@
+use super::*;
+use temp_dir::TempDir;
+
+/// A GitHub origin, with its API served from files.
+fn setup() -> (TestContext, TempDir) {
+    let mut ctx = TestContext::setup_clone();
+    run(
+        ctx.dir.path(),
+        &[
+            "git",
+            "remote",
+            "set-url",
+            "origin",
+            "git@github.com:o/r.git",
+        ],
+    );
+
+    let api = TempDir::new().unwrap();
+    let repo = api.child("repos/o/r");
+    fs::create_dir_all(&repo).unwrap();
+    fs::write(
+        repo.join("pulls"),
+        r#"[
+            {"number": 2, "title": "Add a feature", "user": {"login": "alice"}, "head": {"ref": "feature"}},
+            {"number": 1, "title": "Fix a bug", "user": {"login": "bob"}, "head": {"ref": "fix"}}
+        ]"#,
+    )
+    .unwrap();
+    fs::write(
+        api.child("graphql"),
+        rollups(&[
+            (2, Some("SUCCESS")),
+            (1, Some("FAILURE")),
+            (3, Some("SUCCESS")),
+        ]),
+    )
+    .unwrap();
+
+    ctx.config().forge.github_token = Some("token".into());
+    ctx.config().forge.api_url = Some(format!("file://{}", api.path().display()));
+    (ctx, api)
+}
+
+/// A GraphQL response with the check rollup state of each pull request.
+fn rollups(states: &[(u64, Option<&str>)]) -> String {
+    let nodes = states
+        .iter()
+        .map(|(number, state)| {
+            let rollup = state.map_or("null".into(), |state| format!(r#"{{"state": "{}"}}"#, state));
+            format!(
+                r#"{{"number": {}, "commits": {{"nodes": [{{"commit": {{"statusCheckRollup": {}}}}}]}}}}"#,
+                number, rollup
+            )
+        })
+        .collect::<Vec<_>>();
+
+    format!(
+        r#"{{"data": {{"repository": {{"pullRequests": {{"nodes": [{}]}}}}}}}}"#,
+        nodes.join(", ")
+    )
+}
+
+#[test]
+fn show_pull_requests() {
+    let (ctx, _api) = setup();
+    snapshot!(ctx, "R");
+}
+
+#[test]
+fn pull_requests_without_ci_state() {
+    let (ctx, api) = setup();
+    fs::write(
+        api.child("graphql"),
+        rollups(&[(2, Some("PENDING")), (1, None)]),
+    )
+    .unwrap();
+    snapshot!(ctx, "R");
+}
+
+#[test]
+fn pull_requests_with_ci_state_unavailable() {
+    let (mut ctx, api) = setup();
+    ctx.config().forge.github_token = None;
+    fs::remove_file(api.child("graphql")).unwrap();
+    snapshot!(ctx, "R");
+}
+
+#[test]
+fn pull_requests_fetched_again_only_on_refresh() {
+    let (mut ctx, api) = setup();
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("R")).unwrap();
+    fs::write(
+        api.child("repos/o/r/pulls"),
+        r#"[{"number": 3, "title": "Another", "user": {"login": "carol"}, "head": {"ref": "other"}}]"#,
+    )
+    .unwrap();
+
+    // Like after a command has run
+    state.screen_mut().update().unwrap();
+    state.update(&mut ctx.term, &[]).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+
+    state.update(&mut ctx.term, &keys("g")).unwrap();
+    insta::assert_snapshot!("pull_requests_refreshed", ctx.redact_buffer());
+}
+
+#[test]
+fn pull_requests_on_unsupported_forge() {
+    let ctx = TestContext::setup_clone();
+    run(
+        ctx.dir.path(),
+        &[
+            "git",
+            "remote",
+            "set-url",
+            "origin",
+            "git@codeberg.org:o/r.git",
+        ],
+    );
+    snapshot!(ctx, "R");
+}
+
+#[test]
+fn merge_requests_on_gitlab() {
+    let mut ctx = TestContext::setup_clone();
+    run(
+        ctx.dir.path(),
+        &[
+            "git",
+            "remote",
+            "set-url",
+            "origin",
+            "git@gitlab.com:o/r.git",
+        ],
+    );
+
+    let api = TempDir::new().unwrap();
+    let project = api.child("projects/o/r");
+    fs::create_dir_all(&project).unwrap();
+    fs::write(
+        project.join("merge_requests"),
+        r#"[
+            {"iid": 2, "title": "Add a feature", "author": {"username": "alice"}, "source_branch": "feature"},
+            {"iid": 1, "title": "Fix a bug", "author": {"username": "bob"}, "source_branch": "fix"}
+        ]"#,
+    )
+    .unwrap();
+    fs::write(
+        api.child("graphql"),
+        r#"{"data": {"project": {"mergeRequests": {"nodes": [
+            {"iid": "2", "headPipeline": {"status": "RUNNING"}},
+            {"iid": "1", "headPipeline": {"status": "FAILED"}}
+        ]}}}}"#,
+    )
+    .unwrap();
+
+    ctx.config().forge.api_url = Some(format!("file://{}", api.path().display()));
+    snapshot!(ctx, "R");
+}
+
+#[test]
+fn checkout_pull_request_menu() {
+    let (ctx, _api) = setup();
+    snapshot!(ctx, "Rjb");
+}
+
+#[test]
+fn checkout_pull_request() {
+    let (mut ctx, _api) = setup();
+    let remote = ctx.remote_dir.path().to_string_lossy().to_string();
+    run(ctx.dir.path(), &["git", "checkout", "-b", "feature"]);
+    commit(ctx.dir.path(), "feature-file", "feature\n");
+    run(
+        ctx.dir.path(),
+        &["git", "push", &remote, "feature:refs/pull/2/head"],
+    );
+    let pull_request_head = rev_parse(&ctx, "feature");
+
+    // A local branch named like the pull request's, which is left alone
+    run(ctx.dir.path(), &["git", "checkout", "main"]);
+    run(ctx.dir.path(), &["git", "branch", "-f", "feature", "main"]);
+    // Fetches from the remote, while gitu still sees a GitHub origin. Only git itself
+    // knows the `hasconfig` condition, libgit2 skips the include.
+    fs::write(
+        ctx.dir.child(".git/fetch-from-remote"),
+        format!(
+            "[url \"{}\"]\n\tinsteadOf = git@github.com:o/r.git\n",
+            remote
+        ),
+    )
+    .unwrap();
+    run(
+        ctx.dir.path(),
+        &[
+            "git",
+            "config",
+            "includeIf.hasconfig:remote.*.url:git@github.com:o/r.git.path",
+            "fetch-from-remote",
+        ],
+    );
+
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("Rjbp")).unwrap();
+    state.update(&mut ctx.term, &[]).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+
+    assert_eq!(rev_parse(&ctx, "pr-2"), pull_request_head);
+    assert_eq!(rev_parse(&ctx, "HEAD"), pull_request_head);
+    assert_eq!(rev_parse(&ctx, "feature"), rev_parse(&ctx, "main"));
+}
+
+#[test]
+fn create_pull_request() {
+    let (mut ctx, _api) = setup();
+    ctx.config().general.browser = Some("echo".into());
+    snapshot!(ctx, "Pr");
+}
+
+#[test]
+fn create_pull_request_of_unpushed_branch() {
+    let (ctx, _api) = setup();
+    run(ctx.dir.path(), &["git", "checkout", "-b", "feature"]);
+    snapshot!(ctx, "Pr");
+}
+
+/// A GitHub origin that is fetched from the local remote, with `refs/pull/7/head` on it.
+fn setup_pull_request_on_remote() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    let remote = ctx.remote_dir.path().to_str().unwrap();
+    commit(ctx.dir.path(), "contribution", "");
+    run(
+        ctx.dir.path(),
+        &["git", "push", remote, "HEAD:refs/pull/7/head"],
+    );
+    run(ctx.dir.path(), &["git", "reset", "--hard", "HEAD~1"]);
+
+    run(
+        ctx.dir.path(),
+        &[
+            "git",
+            "remote",
+            "set-url",
+            "origin",
+            "git@github.com:o/r.git",
+        ],
+    );
+    // Talk to the local remote in place of the forge's ssh
+    run(
+        ctx.dir.path(),
+        &[
+            "git",
+            "config",
+            "core.sshCommand",
+            &format!("f() {{ git-upload-pack '{}'; }}; f", remote),
+        ],
+    );
+    ctx
+}
+
+/// Replaces the pull request with another commit, like its author rewriting it.
+fn force_push_pull_request(ctx: &TestContext) -> String {
+    let remote = ctx.remote_dir.path().to_str().unwrap();
+    run(
+        ctx.dir.path(),
+        &["git", "checkout", "-b", "rewrite", "main"],
+    );
+    commit(ctx.dir.path(), "rewritten-contribution", "");
+    run(
+        ctx.dir.path(),
+        &["git", "push", "--force", remote, "HEAD:refs/pull/7/head"],
+    );
+    let head = rev_parse(ctx, "rewrite");
+    run(ctx.dir.path(), &["git", "checkout", "main"]);
+    head
+}
+
+#[test]
+fn checkout_pull_request_by_number() {
+    let mut ctx = setup_pull_request_on_remote();
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("bp7<enter>")).unwrap();
+    // Shows the output of the queued checkout
+    state.update(&mut ctx.term, &[]).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn checkout_force_pushed_pull_request() {
+    let mut ctx = setup_pull_request_on_remote();
+    run(
+        ctx.dir.path(),
+        &["git", "fetch", "origin", "refs/pull/7/head:refs/heads/pr-7"],
+    );
+    let head = force_push_pull_request(&ctx);
+
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("bp7<enter>")).unwrap();
+    state.update(&mut ctx.term, &[]).unwrap();
+
+    assert_eq!(rev_parse(&ctx, "HEAD"), head);
+    assert_eq!(rev_parse(&ctx, "pr-7"), head);
+}
+
+#[test]
+fn checkout_force_pushed_pull_request_while_on_it() {
+    let mut ctx = setup_pull_request_on_remote();
+    let head = force_push_pull_request(&ctx);
+    run(
+        ctx.dir.path(),
+        &["git", "fetch", "origin", "refs/pull/7/head:refs/heads/pr-7"],
+    );
+    run(ctx.dir.path(), &["git", "checkout", "pr-7"]);
+    run(ctx.dir.path(), &["git", "reset", "--hard", "main"]);
+
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("bp7<enter>")).unwrap();
+    state.update(&mut ctx.term, &[]).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+
+    assert_eq!(rev_parse(&ctx, "HEAD"), head);
+    assert_eq!(rev_parse(&ctx, "pr-7"), head);
+}
+
+fn rev_parse(ctx: &TestContext, rev: &str) -> String {
+    let out = std::process::Command::new("git")
+        .args(["rev-parse", rev])
+        .current_dir(ctx.dir.path())
+        .output()
+        .unwrap();
+    String::from_utf8(out.stdout).unwrap()
+}
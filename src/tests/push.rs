@@ -30,3 +30,137 @@ fn push_elsewhere_prompt() {
 fn push_elsewhere() {
     snapshot!(TestContext::setup_clone(), "Peorigin<enter>");
 }
+
+fn setup_confirm() -> TestContext {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.confirm_push.enabled = true;
+    commit(ctx.dir.path(), "first-file", "one");
+    commit(ctx.dir.path(), "second-file", "two");
+    ctx
+}
+
+#[test]
+fn confirm_push_review() {
+    snapshot!(setup_confirm(), "Pp");
+}
+
+#[test]
+fn confirm_push() {
+    snapshot!(setup_confirm(), "Ppy");
+}
+
+#[test]
+fn confirm_push_abort() {
+    snapshot!(setup_confirm(), "Ppn");
+}
+
+#[test]
+fn confirm_push_escape() {
+    snapshot!(setup_confirm(), "Pp<esc>");
+}
+
+#[test]
+fn confirm_push_new_branch() {
+    let ctx = setup_confirm();
+    run(ctx.dir.path(), &["git", "push"]);
+    run(ctx.dir.path(), &["git", "checkout", "-b", "feature"]);
+    commit(ctx.dir.path(), "feature-file", "feature");
+    snapshot!(ctx, "Pp");
+}
+
+#[test]
+fn confirm_push_nothing_to_push() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.confirm_push.enabled = true;
+    snapshot!(ctx, "Pp");
+}
+
+/// A push whose pre-push hook runs `then` once `release_push` is called, so that it's still
+/// running while the test goes on.
+fn setup_slow_push(then: &str) -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "new-file", "");
+    let hooks = ctx.dir.path().join(".git/hooks");
+    let release = ctx.dir.path().join(".git/release-push");
+    fs::write(
+        hooks.join("pre-push"),
+        format!(
+            "#!/bin/sh\nwhile [ ! -e '{}' ]; do sleep 0.01; done\n{}",
+            release.display(),
+            then
+        ),
+    )
+    .unwrap();
+    run(&hooks, &["chmod", "+x", "pre-push"]);
+    ctx
+}
+
+fn release_push(ctx: &TestContext) {
+    fs::write(ctx.dir.path().join(".git/release-push"), "").unwrap();
+}
+
+#[test]
+fn queue_fetch_while_pushing() {
+    let mut ctx = setup_slow_push("");
+    let mut state = ctx.init_async_state();
+    state.update(&mut ctx.term, &keys("Ppfa")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+
+    release_push(&ctx);
+    await_cmds(&mut ctx, &mut state);
+    insta::assert_snapshot!("queue_fetch_while_pushing_done", ctx.redact_buffer());
+}
+
+#[test]
+fn failed_push_cancels_queue() {
+    let mut ctx = setup_slow_push("exit 1\n");
+    let mut state = ctx.init_async_state();
+    state.update(&mut ctx.term, &keys("Ppfa")).unwrap();
+
+    release_push(&ctx);
+    await_cmds(&mut ctx, &mut state);
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn aborted_credentials_cancel_queue() {
+    let mut ctx = setup_slow_push(
+        "echo \"fatal: could not read Username for 'https://example.com': terminal prompts disabled\" >&2\nexit 1\n",
+    );
+    let mut state = ctx.init_async_state();
+    state.update(&mut ctx.term, &keys("Ppfa")).unwrap();
+
+    release_push(&ctx);
+    await_cmds(&mut ctx, &mut state);
+    state.update(&mut ctx.term, &keys("<esc>")).unwrap();
+    assert!(state.queued_cmds.is_empty());
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn push_for_review() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().gerrit.reviewers = vec!["reviewer@example.com".into()];
+    commit(ctx.dir.path(), "new-file", "");
+    snapshot!(ctx, "Pg<enter>some-topic<enter>");
+}
+
+#[test]
+fn push_remote_commits() {
+    let mut ctx = TestContext::setup_clone();
+    run(
+        ctx.dir.path(),
+        &["git", "checkout", "-b", "feature", "--track", "origin/main"],
+    );
+    commit(ctx.dir.path(), "local-file", "hi");
+    run(ctx.dir.path(), &["git", "push", "origin", "feature"]);
+    commit(ctx.dir.path(), "unpushed-file", "hello");
+    run(
+        ctx.dir.path(),
+        &["git", "config", "remote.pushDefault", "origin"],
+    );
+    ctx.config().general.upstream_commits.enabled = true;
+
+    ctx.init_state();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
@@ -23,3 +23,81 @@ fn rebase_elsewhere_prompt() {
 fn rebase_elsewhere() {
     snapshot!(setup(), "remain<enter>");
 }
+
+fn setup_onto() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "file-1", "");
+    commit(ctx.dir.path(), "file-2", "");
+    commit(ctx.dir.path(), "file-3", "");
+    ctx
+}
+
+#[test]
+fn rebase_elsewhere_defaults_to_upstream() {
+    snapshot!(setup_onto(), "re");
+}
+
+#[test]
+fn rebase_elsewhere_fork_point() {
+    snapshot!(setup_onto(), "r-Fe<enter>");
+}
+
+#[test]
+fn rebase_onto_prompt() {
+    snapshot!(setup_onto(), "ro");
+}
+
+#[test]
+fn rebase_onto_upstream_prompt() {
+    snapshot!(setup_onto(), "roorigin/main<enter>");
+}
+
+#[test]
+fn rebase_onto() {
+    snapshot!(setup_onto(), "roorigin/main<enter>HEAD~1<enter>");
+}
+
+fn setup_todo() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "file-1", "");
+    commit(ctx.dir.path(), "file-2", "");
+    commit(ctx.dir.path(), "file-3", "");
+    ctx
+}
+
+#[test]
+fn rebase_interactive_todo() {
+    snapshot!(setup_todo(), "lljjri");
+}
+
+#[test]
+fn rebase_todo_set_actions() {
+    snapshot!(setup_todo(), "lljjrijsr");
+}
+
+#[test]
+fn rebase_todo_move_commit() {
+    snapshot!(setup_todo(), "lljjri<alt+j><alt+j>");
+}
+
+#[test]
+fn rebase_todo_abort() {
+    snapshot!(setup_todo(), "lljjriq");
+}
+
+#[test]
+fn rebase_todo_start() {
+    snapshot!(setup_todo(), "lljjrijfd<enter>");
+}
+
+#[test]
+fn rebase_todo_autosquash() {
+    let ctx = setup_todo();
+    fs::write(ctx.dir.path().join("file-1"), "fix").unwrap();
+    run(ctx.dir.path(), &["git", "add", "file-1"]);
+    run(
+        ctx.dir.path(),
+        &["git", "commit", "-m", "fixup! add file-1"],
+    );
+    snapshot!(ctx, "lljjjr-ai");
+}
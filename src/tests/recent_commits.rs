@@ -0,0 +1,40 @@
+use super::*;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "first-file", "");
+    commit(ctx.dir.path(), "second-file", "");
+    commit(ctx.dir.path(), "third-file", "");
+    ctx
+}
+
+#[test]
+fn limited_count() {
+    let mut ctx = setup();
+    ctx.config().general.recent_commits.count = 2;
+    snapshot!(ctx, "");
+}
+
+#[test]
+fn show_more() {
+    let mut ctx = setup();
+    ctx.config().general.recent_commits.count = 2;
+    snapshot!(ctx, "jjjjj<enter>");
+}
+
+#[test]
+fn show_more_of_none() {
+    let mut ctx = setup();
+    ctx.config().general.recent_commits.count = 0;
+    snapshot!(ctx, "jjj<enter>");
+}
+
+#[test]
+fn unmerged_branches() {
+    let mut ctx = setup();
+    ctx.config().general.recent_commits.unmerged_branches = true;
+    run(ctx.dir.path(), &["git", "checkout", "-b", "feature"]);
+    commit(ctx.dir.path(), "feature-file", "");
+    run(ctx.dir.path(), &["git", "checkout", "main"]);
+    snapshot!(ctx, "");
+}
@@ -0,0 +1,31 @@
+use super::*;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "testfile", "testing\ntesttest\n");
+    commit(ctx.dir.path(), "testfile", "test\ntesttest\n");
+    ctx
+}
+
+#[test]
+fn reverse_hunk_prompt() {
+    snapshot!(setup(), "ll<enter>v");
+}
+
+#[test]
+fn reverse_hunk_from_show() {
+    snapshot!(setup(), "ll<enter>vyqq");
+}
+
+#[test]
+fn reverse_file_from_show() {
+    snapshot!(setup(), "ll<enter>kvyqq");
+}
+
+#[test]
+fn reverse_unstaged_line() {
+    let ctx = TestContext::setup_init();
+    commit(ctx.dir.path(), "firstfile", "testing\ntesttest\n");
+    fs::write(ctx.dir.child("firstfile"), "weehooo\nblrergh\n").unwrap();
+    snapshot!(ctx, "jj<tab><ctrl+j><ctrl+j>vy");
+}
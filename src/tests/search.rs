@@ -0,0 +1,71 @@
+use super::*;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "first-file", "");
+    commit(ctx.dir.path(), "second-file", "");
+    commit(ctx.dir.path(), "third-file", "");
+    fs::write(ctx.dir.child("first-file"), "hello\nworld\n").unwrap();
+    ctx
+}
+
+#[test]
+fn search_as_typed() {
+    snapshot!(setup(), "/seco");
+}
+
+#[test]
+fn search_expands_sections() {
+    snapshot!(setup(), "/world<enter>");
+}
+
+#[test]
+fn search_collapsed_by_config() {
+    let mut ctx = setup();
+    ctx.config().general.collapsed_sections = vec!["Recent commits".to_string()];
+    snapshot!(ctx, "/third<enter>");
+}
+
+#[test]
+fn search_next() {
+    snapshot!(setup(), "/add<enter>nn");
+}
+
+#[test]
+fn search_previous_wraps_around() {
+    snapshot!(setup(), "/add<enter>N");
+}
+
+#[test]
+fn search_no_match() {
+    snapshot!(setup(), "/doesntexist<enter>");
+}
+
+#[test]
+fn search_next_without_search() {
+    snapshot!(setup(), "n");
+}
+
+#[test]
+fn search_log() {
+    snapshot!(setup(), "ll/second<enter>");
+}
+
+#[test]
+fn search_show_message() {
+    let ctx = TestContext::setup_clone();
+    fs::write(ctx.dir.child("file"), "hello\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "file"]);
+    run(
+        ctx.dir.path(),
+        &[
+            "git",
+            "commit",
+            "-m",
+            "add file",
+            "-m",
+            "The first line about the file.\nThe second line about the file.",
+        ],
+    );
+    snapshot!(ctx, "ll<enter>/about<enter>n");
+}
@@ -54,3 +54,14 @@ fn stage_changes_crlf() {
 
     snapshot!(ctx, "jj<tab>");
 }
+
+#[test]
+fn stage_from_unstaged_changes_screen() {
+    let ctx = TestContext::setup_init();
+    commit(ctx.dir.path(), "firstfile", "testing\ntesttest\n");
+    commit(ctx.dir.path(), "secondfile", "testing\ntesttest\n");
+
+    fs::write(ctx.dir.child("firstfile"), "blahonga\n").unwrap();
+    fs::write(ctx.dir.child("secondfile"), "blahonga\n").unwrap();
+    snapshot!(ctx, "j<enter>js");
+}
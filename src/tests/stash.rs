@@ -101,6 +101,23 @@ pub(crate) fn stash_pop_default() {
     snapshot!(setup_two_stashes(), "zp<enter>");
 }
 
+fn setup_conflicting_stash() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "file-one", "original\n");
+    fs::write(ctx.dir.child("file-one"), "stashed\n").unwrap();
+    run(
+        ctx.dir.path(),
+        &["git", "stash", "push", "-m", "conflicting"],
+    );
+    fs::write(ctx.dir.child("file-one"), "changed\n").unwrap();
+    ctx
+}
+
+#[test]
+pub(crate) fn stash_pop_expected_conflicts() {
+    snapshot!(setup_conflicting_stash(), "zp<enter>");
+}
+
 #[test]
 pub(crate) fn stash_apply_prompt() {
     snapshot!(setup_two_stashes(), "za");
@@ -130,3 +147,131 @@ pub(crate) fn stash_drop() {
 pub(crate) fn stash_drop_default() {
     snapshot!(setup_two_stashes(), "zk<enter>");
 }
+
+#[test]
+pub(crate) fn show_stash_with_untracked() {
+    let ctx = setup();
+    run(
+        ctx.dir.path(),
+        &[
+            "git",
+            "stash",
+            "push",
+            "--include-untracked",
+            "-m",
+            "untracked",
+        ],
+    );
+    snapshot!(ctx, "jj<enter>");
+}
+
+fn setup_show() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    let lines = (1..=12)
+        .map(|i| format!("line {}\n", i))
+        .collect::<String>();
+    commit(ctx.dir.path(), "file-one", &lines);
+    fs::write(
+        ctx.dir.child("file-one"),
+        lines
+            .replace("line 1\n", "line 1 changed\n")
+            .replace("line 12\n", "line 12 changed\n"),
+    )
+    .unwrap();
+    run(ctx.dir.path(), &["git", "stash", "push", "-m", "two hunks"]);
+    ctx
+}
+
+#[test]
+pub(crate) fn show_stash() {
+    snapshot!(setup_show(), "jj<enter>");
+}
+
+#[test]
+pub(crate) fn show_stash_apply_hunk() {
+    let mut ctx = setup_show();
+    let mut state = ctx.init_state();
+    state
+        .update(&mut ctx.term, &keys("jj<enter>ja<esc>"))
+        .unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+
+    let content = fs::read_to_string(ctx.dir.child("file-one")).unwrap();
+    assert!(content.contains("line 1\n"));
+    assert!(content.contains("line 12 changed\n"));
+}
+
+fn setup_wip_snapshots() -> TestContext {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.wip_snapshots.enabled = true;
+    commit(ctx.dir.path(), "file", "original\n");
+    fs::write(ctx.dir.child("file"), "changed\n").unwrap();
+    ctx
+}
+
+#[test]
+pub(crate) fn wip_snapshot_before_hard_reset() {
+    snapshot!(setup_wip_snapshots(), "XhHEAD<enter>zW");
+}
+
+#[test]
+pub(crate) fn wip_snapshot_before_discard() {
+    snapshot!(setup_wip_snapshots(), "jjKyzW");
+}
+
+#[test]
+pub(crate) fn wip_snapshots_disabled() {
+    let mut ctx = setup_wip_snapshots();
+    ctx.config().general.wip_snapshots.enabled = false;
+    snapshot!(ctx, "XhHEAD<enter>zW");
+}
+
+#[test]
+pub(crate) fn wip_snapshot_show() {
+    snapshot!(setup_wip_snapshots(), "XhHEAD<enter>zWj<enter>");
+}
+
+#[test]
+pub(crate) fn wip_snapshot_apply() {
+    snapshot!(setup_wip_snapshots(), "XhHEAD<enter>zWjza<enter>q");
+}
+
+#[test]
+pub(crate) fn wip_snapshot_pop() {
+    snapshot!(setup_wip_snapshots(), "XhHEAD<enter>zWjzp<enter>q");
+}
+
+#[test]
+pub(crate) fn wip_snapshot_drop() {
+    let mut ctx = setup_wip_snapshots();
+    let repo = git2::Repository::open(ctx.dir.path()).unwrap();
+    crate::git::wip_snapshot(&repo, "First").unwrap();
+    fs::write(ctx.dir.child("file"), "changed again\n").unwrap();
+    crate::git::wip_snapshot(&repo, "Second").unwrap();
+
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("zWjjzk<enter>")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+
+    let snapshots = crate::git::wip_snapshots(&repo).unwrap();
+    assert_eq!(snapshots.len(), 1);
+    assert_eq!(snapshots[0].message, "Second");
+}
+
+#[test]
+pub(crate) fn wip_snapshot_restores_discarded_untracked_file() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.wip_snapshots.enabled = true;
+    fs::write(ctx.dir.child("new-file"), "untracked\n").unwrap();
+
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("jKy")).unwrap();
+    assert!(!ctx.dir.child("new-file").exists());
+
+    state.update(&mut ctx.term, &keys("zWjza<enter>q")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+    assert_eq!(
+        fs::read_to_string(ctx.dir.child("new-file")).unwrap(),
+        "untracked\n"
+    );
+}
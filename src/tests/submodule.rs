@@ -0,0 +1,45 @@
+use super::*;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    run(
+        ctx.dir.path(),
+        &[
+            "git",
+            "-c",
+            "protocol.file.allow=always",
+            "submodule",
+            "add",
+            ctx.remote_dir.path().to_str().unwrap(),
+            "test-submodule",
+        ],
+    );
+    run(ctx.dir.path(), &["git", "commit", "-m", "add submodule"]);
+    fs::write(ctx.dir.child("test-submodule/new-file"), "hello").unwrap();
+    ctx
+}
+
+#[test]
+fn submodule_menu() {
+    snapshot!(setup(), "o");
+}
+
+#[test]
+fn dirty_submodule_section() {
+    snapshot!(setup(), "");
+}
+
+#[test]
+fn visit_submodule() {
+    snapshot!(setup(), "jj<enter>");
+}
+
+#[test]
+fn submodule_sync() {
+    snapshot!(setup(), "jjos");
+}
+
+#[test]
+fn submodule_deinit_prompt() {
+    snapshot!(setup(), "jjod");
+}
@@ -25,3 +25,15 @@ fn unstage_added_line() {
     run(ctx.dir.path(), &["git", "add", "."]);
     snapshot!(ctx, "jj<tab><ctrl+j><ctrl+j><ctrl+j><ctrl+j>u");
 }
+
+#[test]
+fn unstage_from_staged_changes_screen() {
+    let ctx = TestContext::setup_init();
+    commit(ctx.dir.path(), "firstfile", "testing\ntesttest\n");
+    commit(ctx.dir.path(), "secondfile", "testing\ntesttest\n");
+
+    fs::write(ctx.dir.child("firstfile"), "blahonga\n").unwrap();
+    fs::write(ctx.dir.child("secondfile"), "blahonga\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "."]);
+    snapshot!(ctx, "j<enter>ju");
+}
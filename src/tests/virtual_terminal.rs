@@ -0,0 +1,67 @@
+use super::{helpers::RepoTestContext, *};
+use crate::testing::VirtualTerminal;
+use crossterm::event::Event;
+
+fn setup(config: &str) -> (RepoTestContext, VirtualTerminal) {
+    let ctx = RepoTestContext::setup_clone();
+    let vt = VirtualTerminal::new(ctx.dir.path(), config, 80, 20).unwrap();
+    (ctx, vt)
+}
+
+#[test]
+fn renders_status() {
+    let (_ctx, vt) = setup("");
+    assert_eq!(vt.lines().len(), 20);
+    assert_eq!(vt.lines()[0], "▌On branch main");
+    assert!(vt.contains("Your branch is up to date with 'origin/main'."));
+}
+
+#[test]
+fn keys_run_commands() {
+    let (ctx, mut vt) = setup("");
+    commit(ctx.dir.path(), "new-file", "");
+    vt.keys("g").unwrap();
+    assert!(vt.contains("Your branch is ahead of 'origin/main' by 1 commit."));
+
+    vt.keys("Pp").unwrap();
+    assert!(vt.contains("$ git push --progress"));
+    assert!(vt.contains("Your branch is up to date with 'origin/main'."));
+}
+
+#[test]
+fn custom_bindings() {
+    let (_ctx, mut vt) = setup("[bindings]\nroot.show_refs = [\"<ctrl+r>\"]");
+    vt.keys("<ctrl+r>").unwrap();
+    assert!(vt.contains("Branches"));
+}
+
+#[test]
+fn invalid_config() {
+    let ctx = RepoTestContext::setup_clone();
+    assert!(VirtualTerminal::new(ctx.dir.path(), "[general", 80, 20).is_err());
+}
+
+#[test]
+fn invalid_keys() {
+    let (_ctx, mut vt) = setup("");
+    assert_eq!(
+        vt.keys("<nope>").unwrap_err().to_string(),
+        "Invalid keys: <nope>"
+    );
+}
+
+#[test]
+fn resize() {
+    let (_ctx, mut vt) = setup("");
+    vt.events(&[Event::Resize(40, 10)]).unwrap();
+    assert_eq!(vt.lines().len(), 10);
+    assert_eq!(vt.buffer().area.width, 40);
+}
+
+#[test]
+fn quit() {
+    let (_ctx, mut vt) = setup("");
+    assert!(!vt.has_quit());
+    vt.keys("q").unwrap();
+    assert!(vt.has_quit());
+}
@@ -0,0 +1,52 @@
+use super::*;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "file", "one\ntwo\n");
+    fs::write(ctx.dir.child("file"), "one  \n  \ttwo\n\tthree\n").unwrap();
+    ctx
+}
+
+#[test]
+fn whitespace_errors_highlighted() {
+    snapshot!(setup(), "jj<tab>");
+}
+
+#[test]
+fn whitespace_errors_per_core_whitespace() {
+    let ctx = setup();
+    run(
+        ctx.dir.path(),
+        &[
+            "git",
+            "config",
+            "core.whitespace",
+            "-trailing-space,tab-in-indent",
+        ],
+    );
+    snapshot!(ctx, "jj<tab>");
+}
+
+#[test]
+fn commit_warns_about_whitespace_errors() {
+    let ctx = setup();
+    run(ctx.dir.path(), &["git", "config", "core.editor", "true"]);
+    run(ctx.dir.path(), &["git", "add", "file"]);
+    snapshot!(ctx, "cc");
+}
+
+#[test]
+fn commit_all_on_unborn_head() {
+    let ctx = TestContext::setup_init();
+    run(ctx.dir.path(), &["git", "config", "core.editor", "true"]);
+    let hooks = ctx.dir.path().join(".git/hooks");
+    fs::write(
+        hooks.join("prepare-commit-msg"),
+        "#!/bin/sh\necho Message > \"$1\"\n",
+    )
+    .unwrap();
+    run(&hooks, &["chmod", "+x", "prepare-commit-msg"]);
+    fs::write(ctx.dir.child("file"), "one  \n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "file"]);
+    snapshot!(ctx, "c-ac");
+}
@@ -0,0 +1,63 @@
+use super::*;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    let linked = ctx.remote_dir.child("linked");
+    run(
+        ctx.dir.path(),
+        &[
+            "git",
+            "worktree",
+            "add",
+            "-b",
+            "linked-branch",
+            linked.to_str().unwrap(),
+        ],
+    );
+    ctx
+}
+
+#[test]
+fn worktree_menu() {
+    snapshot!(setup(), "Z");
+}
+
+#[test]
+fn show_worktrees() {
+    snapshot!(setup(), "ZZ");
+}
+
+#[test]
+fn visit_worktree() {
+    snapshot!(setup(), "ZZjj<enter>");
+}
+
+#[test]
+fn lock_worktree() {
+    snapshot!(setup(), "ZZjjZl<enter>");
+}
+
+#[test]
+fn unlock_worktree() {
+    snapshot!(setup(), "ZZjjZl<enter>Zu<enter>");
+}
+
+#[test]
+fn remove_worktree() {
+    snapshot!(setup(), "ZZjjZk<enter>");
+}
+
+#[test]
+fn add_worktree() {
+    let mut ctx = TestContext::setup_clone();
+    let mut state = ctx.init_state();
+    let path = ctx.remote_dir.child("added");
+    state
+        .update(
+            &mut ctx.term,
+            &keys(&format!("Zc{}<enter>ZZ", path.to_str().unwrap())),
+        )
+        .unwrap();
+
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
@@ -0,0 +1,60 @@
+use super::*;
+use std::process::Command;
+
+fn yank(mut ctx: TestContext, keys_before: &str) -> TestContext {
+    let mut state = ctx.init_state();
+    // Use the OSC 52 fallback, rather than the system clipboard
+    state.clipboard = None;
+    state
+        .update(&mut ctx.term, &keys(&format!("{}y", keys_before)))
+        .unwrap();
+    ctx
+}
+
+#[test]
+fn yank_commit_hash() {
+    let ctx = yank(TestContext::setup_clone(), "jj");
+    insta::assert_snapshot!(ctx.redact_buffer());
+
+    let head = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .current_dir(ctx.dir.path())
+        .output()
+        .unwrap();
+    assert_eq!(
+        ctx.clipboard(),
+        Some(String::from_utf8(head.stdout).unwrap().trim())
+    );
+}
+
+#[test]
+fn yank_branch_name() {
+    let ctx = yank(TestContext::setup_clone(), "Yj");
+    insta::assert_snapshot!(ctx.redact_buffer());
+    assert_eq!(ctx.clipboard(), Some("main"));
+}
+
+#[test]
+fn yank_remote_url() {
+    let ctx = yank(TestContext::setup_clone(), "Ijjjjjjjj");
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn yank_file_path() {
+    let ctx = TestContext::setup_init();
+    run(ctx.dir.path(), &["touch", "new-file"]);
+    let ctx = yank(ctx, "jj");
+    insta::assert_snapshot!(ctx.redact_buffer());
+    assert_eq!(ctx.clipboard(), Some("new-file"));
+}
+
+#[test]
+fn yank_hunk() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "file", "one\n");
+    fs::write(ctx.dir.child("file"), "two\n").unwrap();
+    let ctx = yank(ctx, "jj<tab>j");
+    insta::assert_snapshot!(ctx.redact_buffer());
+    assert_eq!(ctx.clipboard(), Some("@@ -1 +1 @@\n-one\n+two\n"));
+}
@@ -1,3 +1,4 @@
+use crate::cmd_log::command_args;
 use crate::state::State;
 use ratatui::prelude::*;
 use ratatui::style::Stylize;
@@ -5,6 +6,7 @@ use ratatui::widgets::*;
 use ratatui::Frame;
 use tui_prompts::State as _;
 use tui_prompts::TextPrompt;
+use tui_prompts::TextRenderStyle;
 
 mod menu;
 
@@ -27,9 +29,21 @@ impl<W: StatefulWidget> StatefulWidget for SizedWidget<W> {
     }
 }
 
+/// A message shown on top of everything else, closed by pressing any key.
+pub(crate) struct Popup {
+    pub title: String,
+    pub lines: Vec<String>,
+}
+
 pub(crate) fn ui(frame: &mut Frame, state: &mut State) {
-    let maybe_log = if !state.current_cmd_log.is_empty() {
-        let text: Text = state.current_cmd_log.format_log(&state.config);
+    let maybe_log = if !state.current_cmd_log.is_empty() || !state.queued_cmds.is_empty() {
+        let mut text: Text = state.current_cmd_log.format_log(&state.config);
+        text.lines.extend(state.queued_cmds.iter().map(|(cmd, _)| {
+            Line::styled(
+                format!("Queued: {}", command_args(cmd)),
+                &state.config.style.command,
+            )
+        }));
 
         Some(SizedWidget {
             widget: Paragraph::new(text.clone()).block(popup_block()),
@@ -54,7 +68,13 @@ pub(crate) fn ui(frame: &mut Frame, state: &mut State) {
 
     let maybe_prompt = state.prompt.data.as_ref().map(|prompt_data| SizedWidget {
         height: 2,
-        widget: TextPrompt::new(prompt_data.prompt_text.clone()).with_block(popup_block()),
+        widget: TextPrompt::new(prompt_data.prompt_text.clone())
+            .with_block(popup_block())
+            .with_render_style(if state.prompt.hide_input {
+                TextRenderStyle::Password
+            } else {
+                TextRenderStyle::Default
+            }),
     });
 
     let layout = Layout::new(
@@ -79,9 +99,44 @@ pub(crate) fn ui(frame: &mut Frame, state: &mut State) {
     maybe_render(maybe_menu, frame, layout[2]);
     maybe_render(maybe_log, frame, layout[3]);
 
+    if let Some(popup) = &state.popup {
+        render_popup(popup, frame);
+    }
+
     state.screens.last_mut().unwrap().size = layout[0];
 }
 
+fn render_popup(popup: &Popup, frame: &mut Frame) {
+    let area = frame.size();
+    let width = popup
+        .lines
+        .iter()
+        .map(|line| line.chars().count())
+        .chain([popup.title.chars().count(), 0])
+        .max()
+        .unwrap_or(0) as u16
+        + 4;
+    let height = popup.lines.len() as u16 + 2;
+
+    let popup_area = Rect {
+        x: area.width.saturating_sub(width) / 2,
+        y: area.height.saturating_sub(height) / 2,
+        width: width.min(area.width),
+        height: height.min(area.height),
+    };
+
+    let text = Text::from_iter(popup.lines.iter().map(|line| Line::raw(line.as_str())));
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(
+        Paragraph::new(text).block(
+            Block::bordered()
+                .title(popup.title.as_str())
+                .padding(Padding::horizontal(1)),
+        ),
+        popup_area,
+    );
+}
+
 fn popup_block() -> Block<'static> {
     Block::new()
         .borders(Borders::TOP)
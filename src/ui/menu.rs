@@ -27,6 +27,7 @@ impl<'a> MenuWidget<'a> {
         let non_target_binds = bindings
             .list(&pending.menu)
             .filter(|keybind| !keybind.op.clone().implementation().is_target_op())
+            .filter(|keybind| pending.offers(&keybind.op))
             .collect::<Vec<_>>();
 
         let mut pending_binds_column = vec![];
@@ -96,6 +97,24 @@ impl<'a> MenuWidget<'a> {
             }
         }
 
+        let skip_hooks_bind = arg_binds
+            .iter()
+            .find(|bind| matches!(&bind.op, Op::ToggleArg(name) if name == "--no-verify"));
+
+        let running_hooks = pending.running_hooks();
+        if !running_hooks.is_empty() {
+            right_column.push(Line::styled("Hooks", &style.command));
+            right_column.push(Line::raw(format!("Runs {}", running_hooks.join(", "))));
+        }
+
+        let skippable_hooks = pending.skippable_hooks();
+        if let (false, Some(bind)) = (skippable_hooks.is_empty(), skip_hooks_bind) {
+            right_column.push(Line::from(vec![
+                Span::styled(&bind.raw, &style.hotkey),
+                Span::raw(format!(" Skip {}", skippable_hooks.join(", "))),
+            ]));
+        }
+
         if !arg_binds.is_empty() {
             right_column.push(Line::styled("Arguments", &style.command));
         }